@@ -68,18 +68,36 @@ export declare function __macroforgeRunDefault(contextJson: string): string
  */
 export declare function __macroforgeRunDeserialize(contextJson: string): string
 
+/**
+ * r" Run this macro with the given context
+ * r" Called by the TS plugin to execute macro expansion
+ */
+export declare function __macroforgeRunEnumUtils(contextJson: string): string
+
 /**
  * r" Run this macro with the given context
  * r" Called by the TS plugin to execute macro expansion
  */
 export declare function __macroforgeRunHash(contextJson: string): string
 
+/**
+ * r" Run this macro with the given context
+ * r" Called by the TS plugin to execute macro expansion
+ */
+export declare function __macroforgeRunMock(contextJson: string): string
+
 /**
  * r" Run this macro with the given context
  * r" Called by the TS plugin to execute macro expansion
  */
 export declare function __macroforgeRunOrd(contextJson: string): string
 
+/**
+ * r" Run this macro with the given context
+ * r" Called by the TS plugin to execute macro expansion
+ */
+export declare function __macroforgeRunPartial(contextJson: string): string
+
 /**
  * r" Run this macro with the given context
  * r" Called by the TS plugin to execute macro expansion
@@ -92,6 +110,12 @@ export declare function __macroforgeRunPartialEq(contextJson: string): string
  */
 export declare function __macroforgeRunPartialOrd(contextJson: string): string
 
+/**
+ * r" Run this macro with the given context
+ * r" Called by the TS plugin to execute macro expansion
+ */
+export declare function __macroforgeRunPatch(contextJson: string): string
+
 /**
  * r" Run this macro with the given context
  * r" Called by the TS plugin to execute macro expansion
@@ -150,6 +174,10 @@ export interface MacroDiagnostic {
   message: string
   start?: number
   end?: number
+  /** Stable machine-readable code, when the producer assigned one */
+  code?: string
+  notes: Array<string>
+  help?: string
 }
 
 export interface MacroManifest {
@@ -586,10 +586,14 @@ module.exports.__macroforgeRunClone = nativeBinding.__macroforgeRunClone
 module.exports.__macroforgeRunDebug = nativeBinding.__macroforgeRunDebug
 module.exports.__macroforgeRunDefault = nativeBinding.__macroforgeRunDefault
 module.exports.__macroforgeRunDeserialize = nativeBinding.__macroforgeRunDeserialize
+module.exports.__macroforgeRunEnumUtils = nativeBinding.__macroforgeRunEnumUtils
 module.exports.__macroforgeRunHash = nativeBinding.__macroforgeRunHash
+module.exports.__macroforgeRunMock = nativeBinding.__macroforgeRunMock
 module.exports.__macroforgeRunOrd = nativeBinding.__macroforgeRunOrd
+module.exports.__macroforgeRunPartial = nativeBinding.__macroforgeRunPartial
 module.exports.__macroforgeRunPartialEq = nativeBinding.__macroforgeRunPartialEq
 module.exports.__macroforgeRunPartialOrd = nativeBinding.__macroforgeRunPartialOrd
+module.exports.__macroforgeRunPatch = nativeBinding.__macroforgeRunPatch
 module.exports.__macroforgeRunSerialize = nativeBinding.__macroforgeRunSerialize
 module.exports.checkSyntax = nativeBinding.checkSyntax
 module.exports.Derive = nativeBinding.Derive
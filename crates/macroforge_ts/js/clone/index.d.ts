@@ -0,0 +1,19 @@
+/**
+ * Clone runtime helpers for the macroforge Clone macro.
+ * Like the serde contexts, a CloneContext tracks objects already copied so
+ * shared references and cycles are preserved in the clone.
+ */
+export interface CloneContext {
+    /** The copy made for `original` in this clone, if any */
+    get<T extends object>(original: T): T | undefined;
+    /** Record `copy` as the clone of `original` (before copying its fields) */
+    set<T extends object>(original: T, copy: T): void;
+}
+export declare namespace CloneContext {
+    function create(): CloneContext;
+}
+/**
+ * Deep-copy a value: Clone-derived objects, arrays, Maps, Sets, Dates and
+ * plain objects are copied; other class instances are shared
+ */
+export declare function cloneValue<T>(value: T, ctx: CloneContext): T;
@@ -0,0 +1,65 @@
+// js/clone/index.ts
+var CloneContext;
+((CloneContext) => {
+  function create() {
+    const copies = new WeakMap;
+    return {
+      get: (original) => copies.get(original),
+      set: (original, copy) => {
+        copies.set(original, copy);
+      }
+    };
+  }
+  CloneContext.create = create;
+})(CloneContext ||= {});
+function isCloneable(value) {
+  return typeof value.__clone === "function";
+}
+function cloneValue(value, ctx) {
+  if (typeof value !== "object" || value === null)
+    return value;
+  const existing = ctx.get(value);
+  if (existing !== undefined)
+    return existing;
+  if (isCloneable(value))
+    return value.__clone(ctx);
+  if (Array.isArray(value)) {
+    const copy2 = [];
+    ctx.set(value, copy2);
+    for (const item of value)
+      copy2.push(cloneValue(item, ctx));
+    return copy2;
+  }
+  if (value instanceof Date) {
+    const copy2 = new Date(value.getTime());
+    ctx.set(value, copy2);
+    return copy2;
+  }
+  if (value instanceof Map) {
+    const copy2 = new Map;
+    ctx.set(value, copy2);
+    for (const [key, item] of value)
+      copy2.set(cloneValue(key, ctx), cloneValue(item, ctx));
+    return copy2;
+  }
+  if (value instanceof Set) {
+    const copy2 = new Set;
+    ctx.set(value, copy2);
+    for (const item of value)
+      copy2.add(cloneValue(item, ctx));
+    return copy2;
+  }
+  const proto = Object.getPrototypeOf(value);
+  if (proto !== Object.prototype && proto !== null)
+    return value;
+  const copy = Object.create(proto);
+  ctx.set(value, copy);
+  for (const [key, item] of Object.entries(value)) {
+    copy[key] = cloneValue(item, ctx);
+  }
+  return copy;
+}
+export {
+  cloneValue,
+  CloneContext
+};
@@ -0,0 +1,80 @@
+/**
+ * Clone runtime helpers for the macroforge Clone macro.
+ * Like the serde contexts, a CloneContext tracks objects already copied so
+ * shared references and cycles are preserved in the clone.
+ */
+
+export interface CloneContext {
+  /** The copy made for `original` in this clone, if any */
+  get<T extends object>(original: T): T | undefined;
+  /** Record `copy` as the clone of `original` (before copying its fields) */
+  set<T extends object>(original: T, copy: T): void;
+}
+
+export namespace CloneContext {
+  export function create(): CloneContext {
+    const copies = new WeakMap<object, object>();
+    return {
+      get: <T extends object>(original: T) => copies.get(original) as T | undefined,
+      set: (original, copy) => {
+        copies.set(original, copy);
+      },
+    };
+  }
+}
+
+/** Values with a Clone-derived `__clone` */
+interface Cloneable {
+  __clone(ctx: CloneContext): unknown;
+}
+
+function isCloneable(value: object): value is Cloneable {
+  return typeof (value as Cloneable).__clone === "function";
+}
+
+/**
+ * Deep-copy a value: Clone-derived objects, arrays, Maps, Sets, Dates and
+ * plain objects are copied; other class instances are shared
+ */
+export function cloneValue<T>(value: T, ctx: CloneContext): T {
+  if (typeof value !== "object" || value === null) return value;
+
+  const existing = ctx.get(value);
+  if (existing !== undefined) return existing;
+
+  if (isCloneable(value)) return value.__clone(ctx) as T;
+
+  if (Array.isArray(value)) {
+    const copy: unknown[] = [];
+    ctx.set(value, copy as T & object);
+    for (const item of value) copy.push(cloneValue(item, ctx));
+    return copy as T;
+  }
+  if (value instanceof Date) {
+    const copy = new Date(value.getTime());
+    ctx.set(value, copy);
+    return copy;
+  }
+  if (value instanceof Map) {
+    const copy = new Map();
+    ctx.set(value, copy);
+    for (const [key, item] of value) copy.set(cloneValue(key, ctx), cloneValue(item, ctx));
+    return copy as T;
+  }
+  if (value instanceof Set) {
+    const copy = new Set();
+    ctx.set(value, copy);
+    for (const item of value) copy.add(cloneValue(item, ctx));
+    return copy as T;
+  }
+
+  const proto = Object.getPrototypeOf(value);
+  if (proto !== Object.prototype && proto !== null) return value;
+
+  const copy: Record<string, unknown> = Object.create(proto);
+  ctx.set(value, copy as T & object);
+  for (const [key, item] of Object.entries(value)) {
+    copy[key] = cloneValue(item, ctx);
+  }
+  return copy as T;
+}
@@ -0,0 +1,34 @@
+/**
+ * Debug runtime helpers for the macroforge Debug macro.
+ * Used by generated code when a type opts into structured output
+ * (pretty printing, depth limits, cycle detection, toJSONDebug).
+ */
+/** Printed in place of fields marked `@debug(redact)` */
+export declare const REDACTED = "***";
+export interface DebugOptions {
+    /** Multi-line output, one field per line */
+    pretty?: boolean;
+    /** Spaces per nesting level in pretty mode (default 2) */
+    indent?: number;
+    /** Nesting levels to print before abbreviating to `[Name]` (default unlimited) */
+    depth?: number;
+}
+/** `[label, value, redacted]` */
+export type DebugField = [label: string, value: unknown, redacted?: boolean];
+/** Name and fields of a Debug-derived value, as returned by `__debug()` */
+export interface DebugStruct {
+    name: string;
+    fields: DebugField[];
+}
+export interface Debuggable {
+    __debug(): DebugStruct;
+}
+/** Wrap the fields of a value without methods (interfaces, type aliases) */
+export declare function debugStruct(name: string, fields: DebugField[]): Debuggable;
+/** Format any value in Debug style, following nested Debug-derived values */
+export declare function formatDebug(value: unknown, options?: DebugOptions): string;
+/**
+ * Structured form of a Debug-derived value for loggers: a plain,
+ * JSON-safe object tree with redaction, cycle markers and depth limits applied
+ */
+export declare function debugJSON(value: unknown, options?: DebugOptions): unknown;
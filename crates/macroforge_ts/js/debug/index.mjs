@@ -0,0 +1,145 @@
+// js/debug/index.ts
+var REDACTED = "***";
+function debugStruct(name, fields) {
+  return { __debug: () => ({ name, fields }) };
+}
+function isDebuggable(value) {
+  return typeof value === "object" && value !== null && typeof value.__debug === "function";
+}
+function typeName(value) {
+  if (isDebuggable(value))
+    return value.__debug().name;
+  if (Array.isArray(value))
+    return "Array";
+  return value.constructor?.name ?? "Object";
+}
+function formatDebug(value, options = {}) {
+  return format(value, { options, ancestors: [], level: 0 });
+}
+function format(value, walk) {
+  switch (typeof value) {
+    case "string":
+      return JSON.stringify(value);
+    case "bigint":
+      return `${value}n`;
+    case "function":
+      return `[Function ${value.name || "anonymous"}]`;
+    case "object":
+      break;
+    default:
+      return String(value);
+  }
+  if (value === null)
+    return "null";
+  if (value instanceof Date)
+    return value.toISOString();
+  if (value instanceof RegExp)
+    return String(value);
+  if (walk.ancestors.includes(value))
+    return `[Circular ${typeName(value)}]`;
+  const depth = walk.options.depth ?? Infinity;
+  if (walk.level > depth)
+    return `[${typeName(value)}]`;
+  const inner = {
+    options: walk.options,
+    ancestors: [...walk.ancestors, value],
+    level: walk.level + 1
+  };
+  const entries = (items) => items.map(([key, item]) => {
+    const text = format(item, inner);
+    return key === null ? text : `${key}: ${text}`;
+  });
+  if (isDebuggable(value)) {
+    const { name: name2, fields } = value.__debug();
+    const parts = fields.map(([label, item, redacted]) => redacted ? `${label}: ${REDACTED}` : `${label}: ${format(item, inner)}`);
+    return wrap(name2, "{", "}", parts, walk);
+  }
+  if (Array.isArray(value)) {
+    return wrap("", "[", "]", entries(value.map((item) => [null, item])), walk);
+  }
+  if (value instanceof Map) {
+    const parts = [...value].map(([key, item]) => `${format(key, inner)} => ${format(item, inner)}`);
+    return wrap("Map", "{", "}", parts, walk);
+  }
+  if (value instanceof Set) {
+    return wrap("Set", "{", "}", entries([...value].map((item) => [null, item])), walk);
+  }
+  const name = value.constructor && value.constructor !== Object ? value.constructor.name : "";
+  return wrap(name, "{", "}", entries(Object.entries(value)), walk);
+}
+function wrap(name, open, close, parts, walk) {
+  const prefix = name ? `${name} ` : "";
+  if (parts.length === 0)
+    return `${prefix}${open}${close}`;
+  if (!walk.options.pretty) {
+    const pad = open === "[" ? "" : " ";
+    return `${prefix}${open}${pad}${parts.join(", ")}${pad}${close}`;
+  }
+  const unit = " ".repeat(walk.options.indent ?? 2);
+  const outer = unit.repeat(walk.level);
+  const lines = parts.map((part) => `${outer}${unit}${part},`);
+  return `${prefix}${open}
+${lines.join(`
+`)}
+${outer}${close}`;
+}
+function debugJSON(value, options = {}) {
+  return toJSON(value, { options, ancestors: [], level: 0 });
+}
+function toJSON(value, walk) {
+  switch (typeof value) {
+    case "bigint":
+      return `${value}n`;
+    case "function":
+      return `[Function ${value.name || "anonymous"}]`;
+    case "symbol":
+      return String(value);
+    case "undefined":
+      return null;
+    case "object":
+      break;
+    default:
+      return value;
+  }
+  if (value === null)
+    return null;
+  if (value instanceof Date)
+    return value.toISOString();
+  if (value instanceof RegExp)
+    return String(value);
+  if (walk.ancestors.includes(value))
+    return `[Circular ${typeName(value)}]`;
+  const depth = walk.options.depth ?? Infinity;
+  if (walk.level > depth)
+    return `[${typeName(value)}]`;
+  const inner = {
+    options: walk.options,
+    ancestors: [...walk.ancestors, value],
+    level: walk.level + 1
+  };
+  if (isDebuggable(value)) {
+    const out2 = {};
+    for (const [label, item, redacted] of value.__debug().fields) {
+      out2[label] = redacted ? REDACTED : toJSON(item, inner);
+    }
+    return out2;
+  }
+  if (Array.isArray(value))
+    return value.map((item) => toJSON(item, inner));
+  if (value instanceof Map) {
+    return [...value].map(([key, item]) => [toJSON(key, inner), toJSON(item, inner)]);
+  }
+  if (value instanceof Set)
+    return [...value].map((item) => toJSON(item, inner));
+  const out = {};
+  for (const [key, item] of Object.entries(value)) {
+    out[key] = toJSON(item, inner);
+  }
+  return out;
+}
+export {
+  formatDebug,
+  debugStruct,
+  debugJSON,
+  REDACTED
+};
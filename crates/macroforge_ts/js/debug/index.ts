@@ -0,0 +1,187 @@
+/**
+ * Debug runtime helpers for the macroforge Debug macro.
+ * Used by generated code when a type opts into structured output
+ * (pretty printing, depth limits, cycle detection, toJSONDebug).
+ */
+
+/** Printed in place of fields marked `@debug(redact)` */
+export const REDACTED = "***";
+
+export interface DebugOptions {
+  /** Multi-line output, one field per line */
+  pretty?: boolean;
+  /** Spaces per nesting level in pretty mode (default 2) */
+  indent?: number;
+  /** Nesting levels to print before abbreviating to `[Name]` (default unlimited) */
+  depth?: number;
+}
+
+/** `[label, value, redacted]` */
+export type DebugField = [label: string, value: unknown, redacted?: boolean];
+
+/** Name and fields of a Debug-derived value, as returned by `__debug()` */
+export interface DebugStruct {
+  name: string;
+  fields: DebugField[];
+}
+
+export interface Debuggable {
+  __debug(): DebugStruct;
+}
+
+/** Wrap the fields of a value without methods (interfaces, type aliases) */
+export function debugStruct(name: string, fields: DebugField[]): Debuggable {
+  return { __debug: () => ({ name, fields }) };
+}
+
+function isDebuggable(value: unknown): value is Debuggable {
+  return (
+    typeof value === "object" &&
+    value !== null &&
+    typeof (value as Debuggable).__debug === "function"
+  );
+}
+
+function typeName(value: object): string {
+  if (isDebuggable(value)) return value.__debug().name;
+  if (Array.isArray(value)) return "Array";
+  return value.constructor?.name ?? "Object";
+}
+
+interface Walk {
+  options: DebugOptions;
+  /** Objects on the path from the root, for cycle detection */
+  ancestors: object[];
+  level: number;
+}
+
+/** Format any value in Debug style, following nested Debug-derived values */
+export function formatDebug(value: unknown, options: DebugOptions = {}): string {
+  return format(value, { options, ancestors: [], level: 0 });
+}
+
+function format(value: unknown, walk: Walk): string {
+  switch (typeof value) {
+    case "string":
+      return JSON.stringify(value);
+    case "bigint":
+      return `${value}n`;
+    case "function":
+      return `[Function ${value.name || "anonymous"}]`;
+    case "object":
+      break;
+    default:
+      return String(value);
+  }
+  if (value === null) return "null";
+  if (value instanceof Date) return value.toISOString();
+  if (value instanceof RegExp) return String(value);
+
+  if (walk.ancestors.includes(value)) return `[Circular ${typeName(value)}]`;
+  const depth = walk.options.depth ?? Infinity;
+  if (walk.level > depth) return `[${typeName(value)}]`;
+
+  const inner: Walk = {
+    options: walk.options,
+    ancestors: [...walk.ancestors, value],
+    level: walk.level + 1,
+  };
+  const entries = (items: Array<[string | null, unknown]>): string[] =>
+    items.map(([key, item]) => {
+      const text = format(item, inner);
+      return key === null ? text : `${key}: ${text}`;
+    });
+
+  if (isDebuggable(value)) {
+    const { name, fields } = value.__debug();
+    const parts = fields.map(([label, item, redacted]) =>
+      redacted ? `${label}: ${REDACTED}` : `${label}: ${format(item, inner)}`,
+    );
+    return wrap(name, "{", "}", parts, walk);
+  }
+  if (Array.isArray(value)) {
+    return wrap("", "[", "]", entries(value.map((item) => [null, item])), walk);
+  }
+  if (value instanceof Map) {
+    const parts = [...value].map(
+      ([key, item]) => `${format(key, inner)} => ${format(item, inner)}`,
+    );
+    return wrap("Map", "{", "}", parts, walk);
+  }
+  if (value instanceof Set) {
+    return wrap("Set", "{", "}", entries([...value].map((item) => [null, item])), walk);
+  }
+
+  const name = value.constructor && value.constructor !== Object ? value.constructor.name : "";
+  return wrap(name, "{", "}", entries(Object.entries(value)), walk);
+}
+
+function wrap(name: string, open: string, close: string, parts: string[], walk: Walk): string {
+  const prefix = name ? `${name} ` : "";
+  if (parts.length === 0) return `${prefix}${open}${close}`;
+  if (!walk.options.pretty) {
+    const pad = open === "[" ? "" : " ";
+    return `${prefix}${open}${pad}${parts.join(", ")}${pad}${close}`;
+  }
+  const unit = " ".repeat(walk.options.indent ?? 2);
+  const outer = unit.repeat(walk.level);
+  const lines = parts.map((part) => `${outer}${unit}${part},`);
+  return `${prefix}${open}\n${lines.join("\n")}\n${outer}${close}`;
+}
+
+/**
+ * Structured form of a Debug-derived value for loggers: a plain,
+ * JSON-safe object tree with redaction, cycle markers and depth limits applied
+ */
+export function debugJSON(value: unknown, options: DebugOptions = {}): unknown {
+  return toJSON(value, { options, ancestors: [], level: 0 });
+}
+
+function toJSON(value: unknown, walk: Walk): unknown {
+  switch (typeof value) {
+    case "bigint":
+      return `${value}n`;
+    case "function":
+      return `[Function ${value.name || "anonymous"}]`;
+    case "symbol":
+      return String(value);
+    case "undefined":
+      return null;
+    case "object":
+      break;
+    default:
+      return value;
+  }
+  if (value === null) return null;
+  if (value instanceof Date) return value.toISOString();
+  if (value instanceof RegExp) return String(value);
+
+  if (walk.ancestors.includes(value)) return `[Circular ${typeName(value)}]`;
+  const depth = walk.options.depth ?? Infinity;
+  if (walk.level > depth) return `[${typeName(value)}]`;
+
+  const inner: Walk = {
+    options: walk.options,
+    ancestors: [...walk.ancestors, value],
+    level: walk.level + 1,
+  };
+
+  if (isDebuggable(value)) {
+    const out: Record<string, unknown> = {};
+    for (const [label, item, redacted] of value.__debug().fields) {
+      out[label] = redacted ? REDACTED : toJSON(item, inner);
+    }
+    return out;
+  }
+  if (Array.isArray(value)) return value.map((item) => toJSON(item, inner));
+  if (value instanceof Map) {
+    return [...value].map(([key, item]) => [toJSON(key, inner), toJSON(item, inner)]);
+  }
+  if (value instanceof Set) return [...value].map((item) => toJSON(item, inner));
+
+  const out: Record<string, unknown> = {};
+  for (const [key, item] of Object.entries(value)) {
+    out[key] = toJSON(item, inner);
+  }
+  return out;
+}
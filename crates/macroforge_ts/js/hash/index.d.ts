@@ -0,0 +1,28 @@
+/**
+ * Hash runtime helpers for the macroforge Hash macro.
+ * Used by generated code when a type selects a hash algorithm, so nested
+ * values stream into a single hasher through `hashInto`.
+ */
+/**
+ * - `java`: the 31-multiplier hash used by the default `hashCode()`
+ * - `fnv1a`: 32-bit FNV-1a over the bytes of each written word
+ * - `xxhash32`: xxHash32 rounds and avalanche over 32-bit words
+ */
+export type HashAlgorithm = "java" | "fnv1a" | "xxhash32";
+export interface Hasher {
+    readonly algorithm: HashAlgorithm;
+    /** Mix in a 32-bit integer */
+    writeInt(value: number): void;
+    /** Mix in each UTF-16 code unit of a string, then its length */
+    writeString(value: string): void;
+    /** The hash of everything written so far, as a signed 32-bit integer */
+    finish(): number;
+}
+/** Values whose fields can be streamed into a hasher */
+export interface HashInto {
+    hashInto(hasher: Hasher): void;
+}
+/** Create an empty hasher for the given algorithm */
+export declare function createHasher(algorithm?: HashAlgorithm): Hasher;
+/** Stream any value into a hasher, following nested `hashInto` implementations */
+export declare function hashValue(hasher: Hasher, value: unknown): void;
@@ -0,0 +1,172 @@
+// js/hash/index.ts
+var FNV_OFFSET = 2166136261;
+var FNV_PRIME = 16777619;
+var XX_PRIME2 = 2246822519;
+var XX_PRIME3 = 3266489917;
+var XX_PRIME4 = 668265263;
+var XX_PRIME5 = 374761393;
+
+class JavaHasher {
+  algorithm = "java";
+  hash = 17;
+  writeInt(value) {
+    this.hash = Math.imul(this.hash, 31) + (value | 0) | 0;
+  }
+  writeString(value) {
+    for (let i = 0;i < value.length; i++) {
+      this.hash = Math.imul(this.hash, 31) + value.charCodeAt(i) | 0;
+    }
+    this.writeInt(value.length);
+  }
+  finish() {
+    return this.hash;
+  }
+}
+
+class Fnv1aHasher {
+  algorithm = "fnv1a";
+  hash = FNV_OFFSET;
+  byte(value) {
+    this.hash = Math.imul(this.hash ^ value & 255, FNV_PRIME);
+  }
+  writeInt(value) {
+    this.byte(value);
+    this.byte(value >>> 8);
+    this.byte(value >>> 16);
+    this.byte(value >>> 24);
+  }
+  writeString(value) {
+    for (let i = 0;i < value.length; i++) {
+      const unit = value.charCodeAt(i);
+      this.byte(unit);
+      this.byte(unit >>> 8);
+    }
+    this.writeInt(value.length);
+  }
+  finish() {
+    return this.hash | 0;
+  }
+}
+
+class XxHash32Hasher {
+  algorithm = "xxhash32";
+  hash = XX_PRIME5;
+  length = 0;
+  writeInt(value) {
+    const mixed = this.hash + Math.imul(value | 0, XX_PRIME3) | 0;
+    this.hash = Math.imul(mixed << 17 | mixed >>> 15, XX_PRIME4);
+    this.length += 4;
+  }
+  writeString(value) {
+    for (let i = 0;i < value.length; i += 2) {
+      const low = value.charCodeAt(i);
+      const high = i + 1 < value.length ? value.charCodeAt(i + 1) : 0;
+      this.writeInt(low | high << 16);
+    }
+    this.writeInt(value.length);
+  }
+  finish() {
+    let h = this.hash + this.length | 0;
+    h ^= h >>> 15;
+    h = Math.imul(h, XX_PRIME2);
+    h ^= h >>> 13;
+    h = Math.imul(h, XX_PRIME3);
+    h ^= h >>> 16;
+    return h | 0;
+  }
+}
+function createHasher(algorithm = "java") {
+  switch (algorithm) {
+    case "fnv1a":
+      return new Fnv1aHasher;
+    case "xxhash32":
+      return new XxHash32Hasher;
+    default:
+      return new JavaHasher;
+  }
+}
+function hasHashInto(value) {
+  return typeof value.hashInto === "function";
+}
+function writeUnordered(hasher, items, write) {
+  let sum = 0;
+  let count = 0;
+  for (const item of items) {
+    const itemHasher = createHasher(hasher.algorithm);
+    write(itemHasher, item);
+    sum = sum + itemHasher.finish() | 0;
+    count++;
+  }
+  hasher.writeInt(count);
+  hasher.writeInt(sum);
+}
+function hashValue(hasher, value) {
+  switch (typeof value) {
+    case "undefined":
+      hasher.writeInt(0);
+      return;
+    case "boolean":
+      hasher.writeInt(value ? 1231 : 1237);
+      return;
+    case "number":
+      if (Number.isInteger(value) && value === (value | 0)) {
+        hasher.writeInt(value);
+      } else {
+        hasher.writeString(String(value));
+      }
+      return;
+    case "bigint":
+    case "symbol":
+    case "function":
+      hasher.writeString(String(value));
+      return;
+    case "string":
+      hasher.writeString(value);
+      return;
+  }
+  if (value === null) {
+    hasher.writeInt(0);
+    return;
+  }
+  if (hasHashInto(value)) {
+    value.hashInto(hasher);
+    return;
+  }
+  if (typeof value.hashCode === "function") {
+    hasher.writeInt(value.hashCode());
+    return;
+  }
+  if (value instanceof Date) {
+    const time = value.getTime();
+    hasher.writeInt(Math.floor(time / 4294967296));
+    hasher.writeInt(time | 0);
+    return;
+  }
+  if (Array.isArray(value)) {
+    hasher.writeInt(value.length);
+    for (const item of value)
+      hashValue(hasher, item);
+    return;
+  }
+  if (value instanceof Map) {
+    writeUnordered(hasher, value, (h, [key, item]) => {
+      hashValue(h, key);
+      hashValue(h, item);
+    });
+    return;
+  }
+  if (value instanceof Set) {
+    writeUnordered(hasher, value, hashValue);
+    return;
+  }
+  const keys = Object.keys(value).sort();
+  hasher.writeInt(keys.length);
+  for (const key of keys) {
+    hasher.writeString(key);
+    hashValue(hasher, value[key]);
+  }
+}
+export {
+  hashValue,
+  createHasher
+};
@@ -0,0 +1,215 @@
+/**
+ * Hash runtime helpers for the macroforge Hash macro.
+ * Used by generated code when a type selects a hash algorithm, so nested
+ * values stream into a single hasher through `hashInto`.
+ */
+
+/**
+ * - `java`: the 31-multiplier hash used by the default `hashCode()`
+ * - `fnv1a`: 32-bit FNV-1a over the bytes of each written word
+ * - `xxhash32`: xxHash32 rounds and avalanche over 32-bit words
+ */
+export type HashAlgorithm = "java" | "fnv1a" | "xxhash32";
+
+export interface Hasher {
+  readonly algorithm: HashAlgorithm;
+  /** Mix in a 32-bit integer */
+  writeInt(value: number): void;
+  /** Mix in each UTF-16 code unit of a string, then its length */
+  writeString(value: string): void;
+  /** The hash of everything written so far, as a signed 32-bit integer */
+  finish(): number;
+}
+
+/** Values whose fields can be streamed into a hasher */
+export interface HashInto {
+  hashInto(hasher: Hasher): void;
+}
+
+const FNV_OFFSET = 0x811c9dc5;
+const FNV_PRIME = 0x01000193;
+
+const XX_PRIME2 = 0x85ebca77;
+const XX_PRIME3 = 0xc2b2ae3d;
+const XX_PRIME4 = 0x27d4eb2f;
+const XX_PRIME5 = 0x165667b1;
+
+class JavaHasher implements Hasher {
+  readonly algorithm = "java";
+  private hash = 17;
+
+  writeInt(value: number): void {
+    this.hash = (Math.imul(this.hash, 31) + (value | 0)) | 0;
+  }
+
+  writeString(value: string): void {
+    for (let i = 0; i < value.length; i++) {
+      this.hash = (Math.imul(this.hash, 31) + value.charCodeAt(i)) | 0;
+    }
+    this.writeInt(value.length);
+  }
+
+  finish(): number {
+    return this.hash;
+  }
+}
+
+class Fnv1aHasher implements Hasher {
+  readonly algorithm = "fnv1a";
+  private hash = FNV_OFFSET;
+
+  private byte(value: number): void {
+    this.hash = Math.imul(this.hash ^ (value & 0xff), FNV_PRIME);
+  }
+
+  writeInt(value: number): void {
+    this.byte(value);
+    this.byte(value >>> 8);
+    this.byte(value >>> 16);
+    this.byte(value >>> 24);
+  }
+
+  writeString(value: string): void {
+    for (let i = 0; i < value.length; i++) {
+      const unit = value.charCodeAt(i);
+      this.byte(unit);
+      this.byte(unit >>> 8);
+    }
+    this.writeInt(value.length);
+  }
+
+  finish(): number {
+    return this.hash | 0;
+  }
+}
+
+class XxHash32Hasher implements Hasher {
+  readonly algorithm = "xxhash32";
+  private hash = XX_PRIME5;
+  private length = 0;
+
+  writeInt(value: number): void {
+    const mixed = (this.hash + Math.imul(value | 0, XX_PRIME3)) | 0;
+    this.hash = Math.imul((mixed << 17) | (mixed >>> 15), XX_PRIME4);
+    this.length += 4;
+  }
+
+  writeString(value: string): void {
+    for (let i = 0; i < value.length; i += 2) {
+      const low = value.charCodeAt(i);
+      const high = i + 1 < value.length ? value.charCodeAt(i + 1) : 0;
+      this.writeInt(low | (high << 16));
+    }
+    this.writeInt(value.length);
+  }
+
+  finish(): number {
+    let h = (this.hash + this.length) | 0;
+    h ^= h >>> 15;
+    h = Math.imul(h, XX_PRIME2);
+    h ^= h >>> 13;
+    h = Math.imul(h, XX_PRIME3);
+    h ^= h >>> 16;
+    return h | 0;
+  }
+}
+
+/** Create an empty hasher for the given algorithm */
+export function createHasher(algorithm: HashAlgorithm = "java"): Hasher {
+  switch (algorithm) {
+    case "fnv1a":
+      return new Fnv1aHasher();
+    case "xxhash32":
+      return new XxHash32Hasher();
+    default:
+      return new JavaHasher();
+  }
+}
+
+function hasHashInto(value: object): value is HashInto {
+  return typeof (value as HashInto).hashInto === "function";
+}
+
+/**
+ * Mix the hashes of `items` so the result does not depend on their order:
+ * each item is hashed on its own and the hashes are summed
+ */
+function writeUnordered<T>(hasher: Hasher, items: Iterable<T>, write: (h: Hasher, item: T) => void): void {
+  let sum = 0;
+  let count = 0;
+  for (const item of items) {
+    const itemHasher = createHasher(hasher.algorithm);
+    write(itemHasher, item);
+    sum = (sum + itemHasher.finish()) | 0;
+    count++;
+  }
+  hasher.writeInt(count);
+  hasher.writeInt(sum);
+}
+
+/** Stream any value into a hasher, following nested `hashInto` implementations */
+export function hashValue(hasher: Hasher, value: unknown): void {
+  switch (typeof value) {
+    case "undefined":
+      hasher.writeInt(0);
+      return;
+    case "boolean":
+      hasher.writeInt(value ? 1231 : 1237);
+      return;
+    case "number":
+      if (Number.isInteger(value) && value === (value | 0)) {
+        hasher.writeInt(value);
+      } else {
+        hasher.writeString(String(value));
+      }
+      return;
+    case "bigint":
+    case "symbol":
+    case "function":
+      hasher.writeString(String(value));
+      return;
+    case "string":
+      hasher.writeString(value);
+      return;
+  }
+  if (value === null) {
+    hasher.writeInt(0);
+    return;
+  }
+  if (hasHashInto(value)) {
+    value.hashInto(hasher);
+    return;
+  }
+  if (typeof (value as { hashCode?: unknown }).hashCode === "function") {
+    hasher.writeInt((value as { hashCode(): number }).hashCode());
+    return;
+  }
+  if (value instanceof Date) {
+    const time = value.getTime();
+    hasher.writeInt(Math.floor(time / 0x100000000));
+    hasher.writeInt(time | 0);
+    return;
+  }
+  if (Array.isArray(value)) {
+    hasher.writeInt(value.length);
+    for (const item of value) hashValue(hasher, item);
+    return;
+  }
+  if (value instanceof Map) {
+    writeUnordered(hasher, value, (h, [key, item]) => {
+      hashValue(h, key);
+      hashValue(h, item);
+    });
+    return;
+  }
+  if (value instanceof Set) {
+    writeUnordered(hasher, value, hashValue);
+    return;
+  }
+  const keys = Object.keys(value).sort();
+  hasher.writeInt(keys.length);
+  for (const key of keys) {
+    hasher.writeString(key);
+    hashValue(hasher, (value as Record<string, unknown>)[key]);
+  }
+}
@@ -0,0 +1,75 @@
+/**
+ * Mock runtime helpers for the macroforge Mock macro.
+ * Generated `arbitrary(seed)` factories draw every value from one seeded
+ * `MockRng`, so the same seed always yields the same instance.
+ */
+/** Seeded source of randomness shared by nested `arbitrary()` calls */
+export interface MockRng {
+    /** Float in `[0, 1)` */
+    next(): number;
+    /** Integer in `[min, max]` */
+    int(min: number, max: number): number;
+    bool(): boolean;
+    pick<T>(items: readonly T[]): T;
+}
+/** Generator for one value, e.g. `User.arbitrary` or a `@mock({ with })` function */
+export type Arbitrary<T> = (rng: MockRng) => T;
+/** An instance that fails exactly one validator, from `arbitraryInvalid()` */
+export interface MockInvalid<T> {
+    /** Field key as reported in deserialization errors */
+    field: string;
+    /** Error code of the violated validator, e.g. `"minLength"` */
+    validator: string;
+    value: T;
+}
+export interface StringConstraints {
+    minLength?: number;
+    maxLength?: number;
+    format?: "email" | "url" | "uuid";
+    case?: "lower" | "upper" | "capitalized" | "uncapitalized";
+    startsWith?: string;
+    endsWith?: string;
+    includes?: string;
+}
+/** Bounds are combined with `int` and `multipleOf` */
+export interface NumberConstraints {
+    gt?: number;
+    gte?: number;
+    lt?: number;
+    lte?: number;
+    int?: boolean;
+    multipleOf?: number;
+}
+export interface BigIntConstraints {
+    gt?: bigint;
+    gte?: bigint;
+    lt?: bigint;
+    lte?: bigint;
+}
+/** Bounds are date strings, as written in the date validators */
+export interface DateConstraints {
+    gt?: string;
+    gte?: string;
+    lt?: string;
+    lte?: string;
+}
+export interface ItemsConstraints {
+    minItems?: number;
+    maxItems?: number;
+}
+export declare namespace Mock {
+    /** A `MockRng` for `seed` (mulberry32); an existing `MockRng` is passed through */
+    function seeded(seed?: number | MockRng): MockRng;
+    function string(rng: MockRng, constraints?: StringConstraints): string;
+    function number(rng: MockRng, constraints?: NumberConstraints): number;
+    function bigint(rng: MockRng, constraints?: BigIntConstraints): bigint;
+    function date(rng: MockRng, constraints?: DateConstraints): Date;
+    function bytes(rng: MockRng): Uint8Array;
+    function array<T>(rng: MockRng, item: Arbitrary<T>, constraints?: ItemsConstraints): T[];
+    /** Distinct items; stops short of the count if `item` keeps repeating itself */
+    function set<T>(rng: MockRng, item: Arbitrary<T>, constraints?: ItemsConstraints): Set<T>;
+    function map<K, V>(rng: MockRng, key: Arbitrary<K>, value: Arbitrary<V>, constraints?: ItemsConstraints): Map<K, V>;
+    function record<V>(rng: MockRng, value: Arbitrary<V>, constraints?: ItemsConstraints): Record<string, V>;
+    /** A value from one of the member generators, chosen at random */
+    function union<M extends readonly Arbitrary<unknown>[]>(rng: MockRng, members: M): ReturnType<M[number]>;
+}
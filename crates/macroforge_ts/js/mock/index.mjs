@@ -0,0 +1,190 @@
+// js/mock/index.ts
+var LETTERS = "abcdefghijklmnopqrstuvwxyz";
+var HEX = "0123456789abcdef";
+var DEFAULT_SPAN = 1000;
+var DAY_MS = 86400000;
+var DEFAULT_DATE_MIN = Date.UTC(2020, 0, 1);
+var DEFAULT_DATE_MAX = Date.UTC(2030, 0, 1);
+function letters(rng, count) {
+  let result = "";
+  for (let i = 0;i < count; i++) {
+    result += LETTERS[rng.int(0, LETTERS.length - 1)];
+  }
+  return result;
+}
+function uuid(rng) {
+  let result = "";
+  for (let i = 0;i < 32; i++) {
+    if (i === 12) {
+      result += "4";
+    } else if (i === 16) {
+      result += HEX[rng.int(8, 11)];
+    } else {
+      result += HEX[rng.int(0, 15)];
+    }
+    if (i === 7 || i === 11 || i === 15 || i === 19) {
+      result += "-";
+    }
+  }
+  return result;
+}
+function split(rng, count) {
+  const first = rng.int(1, Math.max(1, count - 1));
+  return [letters(rng, first), letters(rng, Math.max(1, count - first))];
+}
+function itemCount(rng, constraints) {
+  const min = constraints.minItems ?? 0;
+  return rng.int(min, Math.max(min, constraints.maxItems ?? min + 3));
+}
+function applyCase(value, kind) {
+  switch (kind) {
+    case "lower":
+      return value.toLowerCase();
+    case "upper":
+      return value.toUpperCase();
+    case "capitalized":
+      return value.charAt(0).toUpperCase() + value.slice(1);
+    case "uncapitalized":
+      return value.charAt(0).toLowerCase() + value.slice(1);
+    default:
+      return value;
+  }
+}
+var Mock;
+((Mock) => {
+  function seeded(seed) {
+    if (typeof seed === "object") {
+      return seed;
+    }
+    let state = (seed ?? Math.floor(Math.random() * 4294967296)) >>> 0;
+    const next = () => {
+      state = state + 1831565813 >>> 0;
+      let t = state;
+      t = Math.imul(t ^ t >>> 15, t | 1);
+      t ^= t + Math.imul(t ^ t >>> 7, t | 61);
+      return ((t ^ t >>> 14) >>> 0) / 4294967296;
+    };
+    return {
+      next,
+      int: (min, max) => min + Math.floor(next() * (max - min + 1)),
+      bool: () => next() < 0.5,
+      pick: (items) => items[Math.floor(next() * items.length)]
+    };
+  }
+  Mock.seeded = seeded;
+  function string(rng, constraints = {}) {
+    const prefix = constraints.startsWith ?? "";
+    const suffix = constraints.endsWith ?? "";
+    const infix = constraints.includes ?? "";
+    if (constraints.format === "uuid") {
+      return applyCase(uuid(rng), constraints.case);
+    }
+    const overhead = prefix.length + infix.length + suffix.length + (constraints.format === "email" ? 5 : constraints.format === "url" ? 13 : 0);
+    const minLetters = constraints.format ? 2 : 0;
+    const min = Math.max(constraints.minLength ?? 0, overhead + minLetters);
+    const max = Math.max(min, constraints.maxLength ?? min + 12);
+    const count = rng.int(min, max) - overhead;
+    let core;
+    if (constraints.format === "email") {
+      const [local, domain] = split(rng, count);
+      core = `${local}@${domain}.com`;
+    } else if (constraints.format === "url") {
+      const [host, path] = split(rng, count);
+      core = `https://${host}.com/${path}`;
+    } else {
+      core = letters(rng, count);
+    }
+    return applyCase(prefix + core + infix + suffix, constraints.case);
+  }
+  Mock.string = string;
+  function number(rng, constraints = {}) {
+    const lowerExclusive = constraints.gte === undefined && constraints.gt !== undefined;
+    const upperExclusive = constraints.lte === undefined && constraints.lt !== undefined;
+    let lo = constraints.gte ?? constraints.gt;
+    let hi = constraints.lte ?? constraints.lt;
+    lo ??= hi === undefined ? -DEFAULT_SPAN : hi - DEFAULT_SPAN;
+    hi ??= lo + DEFAULT_SPAN;
+    const step = constraints.multipleOf ?? (constraints.int ? 1 : undefined);
+    if (step !== undefined && step > 0) {
+      let first = Math.ceil(lo / step);
+      let last = Math.floor(hi / step);
+      if (lowerExclusive && first * step <= lo) {
+        first++;
+      }
+      if (upperExclusive && last * step >= hi) {
+        last--;
+      }
+      return rng.int(first, Math.max(first, last)) * step + 0;
+    }
+    const value = Math.round((lo + rng.next() * (hi - lo)) * 100) / 100;
+    const inRange = (lowerExclusive ? value > lo : value >= lo) && (upperExclusive ? value < hi : value <= hi);
+    return inRange ? value : (lo + hi) / 2;
+  }
+  Mock.number = number;
+  function bigint(rng, constraints = {}) {
+    const span = BigInt(DEFAULT_SPAN);
+    let lo = constraints.gte ?? (constraints.gt !== undefined ? constraints.gt + 1n : undefined);
+    let hi = constraints.lte ?? (constraints.lt !== undefined ? constraints.lt - 1n : undefined);
+    lo ??= hi === undefined ? -span : hi - span;
+    hi ??= lo + span;
+    if (hi <= lo) {
+      return lo;
+    }
+    const offset = BigInt(Math.floor(rng.next() * Number(hi - lo + 1n)));
+    return offset > hi - lo ? hi : lo + offset;
+  }
+  Mock.bigint = bigint;
+  function date(rng, constraints = {}) {
+    const time = (date2) => date2 === undefined ? undefined : new Date(date2).getTime();
+    let lo = time(constraints.gte) ?? (constraints.gt !== undefined ? time(constraints.gt) + 1 : undefined);
+    let hi = time(constraints.lte) ?? (constraints.lt !== undefined ? time(constraints.lt) - 1 : undefined);
+    if (lo === undefined && hi === undefined) {
+      lo = DEFAULT_DATE_MIN;
+      hi = DEFAULT_DATE_MAX;
+    }
+    lo ??= hi - 365 * DAY_MS;
+    hi ??= lo + 365 * DAY_MS;
+    return new Date(lo + Math.floor(rng.next() * Math.max(0, hi - lo + 1)));
+  }
+  Mock.date = date;
+  function bytes(rng) {
+    const result = new Uint8Array(rng.int(0, 16));
+    for (let i = 0;i < result.length; i++) {
+      result[i] = rng.int(0, 255);
+    }
+    return result;
+  }
+  Mock.bytes = bytes;
+  function array(rng, item, constraints = {}) {
+    return Array.from({ length: itemCount(rng, constraints) }, () => item(rng));
+  }
+  Mock.array = array;
+  function set(rng, item, constraints = {}) {
+    const target = itemCount(rng, constraints);
+    const result = new Set;
+    for (let attempt = 0;result.size < target && attempt < target * 10; attempt++) {
+      result.add(item(rng));
+    }
+    return result;
+  }
+  Mock.set = set;
+  function map(rng, key, value, constraints = {}) {
+    return new Map(Array.from(set(rng, key, constraints), (k) => [k, value(rng)]));
+  }
+  Mock.map = map;
+  function record(rng, value, constraints = {}) {
+    const result = {};
+    for (const key of set(rng, () => letters(rng, rng.int(3, 8)), constraints)) {
+      result[key] = value(rng);
+    }
+    return result;
+  }
+  Mock.record = record;
+  function union(rng, members) {
+    return rng.pick(members)(rng);
+  }
+  Mock.union = union;
+})(Mock ||= {});
+export {
+  Mock
+};
@@ -0,0 +1,279 @@
+/**
+ * Mock runtime helpers for the macroforge Mock macro.
+ * Generated `arbitrary(seed)` factories draw every value from one seeded
+ * `MockRng`, so the same seed always yields the same instance.
+ */
+
+/** Seeded source of randomness shared by nested `arbitrary()` calls */
+export interface MockRng {
+  /** Float in `[0, 1)` */
+  next(): number;
+  /** Integer in `[min, max]` */
+  int(min: number, max: number): number;
+  bool(): boolean;
+  pick<T>(items: readonly T[]): T;
+}
+
+/** Generator for one value, e.g. `User.arbitrary` or a `@mock({ with })` function */
+export type Arbitrary<T> = (rng: MockRng) => T;
+
+/** An instance that fails exactly one validator, from `arbitraryInvalid()` */
+export interface MockInvalid<T> {
+  /** Field key as reported in deserialization errors */
+  field: string;
+  /** Error code of the violated validator, e.g. `"minLength"` */
+  validator: string;
+  value: T;
+}
+
+export interface StringConstraints {
+  minLength?: number;
+  maxLength?: number;
+  format?: "email" | "url" | "uuid";
+  case?: "lower" | "upper" | "capitalized" | "uncapitalized";
+  startsWith?: string;
+  endsWith?: string;
+  includes?: string;
+}
+
+/** Bounds are combined with `int` and `multipleOf` */
+export interface NumberConstraints {
+  gt?: number;
+  gte?: number;
+  lt?: number;
+  lte?: number;
+  int?: boolean;
+  multipleOf?: number;
+}
+
+export interface BigIntConstraints {
+  gt?: bigint;
+  gte?: bigint;
+  lt?: bigint;
+  lte?: bigint;
+}
+
+/** Bounds are date strings, as written in the date validators */
+export interface DateConstraints {
+  gt?: string;
+  gte?: string;
+  lt?: string;
+  lte?: string;
+}
+
+export interface ItemsConstraints {
+  minItems?: number;
+  maxItems?: number;
+}
+
+const LETTERS = "abcdefghijklmnopqrstuvwxyz";
+const HEX = "0123456789abcdef";
+/** Width of the range used when a bound is missing */
+const DEFAULT_SPAN = 1000;
+const DAY_MS = 86_400_000;
+const DEFAULT_DATE_MIN = Date.UTC(2020, 0, 1);
+const DEFAULT_DATE_MAX = Date.UTC(2030, 0, 1);
+
+function letters(rng: MockRng, count: number): string {
+  let result = "";
+  for (let i = 0; i < count; i++) {
+    result += LETTERS[rng.int(0, LETTERS.length - 1)];
+  }
+  return result;
+}
+
+function uuid(rng: MockRng): string {
+  let result = "";
+  for (let i = 0; i < 32; i++) {
+    if (i === 12) {
+      result += "4";
+    } else if (i === 16) {
+      result += HEX[rng.int(8, 11)];
+    } else {
+      result += HEX[rng.int(0, 15)];
+    }
+    if (i === 7 || i === 11 || i === 15 || i === 19) {
+      result += "-";
+    }
+  }
+  return result;
+}
+
+/** Split `count` letters into two non-empty parts */
+function split(rng: MockRng, count: number): [string, string] {
+  const first = rng.int(1, Math.max(1, count - 1));
+  return [letters(rng, first), letters(rng, Math.max(1, count - first))];
+}
+
+function itemCount(rng: MockRng, constraints: ItemsConstraints): number {
+  const min = constraints.minItems ?? 0;
+  return rng.int(min, Math.max(min, constraints.maxItems ?? min + 3));
+}
+
+function applyCase(value: string, kind: StringConstraints["case"]): string {
+  switch (kind) {
+    case "lower":
+      return value.toLowerCase();
+    case "upper":
+      return value.toUpperCase();
+    case "capitalized":
+      return value.charAt(0).toUpperCase() + value.slice(1);
+    case "uncapitalized":
+      return value.charAt(0).toLowerCase() + value.slice(1);
+    default:
+      return value;
+  }
+}
+
+export namespace Mock {
+  /** A `MockRng` for `seed` (mulberry32); an existing `MockRng` is passed through */
+  export function seeded(seed?: number | MockRng): MockRng {
+    if (typeof seed === "object") {
+      return seed;
+    }
+    let state = (seed ?? Math.floor(Math.random() * 0x100000000)) >>> 0;
+    const next = (): number => {
+      state = (state + 0x6d2b79f5) >>> 0;
+      let t = state;
+      t = Math.imul(t ^ (t >>> 15), t | 1);
+      t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+      return ((t ^ (t >>> 14)) >>> 0) / 0x100000000;
+    };
+    return {
+      next,
+      int: (min, max) => min + Math.floor(next() * (max - min + 1)),
+      bool: () => next() < 0.5,
+      pick: (items) => items[Math.floor(next() * items.length)],
+    };
+  }
+
+  export function string(rng: MockRng, constraints: StringConstraints = {}): string {
+    const prefix = constraints.startsWith ?? "";
+    const suffix = constraints.endsWith ?? "";
+    const infix = constraints.includes ?? "";
+    if (constraints.format === "uuid") {
+      return applyCase(uuid(rng), constraints.case);
+    }
+
+    // Characters the format adds around the random letters
+    const overhead =
+      prefix.length +
+      infix.length +
+      suffix.length +
+      (constraints.format === "email" ? 5 : constraints.format === "url" ? 13 : 0);
+    const minLetters = constraints.format ? 2 : 0;
+    const min = Math.max(constraints.minLength ?? 0, overhead + minLetters);
+    const max = Math.max(min, constraints.maxLength ?? min + 12);
+    const count = rng.int(min, max) - overhead;
+
+    let core: string;
+    if (constraints.format === "email") {
+      const [local, domain] = split(rng, count);
+      core = `${local}@${domain}.com`;
+    } else if (constraints.format === "url") {
+      const [host, path] = split(rng, count);
+      core = `https://${host}.com/${path}`;
+    } else {
+      core = letters(rng, count);
+    }
+    return applyCase(prefix + core + infix + suffix, constraints.case);
+  }
+
+  export function number(rng: MockRng, constraints: NumberConstraints = {}): number {
+    const lowerExclusive = constraints.gte === undefined && constraints.gt !== undefined;
+    const upperExclusive = constraints.lte === undefined && constraints.lt !== undefined;
+    let lo = constraints.gte ?? constraints.gt;
+    let hi = constraints.lte ?? constraints.lt;
+    lo ??= hi === undefined ? -DEFAULT_SPAN : hi - DEFAULT_SPAN;
+    hi ??= lo + DEFAULT_SPAN;
+
+    const step = constraints.multipleOf ?? (constraints.int ? 1 : undefined);
+    if (step !== undefined && step > 0) {
+      let first = Math.ceil(lo / step);
+      let last = Math.floor(hi / step);
+      if (lowerExclusive && first * step <= lo) {
+        first++;
+      }
+      if (upperExclusive && last * step >= hi) {
+        last--;
+      }
+      // `0 * step` would be `-0` for negative multiples
+      return rng.int(first, Math.max(first, last)) * step + 0;
+    }
+
+    const value = Math.round((lo + rng.next() * (hi - lo)) * 100) / 100;
+    const inRange =
+      (lowerExclusive ? value > lo : value >= lo) && (upperExclusive ? value < hi : value <= hi);
+    return inRange ? value : (lo + hi) / 2;
+  }
+
+  export function bigint(rng: MockRng, constraints: BigIntConstraints = {}): bigint {
+    const span = BigInt(DEFAULT_SPAN);
+    let lo = constraints.gte ?? (constraints.gt !== undefined ? constraints.gt + 1n : undefined);
+    let hi = constraints.lte ?? (constraints.lt !== undefined ? constraints.lt - 1n : undefined);
+    lo ??= hi === undefined ? -span : hi - span;
+    hi ??= lo + span;
+    if (hi <= lo) {
+      return lo;
+    }
+    const offset = BigInt(Math.floor(rng.next() * Number(hi - lo + 1n)));
+    return offset > hi - lo ? hi : lo + offset;
+  }
+
+  export function date(rng: MockRng, constraints: DateConstraints = {}): Date {
+    const time = (date: string | undefined) => (date === undefined ? undefined : new Date(date).getTime());
+    let lo = time(constraints.gte) ?? (constraints.gt !== undefined ? time(constraints.gt)! + 1 : undefined);
+    let hi = time(constraints.lte) ?? (constraints.lt !== undefined ? time(constraints.lt)! - 1 : undefined);
+    if (lo === undefined && hi === undefined) {
+      lo = DEFAULT_DATE_MIN;
+      hi = DEFAULT_DATE_MAX;
+    }
+    lo ??= hi! - 365 * DAY_MS;
+    hi ??= lo + 365 * DAY_MS;
+    return new Date(lo + Math.floor(rng.next() * Math.max(0, hi - lo + 1)));
+  }
+
+  export function bytes(rng: MockRng): Uint8Array {
+    const result = new Uint8Array(rng.int(0, 16));
+    for (let i = 0; i < result.length; i++) {
+      result[i] = rng.int(0, 255);
+    }
+    return result;
+  }
+
+  export function array<T>(rng: MockRng, item: Arbitrary<T>, constraints: ItemsConstraints = {}): T[] {
+    return Array.from({ length: itemCount(rng, constraints) }, () => item(rng));
+  }
+
+  /** Distinct items; stops short of the count if `item` keeps repeating itself */
+  export function set<T>(rng: MockRng, item: Arbitrary<T>, constraints: ItemsConstraints = {}): Set<T> {
+    const target = itemCount(rng, constraints);
+    const result = new Set<T>();
+    for (let attempt = 0; result.size < target && attempt < target * 10; attempt++) {
+      result.add(item(rng));
+    }
+    return result;
+  }
+
+  export function map<K, V>(
+    rng: MockRng,
+    key: Arbitrary<K>,
+    value: Arbitrary<V>,
+    constraints: ItemsConstraints = {},
+  ): Map<K, V> {
+    return new Map(Array.from(set(rng, key, constraints), (k) => [k, value(rng)] as [K, V]));
+  }
+
+  export function record<V>(rng: MockRng, value: Arbitrary<V>, constraints: ItemsConstraints = {}): Record<string, V> {
+    const result: Record<string, V> = {};
+    for (const key of set(rng, () => letters(rng, rng.int(3, 8)), constraints)) {
+      result[key] = value(rng);
+    }
+    return result;
+  }
+
+  /** A value from one of the member generators, chosen at random */
+  export function union<M extends readonly Arbitrary<unknown>[]>(rng: MockRng, members: M): ReturnType<M[number]> {
+    return rng.pick(members)(rng) as ReturnType<M[number]>;
+  }
+}
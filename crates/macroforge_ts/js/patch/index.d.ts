@@ -0,0 +1,12 @@
+/**
+ * Patch runtime helpers for the macroforge Patch macro.
+ * `diff()` uses `valuesEqual` to decide which fields a patch must carry.
+ */
+/**
+ * Structural equality for patch diffs: `equals()` when available, then
+ * Dates, byte arrays, URLs, RegExps, arrays, Maps, Sets and plain objects
+ * by content; other class instances by reference
+ */
+export declare function valuesEqual(a: unknown, b: unknown): boolean;
+/** True if a patch produced by `diff()` carries no changes */
+export declare function isEmptyPatch(patch: object): boolean;
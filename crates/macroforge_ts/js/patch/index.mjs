@@ -0,0 +1,65 @@
+// js/patch/index.ts
+function isEquatable(value) {
+  return typeof value.equals === "function";
+}
+function valuesEqual(a, b) {
+  if (Object.is(a, b))
+    return true;
+  if (typeof a !== "object" || typeof b !== "object" || a === null || b === null)
+    return false;
+  if (isEquatable(a))
+    return a.equals(b);
+  if (a instanceof Date || b instanceof Date) {
+    return a instanceof Date && b instanceof Date && a.getTime() === b.getTime();
+  }
+  if (a instanceof URL || a instanceof RegExp) {
+    return Object.getPrototypeOf(a) === Object.getPrototypeOf(b) && String(a) === String(b);
+  }
+  if (a instanceof ArrayBuffer && b instanceof ArrayBuffer) {
+    return valuesEqual(new Uint8Array(a), new Uint8Array(b));
+  }
+  if (ArrayBuffer.isView(a) || ArrayBuffer.isView(b)) {
+    if (!ArrayBuffer.isView(a) || !ArrayBuffer.isView(b) || a.byteLength !== b.byteLength)
+      return false;
+    const left = new Uint8Array(a.buffer, a.byteOffset, a.byteLength);
+    const right = new Uint8Array(b.buffer, b.byteOffset, b.byteLength);
+    return left.every((byte, i) => byte === right[i]);
+  }
+  if (Array.isArray(a) || Array.isArray(b)) {
+    return Array.isArray(a) && Array.isArray(b) && a.length === b.length && a.every((item, i) => valuesEqual(item, b[i]));
+  }
+  if (a instanceof Map || b instanceof Map) {
+    if (!(a instanceof Map) || !(b instanceof Map) || a.size !== b.size)
+      return false;
+    for (const [key, item] of a) {
+      if (!b.has(key) || !valuesEqual(item, b.get(key)))
+        return false;
+    }
+    return true;
+  }
+  if (a instanceof Set || b instanceof Set) {
+    if (!(a instanceof Set) || !(b instanceof Set) || a.size !== b.size)
+      return false;
+    for (const item of a) {
+      if (!b.has(item))
+        return false;
+    }
+    return true;
+  }
+  const proto = Object.getPrototypeOf(a);
+  if (proto !== Object.getPrototypeOf(b))
+    return false;
+  if (proto !== Object.prototype && proto !== null)
+    return false;
+  const keys = Object.keys(a);
+  if (keys.length !== Object.keys(b).length)
+    return false;
+  return keys.every((key) => Object.prototype.hasOwnProperty.call(b, key) && valuesEqual(a[key], b[key]));
+}
+function isEmptyPatch(patch) {
+  return Object.keys(patch).length === 0;
+}
+export {
+  valuesEqual,
+  isEmptyPatch
+};
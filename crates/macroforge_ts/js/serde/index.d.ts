@@ -8,9 +8,30 @@ export interface SerializeContext {
     getId(obj: object): number | undefined;
     /** Register an object and return its assigned ID */
     register(obj: object): number;
+    /**
+     * Mark an object as being written in plain mode, which has no `__ref`
+     * markers. Throws if the object is already being written (a cycle).
+     */
+    enter(obj: object, typeName: string): void;
+    /** Mark an object entered with `enter` as done */
+    leave(obj: object): void;
+    /**
+     * Set when the value being written is a `plain` container: nested values
+     * are written without metadata too, whatever their own options
+     */
+    readonly plain: boolean;
+}
+export interface SerializeContextOptions {
+    plain?: boolean;
 }
 export declare namespace SerializeContext {
-    function create(): SerializeContext;
+    function create(options?: SerializeContextOptions): SerializeContext;
+    /**
+     * Serialize a value whose type is only known at runtime, such as a field
+     * typed by a type parameter: dispatches to `__serialize` and recurses into
+     * arrays, maps and sets.
+     */
+    function serializeAny(value: unknown, ctx: SerializeContext): unknown;
 }
 export interface DeserializeContext {
     /** Register an object with a known ID */
@@ -25,6 +46,17 @@ export interface DeserializeContext {
     applyPatches(): void;
     /** Freeze all tracked objects (call after applyPatches if immutability is desired) */
     freezeAll(): void;
+    /**
+     * Record the result of a custom validator: `false` fails with `message`,
+     * a string fails with that string, an array adds its field errors and a
+     * promise is settled later by `settleValidations` (its rejection counts
+     * as handled until then)
+     */
+    checkValidation(result: unknown, field: string, message: string, errors: FieldError[]): void;
+    /** True if a validator returned a promise that has not been settled */
+    hasPendingValidations(): boolean;
+    /** Await deferred validators and return the errors they produced */
+    settleValidations(): Promise<FieldError[]>;
 }
 export declare namespace DeserializeContext {
     function create(): DeserializeContext;
@@ -38,17 +70,81 @@ export declare namespace PendingRef {
     function create(id: number): PendingRef;
     function is(value: any): value is PendingRef;
 }
+/**
+ * Deserializer for a type parameter of a generic type, e.g. `User` in
+ * `Page.fromObject(raw, { T: User })`: a class or namespace with
+ * `__deserialize`, or a plain function such as `Number`.
+ */
+export type TypeDeserializer<T = unknown> = {
+    __deserialize(value: any, ctx: DeserializeContext): T | PendingRef;
+} | ((value: any, ctx: DeserializeContext) => T | PendingRef);
+export declare namespace TypeDeserializer {
+    /** Deserialize `value` with `deserializer`; without one the value is returned as-is */
+    function apply<T>(deserializer: TypeDeserializer<T> | undefined, value: unknown, ctx: DeserializeContext): T | PendingRef;
+    /** Type deserializers given in declaration order, as passed for a field such as `page: Page<User>` */
+    type Positional = ReadonlyArray<TypeDeserializer<any> | undefined>;
+    /** Key positional type deserializers by the type parameter `names`; named ones are returned as-is */
+    function named<T extends object>(types: T | Positional | undefined, names: readonly string[]): T | undefined;
+    /** Deserializer for a generic type applied to type deserializers in declaration order, e.g. `Box<User>` */
+    function of<T>(generic: {
+        __deserialize(value: any, ctx: DeserializeContext, types?: Positional): T | PendingRef;
+    }, types: Positional): TypeDeserializer<T>;
+}
+/**
+ * Conversions for field types JSON cannot represent directly: `bigint` is
+ * written as a decimal string, `Uint8Array`/`ArrayBuffer` as base64 and
+ * `RegExp` as `/source/flags`.
+ */
+export declare namespace Codec {
+    /** Read a `bigint` written as a decimal string (integer numbers are accepted too) */
+    function decodeBigInt(raw: unknown): bigint;
+    /** Base64-encode the bytes of a `Uint8Array` or `ArrayBuffer` */
+    function encodeBase64(bytes: Uint8Array | ArrayBuffer): string;
+    /** Read bytes written by `encodeBase64` (bytes already decoded are passed through) */
+    function decodeBase64(raw: unknown): Uint8Array;
+    /** Read a `RegExp` written as `/source/flags` (a bare string is used as the source) */
+    function decodeRegExp(raw: unknown): RegExp;
+    /** Map every value of a record, keeping its keys */
+    function mapValues<T, U>(record: Record<string, T>, fn: (value: T) => U): Record<string, U>;
+}
 export interface DeserializeOptions {
     /** If true, freeze all deserialized objects after patching */
     freeze?: boolean;
+    /** Rewrites each error's `message`, e.g. to translate it by `code` and `params` */
+    formatMessage?: (error: FieldError) => string;
 }
 /** Structured field error for validation failures */
 export interface FieldError {
     field: string;
     message: string;
+    /** Stable identifier of the failed check, e.g. `"minLength"` or `"required"` */
+    code?: string;
+    /** Values referenced by the check, e.g. `{ min: 3 }` for `minLength(3)` */
+    params?: Record<string, unknown>;
+}
+export declare namespace FieldError {
+    /** Apply `opts.formatMessage` to each error; returns `errors` unchanged without one */
+    function format(errors: FieldError[], opts?: DeserializeOptions): FieldError[];
 }
 /** Error class that carries structured field errors */
 export declare class DeserializeError extends Error {
     readonly errors: FieldError[];
     constructor(errors: FieldError[]);
 }
+/**
+ * MessagePack codec for the plain values produced by `__serialize`.
+ * `bigint` values are written as 64-bit integers and `Uint8Array` as binary;
+ * integers outside the safe range decode back to `bigint`.
+ */
+export declare namespace MsgPack {
+    function encode(value: unknown): Uint8Array;
+    function decode(bytes: Uint8Array): unknown;
+}
+/**
+ * Incremental reader for a top-level JSON array. Only one element is held in
+ * memory at a time, so arbitrarily large exports can be processed.
+ */
+export declare namespace JsonStream {
+    /** Yield each element of a JSON array as it is completed in `chunks` */
+    function items(chunks: AsyncIterable<string | Uint8Array>): AsyncGenerator<unknown>;
+}
@@ -1,19 +1,49 @@
 // js/serde/index.ts
 var SerializeContext;
 ((SerializeContext) => {
-  function create() {
+  function create(options = {}) {
     const ids = new WeakMap;
+    const active = new WeakSet;
     let nextId = 0;
     return {
+      plain: options.plain ?? false,
       getId: (obj) => ids.get(obj),
       register: (obj) => {
         const id = nextId++;
         ids.set(obj, id);
         return id;
+      },
+      enter: (obj, typeName) => {
+        if (active.has(obj)) {
+          throw new Error(`${typeName}.__serialize: cycle detected (plain JSON cannot represent cycles)`);
+        }
+        active.add(obj);
+      },
+      leave: (obj) => {
+        active.delete(obj);
       }
     };
   }
   SerializeContext.create = create;
+  function serializeAny(value, ctx) {
+    if (typeof value?.__serialize === "function") {
+      return value.__serialize(ctx);
+    }
+    if (value instanceof Date) {
+      return value.toISOString();
+    }
+    if (Array.isArray(value)) {
+      return value.map((item) => serializeAny(item, ctx));
+    }
+    if (value instanceof Map) {
+      return Object.fromEntries(Array.from(value, ([k, v]) => [k, serializeAny(v, ctx)]));
+    }
+    if (value instanceof Set) {
+      return Array.from(value, (item) => serializeAny(item, ctx));
+    }
+    return value;
+  }
+  SerializeContext.serializeAny = serializeAny;
 })(SerializeContext ||= {});
 var DeserializeContext;
 ((DeserializeContext) => {
@@ -21,6 +51,19 @@ var DeserializeContext;
     const registry = new Map;
     const patches = [];
     const toFreeze = [];
+    const pending = [];
+    const collect = (result, field, message, errors) => {
+      if (result === false) {
+        errors.push({ field, message, code: "custom" });
+      } else if (typeof result === "string") {
+        errors.push({ field, message: result, code: "custom" });
+      } else if (Array.isArray(result)) {
+        errors.push(...result);
+      } else if (typeof result?.then === "function") {
+        result.then(undefined, () => {});
+        pending.push({ result, field, message });
+      }
+    };
     return {
       register: (id, instance) => {
         registry.set(id, instance);
@@ -49,6 +92,16 @@ var DeserializeContext;
         for (const obj of toFreeze) {
           Object.freeze(obj);
         }
+      },
+      checkValidation: collect,
+      hasPendingValidations: () => pending.length > 0,
+      settleValidations: async () => {
+        const errors = [];
+        while (pending.length > 0) {
+          const { result, field, message } = pending.shift();
+          collect(await result, field, message, errors);
+        }
+        return errors;
       }
     };
   }
@@ -65,6 +118,115 @@ var PendingRef;
   }
   PendingRef.is = is;
 })(PendingRef ||= {});
+var TypeDeserializer;
+((TypeDeserializer) => {
+  function apply(deserializer, value, ctx) {
+    if (deserializer === undefined) {
+      return value;
+    }
+    if (typeof deserializer.__deserialize === "function") {
+      return deserializer.__deserialize(value, ctx);
+    }
+    if (deserializer === Date) {
+      return new Date(value);
+    }
+    return deserializer(value, ctx);
+  }
+  TypeDeserializer.apply = apply;
+  function named(types, names) {
+    if (!Array.isArray(types)) {
+      return types;
+    }
+    const out = {};
+    names.forEach((name, i) => {
+      if (types[i] !== undefined) {
+        out[name] = types[i];
+      }
+    });
+    return out;
+  }
+  TypeDeserializer.named = named;
+  function of(generic, types) {
+    return { __deserialize: (value, ctx) => generic.__deserialize(value, ctx, types) };
+  }
+  TypeDeserializer.of = of;
+})(TypeDeserializer ||= {});
+var Codec;
+((Codec) => {
+  function decodeBigInt(raw) {
+    if (typeof raw === "bigint") {
+      return raw;
+    }
+    if (typeof raw === "string" || typeof raw === "number") {
+      return BigInt(raw);
+    }
+    throw new TypeError("expected a bigint string");
+  }
+  Codec.decodeBigInt = decodeBigInt;
+  function encodeBase64(bytes) {
+    const view = bytes instanceof Uint8Array ? bytes : new Uint8Array(bytes);
+    let binary = "";
+    for (let i = 0; i < view.length; i += 32768) {
+      binary += String.fromCharCode(...view.subarray(i, i + 32768));
+    }
+    return btoa(binary);
+  }
+  Codec.encodeBase64 = encodeBase64;
+  function decodeBase64(raw) {
+    if (raw instanceof Uint8Array) {
+      return raw;
+    }
+    if (raw instanceof ArrayBuffer) {
+      return new Uint8Array(raw);
+    }
+    if (typeof raw !== "string") {
+      throw new TypeError("expected a base64 string");
+    }
+    const binary = atob(raw);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+      bytes[i] = binary.charCodeAt(i);
+    }
+    return bytes;
+  }
+  Codec.decodeBase64 = decodeBase64;
+  function decodeRegExp(raw) {
+    if (raw instanceof RegExp) {
+      return raw;
+    }
+    if (typeof raw !== "string") {
+      throw new TypeError("expected a regular expression string");
+    }
+    const end = raw.lastIndexOf("/");
+    if (raw.startsWith("/") && end > 0) {
+      return new RegExp(raw.slice(1, end), raw.slice(end + 1));
+    }
+    return new RegExp(raw);
+  }
+  Codec.decodeRegExp = decodeRegExp;
+  function mapValues(record, fn) {
+    if (typeof record !== "object" || record === null || Array.isArray(record)) {
+      throw new TypeError("expected an object");
+    }
+    const result = {};
+    for (const [key, value] of Object.entries(record)) {
+      result[key] = fn(value);
+    }
+    return result;
+  }
+  Codec.mapValues = mapValues;
+})(Codec ||= {});
+var FieldError;
+((FieldError) => {
+  function format(errors, opts) {
+    const formatMessage = opts?.formatMessage;
+    if (!formatMessage) {
+      return errors;
+    }
+    return errors.map((error) => ({ ...error, message: formatMessage(error) }));
+  }
+  FieldError.format = format;
+})(FieldError ||= {});
 
 class DeserializeError extends Error {
   errors;
@@ -75,9 +237,370 @@ class DeserializeError extends Error {
     this.errors = errors;
   }
 }
+var MsgPack;
+((MsgPack) => {
+  const textEncoder = new TextEncoder;
+  const textDecoder = new TextDecoder;
+  function encode(value) {
+    const writer = new Writer;
+    writer.write(value);
+    return writer.finish();
+  }
+  MsgPack.encode = encode;
+  function decode(bytes) {
+    const reader = new Reader(bytes);
+    const value = reader.read();
+    if (reader.offset !== bytes.length) {
+      throw new Error(`MsgPack.decode: ${bytes.length - reader.offset} trailing bytes`);
+    }
+    return value;
+  }
+  MsgPack.decode = decode;
+
+  class Writer {
+    buf = new Uint8Array(256);
+    view = new DataView(this.buf.buffer);
+    len = 0;
+    finish() {
+      return this.buf.slice(0, this.len);
+    }
+    reserve(n) {
+      if (this.len + n > this.buf.length) {
+        let size = this.buf.length * 2;
+        while (size < this.len + n)
+          size *= 2;
+        const next = new Uint8Array(size);
+        next.set(this.buf.subarray(0, this.len));
+        this.buf = next;
+        this.view = new DataView(next.buffer);
+      }
+      const at = this.len;
+      this.len += n;
+      return at;
+    }
+    u8(v) {
+      const at = this.reserve(1);
+      this.view.setUint8(at, v);
+    }
+    u16(v) {
+      const at = this.reserve(2);
+      this.view.setUint16(at, v);
+    }
+    u32(v) {
+      const at = this.reserve(4);
+      this.view.setUint32(at, v);
+    }
+    bytes(v) {
+      const at = this.reserve(v.length);
+      this.buf.set(v, at);
+    }
+    header(len, fix, fixMax, b8, b16, b32) {
+      if (len <= fixMax) {
+        this.u8(fix | len);
+      } else if (b8 !== null && len <= 255) {
+        this.u8(b8);
+        this.u8(len);
+      } else if (len <= 65535) {
+        this.u8(b16);
+        this.u16(len);
+      } else {
+        this.u8(b32);
+        this.u32(len);
+      }
+    }
+    write(value) {
+      if (value === null || value === undefined) {
+        this.u8(192);
+      } else if (typeof value === "boolean") {
+        this.u8(value ? 195 : 194);
+      } else if (typeof value === "number") {
+        this.number(value);
+      } else if (typeof value === "bigint") {
+        this.bigint(value);
+      } else if (typeof value === "string") {
+        const encoded = textEncoder.encode(value);
+        this.header(encoded.length, 160, 31, 217, 218, 219);
+        this.bytes(encoded);
+      } else if (value instanceof Uint8Array) {
+        this.header(value.length, 0, -1, 196, 197, 198);
+        this.bytes(value);
+      } else if (Array.isArray(value)) {
+        this.header(value.length, 144, 15, null, 220, 221);
+        for (const item of value)
+          this.write(item);
+      } else if (typeof value === "object") {
+        const json = value.toJSON;
+        if (typeof json === "function") {
+          this.write(json.call(value));
+          return;
+        }
+        const entries = Object.entries(value).filter(([, v]) => v !== undefined);
+        this.header(entries.length, 128, 15, null, 222, 223);
+        for (const [k, v] of entries) {
+          this.write(k);
+          this.write(v);
+        }
+      } else {
+        throw new Error(`MsgPack.encode: cannot encode ${typeof value}`);
+      }
+    }
+    number(v) {
+      if (!Number.isSafeInteger(v)) {
+        this.u8(203);
+        const at = this.reserve(8);
+        this.view.setFloat64(at, v);
+      } else if (v >= 0) {
+        if (v < 128) {
+          this.u8(v);
+        } else if (v <= 255) {
+          this.u8(204);
+          this.u8(v);
+        } else if (v <= 65535) {
+          this.u8(205);
+          this.u16(v);
+        } else if (v <= 4294967295) {
+          this.u8(206);
+          this.u32(v);
+        } else {
+          this.bigint(BigInt(v));
+        }
+      } else if (v >= -32) {
+        this.u8(v & 255);
+      } else if (v >= -128) {
+        this.u8(208);
+        this.u8(v & 255);
+      } else if (v >= -32768) {
+        this.u8(209);
+        this.u16(v & 65535);
+      } else if (v >= -2147483648) {
+        this.u8(210);
+        this.u32(v >>> 0);
+      } else {
+        this.bigint(BigInt(v));
+      }
+    }
+    bigint(v) {
+      if (v >= 0n) {
+        if (v > 0xffffffffffffffffn)
+          throw new Error("MsgPack.encode: bigint exceeds 64 bits");
+        this.u8(207);
+        const at = this.reserve(8);
+        this.view.setBigUint64(at, v);
+      } else {
+        if (v < -0x8000000000000000n)
+          throw new Error("MsgPack.encode: bigint exceeds 64 bits");
+        this.u8(211);
+        const at = this.reserve(8);
+        this.view.setBigInt64(at, v);
+      }
+    }
+  }
+
+  class Reader {
+    bytes;
+    offset = 0;
+    view;
+    constructor(bytes) {
+      this.bytes = bytes;
+      this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+    }
+    take(n) {
+      if (this.offset + n > this.bytes.length) {
+        throw new Error("MsgPack.decode: unexpected end of input");
+      }
+      const at = this.offset;
+      this.offset += n;
+      return at;
+    }
+    int64(v) {
+      return v >= BigInt(Number.MIN_SAFE_INTEGER) && v <= BigInt(Number.MAX_SAFE_INTEGER) ? Number(v) : v;
+    }
+    str(len) {
+      const at = this.take(len);
+      return textDecoder.decode(this.bytes.subarray(at, at + len));
+    }
+    bin(len) {
+      const at = this.take(len);
+      return this.bytes.slice(at, at + len);
+    }
+    array(len) {
+      const out = [];
+      for (let i = 0;i < len; i++)
+        out.push(this.read());
+      return out;
+    }
+    map(len) {
+      const out = {};
+      for (let i = 0;i < len; i++) {
+        const key = String(this.read());
+        Object.defineProperty(out, key, {
+          value: this.read(),
+          enumerable: true,
+          writable: true,
+          configurable: true
+        });
+      }
+      return out;
+    }
+    read() {
+      const v = this.view;
+      const b = v.getUint8(this.take(1));
+      if (b < 128)
+        return b;
+      if (b >= 224)
+        return b - 256;
+      if (b >= 160 && b <= 191)
+        return this.str(b & 31);
+      if (b >= 144 && b <= 159)
+        return this.array(b & 15);
+      if (b >= 128 && b <= 143)
+        return this.map(b & 15);
+      switch (b) {
+        case 192:
+          return null;
+        case 194:
+          return false;
+        case 195:
+          return true;
+        case 196:
+          return this.bin(v.getUint8(this.take(1)));
+        case 197:
+          return this.bin(v.getUint16(this.take(2)));
+        case 198:
+          return this.bin(v.getUint32(this.take(4)));
+        case 202:
+          return v.getFloat32(this.take(4));
+        case 203:
+          return v.getFloat64(this.take(8));
+        case 204:
+          return v.getUint8(this.take(1));
+        case 205:
+          return v.getUint16(this.take(2));
+        case 206:
+          return v.getUint32(this.take(4));
+        case 207:
+          return this.int64(v.getBigUint64(this.take(8)));
+        case 208:
+          return v.getInt8(this.take(1));
+        case 209:
+          return v.getInt16(this.take(2));
+        case 210:
+          return v.getInt32(this.take(4));
+        case 211:
+          return this.int64(v.getBigInt64(this.take(8)));
+        case 217:
+          return this.str(v.getUint8(this.take(1)));
+        case 218:
+          return this.str(v.getUint16(this.take(2)));
+        case 219:
+          return this.str(v.getUint32(this.take(4)));
+        case 220:
+          return this.array(v.getUint16(this.take(2)));
+        case 221:
+          return this.array(v.getUint32(this.take(4)));
+        case 222:
+          return this.map(v.getUint16(this.take(2)));
+        case 223:
+          return this.map(v.getUint32(this.take(4)));
+        default:
+          throw new Error(`MsgPack.decode: unsupported type byte 0x${b.toString(16)}`);
+      }
+    }
+  }
+})(MsgPack ||= {});
+var JsonStream;
+((JsonStream) => {
+  async function* items(chunks) {
+    const decoder = new TextDecoder;
+    const scanner = new Scanner;
+    for await (const chunk of chunks) {
+      const text = typeof chunk === "string" ? chunk : decoder.decode(chunk, { stream: true });
+      yield* scanner.push(text);
+    }
+    yield* scanner.push(decoder.decode());
+    scanner.finish();
+  }
+  JsonStream.items = items;
+
+  class Scanner {
+    state = "before";
+    buf = "";
+    depth = 0;
+    inString = false;
+    escaped = false;
+    *push(text) {
+      let start = 0;
+      for (let i = 0;i < text.length; i++) {
+        const ch = text[i];
+        if (this.state === "element") {
+          if (this.inString) {
+            if (this.escaped)
+              this.escaped = false;
+            else if (ch === "\\")
+              this.escaped = true;
+            else if (ch === '"')
+              this.inString = false;
+          } else if (ch === '"') {
+            this.inString = true;
+          } else if (ch === "{" || ch === "[") {
+            this.depth++;
+          } else if (ch === "}" || ch === "]") {
+            if (this.depth === 0 && ch === "]") {
+              yield this.complete(text.slice(start, i));
+              this.state = "done";
+            } else {
+              this.depth--;
+            }
+          } else if (ch === "," && this.depth === 0) {
+            yield this.complete(text.slice(start, i));
+            this.state = "between";
+          }
+        } else if (isWhitespace(ch)) {
+          continue;
+        } else if (this.state === "before") {
+          if (ch !== "[")
+            throw new Error("JsonStream: expected a top-level array");
+          this.state = "first";
+        } else if (this.state === "first" && ch === "]") {
+          this.state = "done";
+        } else if (this.state === "first" || this.state === "between") {
+          if (ch === "]" || ch === ",")
+            throw new Error(`JsonStream: unexpected '${ch}'`);
+          this.state = "element";
+          start = i;
+          i--;
+        } else {
+          throw new Error("JsonStream: unexpected data after the array");
+        }
+      }
+      if (this.state === "element") {
+        this.buf += text.slice(start);
+      }
+    }
+    finish() {
+      if (this.state !== "done") {
+        throw new Error("JsonStream: unexpected end of input");
+      }
+    }
+    complete(tail) {
+      const source = this.buf + tail;
+      this.buf = "";
+      return JSON.parse(source);
+    }
+  }
+  function isWhitespace(ch) {
+    return ch === " " || ch === `
+` || ch === "\r" || ch === "\t";
+  }
+})(JsonStream ||= {});
 export {
   SerializeContext,
+  FieldError,
   PendingRef,
+  TypeDeserializer,
+  Codec,
+  MsgPack,
+  JsonStream,
   DeserializeError,
   DeserializeContext
 };
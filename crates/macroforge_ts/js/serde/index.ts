@@ -13,21 +13,72 @@ export interface SerializeContext {
   getId(obj: object): number | undefined;
   /** Register an object and return its assigned ID */
   register(obj: object): number;
+  /**
+   * Mark an object as being written in plain mode, which has no `__ref`
+   * markers. Throws if the object is already being written (a cycle).
+   */
+  enter(obj: object, typeName: string): void;
+  /** Mark an object entered with `enter` as done */
+  leave(obj: object): void;
+  /**
+   * Set when the value being written is a `plain` container: nested values
+   * are written without metadata too, whatever their own options
+   */
+  readonly plain: boolean;
+}
+
+export interface SerializeContextOptions {
+  plain?: boolean;
 }
 
 export namespace SerializeContext {
-  export function create(): SerializeContext {
+  export function create(options: SerializeContextOptions = {}): SerializeContext {
     const ids = new WeakMap<object, number>();
+    const active = new WeakSet<object>();
     let nextId = 0;
     return {
+      plain: options.plain ?? false,
       getId: (obj) => ids.get(obj),
       register: (obj) => {
         const id = nextId++;
         ids.set(obj, id);
         return id;
       },
+      enter: (obj, typeName) => {
+        if (active.has(obj)) {
+          throw new Error(`${typeName}.__serialize: cycle detected (plain JSON cannot represent cycles)`);
+        }
+        active.add(obj);
+      },
+      leave: (obj) => {
+        active.delete(obj);
+      },
     };
   }
+
+  /**
+   * Serialize a value whose type is only known at runtime, such as a field
+   * typed by a type parameter: dispatches to `__serialize` and recurses into
+   * arrays, maps and sets.
+   */
+  export function serializeAny(value: unknown, ctx: SerializeContext): unknown {
+    if (typeof (value as any)?.__serialize === "function") {
+      return (value as any).__serialize(ctx);
+    }
+    if (value instanceof Date) {
+      return value.toISOString();
+    }
+    if (Array.isArray(value)) {
+      return value.map((item) => serializeAny(item, ctx));
+    }
+    if (value instanceof Map) {
+      return Object.fromEntries(Array.from(value, ([k, v]) => [k, serializeAny(v, ctx)]));
+    }
+    if (value instanceof Set) {
+      return Array.from(value, (item) => serializeAny(item, ctx));
+    }
+    return value;
+  }
 }
 
 // ============================================================================
@@ -47,6 +98,17 @@ export interface DeserializeContext {
   applyPatches(): void;
   /** Freeze all tracked objects (call after applyPatches if immutability is desired) */
   freezeAll(): void;
+  /**
+   * Record the result of a custom validator: `false` fails with `message`,
+   * a string fails with that string, an array adds its field errors and a
+   * promise is settled later by `settleValidations` (its rejection counts
+   * as handled until then)
+   */
+  checkValidation(result: unknown, field: string, message: string, errors: FieldError[]): void;
+  /** True if a validator returned a promise that has not been settled */
+  hasPendingValidations(): boolean;
+  /** Await deferred validators and return the errors they produced */
+  settleValidations(): Promise<FieldError[]>;
 }
 
 export namespace DeserializeContext {
@@ -54,6 +116,23 @@ export namespace DeserializeContext {
     const registry = new Map<number, any>();
     const patches: Array<{ refId: number; setter: (val: any) => void }> = [];
     const toFreeze: object[] = [];
+    const pending: Array<{ result: PromiseLike<unknown>; field: string; message: string }> = [];
+
+    const collect = (result: unknown, field: string, message: string, errors: FieldError[]) => {
+      if (result === false) {
+        errors.push({ field, message, code: "custom" });
+      } else if (typeof result === "string") {
+        errors.push({ field, message: result, code: "custom" });
+      } else if (Array.isArray(result)) {
+        errors.push(...(result as FieldError[]));
+      } else if (typeof (result as PromiseLike<unknown> | null)?.then === "function") {
+        const promise = result as PromiseLike<unknown>;
+        // Mark a rejection as handled until settleValidations awaits the
+        // original promise, or forever if it is never settled
+        promise.then(undefined, () => {});
+        pending.push({ result: promise, field, message });
+      }
+    };
 
     return {
       register: (id, instance) => {
@@ -89,6 +168,19 @@ export namespace DeserializeContext {
           Object.freeze(obj);
         }
       },
+
+      checkValidation: collect,
+
+      hasPendingValidations: () => pending.length > 0,
+
+      settleValidations: async () => {
+        const errors: FieldError[] = [];
+        while (pending.length > 0) {
+          const { result, field, message } = pending.shift()!;
+          collect(await result, field, message, errors);
+        }
+        return errors;
+      },
     };
   }
 }
@@ -118,6 +210,142 @@ export namespace PendingRef {
   }
 }
 
+// ============================================================================
+// Type Parameter Deserializers
+// ============================================================================
+
+/**
+ * Deserializer for a type parameter of a generic type, e.g. `User` in
+ * `Page.fromObject(raw, { T: User })`: a class or namespace with
+ * `__deserialize`, or a plain function such as `Number`.
+ */
+export type TypeDeserializer<T = unknown> =
+  | { __deserialize(value: any, ctx: DeserializeContext): T | PendingRef }
+  | ((value: any, ctx: DeserializeContext) => T | PendingRef);
+
+export namespace TypeDeserializer {
+  /** Deserialize `value` with `deserializer`; without one the value is returned as-is */
+  export function apply<T>(
+    deserializer: TypeDeserializer<T> | undefined,
+    value: unknown,
+    ctx: DeserializeContext,
+  ): T | PendingRef {
+    if (deserializer === undefined) {
+      return value as T;
+    }
+    if (typeof (deserializer as any).__deserialize === "function") {
+      return (deserializer as { __deserialize: (value: any, ctx: DeserializeContext) => T | PendingRef }).__deserialize(value, ctx);
+    }
+    if ((deserializer as unknown) === Date) {
+      return new Date(value as string) as T;
+    }
+    return (deserializer as (value: any, ctx: DeserializeContext) => T | PendingRef)(value, ctx);
+  }
+
+  /** Type deserializers given in declaration order, as passed for a field such as `page: Page<User>` */
+  export type Positional = ReadonlyArray<TypeDeserializer<any> | undefined>;
+
+  /** Key positional type deserializers by the type parameter `names`; named ones are returned as-is */
+  export function named<T extends object>(types: T | Positional | undefined, names: readonly string[]): T | undefined {
+    if (!Array.isArray(types)) {
+      return types as T | undefined;
+    }
+    const out: Record<string, TypeDeserializer<any>> = {};
+    names.forEach((name, i) => {
+      if (types[i] !== undefined) {
+        out[name] = types[i];
+      }
+    });
+    return out as T;
+  }
+
+  /** Deserializer for a generic type applied to type deserializers in declaration order, e.g. `Box<User>` */
+  export function of<T>(
+    generic: { __deserialize(value: any, ctx: DeserializeContext, types?: Positional): T | PendingRef },
+    types: Positional,
+  ): TypeDeserializer<T> {
+    return { __deserialize: (value, ctx) => generic.__deserialize(value, ctx, types) };
+  }
+}
+
+// ============================================================================
+// Codecs for non-JSON values
+// ============================================================================
+
+/**
+ * Conversions for field types JSON cannot represent directly: `bigint` is
+ * written as a decimal string, `Uint8Array`/`ArrayBuffer` as base64 and
+ * `RegExp` as `/source/flags`.
+ */
+export namespace Codec {
+  /** Read a `bigint` written as a decimal string (integer numbers are accepted too) */
+  export function decodeBigInt(raw: unknown): bigint {
+    if (typeof raw === "bigint") {
+      return raw;
+    }
+    if (typeof raw === "string" || typeof raw === "number") {
+      return BigInt(raw);
+    }
+    throw new TypeError("expected a bigint string");
+  }
+
+  /** Base64-encode the bytes of a `Uint8Array` or `ArrayBuffer` */
+  export function encodeBase64(bytes: Uint8Array | ArrayBuffer): string {
+    const view = bytes instanceof Uint8Array ? bytes : new Uint8Array(bytes);
+    let binary = "";
+    for (let i = 0; i < view.length; i += 0x8000) {
+      binary += String.fromCharCode(...view.subarray(i, i + 0x8000));
+    }
+    return btoa(binary);
+  }
+
+  /** Read bytes written by `encodeBase64` (bytes already decoded are passed through) */
+  export function decodeBase64(raw: unknown): Uint8Array {
+    if (raw instanceof Uint8Array) {
+      return raw;
+    }
+    if (raw instanceof ArrayBuffer) {
+      return new Uint8Array(raw);
+    }
+    if (typeof raw !== "string") {
+      throw new TypeError("expected a base64 string");
+    }
+    const binary = atob(raw);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+      bytes[i] = binary.charCodeAt(i);
+    }
+    return bytes;
+  }
+
+  /** Read a `RegExp` written as `/source/flags` (a bare string is used as the source) */
+  export function decodeRegExp(raw: unknown): RegExp {
+    if (raw instanceof RegExp) {
+      return raw;
+    }
+    if (typeof raw !== "string") {
+      throw new TypeError("expected a regular expression string");
+    }
+    const end = raw.lastIndexOf("/");
+    if (raw.startsWith("/") && end > 0) {
+      return new RegExp(raw.slice(1, end), raw.slice(end + 1));
+    }
+    return new RegExp(raw);
+  }
+
+  /** Map every value of a record, keeping its keys */
+  export function mapValues<T, U>(record: Record<string, T>, fn: (value: T) => U): Record<string, U> {
+    if (typeof record !== "object" || record === null || Array.isArray(record)) {
+      throw new TypeError("expected an object");
+    }
+    const result: Record<string, U> = {};
+    for (const [key, value] of Object.entries(record)) {
+      result[key] = fn(value);
+    }
+    return result;
+  }
+}
+
 // ============================================================================
 // Options for fromStringifiedJSON
 // ============================================================================
@@ -125,6 +353,8 @@ export namespace PendingRef {
 export interface DeserializeOptions {
   /** If true, freeze all deserialized objects after patching */
   freeze?: boolean;
+  /** Rewrites each error's `message`, e.g. to translate it by `code` and `params` */
+  formatMessage?: (error: FieldError) => string;
 }
 
 // ============================================================================
@@ -135,6 +365,21 @@ export interface DeserializeOptions {
 export interface FieldError {
   field: string;
   message: string;
+  /** Stable identifier of the failed check, e.g. `"minLength"` or `"required"` */
+  code?: string;
+  /** Values referenced by the check, e.g. `{ min: 3 }` for `minLength(3)` */
+  params?: Record<string, unknown>;
+}
+
+export namespace FieldError {
+  /** Apply `opts.formatMessage` to each error; returns `errors` unchanged without one */
+  export function format(errors: FieldError[], opts?: DeserializeOptions): FieldError[] {
+    const formatMessage = opts?.formatMessage;
+    if (!formatMessage) {
+      return errors;
+    }
+    return errors.map((error) => ({ ...error, message: formatMessage(error) }));
+  }
 }
 
 /** Error class that carries structured field errors */
@@ -148,3 +393,360 @@ export class DeserializeError extends Error {
     this.errors = errors;
   }
 }
+
+// ============================================================================
+// MessagePack
+// ============================================================================
+
+/**
+ * MessagePack codec for the plain values produced by `__serialize`.
+ * `bigint` values are written as 64-bit integers and `Uint8Array` as binary;
+ * integers outside the safe range decode back to `bigint`.
+ */
+export namespace MsgPack {
+  const textEncoder = new TextEncoder();
+  const textDecoder = new TextDecoder();
+
+  export function encode(value: unknown): Uint8Array {
+    const writer = new Writer();
+    writer.write(value);
+    return writer.finish();
+  }
+
+  export function decode(bytes: Uint8Array): unknown {
+    const reader = new Reader(bytes);
+    const value = reader.read();
+    if (reader.offset !== bytes.length) {
+      throw new Error(`MsgPack.decode: ${bytes.length - reader.offset} trailing bytes`);
+    }
+    return value;
+  }
+
+  class Writer {
+    private buf = new Uint8Array(256);
+    private view = new DataView(this.buf.buffer);
+    private len = 0;
+
+    finish(): Uint8Array {
+      return this.buf.slice(0, this.len);
+    }
+
+    private reserve(n: number): number {
+      if (this.len + n > this.buf.length) {
+        let size = this.buf.length * 2;
+        while (size < this.len + n) size *= 2;
+        const next = new Uint8Array(size);
+        next.set(this.buf.subarray(0, this.len));
+        this.buf = next;
+        this.view = new DataView(next.buffer);
+      }
+      const at = this.len;
+      this.len += n;
+      return at;
+    }
+
+    private u8(v: number): void {
+      const at = this.reserve(1);
+      this.view.setUint8(at, v);
+    }
+
+    private u16(v: number): void {
+      const at = this.reserve(2);
+      this.view.setUint16(at, v);
+    }
+
+    private u32(v: number): void {
+      const at = this.reserve(4);
+      this.view.setUint32(at, v);
+    }
+
+    private bytes(v: Uint8Array): void {
+      const at = this.reserve(v.length);
+      this.buf.set(v, at);
+    }
+
+    private header(len: number, fix: number, fixMax: number, b8: number | null, b16: number, b32: number): void {
+      if (len <= fixMax) {
+        this.u8(fix | len);
+      } else if (b8 !== null && len <= 0xff) {
+        this.u8(b8);
+        this.u8(len);
+      } else if (len <= 0xffff) {
+        this.u8(b16);
+        this.u16(len);
+      } else {
+        this.u8(b32);
+        this.u32(len);
+      }
+    }
+
+    write(value: unknown): void {
+      if (value === null || value === undefined) {
+        this.u8(0xc0);
+      } else if (typeof value === "boolean") {
+        this.u8(value ? 0xc3 : 0xc2);
+      } else if (typeof value === "number") {
+        this.number(value);
+      } else if (typeof value === "bigint") {
+        this.bigint(value);
+      } else if (typeof value === "string") {
+        const encoded = textEncoder.encode(value);
+        this.header(encoded.length, 0xa0, 31, 0xd9, 0xda, 0xdb);
+        this.bytes(encoded);
+      } else if (value instanceof Uint8Array) {
+        this.header(value.length, 0, -1, 0xc4, 0xc5, 0xc6);
+        this.bytes(value);
+      } else if (Array.isArray(value)) {
+        this.header(value.length, 0x90, 15, null, 0xdc, 0xdd);
+        for (const item of value) this.write(item);
+      } else if (typeof value === "object") {
+        const json = (value as { toJSON?: () => unknown }).toJSON;
+        if (typeof json === "function") {
+          this.write(json.call(value));
+          return;
+        }
+        const entries = Object.entries(value).filter(([, v]) => v !== undefined);
+        this.header(entries.length, 0x80, 15, null, 0xde, 0xdf);
+        for (const [k, v] of entries) {
+          this.write(k);
+          this.write(v);
+        }
+      } else {
+        throw new Error(`MsgPack.encode: cannot encode ${typeof value}`);
+      }
+    }
+
+    private number(v: number): void {
+      if (!Number.isSafeInteger(v)) {
+        this.u8(0xcb);
+        const at = this.reserve(8);
+        this.view.setFloat64(at, v);
+      } else if (v >= 0) {
+        if (v < 0x80) {
+          this.u8(v);
+        } else if (v <= 0xff) {
+          this.u8(0xcc);
+          this.u8(v);
+        } else if (v <= 0xffff) {
+          this.u8(0xcd);
+          this.u16(v);
+        } else if (v <= 0xffffffff) {
+          this.u8(0xce);
+          this.u32(v);
+        } else {
+          this.bigint(BigInt(v));
+        }
+      } else if (v >= -32) {
+        this.u8(v & 0xff);
+      } else if (v >= -0x80) {
+        this.u8(0xd0);
+        this.u8(v & 0xff);
+      } else if (v >= -0x8000) {
+        this.u8(0xd1);
+        this.u16(v & 0xffff);
+      } else if (v >= -0x80000000) {
+        this.u8(0xd2);
+        this.u32(v >>> 0);
+      } else {
+        this.bigint(BigInt(v));
+      }
+    }
+
+    private bigint(v: bigint): void {
+      if (v >= 0n) {
+        if (v > 0xffffffffffffffffn) throw new Error("MsgPack.encode: bigint exceeds 64 bits");
+        this.u8(0xcf);
+        const at = this.reserve(8);
+        this.view.setBigUint64(at, v);
+      } else {
+        if (v < -0x8000000000000000n) throw new Error("MsgPack.encode: bigint exceeds 64 bits");
+        this.u8(0xd3);
+        const at = this.reserve(8);
+        this.view.setBigInt64(at, v);
+      }
+    }
+  }
+
+  class Reader {
+    offset = 0;
+    private view: DataView;
+
+    constructor(private bytes: Uint8Array) {
+      this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+    }
+
+    private take(n: number): number {
+      if (this.offset + n > this.bytes.length) {
+        throw new Error("MsgPack.decode: unexpected end of input");
+      }
+      const at = this.offset;
+      this.offset += n;
+      return at;
+    }
+
+    private int64(v: bigint): number | bigint {
+      return v >= BigInt(Number.MIN_SAFE_INTEGER) && v <= BigInt(Number.MAX_SAFE_INTEGER) ? Number(v) : v;
+    }
+
+    private str(len: number): string {
+      const at = this.take(len);
+      return textDecoder.decode(this.bytes.subarray(at, at + len));
+    }
+
+    private bin(len: number): Uint8Array {
+      const at = this.take(len);
+      return this.bytes.slice(at, at + len);
+    }
+
+    private array(len: number): unknown[] {
+      const out: unknown[] = [];
+      for (let i = 0; i < len; i++) out.push(this.read());
+      return out;
+    }
+
+    private map(len: number): Record<string, unknown> {
+      const out: Record<string, unknown> = {};
+      for (let i = 0; i < len; i++) {
+        const key = String(this.read());
+        // defineProperty keeps a "__proto__" key an own property instead of
+        // replacing the prototype of the result
+        Object.defineProperty(out, key, {
+          value: this.read(),
+          enumerable: true,
+          writable: true,
+          configurable: true,
+        });
+      }
+      return out;
+    }
+
+    read(): unknown {
+      const v = this.view;
+      const b = v.getUint8(this.take(1));
+      if (b < 0x80) return b;
+      if (b >= 0xe0) return b - 0x100;
+      if (b >= 0xa0 && b <= 0xbf) return this.str(b & 0x1f);
+      if (b >= 0x90 && b <= 0x9f) return this.array(b & 0x0f);
+      if (b >= 0x80 && b <= 0x8f) return this.map(b & 0x0f);
+      switch (b) {
+        case 0xc0: return null;
+        case 0xc2: return false;
+        case 0xc3: return true;
+        case 0xc4: return this.bin(v.getUint8(this.take(1)));
+        case 0xc5: return this.bin(v.getUint16(this.take(2)));
+        case 0xc6: return this.bin(v.getUint32(this.take(4)));
+        case 0xca: return v.getFloat32(this.take(4));
+        case 0xcb: return v.getFloat64(this.take(8));
+        case 0xcc: return v.getUint8(this.take(1));
+        case 0xcd: return v.getUint16(this.take(2));
+        case 0xce: return v.getUint32(this.take(4));
+        case 0xcf: return this.int64(v.getBigUint64(this.take(8)));
+        case 0xd0: return v.getInt8(this.take(1));
+        case 0xd1: return v.getInt16(this.take(2));
+        case 0xd2: return v.getInt32(this.take(4));
+        case 0xd3: return this.int64(v.getBigInt64(this.take(8)));
+        case 0xd9: return this.str(v.getUint8(this.take(1)));
+        case 0xda: return this.str(v.getUint16(this.take(2)));
+        case 0xdb: return this.str(v.getUint32(this.take(4)));
+        case 0xdc: return this.array(v.getUint16(this.take(2)));
+        case 0xdd: return this.array(v.getUint32(this.take(4)));
+        case 0xde: return this.map(v.getUint16(this.take(2)));
+        case 0xdf: return this.map(v.getUint32(this.take(4)));
+        default:
+          throw new Error(`MsgPack.decode: unsupported type byte 0x${b.toString(16)}`);
+      }
+    }
+  }
+}
+
+// ============================================================================
+// Streaming JSON
+// ============================================================================
+
+/**
+ * Incremental reader for a top-level JSON array. Only one element is held in
+ * memory at a time, so arbitrarily large exports can be processed.
+ */
+export namespace JsonStream {
+  /** Yield each element of a JSON array as it is completed in `chunks` */
+  export async function* items(chunks: AsyncIterable<string | Uint8Array>): AsyncGenerator<unknown> {
+    const decoder = new TextDecoder();
+    const scanner = new Scanner();
+    for await (const chunk of chunks) {
+      const text = typeof chunk === "string" ? chunk : decoder.decode(chunk, { stream: true });
+      yield* scanner.push(text);
+    }
+    yield* scanner.push(decoder.decode());
+    scanner.finish();
+  }
+
+  class Scanner {
+    private state: "before" | "first" | "between" | "element" | "done" = "before";
+    private buf = "";
+    private depth = 0;
+    private inString = false;
+    private escaped = false;
+
+    *push(text: string): Generator<unknown> {
+      let start = 0;
+      for (let i = 0; i < text.length; i++) {
+        const ch = text[i];
+        if (this.state === "element") {
+          if (this.inString) {
+            if (this.escaped) this.escaped = false;
+            else if (ch === "\\") this.escaped = true;
+            else if (ch === '"') this.inString = false;
+          } else if (ch === '"') {
+            this.inString = true;
+          } else if (ch === "{" || ch === "[") {
+            this.depth++;
+          } else if (ch === "}" || ch === "]") {
+            if (this.depth === 0 && ch === "]") {
+              yield this.complete(text.slice(start, i));
+              this.state = "done";
+            } else {
+              this.depth--;
+            }
+          } else if (ch === "," && this.depth === 0) {
+            yield this.complete(text.slice(start, i));
+            this.state = "between";
+          }
+        } else if (isWhitespace(ch)) {
+          continue;
+        } else if (this.state === "before") {
+          if (ch !== "[") throw new Error("JsonStream: expected a top-level array");
+          this.state = "first";
+        } else if (this.state === "first" && ch === "]") {
+          this.state = "done";
+        } else if (this.state === "first" || this.state === "between") {
+          if (ch === "]" || ch === ",") throw new Error(`JsonStream: unexpected '${ch}'`);
+          // Re-scan this character as the start of an element
+          this.state = "element";
+          start = i;
+          i--;
+        } else {
+          throw new Error("JsonStream: unexpected data after the array");
+        }
+      }
+      if (this.state === "element") {
+        this.buf += text.slice(start);
+      }
+    }
+
+    finish(): void {
+      if (this.state !== "done") {
+        throw new Error("JsonStream: unexpected end of input");
+      }
+    }
+
+    private complete(tail: string): unknown {
+      const source = this.buf + tail;
+      this.buf = "";
+      return JSON.parse(source);
+    }
+  }
+
+  function isWhitespace(ch: string): boolean {
+    return ch === " " || ch === "\n" || ch === "\r" || ch === "\t";
+  }
+}
@@ -1,6 +1,13 @@
 use anyhow::{Context, Result, anyhow};
 use clap::{Parser, Subcommand};
-use macroforge_ts::host::{MacroExpander, MacroExpansion};
+use macroforge_ts::host::report::{ExpandReport, ReportDiagnostic, TscReport, to_sarif};
+use macroforge_ts::host::{
+    ConsistencyIssue, DoctorReport, MacroConfig, MacroExpander, MacroExpansion,
+    check_consistency, diff_by_macro, explain_line,
+};
+use macroforge_ts::ts_syn::abi::{
+    Diagnostic, DiagnosticLevel, GeneratedRegion, MappingSegment, SourceMapping, SpanIR,
+};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -34,15 +41,68 @@ enum Command {
         /// Suppress output when no macros are found (exit silently with code 2)
         #[arg(long, short = 'q')]
         quiet: bool,
+        /// Verify that every generated runtime member is declared in the type output
+        #[arg(long)]
+        check: bool,
+        /// Show a unified diff of the generated code, grouped by the macro that produced it
+        #[arg(long)]
+        diff: bool,
+        /// Output format for the expansion result and diagnostics
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Explain which macros were applied to the declaration at `<file>:<line>`
+    Explain {
+        /// Target location, e.g. `src/user.ts:12`
+        location: String,
+        /// Use only built-in Rust macros (faster, but no external macro support)
+        #[arg(long)]
+        builtin_only: bool,
     },
+    /// Show the resolved macro packages and registry for the current project
+    Doctor,
     /// Run tsc with macro expansion baked into file reads (tsc --noEmit semantics)
     Tsc {
         /// Path to tsconfig.json (defaults to tsconfig.json in cwd)
         #[arg(long, short = 'p')]
         project: Option<PathBuf>,
+        /// Output format for diagnostics
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// Human-readable output
+    Text,
+    /// Versioned JSON report (see `macroforge_ts::host::report`)
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
+}
+
+/// Output options shared by both expansion paths
+struct ExpandOptions {
+    out: Option<PathBuf>,
+    types_out: Option<PathBuf>,
+    print: bool,
+    quiet: bool,
+    check: bool,
+    diff: bool,
+    format: OutputFormat,
+}
+
+/// Expansion result in the shape needed for machine-readable reports
+struct ExpansionOutput<'a> {
+    source: &'a str,
+    code: &'a str,
+    types: Option<&'a str>,
+    mapping: Option<&'a SourceMapping>,
+    diagnostics: Vec<Diagnostic>,
+    changed: bool,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -54,54 +114,192 @@ fn main() -> Result<()> {
             print,
             builtin_only,
             quiet,
-        } => expand_file(input, out, types_out, print, builtin_only, quiet),
-        Command::Tsc { project } => run_tsc_wrapper(project),
+            check,
+            diff,
+            format,
+        } => {
+            let options = ExpandOptions {
+                out,
+                types_out,
+                print,
+                quiet,
+                check,
+                diff,
+                format,
+            };
+            expand_file(input, builtin_only, options)
+        }
+        Command::Explain {
+            location,
+            builtin_only,
+        } => explain_location(&location, builtin_only),
+        Command::Doctor => run_doctor(),
+        Command::Tsc { project, format } => run_tsc_wrapper(project, format),
     }
 }
 
-fn expand_file(
-    input: PathBuf,
-    out: Option<PathBuf>,
-    types_out: Option<PathBuf>,
-    print: bool,
-    builtin_only: bool,
-    quiet: bool,
-) -> Result<()> {
+fn expand_file(input: PathBuf, builtin_only: bool, options: ExpandOptions) -> Result<()> {
+    if options.diff && options.format != OutputFormat::Text {
+        anyhow::bail!("--diff can only be used with --format text");
+    }
+
     // Default: use Node.js for full macro support (including external macros)
     // With --builtin-only: use fast Rust expander (built-in macros only)
     if !builtin_only {
-        return expand_file_via_node(input, out, types_out, print, quiet);
+        return expand_file_via_node(input, options);
     }
 
-    let expander = MacroExpander::new().context("failed to initialize macro expander")?;
     let source = fs::read_to_string(&input)
         .with_context(|| format!("failed to read {}", input.display()))?;
-
-    let expansion = expander
-        .expand_source(&source, &input.display().to_string())
-        .map_err(|err| anyhow!(format!("{err:?}")))?;
+    let expansion = expand_builtin(&source, &input)?;
+
+    if options.format != OutputFormat::Text {
+        let output = ExpansionOutput {
+            source: &source,
+            code: &expansion.code,
+            types: expansion.type_output.as_deref(),
+            mapping: expansion.source_mapping.as_ref(),
+            diagnostics: expansion.diagnostics.clone(),
+            changed: expansion.changed,
+        };
+        return emit_report(&input, output, &options);
+    }
 
     if !expansion.changed {
-        if !quiet {
+        if !options.quiet {
             eprintln!("[macroforge] no macros found in {}", input.display());
         }
         std::process::exit(2);
     }
 
     emit_diagnostics(&expansion, &source, &input);
-    emit_runtime_output(&expansion, &input, out.as_ref(), print)?;
-    emit_type_output(&expansion, &input, types_out.as_ref(), print)?;
+    if options.diff {
+        let mapping = expansion.source_mapping.clone().unwrap_or_default();
+        print!("{}", diff_by_macro(&source, &expansion.code, &mapping, &input.display().to_string()));
+    }
+    // With --diff the full expanded file is only printed on request
+    if !options.diff || options.out.is_some() || options.print {
+        emit_runtime_output(&expansion, &input, options.out.as_ref(), options.print)?;
+    }
+    emit_type_output(&expansion, &input, options.types_out.as_ref(), options.print)?;
+
+    if options.check {
+        let issues = expansion
+            .check_consistency(&source)
+            .map_err(|err| anyhow!(format!("{err:?}")))?;
+        report_consistency(&issues, &input);
+    }
 
     Ok(())
 }
 
-fn expand_file_via_node(
-    input: PathBuf,
-    out: Option<PathBuf>,
-    types_out: Option<PathBuf>,
-    print: bool,
-    quiet: bool,
-) -> Result<()> {
+fn expand_builtin(source: &str, input: &Path) -> Result<MacroExpansion> {
+    let expander = MacroExpander::new().context("failed to initialize macro expander")?;
+    expander
+        .expand_source(source, &input.display().to_string())
+        .map_err(|err| anyhow!(format!("{err:?}")))
+}
+
+fn expand_file_via_node(input: PathBuf, options: ExpandOptions) -> Result<()> {
+    let result = run_node_expand(&input)?;
+
+    let code = result["code"]
+        .as_str()
+        .ok_or_else(|| anyhow!("missing 'code' in expansion result"))?;
+    let types = result["types"].as_str();
+
+    // Check if any macros were actually expanded by looking at generatedRegions
+    let has_expansions = result["sourceMapping"]
+        .get("generatedRegions")
+        .and_then(|r| r.as_array())
+        .map(|arr| !arr.is_empty())
+        .unwrap_or(false);
+
+    if options.format != OutputFormat::Text {
+        let source = fs::read_to_string(&input)
+            .with_context(|| format!("failed to read {}", input.display()))?;
+        let mapping = mapping_from_json(&result["sourceMapping"]);
+        let output = ExpansionOutput {
+            source: &source,
+            code,
+            types,
+            mapping: has_expansions.then_some(&mapping),
+            diagnostics: diagnostics_from_json(&result["diagnostics"]),
+            changed: has_expansions,
+        };
+        return emit_report(&input, output, &options);
+    }
+
+    if !has_expansions {
+        if !options.quiet {
+            eprintln!("[macroforge] no macros found in {}", input.display());
+        }
+        std::process::exit(2);
+    }
+
+    if options.diff {
+        let source = fs::read_to_string(&input)
+            .with_context(|| format!("failed to read {}", input.display()))?;
+        let mapping = mapping_from_json(&result["sourceMapping"]);
+        print!("{}", diff_by_macro(&source, code, &mapping, &input.display().to_string()));
+    }
+
+    // Write outputs only if macros were expanded
+    if let Some(out_path) = &options.out
+        && has_expansions
+    {
+        write_file(out_path, code)?;
+        println!(
+            "[macroforge] wrote expanded output for {} to {}",
+            input.display(),
+            out_path.display()
+        );
+    }
+
+    if options.print && has_expansions {
+        println!("// --- {} (expanded) ---", input.display());
+        println!("{}", code);
+    }
+
+    if let Some(types_str) = types
+        && has_expansions
+    {
+        if let Some(types_path) = &options.types_out {
+            write_file(types_path, types_str)?;
+            println!(
+                "[macroforge] wrote type output for {} to {}",
+                input.display(),
+                types_path.display()
+            );
+        } else if options.print {
+            println!("// --- {} (.d.ts) ---", input.display());
+            println!("{}", types_str);
+        }
+    }
+
+    // Print diagnostics
+    if let Some(diags) = result["diagnostics"].as_array() {
+        for diag in diags {
+            if let (Some(level), Some(message)) = (diag["level"].as_str(), diag["message"].as_str())
+            {
+                eprintln!("[macroforge] {} at {}: {}", level, input.display(), message);
+            }
+        }
+    }
+
+    if options.check {
+        let source = fs::read_to_string(&input)
+            .with_context(|| format!("failed to read {}", input.display()))?;
+        let issues = check_consistency(code, types.unwrap_or(&source))
+            .map_err(|err| anyhow!(format!("{err:?}")))?;
+        report_consistency(&issues, &input);
+    }
+
+    Ok(())
+}
+
+/// Run `expandSync` from the project's `macroforge` package and return its JSON result
+fn run_node_expand(input: &Path) -> Result<serde_json::Value> {
     let script = r#"
 const { createRequire } = require('module');
 const fs = require('fs');
@@ -138,7 +336,7 @@ try {
 
     let output = std::process::Command::new("node")
         .arg(&script_path)
-        .arg(&input)
+        .arg(input)
         .current_dir(std::env::current_dir()?)
         .output()
         .context("failed to run node expand wrapper")?;
@@ -149,74 +347,167 @@ try {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let result: serde_json::Value =
-        serde_json::from_str(&stdout).context("failed to parse expansion result from node")?;
+    serde_json::from_str(&stdout).context("failed to parse expansion result from node")
+}
 
-    let code = result["code"]
-        .as_str()
-        .ok_or_else(|| anyhow!("missing 'code' in expansion result"))?;
-    let types = result["types"].as_str();
+/// Rebuild a [`SourceMapping`] from the camelCase `sourceMapping` object returned by node
+fn mapping_from_json(value: &serde_json::Value) -> SourceMapping {
+    let field = |v: &serde_json::Value, name: &str| v[name].as_u64().unwrap_or(0) as u32;
+    let mut mapping = SourceMapping::new();
+    for seg in value["segments"].as_array().into_iter().flatten() {
+        mapping.add_segment(MappingSegment::new(
+            field(seg, "originalStart"),
+            field(seg, "originalEnd"),
+            field(seg, "expandedStart"),
+            field(seg, "expandedEnd"),
+        ));
+    }
+    for region in value["generatedRegions"].as_array().into_iter().flatten() {
+        mapping.add_generated(GeneratedRegion::new(
+            field(region, "start"),
+            field(region, "end"),
+            region["sourceMacro"].as_str().unwrap_or("macro"),
+        ));
+    }
+    mapping
+}
 
-    // Check if any macros were actually expanded by looking at generatedRegions
-    let has_expansions = result["sourceMapping"]
-        .get("generatedRegions")
-        .and_then(|r| r.as_array())
-        .map(|arr| !arr.is_empty())
-        .unwrap_or(false);
+/// Rebuild diagnostics from the `diagnostics` array returned by node
+fn diagnostics_from_json(value: &serde_json::Value) -> Vec<Diagnostic> {
+    let strings = |v: &serde_json::Value| {
+        v.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|s| s.as_str().map(str::to_string))
+            .collect::<Vec<_>>()
+    };
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|diag| {
+            let level = match diag["level"].as_str() {
+                Some("error") => DiagnosticLevel::Error,
+                Some("warning") => DiagnosticLevel::Warning,
+                _ => DiagnosticLevel::Info,
+            };
+            let mut diagnostic =
+                Diagnostic::new(level, diag["message"].as_str().unwrap_or_default())
+                    .with_span(diag["start"].as_u64().map(|start| {
+                        let end = diag["end"].as_u64().unwrap_or(start);
+                        SpanIR::new(start as u32, end as u32)
+                    }));
+            diagnostic.notes = strings(&diag["notes"]);
+            diagnostic.help = diag["help"].as_str().map(str::to_string);
+            diagnostic.code = diag["code"].as_str().map(str::to_string);
+            diagnostic
+        })
+        .collect()
+}
 
-    if !has_expansions {
-        if !quiet {
-            eprintln!("[macroforge] no macros found in {}", input.display());
+/// Print an expansion as a JSON report or SARIF log.
+///
+/// Output files are still written; exit codes match text mode (2 when no
+/// macros were found, 1 when `--check` finds issues).
+fn emit_report(input: &Path, mut output: ExpansionOutput<'_>, options: &ExpandOptions) -> Result<()> {
+    let mut check_failed = false;
+    if output.changed {
+        if let Some(path) = &options.out {
+            write_file(path, output.code)?;
+        }
+        if let (Some(path), Some(types)) = (&options.types_out, output.types) {
+            write_file(path, types)?;
+        }
+        if options.check {
+            let issues = check_consistency(output.code, output.types.unwrap_or(output.source))
+                .map_err(|err| anyhow!(format!("{err:?}")))?;
+            check_failed = !issues.is_empty();
+            output
+                .diagnostics
+                .extend(issues.iter().map(ConsistencyIssue::to_diagnostic));
         }
-        std::process::exit(2);
     }
 
-    // Write outputs only if macros were expanded
-    if let Some(out_path) = out
-        && has_expansions
-    {
-        write_file(&out_path, code)?;
-        println!(
-            "[macroforge] wrote expanded output for {} to {}",
-            input.display(),
-            out_path.display()
-        );
-    }
+    let file = input.display().to_string();
+    let report = ExpandReport::new(
+        &file,
+        output.source,
+        output.code.to_string(),
+        output.types.map(str::to_string),
+        output.mapping,
+        &output.diagnostics,
+    );
+    let rendered = match options.format {
+        OutputFormat::Sarif => serde_json::to_string_pretty(&to_sarif(&report.diagnostics))?,
+        _ => serde_json::to_string_pretty(&report)?,
+    };
+    println!("{rendered}");
 
-    if print && has_expansions {
-        println!("// --- {} (expanded) ---", input.display());
-        println!("{}", code);
+    if !output.changed {
+        std::process::exit(2);
     }
-
-    if let Some(types_str) = types
-        && has_expansions
-    {
-        if let Some(types_path) = types_out {
-            write_file(&types_path, types_str)?;
-            println!(
-                "[macroforge] wrote type output for {} to {}",
-                input.display(),
-                types_path.display()
-            );
-        } else if print {
-            println!("// --- {} (.d.ts) ---", input.display());
-            println!("{}", types_str);
-        }
+    if check_failed {
+        std::process::exit(1);
     }
+    Ok(())
+}
 
-    // Print diagnostics
-    if let Some(diags) = result["diagnostics"].as_array() {
-        for diag in diags {
-            if let (Some(level), Some(message)) = (diag["level"].as_str(), diag["message"].as_str())
-            {
-                eprintln!("[macroforge] {} at {}: {}", level, input.display(), message);
-            }
+fn explain_location(location: &str, builtin_only: bool) -> Result<()> {
+    let (file, line) = location
+        .rsplit_once(':')
+        .and_then(|(file, line)| Some((PathBuf::from(file), line.parse::<usize>().ok()?)))
+        .ok_or_else(|| anyhow!("expected <file>:<line>, got `{location}`"))?;
+
+    let source =
+        fs::read_to_string(&file).with_context(|| format!("failed to read {}", file.display()))?;
+    let (code, mapping) = if builtin_only {
+        let expansion = expand_builtin(&source, &file)?;
+        (expansion.code, expansion.source_mapping.unwrap_or_default())
+    } else {
+        let result = run_node_expand(&file)?;
+        let code = result["code"].as_str().unwrap_or(&source).to_string();
+        (code, mapping_from_json(&result["sourceMapping"]))
+    };
+
+    let explanation = explain_line(&source, &code, &mapping, line)
+        .map_err(|err| anyhow!(format!("{err:?}")))?;
+    match explanation {
+        Some(explanation) => {
+            print!("{explanation}");
+            Ok(())
         }
+        None => anyhow::bail!("no @derive target at {}:{}", file.display(), line),
     }
+}
 
+fn run_doctor() -> Result<()> {
+    let (config, root) = match MacroConfig::find_with_root()? {
+        Some(found) => found,
+        None => (MacroConfig::default(), std::env::current_dir()?),
+    };
+    let expander = MacroExpander::with_config(config, root.clone())?;
+    let report = DoctorReport::new(&expander, &root);
+    print!("{report}");
+    if report.has_problems() {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
+/// Print consistency issues and exit with code 1 if there are any
+fn report_consistency(issues: &[ConsistencyIssue], input: &Path) {
+    for issue in issues {
+        eprintln!(
+            "[macroforge] check failed at {}: {}",
+            input.display(),
+            issue.message()
+        );
+    }
+    if !issues.is_empty() {
+        std::process::exit(1);
+    }
+}
+
 fn emit_runtime_output(
     result: &MacroExpansion,
     input: &Path,
@@ -271,7 +562,7 @@ fn write_file(path: &PathBuf, contents: &str) -> Result<()> {
     Ok(())
 }
 
-fn run_tsc_wrapper(project: Option<PathBuf>) -> Result<()> {
+fn run_tsc_wrapper(project: Option<PathBuf>, format: OutputFormat) -> Result<()> {
     // Write a temporary Node.js script that wraps tsc and expands macros on file load
     let script = r#"
 const ts = require('typescript');
@@ -279,6 +570,7 @@ const macros = require('macroforge');
 const path = require('path');
 
 const projectArg = process.argv[2] || 'tsconfig.json';
+const structured = process.argv[3] === 'json';
 const configPath = ts.findConfigFile(process.cwd(), ts.sys.fileExists, projectArg);
 if (!configPath) {
   console.error(`[macroforge] tsconfig not found: ${projectArg}`);
@@ -308,6 +600,9 @@ const formatHost = {
   getNewLine: () => ts.sys.newLine,
 };
 
+// fileName -> { original, expanded } for mapping positions back in structured mode
+const expansions = new Map();
+
 const host = ts.createCompilerHost(options);
 const origGetSourceFile = host.getSourceFile.bind(host);
 host.getSourceFile = (fileName, languageVersion, ...rest) => {
@@ -320,6 +615,7 @@ host.getSourceFile = (fileName, languageVersion, ...rest) => {
       if (sourceText && sourceText.includes('@derive')) {
         const expanded = macros.expandSync(sourceText, fileName);
         const text = expanded.code || sourceText;
+        expansions.set(fileName, { original: sourceText, expanded });
         return ts.createSourceFile(fileName, text, languageVersion, true);
       }
     }
@@ -331,14 +627,101 @@ host.getSourceFile = (fileName, languageVersion, ...rest) => {
 
 const program = ts.createProgram(parsed.fileNames, options, host);
 const diagnostics = ts.getPreEmitDiagnostics(program);
-if (diagnostics.length) {
-  diagnostics.forEach((d) => {
-    const msg = ts.formatDiagnostic(d, formatHost);
-    console.error(msg.trimEnd());
+const hasError = diagnostics.some((d) => d.category === ts.DiagnosticCategory.Error);
+
+if (!structured) {
+  if (diagnostics.length) {
+    diagnostics.forEach((d) => {
+      const msg = ts.formatDiagnostic(d, formatHost);
+      console.error(msg.trimEnd());
+    });
+  }
+  process.exit(hasError ? 1 : 0);
+}
+
+function lineColumn(text, pos) {
+  let line = 1;
+  let column = 1;
+  for (let i = 0; i < pos && i < text.length; i++) {
+    if (text[i] === '\n') {
+      line++;
+      column = 1;
+    } else {
+      column++;
+    }
+  }
+  return { line, column };
+}
+
+// Map an expanded position back to the original file; generated code maps to
+// its insertion point and reports the macro that produced it
+function toOriginal(entry, pos) {
+  const mapping = entry.expanded.sourceMapping;
+  if (!mapping) return { pos };
+  for (const seg of mapping.segments) {
+    if (pos >= seg.expandedStart && pos < seg.expandedEnd) {
+      return { pos: seg.originalStart + (pos - seg.expandedStart) };
+    }
+  }
+  const region = mapping.generatedRegions.find((r) => pos >= r.start && pos < r.end);
+  let insertion = 0;
+  for (const seg of mapping.segments) {
+    if (seg.expandedEnd <= pos) insertion = Math.max(insertion, seg.originalEnd);
+  }
+  return { pos: insertion, macro: region ? region.sourceMacro : undefined };
+}
+
+const levels = {
+  [ts.DiagnosticCategory.Error]: 'error',
+  [ts.DiagnosticCategory.Warning]: 'warning',
+};
+
+const out = [];
+for (const d of diagnostics) {
+  const notes = [];
+  let span = null;
+  const file = d.file ? d.file.fileName : configPath;
+  if (d.file && d.start !== undefined) {
+    const entry = expansions.get(d.file.fileName);
+    if (entry) {
+      const start = toOriginal(entry, d.start);
+      const end = toOriginal(entry, d.start + (d.length || 0));
+      if (start.macro) notes.push(`in code generated by ${start.macro}`);
+      span = { start: lineColumn(entry.original, start.pos), end: lineColumn(entry.original, end.pos) };
+    } else {
+      const text = d.file.text;
+      span = { start: lineColumn(text, d.start), end: lineColumn(text, d.start + (d.length || 0)) };
+    }
+  }
+  out.push({
+    file,
+    level: levels[d.category] || 'info',
+    code: `TS${d.code}`,
+    message: ts.flattenDiagnosticMessageText(d.messageText, '\n'),
+    span,
+    notes,
+    help: null,
   });
 }
-const hasError = diagnostics.some((d) => d.category === ts.DiagnosticCategory.Error);
-process.exit(hasError ? 1 : 0);
+
+for (const [file, entry] of expansions) {
+  for (const d of entry.expanded.diagnostics || []) {
+    out.push({
+      file,
+      level: d.level,
+      code: d.code || 'macroforge',
+      message: d.message,
+      span: d.start === undefined || d.start === null ? null : {
+        start: lineColumn(entry.original, d.start),
+        end: lineColumn(entry.original, d.end === undefined || d.end === null ? d.start : d.end),
+      },
+      notes: d.notes || [],
+      help: d.help || null,
+    });
+  }
+}
+
+console.log(JSON.stringify({ diagnostics: out }));
 "#;
 
     let mut temp_dir = std::env::temp_dir();
@@ -352,16 +735,49 @@ process.exit(hasError ? 1 : 0);
         .to_string_lossy()
         .to_string();
 
-    let status = std::process::Command::new("node")
+    if format == OutputFormat::Text {
+        let status = std::process::Command::new("node")
+            .arg(script_path)
+            .arg(project_arg)
+            .status()
+            .context("failed to run node tsc wrapper")?;
+
+        if !status.success() {
+            anyhow::bail!("tsc wrapper exited with status {}", status);
+        }
+
+        return Ok(());
+    }
+
+    let output = std::process::Command::new("node")
         .arg(script_path)
         .arg(project_arg)
-        .status()
+        .arg("json")
+        .output()
         .context("failed to run node tsc wrapper")?;
 
-    if !status.success() {
-        anyhow::bail!("tsc wrapper exited with status {}", status);
-    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value = match serde_json::from_str(&stdout) {
+        Ok(value) => value,
+        Err(_) => anyhow::bail!(
+            "tsc wrapper exited with status {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ),
+    };
+    let diagnostics: Vec<ReportDiagnostic> = serde_json::from_value(parsed["diagnostics"].clone())
+        .context("failed to parse diagnostics from tsc wrapper")?;
 
+    let has_error = diagnostics.iter().any(ReportDiagnostic::is_error);
+    let rendered = match format {
+        OutputFormat::Sarif => serde_json::to_string_pretty(&to_sarif(&diagnostics))?,
+        _ => serde_json::to_string_pretty(&TscReport::new(diagnostics))?,
+    };
+    println!("{rendered}");
+
+    if has_error {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
@@ -1,31 +1,168 @@
 //! /** @derive(Clone) */ macro implementation
+//!
+//! Generates a deep `clone()` backed by `__clone(ctx)`, which threads a
+//! `CloneContext` through nested values so shared references and cycles are
+//! preserved. Fields can opt out with `@clone(shallow)`, `@clone(skip)` or
+//! `@clone({ with: "fn" })`.
 
+use crate::builtin::derive_common::{has_flag, is_primitive_type, parse_string_literal};
 use crate::macros::{ts_macro_derive, body, ts_template};
+use crate::ts_syn::abi::DecoratorIR;
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
-#[ts_macro_derive(Clone, description = "Generates a clone() method for deep cloning")]
+/// How a field is copied, from its @clone decorator
+#[derive(Debug, PartialEq)]
+enum CloneStrategy {
+    /// Recursively copy (the default)
+    Deep,
+    /// Copy the reference
+    Shallow,
+    /// Leave the field undefined in the clone
+    Skip,
+    /// Call a function `(value) => copy` in scope
+    With(String),
+}
+
+impl CloneStrategy {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut strategy = CloneStrategy::Deep;
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("clone") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if let Some(function) = extract_with(args) {
+                strategy = CloneStrategy::With(function);
+            } else if has_flag(args, "skip") {
+                strategy = CloneStrategy::Skip;
+            } else if has_flag(args, "shallow") {
+                strategy = CloneStrategy::Shallow;
+            }
+        }
+        strategy
+    }
+}
+
+/// The function named by `with: "fn"`, `with = fn` or `with("fn")`. `with`
+/// must be a whole key outside string literals, so `shallowWithDefaults: x`
+/// or `label: "with"` are not read as it.
+fn extract_with(args: &str) -> Option<String> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in args.char_indices() {
+        if let Some(q) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        if matches!(c, '"' | '\'' | '`') {
+            quote = Some(c);
+            continue;
+        }
+        let Some(rest) = args[i..].strip_prefix("with") else {
+            continue;
+        };
+        if args[..i].ends_with(is_ident) || rest.starts_with(is_ident) {
+            continue;
+        }
+        let rest = rest.trim_start();
+        let value = match rest.strip_prefix([':', '=']) {
+            Some(value) => value.trim_start(),
+            None => rest.strip_prefix('(')?.trim_end().strip_suffix(')')?,
+        };
+        if let Some(function) = parse_string_literal(value) {
+            return Some(function);
+        }
+        let name: String = value
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+            .collect();
+        return (!name.is_empty()).then_some(name);
+    }
+    None
+}
+
+/// Field assignment for `__clone`: (field_name, copy expression)
+type CloneAssignment = (String, String);
+
+/// Copy expressions for each cloned field read from `source`, and whether any
+/// of them needs the `cloneValue` runtime helper
+fn clone_assignments<'a>(
+    fields: impl Iterator<Item = (&'a str, &'a str, &'a [DecoratorIR])>,
+    source: &str,
+) -> (Vec<CloneAssignment>, bool) {
+    let mut uses_clone_value = false;
+    let assignments = fields
+        .filter_map(|(name, ts_type, decorators)| {
+            let value = format!("{source}.{name}");
+            let expr = match CloneStrategy::from_decorators(decorators) {
+                CloneStrategy::Skip => return None,
+                CloneStrategy::Shallow => value,
+                CloneStrategy::With(function) => format!("{function}({value})"),
+                CloneStrategy::Deep if is_primitive_type(ts_type) => value,
+                CloneStrategy::Deep => {
+                    uses_clone_value = true;
+                    format!("cloneValue({value}, ctx)")
+                }
+            };
+            Some((name.to_string(), expr))
+        })
+        .collect();
+    (assignments, uses_clone_value)
+}
+
+fn add_clone_imports(result: &mut TsStream, uses_clone_value: bool) {
+    result.add_import("CloneContext", "macroforge/clone");
+    if uses_clone_value {
+        result.add_import("cloneValue", "macroforge/clone");
+    }
+}
+
+#[ts_macro_derive(
+    Clone,
+    description = "Generates a clone() method for deep cloning",
+    attributes((clone, "Configure how this field is cloned. Options: shallow (copy the reference), skip (leave undefined), with (function producing the copy)"))
+)]
 pub fn derive_clone_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
-            let field_names: Vec<&str> = class.field_names().collect();
-            let has_fields = !field_names.is_empty();
+            let (assignments, uses_clone_value) = clone_assignments(
+                class
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+                "this",
+            );
 
-            Ok(body! {
+            let mut result = body! {
                 clone(): @{class_name} {
+                    return this.__clone(CloneContext.create());
+                }
+
+                {>> "@internal" <<}
+                __clone(ctx: CloneContext): @{class_name} {
+                    const existing = ctx.get(this);
+                    if (existing !== undefined) return existing;
                     const cloned = Object.create(Object.getPrototypeOf(this));
+                    ctx.set(this, cloned);
 
-                    {#if has_fields}
-                        {#for field in field_names}
-                            cloned.@{field} = this.@{field};
-                        {/for}
-                    {/if}
+                    {#for (field, expr) in assignments}
+                        cloned.@{field} = @{expr};
+                    {/for}
 
                     return cloned;
                 }
-            })
+            };
+            add_clone_imports(&mut result, uses_clone_value);
+            Ok(result)
         }
         Data::Enum(_) => {
             // Enums are primitive values, cloning is just returning the value
@@ -40,62 +177,145 @@ pub fn derive_clone_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
-            let field_names: Vec<&str> = interface.field_names().collect();
-            let has_fields = !field_names.is_empty();
+            let (assignments, uses_clone_value) = clone_assignments(
+                interface
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+                "self",
+            );
 
-            Ok(ts_template! {
+            let mut result = ts_template! {
                 export namespace @{interface_name} {
                     export function clone(self: @{interface_name}): @{interface_name} {
-                        return {
-                            {#if has_fields}
-                                {#for field in field_names}
-                                    @{field}: self.@{field},
-                                {/for}
-                            {/if}
-                        };
+                        return __clone(self, CloneContext.create());
+                    }
+
+                    export function __clone(self: @{interface_name}, ctx: CloneContext): @{interface_name} {
+                        const existing = ctx.get(self);
+                        if (existing !== undefined) return existing;
+                        const cloned: any = {};
+                        ctx.set(self, cloned);
+
+                        {#for (field, expr) in assignments}
+                            cloned.@{field} = @{expr};
+                        {/for}
+
+                        return cloned;
                     }
                 }
-            })
+            };
+            add_clone_imports(&mut result, uses_clone_value);
+            Ok(result)
         }
         Data::TypeAlias(type_alias) => {
             let type_name = input.name();
 
             if type_alias.is_object() {
-                // Object type: spread copy
-                let field_names: Vec<&str> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .map(|f| f.name.as_str())
-                    .collect();
-                let has_fields = !field_names.is_empty();
+                // Object type: copy field by field
+                let (assignments, uses_clone_value) = clone_assignments(
+                    type_alias
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+                    "value",
+                );
 
-                Ok(ts_template! {
+                let mut result = ts_template! {
                     export namespace @{type_name} {
                         export function clone(value: @{type_name}): @{type_name} {
-                            return {
-                                {#if has_fields}
-                                    {#for field in field_names}
-                                        @{field}: value.@{field},
-                                    {/for}
-                                {/if}
-                            };
+                            return __clone(value, CloneContext.create());
+                        }
+
+                        export function __clone(value: @{type_name}, ctx: CloneContext): @{type_name} {
+                            const existing = ctx.get(value);
+                            if (existing !== undefined) return existing;
+                            const cloned: any = {};
+                            ctx.set(value, cloned);
+
+                            {#for (field, expr) in assignments}
+                                cloned.@{field} = @{expr};
+                            {/for}
+
+                            return cloned;
                         }
                     }
-                })
+                };
+                add_clone_imports(&mut result, uses_clone_value);
+                Ok(result)
             } else {
-                // Union, tuple, or simple alias: use spread for objects, or return as-is
-                Ok(ts_template! {
+                // Union, tuple, or simple alias: deep copy whatever the value is
+                let mut result = ts_template! {
                     export namespace @{type_name} {
                         export function clone(value: @{type_name}): @{type_name} {
-                            if (typeof value === "object" && value !== null) {
-                                return { ...value } as @{type_name};
-                            }
-                            return value;
+                            return cloneValue(value, CloneContext.create());
                         }
                     }
-                })
+                };
+                add_clone_imports(&mut result, true);
+                Ok(result)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn decorator(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "clone".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    #[test]
+    fn test_clone_strategies() {
+        assert_eq!(CloneStrategy::from_decorators(&[]), CloneStrategy::Deep);
+        assert_eq!(CloneStrategy::from_decorators(&[decorator("shallow")]), CloneStrategy::Shallow);
+        assert_eq!(CloneStrategy::from_decorators(&[decorator("skip")]), CloneStrategy::Skip);
+        assert_eq!(
+            CloneStrategy::from_decorators(&[decorator(r#"{ with: "cloneBuffer" }"#)]),
+            CloneStrategy::With("cloneBuffer".into())
+        );
+        assert_eq!(
+            CloneStrategy::from_decorators(&[decorator("with = Buffers.copy")]),
+            CloneStrategy::With("Buffers.copy".into())
+        );
+        assert_eq!(
+            CloneStrategy::from_decorators(&[decorator("{ shallowWithDefaults: true, shallow: true }")]),
+            CloneStrategy::Shallow
+        );
+        assert_eq!(
+            CloneStrategy::from_decorators(&[decorator(r#"{ label: "with: x", with: "copyIt" }"#)]),
+            CloneStrategy::With("copyIt".into())
+        );
+    }
+
+    #[test]
+    fn test_clone_assignments() {
+        let shallow = [decorator("shallow")];
+        let skip = [decorator("skip")];
+        let fields = [
+            ("id", "number", &[][..]),
+            ("tags", "string[]", &[][..]),
+            ("owner", "User", &shallow[..]),
+            ("cache", "Map<string, number>", &skip[..]),
+        ];
+        let (assignments, uses_clone_value) = clone_assignments(fields.into_iter(), "this");
+        assert!(uses_clone_value);
+        assert_eq!(
+            assignments,
+            vec![
+                ("id".to_string(), "this.id".to_string()),
+                ("tags".to_string(), "cloneValue(this.tags, ctx)".to_string()),
+                ("owner".to_string(), "this.owner".to_string()),
+            ]
+        );
+    }
+}
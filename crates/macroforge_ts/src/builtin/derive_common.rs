@@ -7,10 +7,31 @@ use crate::ts_syn::abi::DecoratorIR;
 // ============================================================================
 
 /// Options parsed from field-level decorators for comparison macros
-/// Supports @partialEq(skip), @hash(skip), @ord(skip)
+/// Supports @partialEq(skip), @hash(skip), @ord(skip), plus
+/// @ord({ compareWith, reverse, priority, caseInsensitive, nullsFirst, nullsLast })
+/// and @partialEq({ eqWith, caseInsensitive })
 #[derive(Default, Clone)]
 pub struct CompareFieldOptions {
     pub skip: bool,
+    /// Function `(a, b) => number` replacing the type-based ordering
+    pub compare_with: Option<String>,
+    /// Function `(a, b) => boolean` replacing the type-based equality
+    pub eq_with: Option<String>,
+    /// Invert the ordering of this field (null placement is unaffected)
+    pub reverse: bool,
+    /// Fields with a higher priority are compared first; ties keep declaration order
+    pub priority: i64,
+    /// Compare strings ignoring case
+    pub case_insensitive: bool,
+    /// Where `null`/`undefined` sort relative to other values
+    pub nulls: Option<NullsOrder>,
+}
+
+/// Placement of `null`/`undefined` values when ordering
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NullsOrder {
+    First,
+    Last,
 }
 
 impl CompareFieldOptions {
@@ -25,11 +46,92 @@ impl CompareFieldOptions {
             if has_flag(args, "skip") {
                 opts.skip = true;
             }
+            if let Some(compare_with) = extract_named_string(args, "comparewith") {
+                opts.compare_with = Some(compare_with);
+            }
+            if let Some(eq_with) = extract_named_string(args, "eqwith") {
+                opts.eq_with = Some(eq_with);
+            }
+            if has_flag(args, "reverse") {
+                opts.reverse = true;
+            }
+            if let Some(priority) = extract_named_number(args, "priority") {
+                opts.priority = priority;
+            }
+            if has_flag(args, "caseInsensitive") {
+                opts.case_insensitive = true;
+            }
+            if has_flag(args, "nullsFirst") {
+                opts.nulls = Some(NullsOrder::First);
+            } else if has_flag(args, "nullsLast") {
+                opts.nulls = Some(NullsOrder::Last);
+            }
         }
         opts
     }
 }
 
+/// Keep the fields that take part in a comparison, ordered by descending
+/// `priority` (stable, so equal priorities keep declaration order)
+pub fn ordered_compare_fields<T>(fields: impl Iterator<Item = (T, CompareFieldOptions)>) -> Vec<(T, CompareFieldOptions)> {
+    let mut fields: Vec<_> = fields.filter(|(_, opts)| !opts.skip).collect();
+    fields.sort_by_key(|(_, opts)| std::cmp::Reverse(opts.priority));
+    fields
+}
+
+/// Apply @ord options to a field comparison expression.
+///
+/// `base` compares `a` and `b` by type; `total` selects Ord semantics (plain
+/// numbers) over PartialOrd semantics (`null` when incomparable).
+pub fn apply_compare_options(base: String, opts: &CompareFieldOptions, a: &str, b: &str, total: bool) -> String {
+    let mut expr = if let Some(compare_with) = &opts.compare_with {
+        format!("{compare_with}({a}, {b})")
+    } else if opts.case_insensitive {
+        format!(
+            "(typeof {a} === 'string' && typeof {b} === 'string' \
+                ? (cmp => cmp < 0 ? -1 : cmp > 0 ? 1 : 0)({a}.localeCompare({b}, undefined, {{ sensitivity: 'accent' }})) \
+                : {base})"
+        )
+    } else {
+        base
+    };
+
+    if opts.reverse {
+        expr = if total {
+            format!("(-{expr})")
+        } else {
+            format!("((cmp: number | null) => cmp === null ? null : -cmp)({expr})")
+        };
+    }
+
+    if let Some(nulls) = opts.nulls {
+        let (before, after) = match nulls {
+            NullsOrder::First => ("-1", "1"),
+            NullsOrder::Last => ("1", "-1"),
+        };
+        expr = format!(
+            "({a} == null || {b} == null \
+                ? ({a} == null ? ({b} == null ? 0 : {before}) : {after}) \
+                : {expr})"
+        );
+    }
+    expr
+}
+
+/// Apply @partialEq options to a field equality expression comparing `a` and `b`
+pub fn apply_eq_options(base: String, opts: &CompareFieldOptions, a: &str, b: &str) -> String {
+    if let Some(eq_with) = &opts.eq_with {
+        format!("{eq_with}({a}, {b})")
+    } else if opts.case_insensitive {
+        format!(
+            "({a} === {b} || (typeof {a} === 'string' && typeof {b} === 'string' \
+                && {a}.localeCompare({b}, undefined, {{ sensitivity: 'accent' }}) === 0))"
+        )
+    } else {
+        base
+    }
+}
+
 // ============================================================================
 // Field Options for Default Macro
 // ============================================================================
@@ -164,6 +266,7 @@ pub fn has_flag(args: &str, flag: &str) -> bool {
 
 fn flag_explicit_false(args: &str, flag: &str) -> bool {
     let lower = args.to_ascii_lowercase();
+    let flag = flag.to_ascii_lowercase();
     let condensed: String = lower.chars().filter(|c| !c.is_whitespace()).collect();
     condensed.contains(&format!("{flag}:false")) || condensed.contains(&format!("{flag}=false"))
 }
@@ -189,7 +292,46 @@ pub fn extract_named_string(args: &str, name: &str) -> Option<String> {
     None
 }
 
-fn parse_string_literal(input: &str) -> Option<String> {
+fn extract_named_number(args: &str, name: &str) -> Option<i64> {
+    let lower = args.to_ascii_lowercase();
+    let idx = lower.find(name)?;
+    let remainder = args[idx + name.len()..].trim_start();
+    let value = remainder.strip_prefix(':').or_else(|| remainder.strip_prefix('='))?.trim_start();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].parse().ok()
+}
+
+/// True if `name` can be written as a bare property name
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || matches!(c, '_' | '$'))
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '$'))
+}
+
+/// `name` as an object literal property, quoted unless it is an identifier
+pub fn property_key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+/// `object.name`, or `object["name"]` when `name` is not an identifier
+pub fn member_access(object: &str, name: &str) -> String {
+    if is_identifier(name) {
+        format!("{object}.{name}")
+    } else {
+        format!("{object}[{name:?}]")
+    }
+}
+
+pub fn parse_string_literal(input: &str) -> Option<String> {
     let trimmed = input.trim();
     let mut chars = trimmed.chars();
     let quote = chars.next()?;
@@ -260,6 +402,68 @@ mod tests {
         assert!(!opts.skip);
     }
 
+    #[test]
+    fn test_compare_field_ordering_options() {
+        let decorator = make_decorator(
+            "ord",
+            r#"{ compareWith: "compareVersions", reverse: true, priority: -2, nullsLast: true }"#,
+        );
+        let opts = CompareFieldOptions::from_decorators(&[decorator], "ord");
+        assert_eq!(opts.compare_with.as_deref(), Some("compareVersions"));
+        assert!(opts.reverse);
+        assert_eq!(opts.priority, -2);
+        assert_eq!(opts.nulls, Some(NullsOrder::Last));
+        assert!(!opts.case_insensitive);
+    }
+
+    #[test]
+    fn test_compare_field_camel_case_flags_false() {
+        let decorator = make_decorator(
+            "ord",
+            "{ caseInsensitive: false, nullsFirst: false, nullsLast: false }",
+        );
+        let opts = CompareFieldOptions::from_decorators(&[decorator], "ord");
+        assert!(!opts.case_insensitive);
+        assert_eq!(opts.nulls, None);
+
+        let decorator = make_decorator("ord", "{ nullsFirst: false, nullsLast: true }");
+        let opts = CompareFieldOptions::from_decorators(&[decorator], "ord");
+        assert_eq!(opts.nulls, Some(NullsOrder::Last));
+    }
+
+    #[test]
+    fn test_ordered_compare_fields_by_priority() {
+        let fields = vec![
+            ("name", CompareFieldOptions::default()),
+            ("id", CompareFieldOptions { skip: true, ..Default::default() }),
+            ("rank", CompareFieldOptions { priority: 1, ..Default::default() }),
+            ("email", CompareFieldOptions::default()),
+        ];
+        let names: Vec<_> = ordered_compare_fields(fields.into_iter())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["rank", "name", "email"]);
+    }
+
+    #[test]
+    fn test_apply_compare_options() {
+        let opts = CompareFieldOptions {
+            reverse: true,
+            nulls: Some(NullsOrder::First),
+            ..Default::default()
+        };
+        let expr = apply_compare_options("base".into(), &opts, "a.x", "b.x", true);
+        assert!(expr.contains("(-base)"), "{expr}");
+        assert!(expr.starts_with("(a.x == null || b.x == null"), "{expr}");
+
+        let opts = CompareFieldOptions {
+            eq_with: Some("sameDay".into()),
+            ..Default::default()
+        };
+        assert_eq!(apply_eq_options("base".into(), &opts, "a.d", "b.d"), "sameDay(a.d, b.d)");
+    }
+
     #[test]
     fn test_default_field_with_string_value() {
         let decorator = make_decorator("default", r#""hello""#);
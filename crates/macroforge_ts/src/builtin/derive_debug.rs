@@ -1,5 +1,6 @@
 //! /** @derive(Debug) */ macro implementation
 
+use crate::builtin::serde::RenameAll;
 use crate::macros::{ts_macro_derive, body, ts_template};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
@@ -8,6 +9,8 @@ use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro
 struct DebugFieldOptions {
     skip: bool,
     rename: Option<String>,
+    /// Print `***` instead of the value
+    redact: bool,
 }
 
 impl DebugFieldOptions {
@@ -27,6 +30,10 @@ impl DebugFieldOptions {
                 opts.skip = true;
             }
 
+            if has_flag(args, "redact") {
+                opts.redact = true;
+            }
+
             if let Some(rename) = extract_named_string(args, "rename") {
                 opts.rename = Some(rename);
             }
@@ -35,6 +42,89 @@ impl DebugFieldOptions {
     }
 }
 
+/// Options parsed from a @debug decorator on the type itself, layered over
+/// `macroOptions.Debug`. Setting any of them switches to structured output,
+/// formatted by the `macroforge/debug` runtime
+#[derive(Default, Debug, PartialEq)]
+struct DebugContainerOptions {
+    /// Multi-line output, one field per line
+    pretty: bool,
+    /// Spaces per nesting level in pretty mode
+    indent: Option<u64>,
+    /// Nesting levels printed before abbreviating to `[Name]`
+    depth: Option<u64>,
+    /// Structured output without other formatting changes, for cycle safety
+    cycles: bool,
+    /// Generate `toJSONDebug()`
+    json: bool,
+    /// Generate `[util.inspect.custom]` (classes only)
+    inspect: bool,
+}
+
+impl DebugContainerOptions {
+    fn with_defaults(defaults: &serde_json::Value, decorators: &[crate::ts_syn::abi::DecoratorIR]) -> Self {
+        let flag = |name: &str| defaults.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+        let mut opts = DebugContainerOptions {
+            pretty: flag("pretty"),
+            indent: defaults.get("indent").and_then(|v| v.as_u64()),
+            depth: defaults.get("depth").and_then(|v| v.as_u64()),
+            cycles: flag("cycles"),
+            json: flag("json"),
+            inspect: flag("inspect"),
+        };
+
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("debug") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+
+            for (name, value) in [
+                ("pretty", &mut opts.pretty),
+                ("cycles", &mut opts.cycles),
+                ("json", &mut opts.json),
+                ("inspect", &mut opts.inspect),
+            ] {
+                if flag_explicit_false(args, name) {
+                    *value = false;
+                } else if has_flag(args, name) {
+                    *value = true;
+                }
+            }
+            if let Some(indent) = extract_named_number(args, "indent") {
+                opts.indent = Some(indent);
+            }
+            if let Some(depth) = extract_named_number(args, "depth") {
+                opts.depth = Some(depth);
+            }
+        }
+        opts
+    }
+
+    fn is_structured(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// `DebugOptions` object literal passed to the runtime
+    fn to_ts_options(&self) -> String {
+        let mut entries = Vec::new();
+        if self.pretty {
+            entries.push("pretty: true".to_string());
+        }
+        if let Some(indent) = self.indent {
+            entries.push(format!("indent: {indent}"));
+        }
+        if let Some(depth) = self.depth {
+            entries.push(format!("depth: {depth}"));
+        }
+        if entries.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", entries.join(", "))
+        }
+    }
+}
+
 fn has_flag(args: &str, flag: &str) -> bool {
     if flag_explicit_false(args, flag) {
         return false;
@@ -71,6 +161,19 @@ fn extract_named_string(args: &str, name: &str) -> Option<String> {
     None
 }
 
+fn extract_named_number(args: &str, name: &str) -> Option<u64> {
+    let lower = args.to_ascii_lowercase();
+    let idx = lower.find(name)?;
+    let remainder = args[idx + name.len()..].trim_start();
+    let value = remainder.strip_prefix(':').or_else(|| remainder.strip_prefix('='))?;
+    let digits: String = value
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
 fn parse_string_literal(input: &str) -> Option<String> {
     let trimmed = input.trim();
     let mut chars = trimmed.chars();
@@ -99,43 +202,113 @@ fn parse_string_literal(input: &str) -> Option<String> {
     None
 }
 
-/// Debug field info: (label, field_name)
-type DebugField = (String, String);
+/// Debug field info: (label, field_name, redacted)
+type DebugField = (String, String, bool);
+
+fn debug_field(
+    name: &str,
+    decorators: &[crate::ts_syn::abi::DecoratorIR],
+    rename_all: RenameAll,
+) -> Option<DebugField> {
+    let opts = DebugFieldOptions::from_decorators(decorators);
+    if opts.skip {
+        return None;
+    }
+    let label = opts.rename.unwrap_or_else(|| rename_all.apply(name));
+    Some((label, name.to_string(), opts.redact))
+}
+
+/// `[label, value, redacted]` entries of a `DebugStruct`, reading fields from `target`
+fn debug_struct_fields(fields: &[DebugField], target: &str) -> String {
+    let entries: Vec<String> = fields
+        .iter()
+        .map(|(label, name, redacted)| {
+            let label = serde_json::to_string(label).unwrap_or_default();
+            if *redacted {
+                format!("[{label}, undefined, true]")
+            } else {
+                format!("[{label}, {target}.{name}]")
+            }
+        })
+        .collect();
+    format!("[{}]", entries.join(", "))
+}
 
 #[ts_macro_derive(
     Debug,
     description = "Generates a toString() method for debugging",
-    attributes((debug, "Configure debug output for this field. Options: skip (exclude from output), rename (custom label)"))
+    attributes((debug, "Configure debug output. Field options: skip (exclude from output), rename (custom label), redact (print ***). Type options: pretty, indent, depth, cycles, json, inspect"))
 )]
 pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
+    // Project-wide label convention from `macroOptions.Debug.renameAll`;
+    // a field's own `rename` still wins
+    let rename_all = input
+        .context
+        .option_str("renameAll")
+        .and_then(RenameAll::from_str)
+        .unwrap_or_default();
 
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
+            let container =
+                DebugContainerOptions::with_defaults(&input.context.macro_options, &class.inner.decorators);
 
             // Collect fields that should be included in debug output
             let debug_fields: Vec<DebugField> = class
                 .fields()
                 .iter()
-                .filter_map(|field| {
-                    let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                    if opts.skip {
-                        return None;
-                    }
-                    let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                    Some((label, field.name.clone()))
-                })
+                .filter_map(|field| debug_field(&field.name, &field.decorators, rename_all))
                 .collect();
 
+            if container.is_structured() {
+                let fields_array = debug_struct_fields(&debug_fields, "this");
+                let options = container.to_ts_options();
+                let json = container.json;
+                let inspect = container.inspect;
+
+                let mut result = body! {
+                    __debug(): DebugStruct {
+                        return { name: "@{class_name}", fields: @{fields_array} };
+                    }
+
+                    toString(): string {
+                        return formatDebug(this, @{options});
+                    }
+
+                    {#if json}
+                        toJSONDebug(): unknown {
+                            return debugJSON(this, @{options});
+                        }
+                    {/if}
+
+                    {#if inspect}
+                        [Symbol.for("nodejs.util.inspect.custom")](): string {
+                            return this.toString();
+                        }
+                    {/if}
+                };
+                result.add_import("formatDebug", "macroforge/debug");
+                if json {
+                    result.add_import("debugJSON", "macroforge/debug");
+                }
+                result.add_type_import("DebugStruct", "macroforge/debug");
+                return Ok(result);
+            }
+
             let has_fields = !debug_fields.is_empty();
 
             Ok(body! {
                 toString(): string {
                     {#if has_fields}
                         const parts: string[] = [];
-                        {#for (label, name) in debug_fields}
-                            parts.push("@{label}: " + this.@{name});
+                        {#for (label, name, redacted) in debug_fields}
+                            {#if redacted}
+                                parts.push("@{label}: ***");
+                            {:else}
+                                parts.push("@{label}: " + this.@{name});
+                            {/if}
                         {/for}
                         return "@{class_name} { " + parts.join(", ") + " }";
                     {:else}
@@ -168,21 +341,20 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
+            let container =
+                DebugContainerOptions::with_defaults(&input.context.macro_options, &interface.inner.decorators);
 
             // Collect fields that should be included in debug output
             let debug_fields: Vec<DebugField> = interface
                 .fields()
                 .iter()
-                .filter_map(|field| {
-                    let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                    if opts.skip {
-                        return None;
-                    }
-                    let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                    Some((label, field.name.clone()))
-                })
+                .filter_map(|field| debug_field(&field.name, &field.decorators, rename_all))
                 .collect();
 
+            if container.is_structured() {
+                return Ok(structured_namespace(interface_name, &debug_fields, &container));
+            }
+
             let has_fields = !debug_fields.is_empty();
 
             Ok(ts_template! {
@@ -190,8 +362,12 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
                     export function toString(self: @{interface_name}): string {
                         {#if has_fields}
                             const parts: string[] = [];
-                            {#for (label, name) in debug_fields}
-                                parts.push("@{label}: " + self.@{name});
+                            {#for (label, name, redacted) in debug_fields}
+                                {#if redacted}
+                                    parts.push("@{label}: ***");
+                                {:else}
+                                    parts.push("@{label}: " + self.@{name});
+                                {/if}
                             {/for}
                             return "@{interface_name} { " + parts.join(", ") + " }";
                         {:else}
@@ -206,21 +382,23 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
 
             // Generate different output based on type body
             if type_alias.is_object() {
+                let container = DebugContainerOptions::with_defaults(
+                    &input.context.macro_options,
+                    &type_alias.inner.decorators,
+                );
+
                 // Object type: show fields
                 let debug_fields: Vec<DebugField> = type_alias
                     .as_object()
                     .unwrap()
                     .iter()
-                    .filter_map(|field| {
-                        let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                        if opts.skip {
-                            return None;
-                        }
-                        let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                        Some((label, field.name.clone()))
-                    })
+                    .filter_map(|field| debug_field(&field.name, &field.decorators, rename_all))
                     .collect();
 
+                if container.is_structured() {
+                    return Ok(structured_namespace(type_name, &debug_fields, &container));
+                }
+
                 let has_fields = !debug_fields.is_empty();
 
                 Ok(ts_template! {
@@ -228,8 +406,12 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
                         export function toString(value: @{type_name}): string {
                             {#if has_fields}
                                 const parts: string[] = [];
-                                {#for (label, name) in debug_fields}
-                                    parts.push("@{label}: " + value.@{name});
+                                {#for (label, name, redacted) in debug_fields}
+                                    {#if redacted}
+                                        parts.push("@{label}: ***");
+                                    {:else}
+                                        parts.push("@{label}: " + value.@{name});
+                                    {/if}
                                 {/for}
                                 return "@{type_name} { " + parts.join(", ") + " }";
                             {:else}
@@ -252,6 +434,39 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
     }
 }
 
+/// Structured Debug output for interfaces and object type aliases, which
+/// have no methods, so nested values are formatted as plain objects
+fn structured_namespace(type_name: &str, debug_fields: &[DebugField], container: &DebugContainerOptions) -> TsStream {
+    let fields_array = debug_struct_fields(debug_fields, "self");
+    let options = container.to_ts_options();
+    let json = container.json;
+
+    let mut result = ts_template! {
+        export namespace @{type_name} {
+            export function __debug(self: @{type_name}): DebugStruct {
+                return { name: "@{type_name}", fields: @{fields_array} };
+            }
+
+            export function toString(self: @{type_name}): string {
+                return formatDebug(debugStruct("@{type_name}", __debug(self).fields), @{options});
+            }
+
+            {#if json}
+                export function toJSONDebug(self: @{type_name}): unknown {
+                    return debugJSON(debugStruct("@{type_name}", __debug(self).fields), @{options});
+                }
+            {/if}
+        }
+    };
+    result.add_import("formatDebug", "macroforge/debug");
+    result.add_import("debugStruct", "macroforge/debug");
+    if json {
+        result.add_import("debugJSON", "macroforge/debug");
+    }
+    result.add_type_import("DebugStruct", "macroforge/debug");
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,4 +514,49 @@ mod tests {
         let opts = DebugFieldOptions::from_decorators(&[decorator]);
         assert_eq!(opts.rename.as_deref(), Some("identifier"));
     }
+
+    #[test]
+    fn test_redact_option() {
+        let decorator = DecoratorIR {
+            name: "debug".into(),
+            args_src: "redact".into(),
+            span: span(),
+            node: None,
+        };
+
+        let opts = DebugFieldOptions::from_decorators(&[decorator]);
+        assert!(opts.redact);
+        assert!(!opts.skip);
+    }
+
+    #[test]
+    fn test_container_options_layer_over_defaults() {
+        let decorator = DecoratorIR {
+            name: "debug".into(),
+            args_src: "{ pretty: false, indent: 4, json: true }".into(),
+            span: span(),
+            node: None,
+        };
+        let defaults = serde_json::json!({ "pretty": true, "depth": 3 });
+
+        let opts = DebugContainerOptions::with_defaults(&defaults, &[decorator]);
+        assert_eq!(
+            opts,
+            DebugContainerOptions {
+                pretty: false,
+                indent: Some(4),
+                depth: Some(3),
+                json: true,
+                ..Default::default()
+            }
+        );
+        assert!(opts.is_structured());
+        assert_eq!(opts.to_ts_options(), "{ indent: 4, depth: 3 }");
+    }
+
+    #[test]
+    fn test_no_container_options_keeps_inline_output() {
+        let opts = DebugContainerOptions::with_defaults(&serde_json::Value::Null, &[]);
+        assert!(!opts.is_structured());
+    }
 }
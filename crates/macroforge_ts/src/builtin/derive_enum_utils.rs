@@ -0,0 +1,208 @@
+//! /** @derive(EnumUtils) */ macro implementation
+//!
+//! Generates everyday helpers for enums and string-literal unions in the
+//! type's namespace: `values()`, `parse()`, `is()`, `next()`, `prev()` and an
+//! exhaustive `match()` whose `Handlers<R>` type needs one handler per member.
+//! Enums additionally get `keys()`.
+
+use crate::builtin::derive_common::{member_access, property_key};
+use crate::macros::{ts_macro_derive, ts_template};
+use crate::ts_syn::abi::TypeMember;
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// `"A" | "B"`, or `never` when there are no members
+fn literal_union(names: &[String]) -> String {
+    if names.is_empty() {
+        "never".to_string()
+    } else {
+        names.iter().map(|n| format!("{n:?}")).collect::<Vec<_>>().join(" | ")
+    }
+}
+
+/// Literal source of each member if the union consists only of string literals
+fn string_literal_members(members: &[TypeMember]) -> Option<Vec<String>> {
+    members
+        .iter()
+        .map(|m| {
+            m.as_literal()
+                .map(str::trim)
+                .filter(|lit| lit.starts_with('"') || lit.starts_with('\''))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+#[ts_macro_derive(
+    EnumUtils,
+    description = "Generates values(), keys(), parse(), is(), next(), prev() and an exhaustive match() for enums and string-literal unions"
+)]
+pub fn derive_enum_utils_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+
+    match &input.data {
+        Data::Enum(enum_data) => {
+            let enum_name = input.name();
+            let names: Vec<String> = enum_data.variants().iter().map(|v| v.name.clone()).collect();
+            let key_type = literal_union(&names);
+            // (quoted key, member access, handler property)
+            let members: Vec<(String, String, String)> = names
+                .iter()
+                .map(|name| (format!("{name:?}"), member_access(enum_name, name), property_key(name)))
+                .collect();
+
+            let mut result = ts_template! {
+                export namespace @{enum_name} {
+                    export type Key = @{key_type};
+
+                    export type Handlers<R> = {
+                        {#for (_, _, handler) in &members}
+                            @{handler}: () => R;
+                        {/for}
+                    };
+
+                    const entries: Array<[Key, @{enum_name}]> = [
+                        {#for (key, access, _) in &members}
+                            [@{key}, @{access}],
+                        {/for}
+                    ];
+
+                    export function values(): @{enum_name}[] {
+                        return entries.map((entry) => entry[1]);
+                    }
+
+                    export function keys(): Key[] {
+                        return entries.map((entry) => entry[0]);
+                    }
+
+                    export function is(value: unknown): value is @{enum_name} {
+                        return entries.some((entry) => entry[1] === value);
+                    }
+
+                    export function parse(str: string): Result<@{enum_name}, string> {
+                        const byValue = entries.find((entry) => String(entry[1]) === str);
+                        if (byValue) return Result.ok(byValue[1]);
+                        const byKey = entries.find((entry) => entry[0] === str);
+                        if (byKey) return Result.ok(byKey[1]);
+                        return Result.err("Unknown @{enum_name}: " + JSON.stringify(str));
+                    }
+
+                    export function next(value: @{enum_name}): @{enum_name} | undefined {
+                        const index = entries.findIndex((entry) => entry[1] === value);
+                        return index < 0 || index + 1 >= entries.length ? undefined : entries[index + 1][1];
+                    }
+
+                    export function prev(value: @{enum_name}): @{enum_name} | undefined {
+                        const index = entries.findIndex((entry) => entry[1] === value);
+                        return index <= 0 ? undefined : entries[index - 1][1];
+                    }
+
+                    export function match<R>(value: @{enum_name}, handlers: Handlers<R>): R {
+                        switch (value) {
+                            {#for (_, access, handler) in &members}
+                                case @{access}: return handlers.@{handler}();
+                            {/for}
+                        }
+                        throw new Error("Unhandled @{enum_name}: " + String(value));
+                    }
+                }
+            };
+            result.add_import("Result", "macroforge/utils");
+            Ok(result)
+        }
+        Data::TypeAlias(type_alias) => {
+            let type_name = input.name();
+            let Some(literals) = type_alias.as_union().and_then(string_literal_members) else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    "/** @derive(EnumUtils) */ type aliases must be a union of string literals",
+                ));
+            };
+
+            let mut result = ts_template! {
+                export namespace @{type_name} {
+                    export type Handlers<R> = {
+                        {#for literal in &literals}
+                            @{literal}: () => R;
+                        {/for}
+                    };
+
+                    const members: Array<@{type_name}> = [
+                        {#for literal in &literals}
+                            @{literal},
+                        {/for}
+                    ];
+
+                    export function values(): @{type_name}[] {
+                        return members.slice();
+                    }
+
+                    export function is(value: unknown): value is @{type_name} {
+                        return members.includes(value as @{type_name});
+                    }
+
+                    export function parse(str: string): Result<@{type_name}, string> {
+                        return is(str) ? Result.ok(str) : Result.err("Unknown @{type_name}: " + JSON.stringify(str));
+                    }
+
+                    export function next(value: @{type_name}): @{type_name} | undefined {
+                        const index = members.indexOf(value);
+                        return index < 0 || index + 1 >= members.length ? undefined : members[index + 1];
+                    }
+
+                    export function prev(value: @{type_name}): @{type_name} | undefined {
+                        const index = members.indexOf(value);
+                        return index <= 0 ? undefined : members[index - 1];
+                    }
+
+                    export function match<R>(value: @{type_name}, handlers: Handlers<R>): R {
+                        const handler = handlers[value];
+                        if (typeof handler !== "function") {
+                            throw new Error("Unhandled @{type_name}: " + String(value));
+                        }
+                        return handler();
+                    }
+                }
+            };
+            result.add_import("Result", "macroforge/utils");
+            Ok(result)
+        }
+        Data::Class(_) | Data::Interface(_) => Err(MacroforgeError::new(
+            input.decorator_span(),
+            "/** @derive(EnumUtils) */ can only be applied to enums and string-literal union types",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::TypeMemberKind;
+
+    #[test]
+    fn test_property_key_and_member_access() {
+        assert_eq!(property_key("Active"), "Active");
+        assert_eq!(property_key("in-progress"), "\"in-progress\"");
+        assert_eq!(member_access("Status", "Active"), "Status.Active");
+        assert_eq!(member_access("Status", "in-progress"), "Status[\"in-progress\"]");
+    }
+
+    #[test]
+    fn test_literal_union() {
+        assert_eq!(literal_union(&[]), "never");
+        assert_eq!(literal_union(&["A".into(), "B".into()]), "\"A\" | \"B\"");
+    }
+
+    #[test]
+    fn test_string_literal_members() {
+        let literal = |s: &str| TypeMember::new(TypeMemberKind::Literal(s.into()));
+        assert_eq!(
+            string_literal_members(&[literal("\"north\""), literal("'south'")]),
+            Some(vec!["\"north\"".to_string(), "'south'".to_string()])
+        );
+        assert_eq!(string_literal_members(&[literal("\"north\""), literal("42")]), None);
+        assert_eq!(
+            string_literal_members(&[TypeMember::new(TypeMemberKind::TypeRef("User".into()))]),
+            None
+        );
+    }
+}
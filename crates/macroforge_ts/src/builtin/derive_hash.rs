@@ -1,10 +1,12 @@
 //! /** @derive(Hash) */ macro implementation
 //!
 //! Generates a `hashCode()` method for hashing.
-//! Supports @hash(skip) decorator on fields to exclude them from the hash.
+//! Supports @hash(skip) decorator on fields to exclude them from the hash, and
+//! `@hash({ algorithm })` on the type to stream fields through `hashInto(hasher)`.
 
-use crate::builtin::derive_common::{is_primitive_type, CompareFieldOptions};
+use crate::builtin::derive_common::{extract_named_string, is_primitive_type, CompareFieldOptions};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::DecoratorIR;
 use crate::ts_syn::{parse_ts_macro_input, Data, DeriveInput, MacroforgeError, TsStream};
 
 /// Field info for hashing: (field_name, ts_type)
@@ -13,89 +15,74 @@ struct HashField {
     ts_type: String,
 }
 
-/// Generate hash contribution code for a single field (class method version)
-fn generate_field_hash(field: &HashField) -> String {
-    let field_name = &field.name;
-    let ts_type = &field.ts_type;
+/// Hash algorithms provided by the `macroforge/hash` runtime
+const HASH_ALGORITHMS: [&str; 3] = ["java", "fnv1a", "xxhash32"];
 
-    if is_primitive_type(ts_type) {
-        match ts_type.as_str() {
-            "number" => {
-                // For numbers, use bit manipulation if integer, otherwise hash string
-                format!(
-                    "(Number.isInteger(this.{field_name}) \
-                        ? this.{field_name} | 0 \
-                        : this.{field_name}.toString().split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0))"
-                )
+/// Options parsed from a @hash decorator on the type itself, layered over
+/// `macroOptions.Hash`
+#[derive(Default, Debug, PartialEq)]
+struct HashContainerOptions {
+    /// Selecting an algorithm generates `hashInto(hasher)` backed by the
+    /// `macroforge/hash` runtime instead of the self-contained `hashCode()`
+    algorithm: Option<String>,
+}
+
+impl HashContainerOptions {
+    fn with_defaults(defaults: &serde_json::Value, decorators: &[DecoratorIR]) -> Self {
+        let mut opts = HashContainerOptions {
+            algorithm: defaults.get("algorithm").and_then(|v| v.as_str()).map(str::to_string),
+        };
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("hash") {
+                continue;
             }
-            "bigint" => {
-                // For bigint, convert to string and hash
-                format!(
-                    "this.{field_name}.toString().split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0)"
-                )
+            if let Some(algorithm) = extract_named_string(decorator.args_src.trim(), "algorithm") {
+                opts.algorithm = Some(algorithm);
             }
-            "string" => {
-                // For strings, hash each character
+        }
+        opts
+    }
+
+    /// The selected algorithm, if any, rejecting names the runtime doesn't know
+    fn algorithm(&self, input: &DeriveInput) -> Result<Option<&str>, MacroforgeError> {
+        match self.algorithm.as_deref() {
+            Some(algorithm) if !HASH_ALGORITHMS.contains(&algorithm) => Err(MacroforgeError::new(
+                input.decorator_span(),
                 format!(
-                    "(this.{field_name} ?? '').split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0)"
-                )
-            }
-            "boolean" => {
-                // For booleans, use 1 for true, 0 for false
-                format!("(this.{field_name} ? 1231 : 1237)")
-            }
-            _ => {
-                // null/undefined
-                format!("(this.{field_name} != null ? 1 : 0)")
-            }
+                    "@derive(Hash): unknown algorithm \"{algorithm}\", expected one of: {}",
+                    HASH_ALGORITHMS.join(", ")
+                ),
+            )),
+            algorithm => Ok(algorithm),
         }
-    } else if ts_type.ends_with("[]") || ts_type.starts_with("Array<") {
-        // For arrays, hash each element and combine
-        format!(
-            "(Array.isArray(this.{field_name}) \
-                ? this.{field_name}.reduce((h, v) => \
-                    (h * 31 + (typeof (v as any)?.hashCode === 'function' \
-                        ? (v as any).hashCode() \
-                        : (v != null ? String(v).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) : 0))) | 0, 0) \
-                : 0)"
-        )
-    } else if ts_type == "Date" {
-        // For Date, hash the timestamp
-        format!(
-            "(this.{field_name} instanceof Date ? this.{field_name}.getTime() | 0 : 0)"
-        )
-    } else if ts_type.starts_with("Map<") {
-        // For Map, hash entries
-        format!(
-            "(this.{field_name} instanceof Map \
-                ? Array.from(this.{field_name}.entries()).reduce((h, [k, v]) => \
-                    (h * 31 + String(k).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) + \
-                    (typeof (v as any)?.hashCode === 'function' ? (v as any).hashCode() : 0)) | 0, 0) \
-                : 0)"
-        )
-    } else if ts_type.starts_with("Set<") {
-        // For Set, hash elements
-        format!(
-            "(this.{field_name} instanceof Set \
-                ? Array.from(this.{field_name}).reduce((h, v) => \
-                    (h * 31 + (typeof (v as any)?.hashCode === 'function' \
-                        ? (v as any).hashCode() \
-                        : (v != null ? String(v).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) : 0))) | 0, 0) \
-                : 0)"
-        )
-    } else {
-        // For objects, check for hashCode method first
-        format!(
-            "(typeof (this.{field_name} as any)?.hashCode === 'function' \
-                ? (this.{field_name} as any).hashCode() \
-                : (this.{field_name} != null \
-                    ? JSON.stringify(this.{field_name}).split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0) \
-                    : 0))"
-        )
     }
 }
 
-/// Generate hash contribution code for interface/type alias fields
+fn collect_hash_fields<'a>(fields: impl Iterator<Item = (&'a str, &'a str, &'a [DecoratorIR])>) -> Vec<HashField> {
+    fields
+        .filter(|(_, _, decorators)| !CompareFieldOptions::from_decorators(decorators, "hash").skip)
+        .map(|(name, ts_type, _)| HashField {
+            name: name.to_string(),
+            ts_type: ts_type.to_string(),
+        })
+        .collect()
+}
+
+/// `hashValue(hasher, ...)` statements streaming each field into `hasher`
+fn hash_into_body(fields: &[HashField], var: &str) -> String {
+    fields
+        .iter()
+        .map(|f| format!("hashValue(hasher, {var}.{});", f.name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Generate hash contribution code for a single field (class method version)
+fn generate_field_hash(field: &HashField) -> String {
+    generate_field_hash_for_interface(field, "this")
+}
+
+/// Generate hash contribution code for a field read from `var`
 fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String {
     let field_name = &field.name;
     let ts_type = &field.ts_type;
@@ -103,6 +90,7 @@ fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String {
     if is_primitive_type(ts_type) {
         match ts_type.as_str() {
             "number" => {
+                // For numbers, use bit manipulation if integer, otherwise hash string
                 format!(
                     "(Number.isInteger({var}.{field_name}) \
                         ? {var}.{field_name} | 0 \
@@ -110,23 +98,28 @@ fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String {
                 )
             }
             "bigint" => {
+                // For bigint, convert to string and hash
                 format!(
                     "{var}.{field_name}.toString().split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0)"
                 )
             }
             "string" => {
+                // For strings, hash each character
                 format!(
                     "({var}.{field_name} ?? '').split('').reduce((h, c) => (h * 31 + c.charCodeAt(0)) | 0, 0)"
                 )
             }
             "boolean" => {
+                // For booleans, use 1 for true, 0 for false
                 format!("({var}.{field_name} ? 1231 : 1237)")
             }
             _ => {
+                // null/undefined
                 format!("({var}.{field_name} != null ? 1 : 0)")
             }
         }
     } else if ts_type.ends_with("[]") || ts_type.starts_with("Array<") {
+        // For arrays, hash each element and combine
         format!(
             "(Array.isArray({var}.{field_name}) \
                 ? {var}.{field_name}.reduce((h, v) => \
@@ -136,27 +129,35 @@ fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String {
                 : 0)"
         )
     } else if ts_type == "Date" {
+        // For Date, hash the timestamp
         format!(
             "({var}.{field_name} instanceof Date ? {var}.{field_name}.getTime() | 0 : 0)"
         )
     } else if ts_type.starts_with("Map<") {
+        // For Map, sum the entry hashes so insertion order doesn't matter
         format!(
             "({var}.{field_name} instanceof Map \
                 ? Array.from({var}.{field_name}.entries()).reduce((h, [k, v]) => \
-                    (h * 31 + String(k).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) + \
-                    (typeof (v as any)?.hashCode === 'function' ? (v as any).hashCode() : 0)) | 0, 0) \
+                    (h + ((String(k).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) * 31 + \
+                    (typeof (v as any)?.hashCode === 'function' \
+                        ? (v as any).hashCode() \
+                        : (v != null ? String(v).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) : 0))) | 0)) | 0, \
+                    {var}.{field_name}.size) \
                 : 0)"
         )
     } else if ts_type.starts_with("Set<") {
+        // For Set, sum the element hashes so insertion order doesn't matter
         format!(
             "({var}.{field_name} instanceof Set \
                 ? Array.from({var}.{field_name}).reduce((h, v) => \
-                    (h * 31 + (typeof (v as any)?.hashCode === 'function' \
+                    (h + (typeof (v as any)?.hashCode === 'function' \
                         ? (v as any).hashCode() \
-                        : (v != null ? String(v).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) : 0))) | 0, 0) \
+                        : (v != null ? String(v).split('').reduce((hh, c) => (hh * 31 + c.charCodeAt(0)) | 0, 0) : 0))) | 0, \
+                    {var}.{field_name}.size) \
                 : 0)"
         )
     } else {
+        // For objects, check for hashCode method first
         format!(
             "(typeof ({var}.{field_name} as any)?.hashCode === 'function' \
                 ? ({var}.{field_name} as any).hashCode() \
@@ -167,31 +168,66 @@ fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String {
     }
 }
 
+/// `hashInto`/`hashCode` namespace functions for interfaces and object type aliases
+fn hash_into_namespace(type_name: &str, hash_fields: &[HashField], algorithm: &str) -> TsStream {
+    let hash_into = hash_into_body(hash_fields, "self");
+    let mut result = ts_template! {
+        export namespace @{type_name} {
+            export function hashInto(self: @{type_name}, hasher: Hasher): void {
+                @{hash_into}
+            }
+
+            export function hashCode(self: @{type_name}): number {
+                const hasher = createHasher("@{algorithm}");
+                hashInto(self, hasher);
+                return hasher.finish();
+            }
+        }
+    };
+    result.add_import("createHasher", "macroforge/hash");
+    result.add_import("hashValue", "macroforge/hash");
+    result.add_type_import("Hasher", "macroforge/hash");
+    result
+}
+
 #[ts_macro_derive(
     Hash,
     description = "Generates a hashCode() method for hashing",
-    attributes(hash)
+    attributes((hash, "Field option: skip (exclude from the hash). Type option: algorithm (java, fnv1a or xxhash32) to generate hashInto(hasher)"))
 )]
 pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
     match &input.data {
         Data::Class(class) => {
+            let container = HashContainerOptions::with_defaults(&input.context.macro_options, &class.inner.decorators);
+
             // Collect fields that should be included in hash
-            let hash_fields: Vec<HashField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                    if opts.skip {
-                        return None;
+            let hash_fields = collect_hash_fields(
+                class
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+            );
+
+            if let Some(algorithm) = container.algorithm(&input)? {
+                let hash_into = hash_into_body(&hash_fields, "this");
+                let mut result = body! {
+                    hashInto(hasher: Hasher): void {
+                        @{hash_into}
                     }
-                    Some(HashField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+
+                    hashCode(): number {
+                        const hasher = createHasher("@{algorithm}");
+                        this.hashInto(hasher);
+                        return hasher.finish();
+                    }
+                };
+                result.add_import("createHasher", "macroforge/hash");
+                result.add_import("hashValue", "macroforge/hash");
+                result.add_type_import("Hasher", "macroforge/hash");
+                return Ok(result);
+            }
 
             let has_fields = !hash_fields.is_empty();
 
@@ -237,21 +273,19 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
+            let container =
+                HashContainerOptions::with_defaults(&input.context.macro_options, &interface.inner.decorators);
 
-            let hash_fields: Vec<HashField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(HashField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let hash_fields = collect_hash_fields(
+                interface
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+            );
+
+            if let Some(algorithm) = container.algorithm(&input)? {
+                return Ok(hash_into_namespace(interface_name, &hash_fields, algorithm));
+            }
 
             let has_fields = !hash_fields.is_empty();
 
@@ -286,21 +320,20 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
             let type_name = input.name();
 
             if type_alias.is_object() {
-                let hash_fields: Vec<HashField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(HashField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
+                let container =
+                    HashContainerOptions::with_defaults(&input.context.macro_options, &type_alias.inner.decorators);
+
+                let hash_fields = collect_hash_fields(
+                    type_alias
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .map(|f| (f.name.as_str(), f.ts_type.as_str(), f.decorators.as_slice())),
+                );
+
+                if let Some(algorithm) = container.algorithm(&input)? {
+                    return Ok(hash_into_namespace(type_name, &hash_fields, algorithm));
+                }
 
                 let has_fields = !hash_fields.is_empty();
 
@@ -353,6 +386,7 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
 mod tests {
     use super::*;
     use crate::macros::body;
+    use crate::ts_syn::abi::SpanIR;
 
     #[test]
     fn test_hash_macro_output() {
@@ -445,4 +479,55 @@ mod tests {
         let result = generate_field_hash(&field);
         assert!(result.contains("hashCode"));
     }
+
+    #[test]
+    fn test_field_hash_set_is_order_independent() {
+        let field = HashField {
+            name: "tags".to_string(),
+            ts_type: "Set<string>".to_string(),
+        };
+        let result = generate_field_hash(&field);
+        // Element hashes are summed rather than chained through `h * 31`
+        assert!(result.contains("(h + "), "{}", result);
+        assert!(!result.contains("(h * 31 + (typeof"), "{}", result);
+    }
+
+    #[test]
+    fn test_container_algorithm_overrides_default() {
+        let decorator = DecoratorIR {
+            name: "hash".into(),
+            args_src: r#"{ algorithm: "xxhash32" }"#.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        let defaults = serde_json::json!({ "algorithm": "fnv1a" });
+
+        assert_eq!(
+            HashContainerOptions::with_defaults(&defaults, &[]).algorithm.as_deref(),
+            Some("fnv1a")
+        );
+        assert_eq!(
+            HashContainerOptions::with_defaults(&defaults, &[decorator]).algorithm.as_deref(),
+            Some("xxhash32")
+        );
+        assert_eq!(HashContainerOptions::with_defaults(&serde_json::Value::Null, &[]).algorithm, None);
+    }
+
+    #[test]
+    fn test_hash_into_body_streams_fields() {
+        let fields = vec![
+            HashField {
+                name: "id".to_string(),
+                ts_type: "number".to_string(),
+            },
+            HashField {
+                name: "child".to_string(),
+                ts_type: "Node".to_string(),
+            },
+        ];
+        assert_eq!(
+            hash_into_body(&fields, "this"),
+            "hashValue(hasher, this.id);\nhashValue(hasher, this.child);"
+        );
+    }
 }
@@ -0,0 +1,1073 @@
+//! /** @derive(Mock) */ macro implementation
+//!
+//! Generates `arbitrary(seed?)`, a seeded factory for test data built from the
+//! field types and their `@serde` validators, and `arbitraryInvalid(seed?)`,
+//! which returns one instance per validator it knows how to break, each failing
+//! only that check. Fields of other types call that type's `arbitrary`, and
+//! anything the generator can't produce takes `@mock({ with: "fn" })`.
+
+use crate::builtin::derive_common::{extract_named_string, has_flag};
+use crate::builtin::serde::derive_deserialize::get_validator_code;
+use crate::builtin::serde::{SerdeContainerOptions, SerdeFieldOptions, Validator};
+use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, InterfaceFieldIR, LiteralTypeIR, TypeIR, TypeMember};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Options from a field's @mock decorator
+#[derive(Debug, Default)]
+struct MockFieldOptions {
+    /// Leave the field unset
+    skip: bool,
+    /// Function `(rng) => value` in scope that generates the field
+    with: Option<String>,
+}
+
+impl MockFieldOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("mock") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if let Some(function) = extract_named_string(args, "with") {
+                opts.with = Some(function);
+            } else if has_flag(args, "skip") {
+                opts.skip = true;
+            }
+        }
+        opts
+    }
+}
+
+/// Field info for mocking
+struct MockField {
+    field_name: String,
+    /// Key reported in deserialization errors
+    json_key: String,
+    /// Expression generating a valid value from `rng`; `None` leaves the field unset
+    arbitrary: Option<String>,
+    /// Set on only some instances
+    optional: bool,
+    /// (validator code, expression for a value failing only that validator)
+    invalid: Vec<(&'static str, String)>,
+}
+
+/// One entry of `arbitraryInvalid()`
+struct InvalidCase {
+    field_name: String,
+    json_key: String,
+    code: &'static str,
+    value: String,
+}
+
+/// Mock info for each field, plus `name (reason)` for the required fields no
+/// valid value can be generated for
+fn mock_fields<'a>(
+    fields: impl Iterator<Item = (&'a str, &'a str, &'a TypeIR, bool, &'a [DecoratorIR])>,
+    container_opts: &SerdeContainerOptions,
+    type_params: &[String],
+) -> (Vec<MockField>, Vec<String>) {
+    let mut missing = Vec::new();
+    let fields = fields
+        .map(|(name, ts_type, type_ir, optional, decorators)| {
+            let mock_opts = MockFieldOptions::from_decorators(decorators);
+            let serde_opts = SerdeFieldOptions::from_decorators(decorators);
+            let validators: Vec<&Validator> = serde_opts
+                .validators
+                .iter()
+                .map(|spec| &spec.validator)
+                .collect();
+
+            // `requiredIf`/`requiredUnless` fields are always set so the condition can't fail
+            let optional = optional
+                && !validators.iter().any(|v| {
+                    matches!(v, Validator::RequiredIf(..) | Validator::RequiredUnless(..))
+                });
+            let unmockable = validators
+                .iter()
+                .find(|v| matches!(v, Validator::Pattern(_) | Validator::Custom(_)));
+
+            let arbitrary = if let Some(function) = &mock_opts.with {
+                Some(format!("{function}(rng)"))
+            } else if mock_opts.skip {
+                None
+            } else if let Some(validator) = unmockable {
+                if !optional {
+                    missing.push(format!(
+                        "{name} ({} validator)",
+                        get_validator_code(validator)
+                    ));
+                }
+                None
+            } else if let Some(Validator::OneOf(values)) =
+                validators.iter().find(|v| matches!(v, Validator::OneOf(_)))
+            {
+                Some(format!("rng.pick([{}] as const)", values.join(", ")))
+            } else {
+                let arbitrary = arbitrary_expr(type_ir, &validators, type_params);
+                if arbitrary.is_none() && !optional {
+                    missing.push(format!("{name} ({ts_type})"));
+                }
+                arbitrary
+            };
+
+            let invalid = if serde_opts.should_deserialize() && arbitrary.is_some() {
+                validators
+                    .iter()
+                    .filter_map(|v| {
+                        let value = invalid_expr(v, &validators, type_ir, type_params)?;
+                        Some((get_validator_code(v), value))
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            MockField {
+                field_name: name.to_string(),
+                json_key: serde_opts
+                    .rename
+                    .clone()
+                    .unwrap_or_else(|| container_opts.rename_all.apply(name)),
+                arbitrary,
+                optional,
+                invalid,
+            }
+        })
+        .collect();
+    (fields, missing)
+}
+
+/// Expression generating a value of `ty` from `rng` that passes `validators`
+fn arbitrary_expr(
+    ty: &TypeIR,
+    validators: &[&Validator],
+    type_params: &[String],
+) -> Option<String> {
+    if let Some(element) = ty.array_element() {
+        let item = arbitrary_expr(element, &[], type_params)?;
+        return Some(format!(
+            "Mock.array(rng, {}{})",
+            thunk(&item),
+            constraints_arg(&items_constraints(validators))
+        ));
+    }
+
+    match ty {
+        TypeIR::Keyword { name } => match name.as_str() {
+            "string" => Some(format!(
+                "Mock.string(rng{})",
+                constraints_arg(&string_constraints(validators))
+            )),
+            "number" => Some(format!(
+                "Mock.number(rng{})",
+                constraints_arg(&number_constraints(validators))
+            )),
+            "bigint" => Some(format!(
+                "Mock.bigint(rng{})",
+                constraints_arg(&bigint_constraints(validators))
+            )),
+            "boolean" => Some("rng.bool()".to_string()),
+            "null" | "any" | "unknown" => Some("null".to_string()),
+            "undefined" | "void" => Some("undefined".to_string()),
+            "object" => Some("{}".to_string()),
+            _ => None,
+        },
+        TypeIR::Literal {
+            value: LiteralTypeIR::Template(_),
+        } => None,
+        TypeIR::Literal { .. } => Some(format!("{ty} as const")),
+        TypeIR::Union { types } => {
+            let members = types
+                .iter()
+                .map(|member| arbitrary_expr(member, validators, type_params))
+                .collect::<Option<Vec<_>>>()?;
+            if types
+                .iter()
+                .all(|member| matches!(member, TypeIR::Literal { .. }))
+            {
+                let literals: Vec<String> = types.iter().map(TypeIR::to_string).collect();
+                return Some(format!("rng.pick([{}] as const)", literals.join(", ")));
+            }
+            let members: Vec<String> = members.iter().map(|m| thunk(m)).collect();
+            Some(format!("Mock.union(rng, [{}])", members.join(", ")))
+        }
+        TypeIR::Tuple { elements } if elements.iter().all(|e| !e.optional && !e.rest) => {
+            let elements = elements
+                .iter()
+                .map(|e| arbitrary_expr(&e.ty, &[], type_params))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("[{}] as {ty}", elements.join(", ")))
+        }
+        TypeIR::Operator { op, operand } if op == "readonly" => {
+            arbitrary_expr(operand, validators, type_params)
+        }
+        TypeIR::Object {
+            members,
+            index_signatures,
+        } if index_signatures.is_empty() => {
+            let entries = members
+                .iter()
+                .map(|m| {
+                    let value = arbitrary_expr(&m.ty, &[], type_params)?;
+                    Some((m.name.as_str(), value, m.optional))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(object_expr(entries))
+        }
+        TypeIR::Reference { name, type_args } => match (name.as_str(), type_args.as_slice()) {
+            (param, []) if type_params.iter().any(|p| p == param) => {
+                Some(format!("arbitraries.{param}(rng)"))
+            }
+            ("Date", []) => Some(format!(
+                "Mock.date(rng{})",
+                constraints_arg(&date_constraints(validators))
+            )),
+            ("Uint8Array", []) => Some("Mock.bytes(rng)".to_string()),
+            ("ArrayBuffer", []) => Some("(Mock.bytes(rng).buffer as ArrayBuffer)".to_string()),
+            ("URL", []) => Some(r#"new URL(Mock.string(rng, { format: "url" }))"#.to_string()),
+            ("RegExp", []) => Some("new RegExp(Mock.string(rng))".to_string()),
+            ("Set", [item]) => Some(format!(
+                "Mock.set(rng, {}{})",
+                thunk(&arbitrary_expr(item, &[], type_params)?),
+                constraints_arg(&items_constraints(validators))
+            )),
+            ("Map", [key, value]) => Some(format!(
+                "Mock.map(rng, {}, {}{})",
+                thunk(&arbitrary_expr(key, &[], type_params)?),
+                thunk(&arbitrary_expr(value, &[], type_params)?),
+                constraints_arg(&items_constraints(validators))
+            )),
+            ("Record", [key, value]) if key.is_keyword("string") => Some(format!(
+                "Mock.record(rng, {}{})",
+                thunk(&arbitrary_expr(value, &[], type_params)?),
+                constraints_arg(&items_constraints(validators))
+            )),
+            // Types of other derives; `Temporal.*` has no `arbitrary` to call
+            (name, []) if !name.starts_with("Temporal.") => Some(format!("{name}.arbitrary(rng)")),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Generator closure over `rng` for `expr`, for the collection and union helpers
+fn thunk(expr: &str) -> String {
+    if expr.starts_with('{') {
+        format!("() => ({expr})")
+    } else {
+        format!("() => {expr}")
+    }
+}
+
+/// Object literal that sets each optional entry on only some values
+fn object_expr(entries: Vec<(&str, String, bool)>) -> String {
+    let entries: Vec<String> = entries
+        .into_iter()
+        .map(|(key, value, optional)| {
+            if optional {
+                format!("...(rng.bool() ? {{ \"{key}\": {value} }} : {{}})")
+            } else {
+                format!("\"{key}\": {value}")
+            }
+        })
+        .collect();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+/// Generator for a union type alias member, written as source
+fn union_member_expr(member: &TypeMember, type_params: &[String]) -> Option<String> {
+    if let Some(literal) = member.as_literal() {
+        return Some(format!("{} as const", literal.trim()));
+    }
+    if let Some(fields) = member.as_object() {
+        let entries = fields
+            .iter()
+            .map(|f| {
+                let value = arbitrary_expr(&f.type_ir, &[], type_params)?;
+                Some((f.name.as_str(), value, f.optional))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        return Some(object_expr(entries));
+    }
+    let name = member.type_name()?.trim();
+    let ty = match name {
+        "string" | "number" | "bigint" | "boolean" | "null" | "undefined" => TypeIR::keyword(name),
+        _ if name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.')) =>
+        {
+            TypeIR::reference(name, vec![])
+        }
+        _ => return None,
+    };
+    arbitrary_expr(&ty, &[], type_params)
+}
+
+/// Runtime constraints as `(key, JS value)`, in validator order
+type Constraints = Vec<(&'static str, String)>;
+
+/// Set `key`, replacing any earlier value and the keys it contradicts
+fn set_constraint(
+    constraints: &mut Constraints,
+    key: &'static str,
+    value: String,
+    replaces: &[&str],
+) {
+    constraints.retain(|(k, _)| *k != key && !replaces.contains(k));
+    constraints.push((key, value));
+}
+
+/// `, { key: value }` for a generator call, empty without constraints
+fn constraints_arg(constraints: &Constraints) -> String {
+    if constraints.is_empty() {
+        return String::new();
+    }
+    let entries: Vec<String> = constraints
+        .iter()
+        .map(|(k, v)| format!("{k}: {v}"))
+        .collect();
+    format!(", {{ {} }}", entries.join(", "))
+}
+
+fn string_constraints(validators: &[&Validator]) -> Constraints {
+    let mut constraints = Constraints::new();
+    for validator in validators {
+        match validator {
+            Validator::Email => {
+                set_constraint(&mut constraints, "format", r#""email""#.into(), &[])
+            }
+            Validator::Url => set_constraint(&mut constraints, "format", r#""url""#.into(), &[]),
+            Validator::Uuid => set_constraint(&mut constraints, "format", r#""uuid""#.into(), &[]),
+            Validator::MaxLength(n) => {
+                set_constraint(&mut constraints, "maxLength", n.to_string(), &[])
+            }
+            Validator::MinLength(n) => {
+                set_constraint(&mut constraints, "minLength", n.to_string(), &[])
+            }
+            Validator::Length(n) => {
+                set_constraint(&mut constraints, "minLength", n.to_string(), &[]);
+                set_constraint(&mut constraints, "maxLength", n.to_string(), &[]);
+            }
+            Validator::LengthRange(min, max) => {
+                set_constraint(&mut constraints, "minLength", min.to_string(), &[]);
+                set_constraint(&mut constraints, "maxLength", max.to_string(), &[]);
+            }
+            Validator::NonEmpty if !constraints.iter().any(|(k, _)| *k == "minLength") => {
+                set_constraint(&mut constraints, "minLength", "1".into(), &[]);
+            }
+            Validator::Lowercase => {
+                set_constraint(&mut constraints, "case", r#""lower""#.into(), &[])
+            }
+            Validator::Uppercase => {
+                set_constraint(&mut constraints, "case", r#""upper""#.into(), &[])
+            }
+            Validator::Capitalized => {
+                set_constraint(&mut constraints, "case", r#""capitalized""#.into(), &[])
+            }
+            Validator::Uncapitalized => {
+                set_constraint(&mut constraints, "case", r#""uncapitalized""#.into(), &[])
+            }
+            Validator::StartsWith(s) => {
+                set_constraint(&mut constraints, "startsWith", format!("{s:?}"), &[])
+            }
+            Validator::EndsWith(s) => {
+                set_constraint(&mut constraints, "endsWith", format!("{s:?}"), &[])
+            }
+            Validator::Includes(s) => {
+                set_constraint(&mut constraints, "includes", format!("{s:?}"), &[])
+            }
+            _ => {}
+        }
+    }
+    constraints
+}
+
+fn number_constraints(validators: &[&Validator]) -> Constraints {
+    let mut constraints = Constraints::new();
+    for validator in validators {
+        match validator {
+            Validator::GreaterThan(n) => {
+                set_constraint(&mut constraints, "gt", n.to_string(), &["gte"])
+            }
+            Validator::GreaterThanOrEqualTo(n) => {
+                set_constraint(&mut constraints, "gte", n.to_string(), &["gt"])
+            }
+            Validator::LessThan(n) => {
+                set_constraint(&mut constraints, "lt", n.to_string(), &["lte"])
+            }
+            Validator::LessThanOrEqualTo(n) => {
+                set_constraint(&mut constraints, "lte", n.to_string(), &["lt"])
+            }
+            Validator::Between(min, max) => {
+                set_constraint(&mut constraints, "gte", min.to_string(), &["gt"]);
+                set_constraint(&mut constraints, "lte", max.to_string(), &["lt"]);
+            }
+            Validator::Positive => set_constraint(&mut constraints, "gt", "0".into(), &["gte"]),
+            Validator::NonNegative => set_constraint(&mut constraints, "gte", "0".into(), &["gt"]),
+            Validator::Negative => set_constraint(&mut constraints, "lt", "0".into(), &["lte"]),
+            Validator::NonPositive => set_constraint(&mut constraints, "lte", "0".into(), &["lt"]),
+            Validator::Int => set_constraint(&mut constraints, "int", "true".into(), &[]),
+            Validator::MultipleOf(n) => {
+                set_constraint(&mut constraints, "multipleOf", n.to_string(), &[])
+            }
+            Validator::Uint8 => {
+                set_constraint(&mut constraints, "gte", "0".into(), &["gt"]);
+                set_constraint(&mut constraints, "lte", "255".into(), &["lt"]);
+                set_constraint(&mut constraints, "int", "true".into(), &[]);
+            }
+            _ => {}
+        }
+    }
+    constraints
+}
+
+fn bigint_constraints(validators: &[&Validator]) -> Constraints {
+    let mut constraints = Constraints::new();
+    for validator in validators {
+        match validator {
+            Validator::GreaterThanBigInt(n) => {
+                set_constraint(&mut constraints, "gt", format!("BigInt({n})"), &["gte"])
+            }
+            Validator::GreaterThanOrEqualToBigInt(n) => {
+                set_constraint(&mut constraints, "gte", format!("BigInt({n})"), &["gt"])
+            }
+            Validator::LessThanBigInt(n) => {
+                set_constraint(&mut constraints, "lt", format!("BigInt({n})"), &["lte"])
+            }
+            Validator::LessThanOrEqualToBigInt(n) => {
+                set_constraint(&mut constraints, "lte", format!("BigInt({n})"), &["lt"])
+            }
+            Validator::BetweenBigInt(min, max) => {
+                set_constraint(&mut constraints, "gte", format!("BigInt({min})"), &["gt"]);
+                set_constraint(&mut constraints, "lte", format!("BigInt({max})"), &["lt"]);
+            }
+            Validator::PositiveBigInt => {
+                set_constraint(&mut constraints, "gt", "0n".into(), &["gte"])
+            }
+            Validator::NonNegativeBigInt => {
+                set_constraint(&mut constraints, "gte", "0n".into(), &["gt"])
+            }
+            Validator::NegativeBigInt => {
+                set_constraint(&mut constraints, "lt", "0n".into(), &["lte"])
+            }
+            Validator::NonPositiveBigInt => {
+                set_constraint(&mut constraints, "lte", "0n".into(), &["lt"])
+            }
+            _ => {}
+        }
+    }
+    constraints
+}
+
+fn date_constraints(validators: &[&Validator]) -> Constraints {
+    let mut constraints = Constraints::new();
+    for validator in validators {
+        match validator {
+            Validator::GreaterThanDate(d) => {
+                set_constraint(&mut constraints, "gt", format!("\"{d}\""), &["gte"])
+            }
+            Validator::GreaterThanOrEqualToDate(d) => {
+                set_constraint(&mut constraints, "gte", format!("\"{d}\""), &["gt"])
+            }
+            Validator::LessThanDate(d) => {
+                set_constraint(&mut constraints, "lt", format!("\"{d}\""), &["lte"])
+            }
+            Validator::LessThanOrEqualToDate(d) => {
+                set_constraint(&mut constraints, "lte", format!("\"{d}\""), &["lt"])
+            }
+            Validator::BetweenDate(min, max) => {
+                set_constraint(&mut constraints, "gte", format!("\"{min}\""), &["gt"]);
+                set_constraint(&mut constraints, "lte", format!("\"{max}\""), &["lt"]);
+            }
+            _ => {}
+        }
+    }
+    constraints
+}
+
+fn items_constraints(validators: &[&Validator]) -> Constraints {
+    let mut constraints = Constraints::new();
+    for validator in validators {
+        match validator {
+            Validator::MaxItems(n) => {
+                set_constraint(&mut constraints, "maxItems", n.to_string(), &[])
+            }
+            Validator::MinItems(n) => {
+                set_constraint(&mut constraints, "minItems", n.to_string(), &[])
+            }
+            Validator::ItemsCount(n) => {
+                set_constraint(&mut constraints, "minItems", n.to_string(), &[]);
+                set_constraint(&mut constraints, "maxItems", n.to_string(), &[]);
+            }
+            _ => {}
+        }
+    }
+    constraints
+}
+
+/// Whether `ty`, ignoring `null`/`undefined`, has a member matching `pred`
+fn accepts(ty: &TypeIR, pred: impl Fn(&TypeIR) -> bool) -> bool {
+    ty.union_members().iter().any(|member| match member {
+        TypeIR::Operator { op, operand } if op == "readonly" => pred(operand),
+        _ => pred(member),
+    })
+}
+
+/// A string of exactly `len` characters that still meets the other string
+/// validators where it can (an email needs at least 7, a URL 15, a UUID 36)
+fn string_of_length(others: &[&Validator], len: usize) -> String {
+    let mut constraints = string_constraints(others);
+    let keeps_format = constraints.iter().any(|(key, value)| {
+        *key == "format"
+            && match value.as_str() {
+                r#""email""# => len >= 7,
+                r#""url""# => len >= 15,
+                _ => false,
+            }
+    });
+    if !keeps_format {
+        constraints.retain(|(key, _)| *key != "format");
+    }
+    set_constraint(&mut constraints, "minLength", len.to_string(), &[]);
+    set_constraint(&mut constraints, "maxLength", len.to_string(), &[]);
+    format!("Mock.string(rng{})", constraints_arg(&constraints))
+}
+
+/// A one-letter string that doesn't start with, end with or contain `s`
+fn string_avoiding(s: &str) -> String {
+    if s == "x" { r#""y""# } else { r#""x""# }.to_string()
+}
+
+/// Expression for a value of the field's type that fails `validator`, or
+/// `None` if it can't be broken without changing the type
+fn invalid_expr(
+    validator: &Validator,
+    validators: &[&Validator],
+    ty: &TypeIR,
+    type_params: &[String],
+) -> Option<String> {
+    let others: Vec<&Validator> = validators
+        .iter()
+        .copied()
+        .filter(|v| !std::ptr::eq(*v, validator))
+        .collect();
+    let is_string = accepts(ty, |t| t.is_keyword("string"));
+    let is_number = accepts(ty, |t| t.is_keyword("number"));
+    let is_bigint = accepts(ty, |t| t.is_keyword("bigint"));
+    let is_date = accepts(ty, |t| {
+        t.as_reference().is_some_and(|(name, _)| name == "Date")
+    });
+
+    let value = match validator {
+        Validator::Email if is_string => r#""not-an-email""#.to_string(),
+        Validator::Url if is_string => r#""not a url""#.to_string(),
+        Validator::Uuid if is_string => r#""not-a-uuid""#.to_string(),
+        Validator::MaxLength(n) | Validator::Length(n) if is_string => {
+            string_of_length(&others, n + 1)
+        }
+        Validator::LengthRange(_, max) if is_string => string_of_length(&others, max + 1),
+        Validator::MinLength(n) if is_string && *n > 0 => string_of_length(&others, n - 1),
+        Validator::NonEmpty if is_string => r#""""#.to_string(),
+        Validator::Trimmed if is_string => r#"" x ""#.to_string(),
+        Validator::Lowercase if is_string => r#""ABC""#.to_string(),
+        Validator::Uppercase | Validator::Capitalized if is_string => r#""abc""#.to_string(),
+        Validator::Uncapitalized if is_string => r#""Abc""#.to_string(),
+        Validator::StartsWith(s) | Validator::EndsWith(s) | Validator::Includes(s)
+            if is_string && !s.is_empty() =>
+        {
+            string_avoiding(s)
+        }
+
+        Validator::GreaterThan(n) | Validator::LessThan(n) if is_number => n.to_string(),
+        Validator::GreaterThanOrEqualTo(n) if is_number => (n - 1.0).to_string(),
+        Validator::LessThanOrEqualTo(n) | Validator::Between(_, n) if is_number => {
+            (n + 1.0).to_string()
+        }
+        Validator::Int if is_number => "0.5".to_string(),
+        Validator::NonNaN if is_number => "NaN".to_string(),
+        Validator::Finite if is_number => "Infinity".to_string(),
+        Validator::Positive | Validator::Negative if is_number => "0".to_string(),
+        Validator::NonNegative if is_number => "-1".to_string(),
+        Validator::NonPositive if is_number => "1".to_string(),
+        Validator::MultipleOf(n) if is_number && *n != 0.0 => (n / 2.0).to_string(),
+        Validator::Uint8 if is_number => "256".to_string(),
+
+        Validator::GreaterThanBigInt(n) | Validator::LessThanBigInt(n) if is_bigint => {
+            format!("BigInt({n})")
+        }
+        Validator::GreaterThanOrEqualToBigInt(n) if is_bigint => format!("BigInt({n}) - 1n"),
+        Validator::LessThanOrEqualToBigInt(n) | Validator::BetweenBigInt(_, n) if is_bigint => {
+            format!("BigInt({n}) + 1n")
+        }
+        Validator::PositiveBigInt | Validator::NegativeBigInt if is_bigint => "0n".to_string(),
+        Validator::NonNegativeBigInt if is_bigint => "-1n".to_string(),
+        Validator::NonPositiveBigInt if is_bigint => "1n".to_string(),
+
+        Validator::ValidDate if is_date => "new Date(NaN)".to_string(),
+        Validator::GreaterThanDate(d) | Validator::LessThanDate(d) if is_date => {
+            format!("new Date(\"{d}\")")
+        }
+        Validator::GreaterThanOrEqualToDate(d) if is_date => {
+            format!("new Date(new Date(\"{d}\").getTime() - 1)")
+        }
+        Validator::LessThanOrEqualToDate(d) | Validator::BetweenDate(_, d) if is_date => {
+            format!("new Date(new Date(\"{d}\").getTime() + 1)")
+        }
+
+        Validator::MaxItems(n) | Validator::ItemsCount(n) => {
+            items_of_count(ty, type_params, n + 1)?
+        }
+        Validator::MinItems(n) if *n > 0 => items_of_count(ty, type_params, n - 1)?,
+
+        // `oneOf`, `pattern`, custom and cross-field rules have no value known to fail
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// An array of exactly `count` valid items, if `ty` is an array type
+fn items_of_count(ty: &TypeIR, type_params: &[String], count: usize) -> Option<String> {
+    let element = ty.union_members().iter().find_map(TypeIR::array_element)?;
+    let item = arbitrary_expr(element, &[], type_params)?;
+    Some(format!(
+        "Mock.array(rng, {}, {{ minItems: {count}, maxItems: {count} }})",
+        thunk(&item)
+    ))
+}
+
+/// All `arbitraryInvalid()` entries, field by field
+fn invalid_cases(fields: &[MockField]) -> Vec<InvalidCase> {
+    fields
+        .iter()
+        .flat_map(|field| {
+            field.invalid.iter().map(|(code, value)| InvalidCase {
+                field_name: field.field_name.clone(),
+                json_key: field.json_key.clone(),
+                code,
+                value: value.clone(),
+            })
+        })
+        .collect()
+}
+
+fn missing_error(input: &DeriveInput, missing: &[String]) -> MacroforgeError {
+    MacroforgeError::new(
+        input.decorator_span(),
+        format!(
+            "@derive(Mock) cannot generate valid values for: {}. Add @mock({{ with: \"fn\" }}) with a function (rng: MockRng) => value",
+            missing.join(", ")
+        ),
+    )
+}
+
+/// `<T, U>` for generic types, empty otherwise
+fn generic_decl(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", type_params.join(", "))
+    }
+}
+
+/// Parameters of `arbitrary`/`arbitraryInvalid`: generic types also take a
+/// generator per type parameter, e.g. `{ T: Arbitrary<T> }` for `Page<T>`
+fn arbitrary_params(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        return "seed?: number | MockRng".to_string();
+    }
+    let entries: Vec<String> = type_params
+        .iter()
+        .map(|param| format!("{param}: Arbitrary<{param}>"))
+        .collect();
+    format!(
+        "seed: number | MockRng | undefined, arbitraries: {{ {} }}",
+        entries.join("; ")
+    )
+}
+
+/// Arguments passing `rng` (and the type parameter generators) on to `arbitrary`
+fn arbitrary_args(type_params: &[String]) -> &'static str {
+    if type_params.is_empty() {
+        "rng"
+    } else {
+        "rng, arbitraries"
+    }
+}
+
+fn add_mock_imports(result: &mut TsStream, type_params: &[String], with_invalid: bool) {
+    result.add_import("Mock", "macroforge/mock");
+    result.add_type_import("MockRng", "macroforge/mock");
+    if with_invalid {
+        result.add_type_import("MockInvalid", "macroforge/mock");
+    }
+    if !type_params.is_empty() {
+        result.add_type_import("Arbitrary", "macroforge/mock");
+    }
+}
+
+/// `arbitrary`/`arbitraryInvalid` in the type's namespace, for interfaces and
+/// object type aliases
+fn namespace_mock(type_name: &str, type_params: &[String], fields: &[MockField]) -> TsStream {
+    let generic_decl = generic_decl(type_params);
+    let full_type_name = format!("{type_name}{generic_decl}");
+    let params = arbitrary_params(type_params);
+    let args = arbitrary_args(type_params);
+    let cases = invalid_cases(fields);
+
+    let mut result = ts_template! {
+        export namespace @{type_name} {
+            export function {|arbitrary@{generic_decl}|}(@{params}): @{full_type_name} {
+                const rng = Mock.seeded(seed);
+                const result: any = {};
+                {#for field in fields}
+                    {#if let Some(arbitrary) = &field.arbitrary}
+                        {#if field.optional}
+                            if (rng.bool()) {
+                                result.@{field.field_name} = @{arbitrary};
+                            }
+                        {:else}
+                            result.@{field.field_name} = @{arbitrary};
+                        {/if}
+                    {/if}
+                {/for}
+                return result;
+            }
+
+            export function {|arbitraryInvalid@{generic_decl}|}(@{params}): MockInvalid<@{full_type_name}>[] {
+                const rng = Mock.seeded(seed);
+                return [
+                    {#for case in &cases}
+                        { field: "@{case.json_key}", validator: "@{case.code}", value: Object.assign(@{type_name}.arbitrary(@{args}), { @{case.field_name}: @{case.value} }) },
+                    {/for}
+                ];
+            }
+        }
+    };
+    add_mock_imports(&mut result, type_params, true);
+    result
+}
+
+#[ts_macro_derive(
+    Mock,
+    description = "Generates seeded arbitrary() and arbitraryInvalid() test data factories from field types and validators",
+    attributes((mock, "Field options: with (function (rng) => value generating the field), skip (leave unset)"))
+)]
+pub fn derive_mock_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+
+    match &input.data {
+        Data::Class(class) => {
+            let class_name = input.name();
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &class.inner.decorators,
+            );
+            let type_params = class.type_params();
+            let (fields, missing) = mock_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        &f.type_ir,
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &container_opts,
+                type_params,
+            );
+            if !missing.is_empty() {
+                return Err(missing_error(&input, &missing));
+            }
+
+            let generic_decl = generic_decl(type_params);
+            let full_type_name = format!("{class_name}{generic_decl}");
+            let params = arbitrary_params(type_params);
+            let args = arbitrary_args(type_params);
+            let cases = invalid_cases(&fields);
+
+            let mut result = body! {
+                static {|arbitrary@{generic_decl}|}(@{params}): @{full_type_name} {
+                    const rng = Mock.seeded(seed);
+                    const instance: any = Object.create(@{class_name}.prototype);
+                    {#for field in &fields}
+                        {#if let Some(arbitrary) = &field.arbitrary}
+                            {#if field.optional}
+                                if (rng.bool()) {
+                                    instance.@{field.field_name} = @{arbitrary};
+                                }
+                            {:else}
+                                instance.@{field.field_name} = @{arbitrary};
+                            {/if}
+                        {/if}
+                    {/for}
+                    return instance;
+                }
+
+                static {|arbitraryInvalid@{generic_decl}|}(@{params}): MockInvalid<@{full_type_name}>[] {
+                    const rng = Mock.seeded(seed);
+                    return [
+                        {#for case in &cases}
+                            { field: "@{case.json_key}", validator: "@{case.code}", value: Object.assign(@{class_name}.arbitrary(@{args}), { @{case.field_name}: @{case.value} }) },
+                        {/for}
+                    ];
+                }
+            };
+            add_mock_imports(&mut result, type_params, true);
+            Ok(result)
+        }
+        Data::Interface(interface) => {
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &interface.inner.decorators,
+            );
+            let type_params = interface.type_params();
+            let (fields, missing) = mock_fields(
+                interface.fields().iter().map(interface_field),
+                &container_opts,
+                type_params,
+            );
+            if !missing.is_empty() {
+                return Err(missing_error(&input, &missing));
+            }
+            Ok(namespace_mock(input.name(), type_params, &fields))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_object() => {
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &type_alias.inner.decorators,
+            );
+            let type_params = type_alias.type_params();
+            let (fields, missing) = mock_fields(
+                type_alias.as_object().unwrap().iter().map(interface_field),
+                &container_opts,
+                type_params,
+            );
+            if !missing.is_empty() {
+                return Err(missing_error(&input, &missing));
+            }
+            Ok(namespace_mock(input.name(), type_params, &fields))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_union() => {
+            let type_name = input.name();
+            let type_params = type_alias.type_params();
+            let members = type_alias.as_union().unwrap();
+            let Some(exprs) = members
+                .iter()
+                .map(|member| union_member_expr(member, type_params))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    "@derive(Mock) can only generate unions of literals, primitives, inline objects and types with their own arbitrary()",
+                ));
+            };
+            let value = if members.iter().all(|m| m.is_literal()) {
+                let literals: Vec<&str> = members
+                    .iter()
+                    .filter_map(|m| m.as_literal())
+                    .map(str::trim)
+                    .collect();
+                format!("rng.pick([{}] as const)", literals.join(", "))
+            } else {
+                let exprs: Vec<String> = exprs.iter().map(|e| thunk(e)).collect();
+                format!("Mock.union(rng, [{}])", exprs.join(", "))
+            };
+
+            let generic_decl = generic_decl(type_params);
+            let full_type_name = format!("{type_name}{generic_decl}");
+            let params = arbitrary_params(type_params);
+
+            let mut result = ts_template! {
+                export namespace @{type_name} {
+                    export function {|arbitrary@{generic_decl}|}(@{params}): @{full_type_name} {
+                        const rng = Mock.seeded(seed);
+                        return @{value};
+                    }
+                }
+            };
+            add_mock_imports(&mut result, type_params, false);
+            Ok(result)
+        }
+        Data::Enum(enum_data) => {
+            let enum_name = input.name();
+            let variants: Vec<String> = enum_data
+                .variants()
+                .iter()
+                .map(|v| format!("{enum_name}.{}", v.name))
+                .collect();
+            let variants = variants.join(", ");
+
+            let mut result = ts_template! {
+                export namespace @{enum_name} {
+                    export function arbitrary(seed?: number | MockRng): @{enum_name} {
+                        return Mock.seeded(seed).pick([@{variants}]);
+                    }
+                }
+            };
+            add_mock_imports(&mut result, &[], false);
+            Ok(result)
+        }
+        Data::TypeAlias(_) => Err(MacroforgeError::new(
+            input.decorator_span(),
+            "/** @derive(Mock) */ can only be applied to classes, interfaces, enums and object or union type aliases",
+        )),
+    }
+}
+
+fn interface_field(f: &InterfaceFieldIR) -> (&str, &str, &TypeIR, bool, &[DecoratorIR]) {
+    (
+        f.name.as_str(),
+        f.ts_type.as_str(),
+        &f.type_ir,
+        f.optional,
+        f.decorators.as_slice(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_expr() {
+        let email = [decorator(
+            "serde",
+            r#"{ validate: ["email", "maxLength(40)"] }"#,
+        )];
+        let age = [decorator(
+            "serde",
+            r#"{ validate: ["int", "positive", "lessThan(130)"] }"#,
+        )];
+        let role = [decorator(
+            "serde",
+            r#"{ validate: ["oneOf('admin', 'user')"] }"#,
+        )];
+        let tags = [decorator("serde", r#"{ validate: ["minItems(1)"] }"#)];
+        let code = [decorator(
+            "serde",
+            r#"{ validate: ["pattern(\"^[A-Z]{3}$\")"] }"#,
+        )];
+        let custom = [decorator("mock", r#"{ with: "fakeCode" }"#)];
+        let string = TypeIR::keyword("string");
+        let number = TypeIR::keyword("number");
+        let status = TypeIR::Union {
+            types: vec![
+                TypeIR::Literal {
+                    value: LiteralTypeIR::String("on".into()),
+                },
+                TypeIR::Literal {
+                    value: LiteralTypeIR::String("off".into()),
+                },
+            ],
+        };
+        let tag_list = TypeIR::Array {
+            element: Box::new(string.clone()),
+        };
+        let address = TypeIR::reference("Address", vec![]);
+        let nickname = TypeIR::Union {
+            types: vec![string.clone(), TypeIR::keyword("null")],
+        };
+        let fields = [
+            ("email", "string", &string, false, &email[..]),
+            ("age", "number", &number, false, &age[..]),
+            ("role", "string", &string, false, &role[..]),
+            ("status", "\"on\" | \"off\"", &status, false, &[][..]),
+            ("tags", "string[]", &tag_list, false, &tags[..]),
+            ("address", "Address", &address, true, &[][..]),
+            ("nickname", "string | null", &nickname, false, &[][..]),
+            ("code", "string", &string, false, &custom[..]),
+        ];
+
+        let (mocked, missing) =
+            mock_fields(fields.into_iter(), &SerdeContainerOptions::default(), &[]);
+        assert!(missing.is_empty());
+        let arbitrary: Vec<_> = mocked
+            .iter()
+            .map(|f| f.arbitrary.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            arbitrary,
+            vec![
+                r#"Mock.string(rng, { format: "email", maxLength: 40 })"#,
+                "Mock.number(rng, { int: true, gt: 0, lt: 130 })",
+                r#"rng.pick(["admin", "user"] as const)"#,
+                r#"rng.pick(["on", "off"] as const)"#,
+                "Mock.array(rng, () => Mock.string(rng), { minItems: 1 })",
+                "Address.arbitrary(rng)",
+                "Mock.union(rng, [() => Mock.string(rng), () => null])",
+                "fakeCode(rng)",
+            ]
+        );
+        assert!(mocked[5].optional);
+
+        // A pattern can't be generated, so a required field needs @mock
+        let (_, missing) = mock_fields(
+            [("code", "string", &string, false, &code[..])].into_iter(),
+            &SerdeContainerOptions::default(),
+            &[],
+        );
+        assert_eq!(missing, vec!["code (pattern validator)"]);
+    }
+
+    #[test]
+    fn test_invalid_cases() {
+        let decorators = [decorator(
+            "serde",
+            r#"{ rename: "mail", validate: ["email", "minLength(3)", "maxLength(40)"] }"#,
+        )];
+        let string = TypeIR::keyword("string");
+        let (fields, _) = mock_fields(
+            [("email", "string", &string, false, &decorators[..])].into_iter(),
+            &SerdeContainerOptions::default(),
+            &[],
+        );
+        let cases: Vec<_> = invalid_cases(&fields)
+            .into_iter()
+            .map(|c| (c.json_key, c.code, c.value))
+            .collect();
+        assert_eq!(
+            cases,
+            vec![
+                ("mail".to_string(), "email", r#""not-an-email""#.to_string()),
+                (
+                    "mail".to_string(),
+                    "minLength",
+                    "Mock.string(rng, { minLength: 2, maxLength: 2 })".to_string()
+                ),
+                (
+                    "mail".to_string(),
+                    "maxLength",
+                    r#"Mock.string(rng, { format: "email", minLength: 41, maxLength: 41 })"#
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_number_constraints_replace_contradicting_bounds() {
+        let validators = [
+            Validator::GreaterThanOrEqualTo(1.0),
+            Validator::Positive,
+            Validator::Between(2.0, 5.0),
+        ];
+        let validators: Vec<&Validator> = validators.iter().collect();
+        assert_eq!(
+            constraints_arg(&number_constraints(&validators)),
+            ", { gte: 2, lte: 5 }"
+        );
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! Generates a `compareTo()` method for total ordering.
 //! Returns -1 (less), 0 (equal), or 1 (greater) - never null.
-//! Supports @ord(skip) decorator on fields to exclude them from comparison.
+//! Supports @ord(skip) decorator on fields to exclude them from comparison, and
+//! @ord({ compareWith, reverse, priority, caseInsensitive, nullsFirst, nullsLast })
+//! to customize how a field is ordered.
 
-use crate::builtin::derive_common::{is_numeric_type, is_primitive_type, CompareFieldOptions};
+use crate::builtin::derive_common::{
+    apply_compare_options, is_numeric_type, is_primitive_type, ordered_compare_fields, CompareFieldOptions,
+};
 use crate::macros::{body, ts_macro_derive, ts_template};
 use crate::ts_syn::{parse_ts_macro_input, Data, DeriveInput, MacroforgeError, TsStream};
 
@@ -135,7 +139,7 @@ fn generate_field_compare_for_interface(
 #[ts_macro_derive(
     Ord,
     description = "Generates a compareTo() method for total ordering (returns -1, 0, or 1, never null)",
-    attributes(ord)
+    attributes((ord, "Field options: skip, compareWith (function name), reverse, priority (higher compares first), caseInsensitive, nullsFirst, nullsLast"))
 )]
 pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
@@ -145,33 +149,39 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
             let class_name = input.name();
 
             // Collect fields for comparison
-            let ord_fields: Vec<OrdField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = ordered_compare_fields(
+                class
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            OrdField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                        )
+                    }),
+            );
 
             let has_fields = !ord_fields.is_empty();
 
-            // Build comparison logic - lexicographic by field order
+            // Build comparison logic - lexicographic by priority, then field order
             let compare_body = if has_fields {
                 ord_fields
                     .iter()
                     .enumerate()
-                    .map(|(i, f)| {
+                    .map(|(i, (f, opts))| {
                         let var_name = format!("cmp{}", i);
                         format!(
                             "const {var_name} = {};\n                    if ({var_name} !== 0) return {var_name};",
-                            generate_field_compare(f)
+                            apply_compare_options(
+                                generate_field_compare(f),
+                                opts,
+                                &format!("this.{}", f.name),
+                                &format!("typedOther.{}", f.name),
+                                true,
+                            )
                         )
                     })
                     .collect::<Vec<_>>()
@@ -212,20 +222,20 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
         Data::Interface(interface) => {
             let interface_name = input.name();
 
-            let ord_fields: Vec<OrdField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = ordered_compare_fields(
+                interface
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            OrdField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                        )
+                    }),
+            );
 
             let has_fields = !ord_fields.is_empty();
 
@@ -233,11 +243,17 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
                 ord_fields
                     .iter()
                     .enumerate()
-                    .map(|(i, f)| {
+                    .map(|(i, (f, opts))| {
                         let var_name = format!("cmp{}", i);
                         format!(
                             "const {var_name} = {};\n                        if ({var_name} !== 0) return {var_name};",
-                            generate_field_compare_for_interface(f, "self", "other")
+                            apply_compare_options(
+                                generate_field_compare_for_interface(f, "self", "other"),
+                                opts,
+                                &format!("self.{}", f.name),
+                                &format!("other.{}", f.name),
+                                true,
+                            )
                         )
                     })
                     .collect::<Vec<_>>()
@@ -262,21 +278,21 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
             let type_name = input.name();
 
             if type_alias.is_object() {
-                let ord_fields: Vec<OrdField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(OrdField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
+                let ord_fields = ordered_compare_fields(
+                    type_alias
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .map(|field| {
+                            (
+                                OrdField {
+                                    name: field.name.clone(),
+                                    ts_type: field.ts_type.clone(),
+                                },
+                                CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                            )
+                        }),
+                );
 
                 let has_fields = !ord_fields.is_empty();
 
@@ -284,11 +300,17 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
                     ord_fields
                         .iter()
                         .enumerate()
-                        .map(|(i, f)| {
+                        .map(|(i, (f, opts))| {
                             let var_name = format!("cmp{}", i);
                             format!(
                                 "const {var_name} = {};\n                        if ({var_name} !== 0) return {var_name};",
-                                generate_field_compare_for_interface(f, "a", "b")
+                                apply_compare_options(
+                                    generate_field_compare_for_interface(f, "a", "b"),
+                                    opts,
+                                    &format!("a.{}", f.name),
+                                    &format!("b.{}", f.name),
+                                    true,
+                                )
                             )
                         })
                         .collect::<Vec<_>>()
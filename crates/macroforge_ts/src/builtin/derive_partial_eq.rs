@@ -1,9 +1,10 @@
 //! /** @derive(PartialEq) */ macro implementation
 //!
 //! Generates an `equals()` method for field-by-field comparison.
-//! Supports @partialEq(skip) decorator on fields to exclude them from comparison.
+//! Supports @partialEq(skip) decorator on fields to exclude them from comparison, and
+//! @partialEq({ eqWith, caseInsensitive }) to customize how a field is compared.
 
-use crate::builtin::derive_common::{is_primitive_type, CompareFieldOptions};
+use crate::builtin::derive_common::{apply_eq_options, is_primitive_type, ordered_compare_fields, CompareFieldOptions};
 use crate::macros::{body, ts_macro_derive, ts_template};
 use crate::ts_syn::{parse_ts_macro_input, Data, DeriveInput, MacroforgeError, TsStream};
 
@@ -116,7 +117,7 @@ fn generate_field_equality_for_interface(field: &EqField, self_var: &str, other_
 #[ts_macro_derive(
     PartialEq,
     description = "Generates an equals() method for field-by-field comparison",
-    attributes(partialEq)
+    attributes((partialEq, "Field options: skip, eqWith (function name), caseInsensitive"))
 )]
 pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
@@ -126,20 +127,20 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
             let class_name = input.name();
 
             // Collect fields that should be included in equality comparison
-            let eq_fields: Vec<EqField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(EqField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let eq_fields = ordered_compare_fields(
+                class
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            EqField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "partialEq"),
+                        )
+                    }),
+            );
 
             // Build comparison expression
             let comparison = if eq_fields.is_empty() {
@@ -147,7 +148,14 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
             } else {
                 eq_fields
                     .iter()
-                    .map(generate_field_equality)
+                    .map(|(f, opts)| {
+                        apply_eq_options(
+                            generate_field_equality(f),
+                            opts,
+                            &format!("this.{}", f.name),
+                            &format!("typedOther.{}", f.name),
+                        )
+                    })
                     .collect::<Vec<_>>()
                     .join(" && ")
             };
@@ -176,20 +184,20 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
             let interface_name = input.name();
 
             // Collect fields for comparison
-            let eq_fields: Vec<EqField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(EqField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let eq_fields = ordered_compare_fields(
+                interface
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            EqField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "partialEq"),
+                        )
+                    }),
+            );
 
             // Build comparison expression
             let comparison = if eq_fields.is_empty() {
@@ -197,7 +205,14 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
             } else {
                 eq_fields
                     .iter()
-                    .map(|f| generate_field_equality_for_interface(f, "self", "other"))
+                    .map(|(f, opts)| {
+                        apply_eq_options(
+                            generate_field_equality_for_interface(f, "self", "other"),
+                            opts,
+                            &format!("self.{}", f.name),
+                            &format!("other.{}", f.name),
+                        )
+                    })
                     .collect::<Vec<_>>()
                     .join(" && ")
             };
@@ -216,29 +231,35 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
 
             if type_alias.is_object() {
                 // Object type: field-by-field comparison
-                let eq_fields: Vec<EqField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts =
-                            CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(EqField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
+                let eq_fields = ordered_compare_fields(
+                    type_alias
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .map(|field| {
+                            (
+                                EqField {
+                                    name: field.name.clone(),
+                                    ts_type: field.ts_type.clone(),
+                                },
+                                CompareFieldOptions::from_decorators(&field.decorators, "partialEq"),
+                            )
+                        }),
+                );
 
                 let comparison = if eq_fields.is_empty() {
                     "true".to_string()
                 } else {
                     eq_fields
                         .iter()
-                        .map(|f| generate_field_equality_for_interface(f, "a", "b"))
+                        .map(|(f, opts)| {
+                        apply_eq_options(
+                            generate_field_equality_for_interface(f, "a", "b"),
+                            opts,
+                            &format!("a.{}", f.name),
+                            &format!("b.{}", f.name),
+                        )
+                    })
                         .collect::<Vec<_>>()
                         .join(" && ")
                 };
@@ -3,9 +3,13 @@
 //! Generates a `compareTo()` method for partial ordering comparison.
 //! Returns Option.some(-1) (less), Option.some(0) (equal), Option.some(1) (greater),
 //! or Option.none() (incomparable).
-//! Supports @ord(skip) decorator on fields to exclude them from comparison.
+//! Supports @ord(skip) decorator on fields to exclude them from comparison, and
+//! @ord({ compareWith, reverse, priority, caseInsensitive, nullsFirst, nullsLast })
+//! to customize how a field is ordered.
 
-use crate::builtin::derive_common::{is_numeric_type, is_primitive_type, CompareFieldOptions};
+use crate::builtin::derive_common::{
+    apply_compare_options, is_numeric_type, is_primitive_type, ordered_compare_fields, CompareFieldOptions,
+};
 use crate::macros::{body, ts_macro_derive, ts_template};
 use crate::ts_syn::{parse_ts_macro_input, Data, DeriveInput, MacroforgeError, TsStream};
 
@@ -169,7 +173,7 @@ fn generate_field_compare_for_interface(
 #[ts_macro_derive(
     PartialOrd,
     description = "Generates a compareTo() method for partial ordering (returns Option<number>: some(-1), some(0), some(1), or none())",
-    attributes(ord)
+    attributes((ord, "Field options: skip, compareWith (function name), reverse, priority (higher compares first), caseInsensitive, nullsFirst, nullsLast"))
 )]
 pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
@@ -179,34 +183,40 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             let class_name = input.name();
 
             // Collect fields for comparison
-            let ord_fields: Vec<OrdField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = ordered_compare_fields(
+                class
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            OrdField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                        )
+                    }),
+            );
 
             let has_fields = !ord_fields.is_empty();
 
-            // Build comparison logic - lexicographic by field order
+            // Build comparison logic - lexicographic by priority, then field order
             // Internal comparisons use raw numbers, final result wrapped in Option
             let compare_body = if has_fields {
                 ord_fields
                     .iter()
                     .enumerate()
-                    .map(|(i, f)| {
+                    .map(|(i, (f, opts))| {
                         let var_name = format!("cmp{}", i);
                         format!(
                             "const {var_name} = {};\n                    if ({var_name} === null) return Option.none();\n                    if ({var_name} !== 0) return Option.some({var_name});",
-                            generate_field_compare(f, true)
+                            apply_compare_options(
+                                generate_field_compare(f, true),
+                                opts,
+                                &format!("this.{}", f.name),
+                                &format!("typedOther.{}", f.name),
+                                false,
+                            )
                         )
                     })
                     .collect::<Vec<_>>()
@@ -251,20 +261,20 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
         Data::Interface(interface) => {
             let interface_name = input.name();
 
-            let ord_fields: Vec<OrdField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = ordered_compare_fields(
+                interface
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        (
+                            OrdField {
+                                name: field.name.clone(),
+                                ts_type: field.ts_type.clone(),
+                            },
+                            CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                        )
+                    }),
+            );
 
             let has_fields = !ord_fields.is_empty();
 
@@ -272,11 +282,17 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 ord_fields
                     .iter()
                     .enumerate()
-                    .map(|(i, f)| {
+                    .map(|(i, (f, opts))| {
                         let var_name = format!("cmp{}", i);
                         format!(
                             "const {var_name} = {};\n                        if ({var_name} === null) return Option.none();\n                        if ({var_name} !== 0) return Option.some({var_name});",
-                            generate_field_compare_for_interface(f, "self", "other", true)
+                            apply_compare_options(
+                                generate_field_compare_for_interface(f, "self", "other", true),
+                                opts,
+                                &format!("self.{}", f.name),
+                                &format!("other.{}", f.name),
+                                false,
+                            )
                         )
                     })
                     .collect::<Vec<_>>()
@@ -303,21 +319,21 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             let type_name = input.name();
 
             if type_alias.is_object() {
-                let ord_fields: Vec<OrdField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(OrdField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
+                let ord_fields = ordered_compare_fields(
+                    type_alias
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .map(|field| {
+                            (
+                                OrdField {
+                                    name: field.name.clone(),
+                                    ts_type: field.ts_type.clone(),
+                                },
+                                CompareFieldOptions::from_decorators(&field.decorators, "ord"),
+                            )
+                        }),
+                );
 
                 let has_fields = !ord_fields.is_empty();
 
@@ -325,11 +341,17 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     ord_fields
                         .iter()
                         .enumerate()
-                        .map(|(i, f)| {
+                        .map(|(i, (f, opts))| {
                             let var_name = format!("cmp{}", i);
                             format!(
                                 "const {var_name} = {};\n                        if ({var_name} === null) return Option.none();\n                        if ({var_name} !== 0) return Option.some({var_name});",
-                                generate_field_compare_for_interface(f, "a", "b", true)
+                                apply_compare_options(
+                                    generate_field_compare_for_interface(f, "a", "b", true),
+                                    opts,
+                                    &format!("a.{}", f.name),
+                                    &format!("b.{}", f.name),
+                                    false,
+                                )
                             )
                         })
                         .collect::<Vec<_>>()
@@ -0,0 +1,603 @@
+//! /** @derive(Patch) */ macro implementation (also available as `Partial`)
+//!
+//! Generates a JSON merge patch type `XPatch` next to the type, with every
+//! field optional (`null` removes an optional field) and fields whose type
+//! derives Patch in the same file patched recursively through their own
+//! `XPatch`. Alongside it come `applyPatch(target, patch)` and `diff(a, b)`.
+//! Patch keys follow the serde rename rules, skipped fields are left out and
+//! values are written in their JSON form, so a patch means the same thing on
+//! both ends of a `JSON.stringify`/`JSON.parse` round trip.
+
+use crate::builtin::serde::{SerdeContainerOptions, SerdeFieldOptions, TypeCategory};
+use crate::macros::{below, body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, TupleElementIR, TypeIR};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Field info for patching
+struct PatchField {
+    field_name: String,
+    json_key: String,
+    /// Type of the patch entry in its JSON form, without the `null` that
+    /// removes optional fields
+    patch_type: String,
+    optional: bool,
+    /// Type whose own `applyPatch`/`diff` handle this field
+    nested: Option<String>,
+    /// Expression `diff` writes to the patch for the new value `b.<field>`
+    diff_expr: String,
+    /// Expression assigned from the patch entry `__value`
+    apply_expr: String,
+}
+
+/// Patch info for each field that round-trips through JSON. `derives_patch`
+/// tells whether a type has its own `XPatch`; other types are replaced whole.
+fn patch_fields<'a>(
+    fields: impl Iterator<Item = (&'a str, &'a str, &'a TypeIR, bool, &'a [DecoratorIR])>,
+    container_opts: &SerdeContainerOptions,
+    type_params: &[String],
+    derives_patch: impl Fn(&str) -> bool,
+) -> Vec<PatchField> {
+    fields
+        .filter_map(|(name, ts_type, type_ir, optional, decorators)| {
+            let opts = SerdeFieldOptions::from_decorators(decorators);
+            if !opts.should_serialize() || !opts.should_deserialize() {
+                return None;
+            }
+            let json_key = opts
+                .rename
+                .clone()
+                .unwrap_or_else(|| container_opts.rename_all.apply(name));
+
+            // Only bare references to Patch types have a matching `XPatch`;
+            // enums, string unions and `Page<User>` are replaced whole
+            let nested = match (
+                TypeCategory::from_type_ir(type_ir).resolve_type_params(type_params),
+                type_ir,
+            ) {
+                (TypeCategory::Serializable(type_name), TypeIR::Reference { type_args, .. })
+                    if type_args.is_empty() && derives_patch(&type_name) =>
+                {
+                    Some(type_name)
+                }
+                _ => None,
+            };
+
+            let value = format!("b.{name}");
+            let (patch_type, diff_expr, apply_expr) = match &nested {
+                Some(type_name) => (
+                    format!("{type_name}Patch"),
+                    value,
+                    format!("{type_name}.applyPatch(result.{name}, __value)"),
+                ),
+                None => match json_codec(type_ir, &value, "__value") {
+                    Some(codec) => (codec.wire.to_string(), codec.encode, codec.decode),
+                    None => (ts_type.to_string(), value, "__value".to_string()),
+                },
+            };
+
+            Some(PatchField {
+                field_name: name.to_string(),
+                json_key,
+                patch_type,
+                optional,
+                nested,
+                diff_expr,
+                apply_expr,
+            })
+        })
+        .collect()
+}
+
+/// How a value that JSON cannot carry as it is travels in a patch
+struct JsonCodec {
+    /// Type of the value once encoded
+    wire: TypeIR,
+    /// Expression encoding the value
+    encode: String,
+    /// Expression rebuilding the value from its encoded form
+    decode: String,
+}
+
+/// Codec for dates, codec values (`bigint`, bytes, `URL`, ...), maps (as
+/// objects) and sets (as arrays), looking inside arrays, nullable and optional
+/// types, tuples and records. Encodes `value` and decodes `raw`; `None` for
+/// values JSON carries as they are.
+fn json_codec(ty: &TypeIR, value: &str, raw: &str) -> Option<JsonCodec> {
+    if let Some(element) = ty.array_element() {
+        let inner = json_codec(element, "x", "x")?;
+        return Some(JsonCodec {
+            wire: TypeIR::Array {
+                element: Box::new(inner.wire),
+            },
+            encode: format!("{value}.map((x: any) => {})", inner.encode),
+            decode: format!("({raw} as any[]).map((x: any) => {})", inner.decode),
+        });
+    }
+
+    match ty {
+        TypeIR::Union { types } if types.iter().any(TypeIR::is_nullish) => {
+            let inner = json_codec(&ty.without(TypeIR::is_nullish)?, value, raw)?;
+            let mut wire = inner.wire.union_members().to_vec();
+            wire.extend(types.iter().filter(|t| t.is_nullish()).cloned());
+            Some(JsonCodec {
+                wire: TypeIR::Union { types: wire },
+                encode: format!("({value} == null ? {value} : {})", inner.encode),
+                decode: format!("({raw} == null ? {raw} : {})", inner.decode),
+            })
+        }
+        TypeIR::Tuple { elements } if elements.iter().all(|e| !e.optional && !e.rest) => {
+            let codecs: Vec<Option<JsonCodec>> = elements
+                .iter()
+                .enumerate()
+                .map(|(i, e)| json_codec(&e.ty, &format!("{value}[{i}]"), &format!("({raw} as any[])[{i}]")))
+                .collect();
+            if codecs.iter().all(Option::is_none) {
+                return None;
+            }
+            let mut wire = Vec::new();
+            let mut encode = Vec::new();
+            let mut decode = Vec::new();
+            for (i, (element, codec)) in elements.iter().zip(codecs).enumerate() {
+                match codec {
+                    Some(codec) => {
+                        wire.push(TupleElementIR {
+                            ty: codec.wire,
+                            ..element.clone()
+                        });
+                        encode.push(codec.encode);
+                        decode.push(codec.decode);
+                    }
+                    None => {
+                        wire.push(element.clone());
+                        encode.push(format!("{value}[{i}]"));
+                        decode.push(format!("({raw} as any[])[{i}]"));
+                    }
+                }
+            }
+            Some(JsonCodec {
+                wire: TypeIR::Tuple { elements: wire },
+                encode: format!("[{}]", encode.join(", ")),
+                decode: format!("[{}]", decode.join(", ")),
+            })
+        }
+        TypeIR::Reference { name, type_args } => match (name.as_str(), type_args.as_slice()) {
+            ("Map" | "ReadonlyMap", [_, inner]) => {
+                let string = TypeIR::keyword("string");
+                Some(match json_codec(inner, "x", "x") {
+                    Some(inner) => JsonCodec {
+                        wire: TypeIR::reference("Record", vec![string, inner.wire]),
+                        encode: format!(
+                            "Object.fromEntries(Array.from({value}, ([k, x]: [any, any]) => [k, {}]))",
+                            inner.encode
+                        ),
+                        decode: format!(
+                            "new Map(Object.entries({raw}).map(([k, x]: [string, any]) => [k, {}]))",
+                            inner.decode
+                        ),
+                    },
+                    None => JsonCodec {
+                        wire: TypeIR::reference("Record", vec![string, inner.clone()]),
+                        encode: format!("Object.fromEntries({value})"),
+                        decode: format!("new Map(Object.entries({raw}))"),
+                    },
+                })
+            }
+            ("Set" | "ReadonlySet", [inner]) => Some(match json_codec(inner, "x", "x") {
+                Some(inner) => JsonCodec {
+                    wire: TypeIR::Array {
+                        element: Box::new(inner.wire),
+                    },
+                    encode: format!("Array.from({value}, (x: any) => {})", inner.encode),
+                    decode: format!("new Set(({raw} as any[]).map((x: any) => {}))", inner.decode),
+                },
+                None => JsonCodec {
+                    wire: TypeIR::Array {
+                        element: Box::new(inner.clone()),
+                    },
+                    encode: format!("Array.from({value})"),
+                    decode: format!("new Set({raw})"),
+                },
+            }),
+            ("Record", [key, inner]) => {
+                let inner = json_codec(inner, "x", "x")?;
+                Some(JsonCodec {
+                    wire: TypeIR::reference("Record", vec![key.clone(), inner.wire]),
+                    encode: format!("Codec.mapValues({value}, (x: any) => {})", inner.encode),
+                    decode: format!(
+                        "Codec.mapValues({raw} as Record<string, unknown>, (x: any) => {})",
+                        inner.decode
+                    ),
+                })
+            }
+            _ => scalar_codec(ty, value, raw),
+        },
+        _ => scalar_codec(ty, value, raw),
+    }
+}
+
+/// Dates and single codec values, all written as strings
+fn scalar_codec(ty: &TypeIR, value: &str, raw: &str) -> Option<JsonCodec> {
+    let type_cat = TypeCategory::from_type_ir(ty);
+    match type_cat {
+        TypeCategory::Date
+        | TypeCategory::BigInt
+        | TypeCategory::Bytes(_)
+        | TypeCategory::Url
+        | TypeCategory::RegExp
+        | TypeCategory::Temporal(_) => Some(JsonCodec {
+            wire: TypeIR::keyword("string"),
+            encode: type_cat.encode_expr(value)?,
+            decode: type_cat.decode_expr(raw)?,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether applying or diffing a patch needs the `Codec` helpers from `macroforge/serde`
+fn uses_codec(fields: &[PatchField]) -> bool {
+    fields
+        .iter()
+        .any(|f| f.apply_expr.contains("Codec.") || f.diff_expr.contains("Codec."))
+}
+
+/// `applyPatch` statements copying each patch entry into `result`
+fn apply_statements(fields: &[PatchField]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let PatchField { field_name, json_key, apply_expr, .. } = field;
+            let assign = if field.optional {
+                format!(
+                    "if (__value === null) {{ delete result.{field_name}; }} else {{ result.{field_name} = {apply_expr}; }}"
+                )
+            } else {
+                format!("result.{field_name} = {apply_expr};")
+            };
+            format!(
+                "if (patch[\"{json_key}\"] !== undefined) {{ const __value: any = patch[\"{json_key}\"]; {assign} }}"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `diff` statements writing each changed field of `b` into `patch`: nested
+/// patches when non-empty, other values when they differ and `null` for
+/// removed optional fields
+fn diff_statements(fields: &[PatchField]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let PatchField { field_name, json_key, diff_expr, .. } = field;
+            let changed = match &field.nested {
+                Some(nested) => format!(
+                    "{{ const __nested = {nested}.diff(prev.{field_name}, b.{field_name}); if (!isEmptyPatch(__nested)) {{ patch[\"{json_key}\"] = __nested; }} }}"
+                ),
+                None => format!(
+                    "if (!valuesEqual(prev.{field_name}, b.{field_name})) {{ patch[\"{json_key}\"] = {diff_expr}; }}"
+                ),
+            };
+            if field.optional {
+                format!(
+                    "if (b.{field_name} === undefined) {{ if (prev.{field_name} !== undefined) {{ patch[\"{json_key}\"] = null; }} }} else {{ {changed} }}"
+                )
+            } else {
+                changed
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `<T, U>` for generic types, empty otherwise
+fn generic_decl(type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", type_params.join(", "))
+    }
+}
+
+fn add_patch_imports(result: &mut TsStream, fields: &[PatchField]) {
+    if fields.iter().any(|f| f.nested.is_none()) {
+        result.add_import("valuesEqual", "macroforge/patch");
+    }
+    if fields.iter().any(|f| f.nested.is_some()) {
+        result.add_import("isEmptyPatch", "macroforge/patch");
+    }
+    if uses_codec(fields) {
+        result.add_import("Codec", "macroforge/serde");
+    }
+}
+
+/// Class members followed by module-level items, keeping the imports of both
+fn concat(first: TsStream, second: TsStream) -> TsStream {
+    let mut combined = TsStream::from_string(format!("{}\n{}", first.source(), second.source()));
+    combined.runtime_patches = first.runtime_patches;
+    combined.runtime_patches.extend(second.runtime_patches);
+    combined
+}
+
+/// `XPatch` type plus `applyPatch`/`diff` in the type's namespace, for
+/// interfaces and object type aliases
+fn namespace_patch(type_name: &str, type_params: &[String], fields: &[PatchField]) -> TsStream {
+    let generic_decl = generic_decl(type_params);
+    let full_type_name = format!("{type_name}{generic_decl}");
+    let patch_type_name = format!("{type_name}Patch{generic_decl}");
+    let apply_statements = apply_statements(fields);
+    let diff_statements = diff_statements(fields);
+
+    let mut result = ts_template! {
+        export type @{patch_type_name} = {
+            {#for field in fields}
+                {#if field.optional}
+                    "@{field.json_key}"?: @{field.patch_type} | null;
+                {:else}
+                    "@{field.json_key}"?: @{field.patch_type};
+                {/if}
+            {/for}
+        };
+
+        export namespace @{type_name} {
+            export function {|applyPatch@{generic_decl}|}(target: @{full_type_name} | undefined, patch: @{patch_type_name}): @{full_type_name} {
+                const result: any = target ?? {};
+                @{apply_statements}
+                return result;
+            }
+
+            export function {|diff@{generic_decl}|}(a: @{full_type_name} | undefined, b: @{full_type_name}): @{patch_type_name} {
+                const prev: any = a ?? {};
+                const patch: any = {};
+                @{diff_statements}
+                return patch;
+            }
+        }
+    };
+    add_patch_imports(&mut result, fields);
+    result
+}
+
+#[ts_macro_derive(
+    Patch,
+    description = "Generates an XPatch merge patch type with applyPatch() and diff()"
+)]
+pub fn derive_patch_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let derives_patch = |type_name: &str| input.context.derives(type_name, &["Patch", "Partial"]);
+
+    match &input.data {
+        Data::Class(class) => {
+            let class_name = input.name();
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &class.inner.decorators,
+            );
+            let type_params = class.type_params();
+            let fields = patch_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        &f.type_ir,
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &container_opts,
+                type_params,
+                derives_patch,
+            );
+
+            let generic_decl = generic_decl(type_params);
+            let full_type_name = format!("{class_name}{generic_decl}");
+            let patch_type_name = format!("{class_name}Patch{generic_decl}");
+            let apply_statements = apply_statements(&fields);
+            let diff_statements = diff_statements(&fields);
+
+            let mut members = body! {
+                static {|applyPatch@{generic_decl}|}(target: @{full_type_name} | undefined, patch: @{patch_type_name}): @{full_type_name} {
+                    const result: any = target ?? Object.create(@{class_name}.prototype);
+                    @{apply_statements}
+                    return result;
+                }
+
+                static {|diff@{generic_decl}|}(a: @{full_type_name} | undefined, b: @{full_type_name}): @{patch_type_name} {
+                    const prev: any = a ?? {};
+                    const patch: any = {};
+                    @{diff_statements}
+                    return patch;
+                }
+            };
+            add_patch_imports(&mut members, &fields);
+
+            let patch_type = below! {
+                export type @{patch_type_name} = {
+                    {#for field in &fields}
+                        {#if field.optional}
+                            "@{field.json_key}"?: @{field.patch_type} | null;
+                        {:else}
+                            "@{field.json_key}"?: @{field.patch_type};
+                        {/if}
+                    {/for}
+                };
+            };
+            Ok(concat(members, patch_type))
+        }
+        Data::Interface(interface) => {
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &interface.inner.decorators,
+            );
+            let type_params = interface.type_params();
+            let fields = patch_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        &f.type_ir,
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &container_opts,
+                type_params,
+                derives_patch,
+            );
+            Ok(namespace_patch(input.name(), type_params, &fields))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_object() => {
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &type_alias.inner.decorators,
+            );
+            let type_params = type_alias.type_params();
+            let fields = patch_fields(
+                type_alias.as_object().unwrap().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        &f.type_ir,
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &container_opts,
+                type_params,
+                derives_patch,
+            );
+            Ok(namespace_patch(input.name(), type_params, &fields))
+        }
+        Data::Enum(_) | Data::TypeAlias(_) => Err(MacroforgeError::new(
+            input.decorator_span(),
+            "/** @derive(Patch) */ can only be applied to classes, interfaces and object type aliases",
+        )),
+    }
+}
+
+#[ts_macro_derive(
+    Partial,
+    description = "Alias of Patch: generates an XPatch merge patch type with applyPatch() and diff()"
+)]
+pub fn derive_partial_macro(input: TsStream) -> Result<TsStream, MacroforgeError> {
+    derive_patch_macro(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::serde::RenameAll;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn serde(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "serde".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    #[test]
+    fn test_patch_fields() {
+        let container_opts = SerdeContainerOptions {
+            rename_all: RenameAll::CamelCase,
+            ..Default::default()
+        };
+        let renamed = [serde(r#"{ rename: "mail" }"#)];
+        let skipped = [serde("skip")];
+        let string = TypeIR::keyword("string");
+        let bigint = TypeIR::keyword("bigint");
+        let address = TypeIR::reference("Address", vec![]);
+        let role = TypeIR::reference("Role", vec![]);
+        let page = TypeIR::reference("Page", vec![TypeIR::reference("User", vec![])]);
+        let tags = TypeIR::reference("Set", vec![string.clone()]);
+        let fields = [
+            ("user_name", "string", &string, false, &[][..]),
+            ("email", "string", &string, true, &renamed[..]),
+            ("secret", "string", &string, false, &skipped[..]),
+            ("id", "bigint", &bigint, false, &[][..]),
+            ("address", "Address", &address, false, &[][..]),
+            ("role", "Role", &role, false, &[][..]),
+            ("page", "Page<User>", &page, false, &[][..]),
+            ("tags", "Set<string>", &tags, false, &[][..]),
+        ];
+
+        let patch = patch_fields(fields.into_iter(), &container_opts, &[], |name| name == "Address");
+        let summary: Vec<_> = patch
+            .iter()
+            .map(|f| {
+                (
+                    f.json_key.as_str(),
+                    f.patch_type.as_str(),
+                    f.optional,
+                    f.diff_expr.as_str(),
+                    f.apply_expr.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("userName", "string", false, "b.user_name", "__value"),
+                ("mail", "string", true, "b.email", "__value"),
+                ("id", "string", false, "String(b.id)", "Codec.decodeBigInt(__value)"),
+                (
+                    "address",
+                    "AddressPatch",
+                    false,
+                    "b.address",
+                    "Address.applyPatch(result.address, __value)"
+                ),
+                ("role", "Role", false, "b.role", "__value"),
+                ("page", "Page<User>", false, "b.page", "__value"),
+                ("tags", "string[]", false, "Array.from(b.tags)", "new Set(__value)"),
+            ]
+        );
+        assert!(uses_codec(&patch));
+        assert_eq!(patch[3].nested.as_deref(), Some("Address"));
+        assert_eq!(patch[4].nested, None);
+        assert_eq!(patch[5].nested, None);
+    }
+
+    #[test]
+    fn test_json_codec() {
+        let date = TypeIR::reference("Date", vec![]);
+        let map = TypeIR::reference("Map", vec![TypeIR::keyword("string"), date.clone()]);
+        let codec = json_codec(&map, "b.seen", "__value").unwrap();
+        assert_eq!(codec.wire.to_string(), "Record<string, string>");
+        assert_eq!(
+            codec.encode,
+            "Object.fromEntries(Array.from(b.seen, ([k, x]: [any, any]) => [k, x.toISOString()]))"
+        );
+
+        let dates = TypeIR::Array {
+            element: Box::new(date.clone()),
+        };
+        let codec = json_codec(&dates, "b.dates", "__value").unwrap();
+        assert_eq!(codec.wire.to_string(), "string[]");
+        assert_eq!(codec.encode, "b.dates.map((x: any) => x.toISOString())");
+        assert_eq!(codec.decode, "(__value as any[]).map((x: any) => new Date(x as string))");
+
+        let maybe = TypeIR::Union {
+            types: vec![date, TypeIR::keyword("null")],
+        };
+        let codec = json_codec(&maybe, "b.maybe", "__value").unwrap();
+        assert_eq!(codec.wire.to_string(), "string | null");
+        assert_eq!(codec.decode, "(__value == null ? __value : new Date(__value as string))");
+
+        let bytes = TypeIR::Array {
+            element: Box::new(TypeIR::reference("Uint8Array", vec![])),
+        };
+        assert_eq!(json_codec(&bytes, "v", "r").unwrap().wire.to_string(), "string[]");
+        let names = TypeIR::Array {
+            element: Box::new(TypeIR::keyword("string")),
+        };
+        assert!(json_codec(&names, "v", "r").is_none());
+    }
+
+    #[test]
+    fn test_generic_decl() {
+        assert_eq!(generic_decl(&[]), "");
+        assert_eq!(generic_decl(&["T".into(), "U".into()]), "<T, U>");
+    }
+}
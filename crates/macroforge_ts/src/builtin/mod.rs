@@ -17,6 +17,15 @@
 //! ## Initialization
 //! - `/** @derive(Default) */` - Generates a static `default()` factory method
 //!
+//! ## Patching
+//! - `/** @derive(Patch) */` (or `Partial`) - Generates an `XPatch` type with `applyPatch()` and `diff()` for JSON merge patches
+//!
+//! ## Test Data
+//! - `/** @derive(Mock) */` - Generates seeded `arbitrary()` and `arbitraryInvalid()` factories that respect the `@serde` validators
+//!
+//! ## Enums
+//! - `/** @derive(EnumUtils) */` - Generates `values()`, `parse()`, `is()`, `next()`/`prev()` and an exhaustive `match()`
+//!
 //! ## Serialization
 //! - `/** @derive(Serialize) */` - Generates a `toJSON()` method for JSON serialization
 //! - `/** @derive(Deserialize) */` - Generates a static `fromJSON()` method for JSON deserialization
@@ -25,8 +34,11 @@ mod derive_clone;
 mod derive_common;
 mod derive_debug;
 mod derive_default;
+mod derive_enum_utils;
 mod derive_hash;
+mod derive_mock;
 mod derive_ord;
 mod derive_partial_eq;
 mod derive_partial_ord;
+mod derive_patch;
 mod serde;
@@ -3,13 +3,27 @@
 //! Generates JSON deserialization methods with cycle/forward-reference support:
 //! - For classes: `static fromStringifiedJSON(json: string, opts?)`, `static __deserialize(value, ctx)`
 //! - For interfaces: `namespace InterfaceName { fromStringifiedJSON, __deserialize }`
+//! - With `@serde(msgpack)`: `fromMsgPack(bytes, opts?)` decoding MessagePack
+//!   and validating through the same `fromObject` path
+//! - With `@serde(stream)`: `fromJSONStream(chunks, opts?)`, an async generator
+//!   yielding one `fromObject` result per element of a top-level JSON array
 //!
-//! Uses deferred patching to handle cycles and forward references.
+//! Uses deferred patching to handle cycles and forward references. Containers
+//! marked `@serde(plain)` read plain JSON without `__type`/`__id`/`__ref`.
+//!
+//! Versioned containers (`@serde({ version: 3, migrate: { 1: "fromV1", 2: "fromV2" } })`)
+//! read the `__version` marker (absent means 1) and run each migration from
+//! that version up before fields are extracted.
 
+use crate::builtin::derive_common::member_access;
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::SpanIR;
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
-use super::{SerdeContainerOptions, SerdeFieldOptions, TypeCategory, Validator, ValidatorSpec};
+use super::{
+    MetaKeys, SerdeContainerOptions, SerdeFieldOptions, TypeCategory, Validator, ValidatorSpec,
+    optional_access, type_args_suffix, type_deserializers_type, type_param_names,
+};
 
 /// Field info for deserialization
 #[derive(Clone)]
@@ -91,6 +105,7 @@ fn generate_validation_condition(validator: &Validator, value_var: &str) -> Stri
         Validator::Uint8 => {
             format!("!Number.isInteger({value_var}) || {value_var} < 0 || {value_var} > 255")
         }
+        Validator::OneOf(values) => format!("![{}].includes({value_var})", values.join(", ")),
 
         // Array validators
         Validator::MaxItems(n) => format!("{value_var}.length > {n}"),
@@ -130,8 +145,18 @@ fn generate_validation_condition(validator: &Validator, value_var: &str) -> Stri
         Validator::NegativeBigInt => format!("{value_var} >= 0n"),
         Validator::NonPositiveBigInt => format!("{value_var} > 0n"),
 
-        // Custom validator - handled specially
-        Validator::Custom(_) => String::new(),
+        // Custom and cross-field validators - handled specially
+        Validator::Custom(_) | Validator::RequiredIf(..) | Validator::RequiredUnless(..) => {
+            String::new()
+        }
+    }
+}
+
+/// A JS literal as it reads in an error message: `'draft'` rather than `"draft"`
+fn display_literal(literal: &str) -> String {
+    match literal.strip_prefix('"').and_then(|l| l.strip_suffix('"')) {
+        Some(inner) => format!("'{inner}'"),
+        None => literal.to_string(),
     }
 }
 
@@ -171,6 +196,18 @@ fn get_validator_message(validator: &Validator) -> String {
         Validator::NonPositive => "must be non-positive".to_string(),
         Validator::MultipleOf(n) => format!("must be a multiple of {n}"),
         Validator::Uint8 => "must be a uint8 (0-255)".to_string(),
+        Validator::OneOf(values) => {
+            let shown: Vec<String> = values.iter().map(|v| display_literal(v)).collect();
+            format!("must be one of {}", shown.join(", "))
+        }
+        Validator::RequiredIf(other, None) => format!("is required when {other} is set"),
+        Validator::RequiredIf(other, Some(value)) => {
+            format!("is required when {other} is {}", display_literal(value))
+        }
+        Validator::RequiredUnless(other, None) => format!("is required unless {other} is set"),
+        Validator::RequiredUnless(other, Some(value)) => {
+            format!("is required unless {other} is {}", display_literal(value))
+        }
         Validator::MaxItems(n) => format!("must have at most {n} items"),
         Validator::MinItems(n) => format!("must have at least {n} items"),
         Validator::ItemsCount(n) => format!("must have exactly {n} items"),
@@ -193,13 +230,131 @@ fn get_validator_message(validator: &Validator) -> String {
     }
 }
 
+/// Stable error code for a validator, for message lookup in `formatMessage`
+pub(crate) fn get_validator_code(validator: &Validator) -> &'static str {
+    match validator {
+        Validator::Email => "email",
+        Validator::Url => "url",
+        Validator::Uuid => "uuid",
+        Validator::MaxLength(_) => "maxLength",
+        Validator::MinLength(_) => "minLength",
+        Validator::Length(_) => "length",
+        Validator::LengthRange(..) => "lengthRange",
+        Validator::Pattern(_) => "pattern",
+        Validator::NonEmpty => "nonEmpty",
+        Validator::Trimmed => "trimmed",
+        Validator::Lowercase => "lowercase",
+        Validator::Uppercase => "uppercase",
+        Validator::Capitalized => "capitalized",
+        Validator::Uncapitalized => "uncapitalized",
+        Validator::StartsWith(_) => "startsWith",
+        Validator::EndsWith(_) => "endsWith",
+        Validator::Includes(_) => "includes",
+        Validator::GreaterThan(_) => "greaterThan",
+        Validator::GreaterThanOrEqualTo(_) => "greaterThanOrEqualTo",
+        Validator::LessThan(_) => "lessThan",
+        Validator::LessThanOrEqualTo(_) => "lessThanOrEqualTo",
+        Validator::Between(..) => "between",
+        Validator::Int => "int",
+        Validator::NonNaN => "nonNaN",
+        Validator::Finite => "finite",
+        Validator::Positive => "positive",
+        Validator::NonNegative => "nonNegative",
+        Validator::Negative => "negative",
+        Validator::NonPositive => "nonPositive",
+        Validator::MultipleOf(_) => "multipleOf",
+        Validator::Uint8 => "uint8",
+        Validator::OneOf(_) => "oneOf",
+        Validator::RequiredIf(..) => "requiredIf",
+        Validator::RequiredUnless(..) => "requiredUnless",
+        Validator::MaxItems(_) => "maxItems",
+        Validator::MinItems(_) => "minItems",
+        Validator::ItemsCount(_) => "itemsCount",
+        Validator::ValidDate => "validDate",
+        Validator::GreaterThanDate(_) => "greaterThanDate",
+        Validator::GreaterThanOrEqualToDate(_) => "greaterThanOrEqualToDate",
+        Validator::LessThanDate(_) => "lessThanDate",
+        Validator::LessThanOrEqualToDate(_) => "lessThanOrEqualToDate",
+        Validator::BetweenDate(..) => "betweenDate",
+        Validator::GreaterThanBigInt(_) => "greaterThanBigInt",
+        Validator::GreaterThanOrEqualToBigInt(_) => "greaterThanOrEqualToBigInt",
+        Validator::LessThanBigInt(_) => "lessThanBigInt",
+        Validator::LessThanOrEqualToBigInt(_) => "lessThanOrEqualToBigInt",
+        Validator::BetweenBigInt(..) => "betweenBigInt",
+        Validator::PositiveBigInt => "positiveBigInt",
+        Validator::NonNegativeBigInt => "nonNegativeBigInt",
+        Validator::NegativeBigInt => "negativeBigInt",
+        Validator::NonPositiveBigInt => "nonPositiveBigInt",
+        Validator::Custom(_) => "custom",
+    }
+}
+
+/// Message parameters for a validator as a JS object literal, if it has any
+fn get_validator_params(validator: &Validator) -> Option<String> {
+    let params = match validator {
+        Validator::MaxLength(n) | Validator::MaxItems(n) => format!("max: {n}"),
+        Validator::MinLength(n) | Validator::MinItems(n) => format!("min: {n}"),
+        Validator::Length(n) => format!("length: {n}"),
+        Validator::ItemsCount(n) => format!("count: {n}"),
+        Validator::LengthRange(min, max) => format!("min: {min}, max: {max}"),
+        Validator::Pattern(p) => format!("pattern: {p:?}"),
+        Validator::StartsWith(s) => format!("prefix: {s:?}"),
+        Validator::EndsWith(s) => format!("suffix: {s:?}"),
+        Validator::Includes(s) => format!("substring: {s:?}"),
+        Validator::GreaterThan(n) | Validator::GreaterThanOrEqualTo(n) => format!("min: {n}"),
+        Validator::LessThan(n) | Validator::LessThanOrEqualTo(n) => format!("max: {n}"),
+        Validator::Between(min, max) => format!("min: {min}, max: {max}"),
+        Validator::MultipleOf(n) => format!("factor: {n}"),
+        Validator::OneOf(values) => format!("values: [{}]", values.join(", ")),
+        Validator::RequiredIf(other, value) | Validator::RequiredUnless(other, value) => {
+            match value {
+                Some(value) => format!("field: {other:?}, value: {value}"),
+                None => format!("field: {other:?}"),
+            }
+        }
+        Validator::GreaterThanDate(d) | Validator::GreaterThanOrEqualToDate(d) => {
+            format!("min: {d:?}")
+        }
+        Validator::LessThanDate(d) | Validator::LessThanOrEqualToDate(d) => format!("max: {d:?}"),
+        Validator::BetweenDate(min, max) => format!("min: {min:?}, max: {max:?}"),
+        Validator::GreaterThanBigInt(n) | Validator::GreaterThanOrEqualToBigInt(n) => {
+            format!("min: {n:?}")
+        }
+        Validator::LessThanBigInt(n) | Validator::LessThanOrEqualToBigInt(n) => {
+            format!("max: {n:?}")
+        }
+        Validator::BetweenBigInt(min, max) => format!("min: {min:?}, max: {max:?}"),
+        Validator::Custom(name) => format!("validator: {name:?}"),
+        _ => return None,
+    };
+    Some(format!("{{ {params} }}"))
+}
+
+/// `{ field, message, code, params }` literal for a failed validator
+fn validator_error(spec: &ValidatorSpec, json_key: &str) -> String {
+    let message = spec
+        .custom_message
+        .clone()
+        .unwrap_or_else(|| get_validator_message(&spec.validator));
+    let code = get_validator_code(&spec.validator);
+    match get_validator_params(&spec.validator) {
+        Some(params) => format!(
+            r#"{{ field: "{json_key}", message: "{message}", code: "{code}", params: {params} }}"#
+        ),
+        None => format!(r#"{{ field: "{json_key}", message: "{message}", code: "{code}" }}"#),
+    }
+}
+
 /// Generate validation code snippet for a field
-/// Generates code that pushes `{ field: string, message: string }` objects to the errors array
+/// Generates code that pushes `FieldError` objects (with `code` and `params`) to the errors array.
+/// Inside `__deserialize` (`in_ctx`), custom results go through `ctx.checkValidation`
+/// so promises are deferred to `fromObjectAsync`.
 fn generate_field_validations(
     validators: &[ValidatorSpec],
     value_var: &str,
     json_key: &str,
     _class_name: &str,
+    in_ctx: bool,
 ) -> String {
     let mut code = String::new();
 
@@ -208,14 +363,31 @@ fn generate_field_validations(
             .custom_message
             .clone()
             .unwrap_or_else(|| get_validator_message(&spec.validator));
+        let error = validator_error(spec, json_key);
+
+        if matches!(
+            spec.validator,
+            Validator::RequiredIf(..) | Validator::RequiredUnless(..)
+        ) {
+            // Checked against the raw object by `generate_conditional_requirements`
+            continue;
+        }
 
-        if let Validator::Custom(fn_name) = &spec.validator {
+        if let Validator::Custom(fn_name) = &spec.validator
+            && in_ctx
+        {
+            code.push_str(&format!(
+                r#"
+                ctx.checkValidation({fn_name}({value_var}), "{json_key}", "{message}", errors);
+"#
+            ));
+        } else if let Validator::Custom(fn_name) = &spec.validator {
             code.push_str(&format!(
                 r#"
                 {{
                     const __customResult = {fn_name}({value_var});
                     if (__customResult === false) {{
-                        errors.push({{ field: "{json_key}", message: "{message}" }});
+                        errors.push({error});
                     }}
                 }}
 "#
@@ -225,7 +397,46 @@ fn generate_field_validations(
             code.push_str(&format!(
                 r#"
                 if ({condition}) {{
-                    errors.push({{ field: "{json_key}", message: "{message}" }});
+                    errors.push({error});
+                }}
+"#
+            ));
+        }
+    }
+
+    code
+}
+
+/// Presence checks for `requiredIf`/`requiredUnless`, evaluated against `obj`
+fn generate_conditional_requirements(fields: &[DeserializeField]) -> String {
+    let json_key_of = |name: &str| {
+        fields
+            .iter()
+            .find(|f| f.field_name == name)
+            .map_or_else(|| name.to_string(), |f| f.json_key.clone())
+    };
+    let mut code = String::new();
+
+    for field in fields {
+        for spec in &field.validators {
+            let (other, value, unless) = match &spec.validator {
+                Validator::RequiredIf(other, value) => (other, value, false),
+                Validator::RequiredUnless(other, value) => (other, value, true),
+                _ => continue,
+            };
+            let other_value = format!("obj[{:?}]", json_key_of(other));
+            let triggered = match (value, unless) {
+                (None, false) => format!("{other_value} != null"),
+                (None, true) => format!("{other_value} == null"),
+                (Some(value), false) => format!("{other_value} === {value}"),
+                (Some(value), true) => format!("{other_value} !== {value}"),
+            };
+            let json_key = &field.json_key;
+            let error = validator_error(spec, json_key);
+            code.push_str(&format!(
+                r#"
+                if (({triggered}) && obj["{json_key}"] == null) {{
+                    errors.push({error});
                 }}
 "#
             ));
@@ -235,6 +446,89 @@ fn generate_field_validations(
     code
 }
 
+/// Whole-object rules from `@serde({ validate: [...] })`, run on `instance`
+fn generate_container_validations(validators: &[ValidatorSpec]) -> String {
+    let mut code = String::new();
+    for spec in validators {
+        let Validator::Custom(fn_name) = &spec.validator else {
+            continue;
+        };
+        let message = spec
+            .custom_message
+            .clone()
+            .unwrap_or_else(|| format!("failed {fn_name}"));
+        code.push_str(&format!(
+            r#"
+                    ctx.checkValidation({fn_name}(instance), "_root", "{message}", errors);
+"#
+        ));
+    }
+    code
+}
+
+/// Whether a field passes `TypeDeserializer.of(...)` for a nested generic
+/// type argument, e.g. `page: Page<Box<User>>`
+fn applies_generic_types(fields: &[DeserializeField], type_params: &[String]) -> bool {
+    fields.iter().any(|f| match &f.type_cat {
+        TypeCategory::Serializable(name) => type_args_suffix(name, type_params).contains("TypeDeserializer."),
+        _ => false,
+    })
+}
+
+/// Reject a `version` below 1, or one whose migration chain has gaps or
+/// stray entries
+fn check_migrations(opts: &SerdeContainerOptions, span: SpanIR) -> Result<(), MacroforgeError> {
+    let version = opts.version.unwrap_or(1);
+    if version < 1 {
+        return Err(MacroforgeError::new(
+            span,
+            format!("@serde version {version} must be at least 1"),
+        ));
+    }
+    let missing = opts.missing_migrations();
+    if !missing.is_empty() {
+        let versions: Vec<String> = missing.iter().map(u32::to_string).collect();
+        return Err(MacroforgeError::new(
+            span,
+            format!(
+                "@serde version {version} has no migration from version {}; add it to `migrate`",
+                versions.join(", ")
+            ),
+        ));
+    }
+    if let Some(from) = opts.migrations.keys().find(|from| **from == 0 || **from >= version) {
+        return Err(MacroforgeError::new(
+            span,
+            format!("@serde migrate entry {from} must be between 1 and {}", version - 1),
+        ));
+    }
+    Ok(())
+}
+
+/// Statements that check `__version` on `obj` and upgrade `obj` in place
+fn generate_migrations(opts: &SerdeContainerOptions, meta: &MetaKeys, type_name: &str) -> String {
+    let version = opts.version.unwrap_or(1);
+    let key = &meta.version_key;
+    let mut code = format!(
+        r#"
+                const __version = obj[{key:?}] ?? 1;
+                if (typeof __version !== "number" || !Number.isInteger(__version) || __version < 1 || __version > {version}) {{
+                    throw new DeserializeError([{{ field: {key:?}, message: "{type_name}: unsupported version " + String(__version), code: "unsupportedVersion", params: {{ version: __version, max: {version} }} }}]);
+                }}
+"#
+    );
+    for (from, migration) in &opts.migrations {
+        code.push_str(&format!(
+            r#"
+                if (__version <= {from}) {{
+                    obj = {migration}(obj as any);
+                }}
+"#
+        ));
+    }
+    code
+}
+
 #[ts_macro_derive(
     Deserialize,
     description = "Generates deserialization methods with cycle/forward-reference support (fromStringifiedJSON, __deserialize)",
@@ -246,7 +540,10 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
-            let container_opts = SerdeContainerOptions::from_decorators(&class.inner.decorators);
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &class.inner.decorators,
+            );
 
             // Check for user-defined constructor with parameters
             if let Some(ctor) = class.method("constructor")
@@ -262,6 +559,33 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 ));
             }
 
+            // Build generic type signature if type has type params
+            let type_params = class.type_params();
+            let is_generic = !type_params.is_empty();
+            let (generic_decl, generic_args) = if type_params.is_empty() {
+                (String::new(), String::new())
+            } else {
+                let params = type_params.join(", ");
+                (format!("<{}>", params), format!("<{}>", params))
+            };
+            let full_type_name = format!("{}{}", class_name, generic_args);
+            let validate_field_generic_decl = if type_params.is_empty() {
+                format!("<K extends keyof {}>", class_name)
+            } else {
+                let params = type_params.join(", ");
+                format!("<{}, K extends keyof {}>", params, full_type_name)
+            };
+            let types_type = type_deserializers_type(type_params);
+            let type_param_names = type_param_names(type_params);
+            let (ctx_params, ctx_args) = if is_generic {
+                (
+                    format!("ctx: DeserializeContext, typeArgs?: {types_type} | TypeDeserializer.Positional"),
+                    "ctx, types",
+                )
+            } else {
+                ("ctx: DeserializeContext".to_string(), "ctx")
+            };
+
             // Collect deserializable fields
             let fields: Vec<DeserializeField> = class
                 .fields()
@@ -277,7 +601,8 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         .clone()
                         .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                    let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                    let type_cat =
+                        TypeCategory::from_type_ir(&field.type_ir).resolve_type_params(type_params);
 
                     Some(DeserializeField {
                         json_key,
@@ -317,6 +642,18 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             let _has_optional = !optional_fields.is_empty();
             let has_flatten = !flatten_fields.is_empty();
             let deny_unknown = container_opts.deny_unknown_fields;
+            let track_refs = !container_opts.plain;
+            let msgpack = container_opts.msgpack;
+            let stream = container_opts.stream;
+            check_migrations(&container_opts, input.decorator_span())?;
+            let has_version = container_opts.version.is_some();
+            let meta = container_opts.meta_keys();
+            let migration_code = generate_migrations(&container_opts, &meta, class_name);
+            let value_ref = optional_access("value", &meta.ref_key);
+            let value_ref_id = member_access("value", &meta.ref_key);
+            let value_id = member_access("value", &meta.id_key);
+            let item_ref = optional_access("item", &meta.ref_key);
+            let item_ref_id = member_access("item", &meta.ref_key);
 
             // All non-flatten fields for assignments
             let all_fields: Vec<_> = fields.iter().filter(|f| !f.flatten).cloned().collect();
@@ -329,6 +666,11 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 .cloned()
                 .collect();
             let has_validators = !fields_with_validators.is_empty();
+            let conditional_code = generate_conditional_requirements(&all_fields);
+            let has_conditional = !conditional_code.is_empty();
+            let rules_code = generate_container_validations(&container_opts.validators);
+            let has_rules = !rules_code.is_empty();
+            let needs_codec = all_fields.iter().any(|f| f.type_cat.decodes_with_runtime());
 
             let mut result = body! {
                 constructor(props: { {#for field in &all_fields} @{field.field_name}{#if field.optional}?{/if}: @{field.ts_type}; {/for} }) {
@@ -337,29 +679,52 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     {/for}
                 }
 
-                static fromStringifiedJSON(json: string, opts?: DeserializeOptions): Result<@{class_name}, Array<{ field: string; message: string }>> {
+                static {|fromStringifiedJSON@{generic_decl}|}(json: string, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                     try {
                         const raw = JSON.parse(json);
-                        return @{class_name}.fromObject(raw, opts);
+                        return @{class_name}.fromObject(raw, {#if is_generic}types, {/if}opts);
                     } catch (e) {
                         if (e instanceof DeserializeError) {
-                            return Result.err(e.errors);
+                            return Result.err(FieldError.format(e.errors, opts));
                         }
                         const message = e instanceof Error ? e.message : String(e);
-                        return Result.err([{ field: "_root", message }]);
+                        return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                     }
                 }
 
-                static fromObject(obj: unknown, opts?: DeserializeOptions): Result<@{class_name}, Array<{ field: string; message: string }>> {
+                {#if stream}
+                    static async *{|fromJSONStream@{generic_decl}|}(chunks: AsyncIterable<string | Uint8Array>, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): AsyncGenerator<Result<@{full_type_name}, Array<FieldError>>> {
+                        for await (const raw of JsonStream.items(chunks)) {
+                            yield @{class_name}.fromObject(raw, {#if is_generic}types, {/if}opts);
+                        }
+                    }
+                {/if}
+
+                {#if msgpack}
+                    static {|fromMsgPack@{generic_decl}|}(bytes: Uint8Array, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
+                        try {
+                            const raw = MsgPack.decode(bytes);
+                            return @{class_name}.fromObject(raw, {#if is_generic}types, {/if}opts);
+                        } catch (e) {
+                            const message = e instanceof Error ? e.message : String(e);
+                            return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
+                        }
+                    }
+                {/if}
+
+                static {|fromObject@{generic_decl}|}(obj: unknown, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                     try {
                         const ctx = DeserializeContext.create();
-                        const resultOrRef = @{class_name}.__deserialize(obj, ctx);
+                        const resultOrRef = @{class_name}.__deserialize(obj, @{ctx_args});
 
                         if (PendingRef.is(resultOrRef)) {
-                            return Result.err([{ field: "_root", message: "@{class_name}.fromObject: root cannot be a forward reference" }]);
+                            return Result.err(FieldError.format([{ field: "_root", message: "@{class_name}.fromObject: root cannot be a forward reference", code: "forwardReference" }], opts));
                         }
 
                         ctx.applyPatches();
+                        if (ctx.hasPendingValidations()) {
+                            return Result.err(FieldError.format([{ field: "_root", message: "@{class_name}.fromObject: async validators require fromObjectAsync", code: "asyncValidation" }], opts));
+                        }
                         if (opts?.freeze) {
                             ctx.freezeAll();
                         }
@@ -367,31 +732,69 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         return Result.ok(resultOrRef);
                     } catch (e) {
                         if (e instanceof DeserializeError) {
-                            return Result.err(e.errors);
+                            return Result.err(FieldError.format(e.errors, opts));
                         }
                         const message = e instanceof Error ? e.message : String(e);
-                        return Result.err([{ field: "_root", message }]);
+                        return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                     }
                 }
 
-                static __deserialize(value: any, ctx: DeserializeContext): @{class_name} | PendingRef {
-                    // Handle reference to already-deserialized object
-                    if (value?.__ref !== undefined) {
-                        return ctx.getOrDefer(value.__ref);
+                static async {|fromObjectAsync@{generic_decl}|}(obj: unknown, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Promise<Result<@{full_type_name}, Array<FieldError>>> {
+                    try {
+                        const ctx = DeserializeContext.create();
+                        const resultOrRef = @{class_name}.__deserialize(obj, @{ctx_args});
+
+                        if (PendingRef.is(resultOrRef)) {
+                            return Result.err(FieldError.format([{ field: "_root", message: "@{class_name}.fromObjectAsync: root cannot be a forward reference", code: "forwardReference" }], opts));
+                        }
+
+                        ctx.applyPatches();
+                        const asyncErrors = await ctx.settleValidations();
+                        if (asyncErrors.length > 0) {
+                            return Result.err(FieldError.format(asyncErrors, opts));
+                        }
+                        if (opts?.freeze) {
+                            ctx.freezeAll();
+                        }
+
+                        return Result.ok(resultOrRef);
+                    } catch (e) {
+                        if (e instanceof DeserializeError) {
+                            return Result.err(FieldError.format(e.errors, opts));
+                        }
+                        const message = e instanceof Error ? e.message : String(e);
+                        return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                     }
+                }
+
+                static {|__deserialize@{generic_decl}|}(value: any, @{ctx_params}): @{full_type_name} | PendingRef {
+                    {#if is_generic}
+                        const types = TypeDeserializer.named(typeArgs, [@{type_param_names}]);
+                    {/if}
+                    // Handle reference to already-deserialized object
+                    {#if track_refs}
+                        if (@{value_ref} !== undefined) {
+                            return ctx.getOrDefer(@{value_ref_id});
+                        }
+                    {/if}
 
                     if (typeof value !== "object" || value === null || Array.isArray(value)) {
-                        throw new DeserializeError([{ field: "_root", message: "@{class_name}.__deserialize: expected an object" }]);
+                        throw new DeserializeError([{ field: "_root", message: "@{class_name}.__deserialize: expected an object", code: "invalidType", params: { expected: "object" } }]);
                     }
 
-                    const obj = value as Record<string, unknown>;
-                    const errors: Array<{ field: string; message: string }> = [];
+                    {#if has_version}
+                        let obj = value as Record<string, unknown>;
+                        @{migration_code}
+                    {:else}
+                        const obj = value as Record<string, unknown>;
+                    {/if}
+                    const errors: Array<FieldError> = [];
 
                     {#if deny_unknown}
-                        const knownKeys = new Set(["__type", "__id", "__ref", {#for key in known_keys}"@{key}", {/for}]);
+                        const knownKeys = new Set([{#if track_refs}"@{meta.type_key}", "@{meta.id_key}", "@{meta.ref_key}", {/if}{#if has_version}"@{meta.version_key}", {/if}{#for key in known_keys}"@{key}", {/for}]);
                         for (const key of Object.keys(obj)) {
                             if (!knownKeys.has(key)) {
-                                errors.push({ field: key, message: "unknown field" });
+                                errors.push({ field: key, message: "unknown field", code: "unknownField" });
                             }
                         }
                     {/if}
@@ -399,22 +802,27 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     {#if has_required}
                         {#for field in &required_fields}
                             if (!("@{field.json_key}" in obj)) {
-                                errors.push({ field: "@{field.json_key}", message: "missing required field" });
+                                errors.push({ field: "@{field.json_key}", message: "missing required field", code: "required" });
                             }
                         {/for}
                     {/if}
 
+                    {#if has_conditional}
+                        @{conditional_code}
+                    {/if}
+
                     if (errors.length > 0) {
                         throw new DeserializeError(errors);
                     }
 
                     // Create instance using Object.create to avoid constructor
-                    const instance = Object.create(@{class_name}.prototype) as @{class_name};
+                    const instance = Object.create(@{class_name}.prototype) as @{full_type_name};
 
-                    // Register with context if __id is present
-                    if (obj.__id !== undefined) {
-                        ctx.register(obj.__id as number, instance);
-                    }
+                    {#if track_refs}
+                        if (@{value_id} !== undefined) {
+                            ctx.register(@{value_id} as number, instance);
+                        }
+                    {/if}
 
                     // Track for optional freezing
                     ctx.trackForFreeze(instance);
@@ -423,14 +831,30 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     {#if has_fields}
                         {#for field in all_fields}
                             {$let raw_var = format!("__raw_{}", field.field_name)}
+                            {$let raw_any = format!("({raw_var} as any)")}
+                            {$let raw_ref = optional_access(&raw_any, &meta.ref_key)}
+                            {$let raw_ref_id = member_access(&raw_any, &meta.ref_key)}
                             {$let has_validators = field.has_validators()}
                             {#if field.optional}
                                 if ("@{field.json_key}" in obj && obj["@{field.json_key}"] !== undefined) {
                                     const @{raw_var} = obj["@{field.json_key}"];
                                     {#match &field.type_cat}
+                                        {:case category if category.is_codec()}
+                                            {$let decoded = field.type_cat.decode_expr(&raw_var).unwrap_or_default()}
+                                            try {
+                                                const __decoded = @{decoded};
+                                                {#if has_validators}
+                                                    {$let validation_code = generate_field_validations(&field.validators, "__decoded", &field.json_key, class_name, true)}
+                                                    @{validation_code}
+                                                {/if}
+                                                instance.@{field.field_name} = __decoded;
+                                            } catch (__e) {
+                                                errors.push({ field: "@{field.json_key}", message: String((__e as Error)?.message ?? __e), code: "invalidType" });
+                                            }
+
                                         {:case TypeCategory::Primitive}
                                             {#if has_validators}
-                                                {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name)}
+                                                {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name, true)}
                                                 @{validation_code}
                                             {/if}
                                             instance.@{field.field_name} = @{raw_var};
@@ -439,7 +863,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                             {
                                                 const __dateVal = typeof @{raw_var} === "string" ? new Date(@{raw_var}) : @{raw_var} as Date;
                                                 {#if has_validators}
-                                                    {$let validation_code = generate_field_validations(&field.validators, "__dateVal", &field.json_key, class_name)}
+                                                    {$let validation_code = generate_field_validations(&field.validators, "__dateVal", &field.json_key, class_name, true)}
                                                     @{validation_code}
                                                 {/if}
                                                 instance.@{field.field_name} = __dateVal;
@@ -448,7 +872,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                         {:case TypeCategory::Array(inner)}
                                             if (Array.isArray(@{raw_var})) {
                                                 {#if has_validators}
-                                                    {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name)}
+                                                    {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name, true)}
                                                     @{validation_code}
                                                 {/if}
                                                 const __arr = (@{raw_var} as any[]).map((item, idx) => {
@@ -460,24 +884,27 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                                         }
                                                         return result;
                                                     }
-                                                    // Check for __ref in array items
-                                                    if (item?.__ref !== undefined) {
-                                                        const result = ctx.getOrDefer(item.__ref);
-                                                        if (PendingRef.is(result)) {
-                                                            // Will be patched after array is assigned
-                                                            return { __pendingIdx: idx, __refId: result.id };
+                                                    {#if track_refs}
+                                                        if (@{item_ref} !== undefined) {
+                                                            const result = ctx.getOrDefer(@{item_ref_id});
+                                                            if (PendingRef.is(result)) {
+                                                                // Will be patched after array is assigned
+                                                                return { __pendingIdx: idx, __refId: result.id };
+                                                            }
+                                                            return result;
                                                         }
-                                                        return result;
-                                                    }
+                                                    {/if}
                                                     return item as @{inner};
                                                 });
                                                 instance.@{field.field_name} = __arr;
-                                                // Patch array items that were pending
-                                                __arr.forEach((item, idx) => {
-                                                    if (item && typeof item === "object" && "__pendingIdx" in item) {
-                                                        ctx.deferPatch((item as any).__refId, (v) => { instance.@{field.field_name}[idx] = v; });
-                                                    }
-                                                });
+                                                {#if track_refs}
+                                                    // Patch array items that were pending
+                                                    __arr.forEach((item, idx) => {
+                                                        if (item && typeof item === "object" && "__pendingIdx" in item) {
+                                                            ctx.deferPatch((item as any).__refId, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                        }
+                                                    });
+                                                {/if}
                                             }
 
                                         {:case TypeCategory::Map(key_type, value_type)}
@@ -494,7 +921,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
 
                                         {:case TypeCategory::Serializable(type_name)}
                                             if (typeof (@{type_name} as any)?.__deserialize === "function") {
-                                                const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx);
+                                                const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx@{type_args_suffix(type_name, type_params)});
                                                 if (PendingRef.is(__result)) {
                                                     instance.@{field.field_name} = null as @{field.ts_type};
                                                     ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
@@ -505,18 +932,50 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                                 instance.@{field.field_name} = @{raw_var};
                                             }
 
-                                        {:case TypeCategory::Nullable(_)}
-                                            if (@{raw_var} === null) {
-                                                instance.@{field.field_name} = null;
-                                            } else if (typeof (@{raw_var} as any)?.__ref !== "undefined") {
-                                                const __result = ctx.getOrDefer((@{raw_var} as any).__ref);
+                                        {:case TypeCategory::Generic(param) | TypeCategory::NullableGeneric(param) | TypeCategory::OptionalGeneric(param)}
+                                            {#if let Some(kept) = field.type_cat.generic_passthrough()}
+                                                if (@{raw_var} === @{kept}) {
+                                                    instance.@{field.field_name} = @{kept};
+                                                } else
+                                            {/if}
+                                            {
+                                                const __result = TypeDeserializer.apply(types?.@{param}, @{raw_var}, ctx);
                                                 if (PendingRef.is(__result)) {
                                                     instance.@{field.field_name} = null as @{field.ts_type};
                                                     ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
                                                 } else {
                                                     instance.@{field.field_name} = __result;
                                                 }
-                                            } else {
+                                            }
+
+                                        {:case TypeCategory::GenericArray(param)}
+                                            if (Array.isArray(@{raw_var})) {
+                                                instance.@{field.field_name} = (@{raw_var} as any[]).map((item, idx) => {
+                                                    const __item = TypeDeserializer.apply(types?.@{param}, item, ctx);
+                                                    if (PendingRef.is(__item)) {
+                                                        ctx.deferPatch(__item.id, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                        return null as @{param};
+                                                    }
+                                                    return __item;
+                                                });
+                                            }
+
+                                        {:case TypeCategory::Nullable(_)}
+                                            if (@{raw_var} === null) {
+                                                instance.@{field.field_name} = null;
+                                            }
+                                            {#if track_refs}
+                                                else if (typeof @{raw_ref} !== "undefined") {
+                                                    const __result = ctx.getOrDefer(@{raw_ref_id});
+                                                    if (PendingRef.is(__result)) {
+                                                        instance.@{field.field_name} = null as @{field.ts_type};
+                                                        ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
+                                                    } else {
+                                                        instance.@{field.field_name} = __result;
+                                                    }
+                                                }
+                                            {/if}
+                                            else {
                                                 instance.@{field.field_name} = @{raw_var};
                                             }
 
@@ -533,9 +992,22 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                 {
                                     const @{raw_var} = obj["@{field.json_key}"];
                                     {#match &field.type_cat}
+                                        {:case category if category.is_codec()}
+                                            {$let decoded = field.type_cat.decode_expr(&raw_var).unwrap_or_default()}
+                                            try {
+                                                const __decoded = @{decoded};
+                                                {#if has_validators}
+                                                    {$let validation_code = generate_field_validations(&field.validators, "__decoded", &field.json_key, class_name, true)}
+                                                    @{validation_code}
+                                                {/if}
+                                                instance.@{field.field_name} = __decoded;
+                                            } catch (__e) {
+                                                errors.push({ field: "@{field.json_key}", message: String((__e as Error)?.message ?? __e), code: "invalidType" });
+                                            }
+
                                         {:case TypeCategory::Primitive}
                                             {#if has_validators}
-                                                {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name)}
+                                                {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name, true)}
                                                 @{validation_code}
                                             {/if}
                                             instance.@{field.field_name} = @{raw_var};
@@ -544,7 +1016,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                             {
                                                 const __dateVal = typeof @{raw_var} === "string" ? new Date(@{raw_var}) : @{raw_var} as Date;
                                                 {#if has_validators}
-                                                    {$let validation_code = generate_field_validations(&field.validators, "__dateVal", &field.json_key, class_name)}
+                                                    {$let validation_code = generate_field_validations(&field.validators, "__dateVal", &field.json_key, class_name, true)}
                                                     @{validation_code}
                                                 {/if}
                                                 instance.@{field.field_name} = __dateVal;
@@ -553,25 +1025,29 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                         {:case TypeCategory::Array(inner)}
                                             if (Array.isArray(@{raw_var})) {
                                                 {#if has_validators}
-                                                    {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name)}
+                                                    {$let validation_code = generate_field_validations(&field.validators, &raw_var, &field.json_key, class_name, true)}
                                                     @{validation_code}
                                                 {/if}
                                                 const __arr = (@{raw_var} as any[]).map((item, idx) => {
-                                                    if (item?.__ref !== undefined) {
-                                                        const result = ctx.getOrDefer(item.__ref);
-                                                        if (PendingRef.is(result)) {
-                                                            return { __pendingIdx: idx, __refId: result.id };
+                                                    {#if track_refs}
+                                                        if (@{item_ref} !== undefined) {
+                                                            const result = ctx.getOrDefer(@{item_ref_id});
+                                                            if (PendingRef.is(result)) {
+                                                                return { __pendingIdx: idx, __refId: result.id };
+                                                            }
+                                                            return result;
                                                         }
-                                                        return result;
-                                                    }
+                                                    {/if}
                                                     return item as @{inner};
                                                 });
                                                 instance.@{field.field_name} = __arr;
-                                                __arr.forEach((item, idx) => {
-                                                    if (item && typeof item === "object" && "__pendingIdx" in item) {
-                                                        ctx.deferPatch((item as any).__refId, (v) => { instance.@{field.field_name}[idx] = v; });
-                                                    }
-                                                });
+                                                {#if track_refs}
+                                                    __arr.forEach((item, idx) => {
+                                                        if (item && typeof item === "object" && "__pendingIdx" in item) {
+                                                            ctx.deferPatch((item as any).__refId, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                        }
+                                                    });
+                                                {/if}
                                             }
 
                                         {:case TypeCategory::Map(key_type, value_type)}
@@ -584,7 +1060,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
 
                                         {:case TypeCategory::Serializable(type_name)}
                                             if (typeof (@{type_name} as any)?.__deserialize === "function") {
-                                                const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx);
+                                                const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx@{type_args_suffix(type_name, type_params)});
                                                 if (PendingRef.is(__result)) {
                                                     instance.@{field.field_name} = null as @{field.ts_type};
                                                     ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
@@ -595,18 +1071,50 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                                 instance.@{field.field_name} = @{raw_var};
                                             }
 
-                                        {:case TypeCategory::Nullable(_)}
-                                            if (@{raw_var} === null) {
-                                                instance.@{field.field_name} = null;
-                                            } else if (typeof (@{raw_var} as any)?.__ref !== "undefined") {
-                                                const __result = ctx.getOrDefer((@{raw_var} as any).__ref);
+                                        {:case TypeCategory::Generic(param) | TypeCategory::NullableGeneric(param) | TypeCategory::OptionalGeneric(param)}
+                                            {#if let Some(kept) = field.type_cat.generic_passthrough()}
+                                                if (@{raw_var} === @{kept}) {
+                                                    instance.@{field.field_name} = @{kept};
+                                                } else
+                                            {/if}
+                                            {
+                                                const __result = TypeDeserializer.apply(types?.@{param}, @{raw_var}, ctx);
                                                 if (PendingRef.is(__result)) {
                                                     instance.@{field.field_name} = null as @{field.ts_type};
                                                     ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
                                                 } else {
                                                     instance.@{field.field_name} = __result;
                                                 }
-                                            } else {
+                                            }
+
+                                        {:case TypeCategory::GenericArray(param)}
+                                            if (Array.isArray(@{raw_var})) {
+                                                instance.@{field.field_name} = (@{raw_var} as any[]).map((item, idx) => {
+                                                    const __item = TypeDeserializer.apply(types?.@{param}, item, ctx);
+                                                    if (PendingRef.is(__item)) {
+                                                        ctx.deferPatch(__item.id, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                        return null as @{param};
+                                                    }
+                                                    return __item;
+                                                });
+                                            }
+
+                                        {:case TypeCategory::Nullable(_)}
+                                            if (@{raw_var} === null) {
+                                                instance.@{field.field_name} = null;
+                                            }
+                                            {#if track_refs}
+                                                else if (typeof @{raw_ref} !== "undefined") {
+                                                    const __result = ctx.getOrDefer(@{raw_ref_id});
+                                                    if (PendingRef.is(__result)) {
+                                                        instance.@{field.field_name} = null as @{field.ts_type};
+                                                        ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
+                                                    } else {
+                                                        instance.@{field.field_name} = __result;
+                                                    }
+                                                }
+                                            {/if}
+                                            else {
                                                 instance.@{field.field_name} = @{raw_var};
                                             }
 
@@ -623,7 +1131,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                             {#match &field.type_cat}
                                 {:case TypeCategory::Serializable(type_name)}
                                     if (typeof (@{type_name} as any)?.__deserialize === "function") {
-                                        const __result = (@{type_name} as any).__deserialize(obj, ctx);
+                                        const __result = (@{type_name} as any).__deserialize(obj, ctx@{type_args_suffix(type_name, type_params)});
                                         if (PendingRef.is(__result)) {
                                             instance.@{field.field_name} = null as @{field.ts_type};
                                             ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
@@ -637,6 +1145,12 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         {/for}
                     {/if}
 
+                    {#if has_rules}
+                        if (errors.length === 0) {
+                            @{rules_code}
+                        }
+                    {/if}
+
                     if (errors.length > 0) {
                         throw new DeserializeError(errors);
                     }
@@ -644,17 +1158,17 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     return instance;
                 }
 
-                static validateField<K extends keyof @{class_name}>(
+                static validateField@{validate_field_generic_decl}(
                     field: K,
-                    value: @{class_name}[K]
-                ): Array<{ field: string; message: string }> {
+                    value: @{full_type_name}[K]
+                ): Array<FieldError> {
                     {#if has_validators}
-                    const errors: Array<{ field: string; message: string }> = [];
+                    const errors: Array<FieldError> = [];
                     switch (field) {
                         {#for field in &fields_with_validators}
                         case "@{field.field_name}": {
                             const __val = value as @{field.ts_type};
-                            {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, class_name)}
+                            {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, class_name, false)}
                             @{validation_code}
                             break;
                         }
@@ -666,15 +1180,15 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     {/if}
                 }
 
-                static validateFields(
-                    partial: Partial<@{class_name}>
-                ): Array<{ field: string; message: string }> {
+                static {|validateFields@{generic_decl}|}(
+                    partial: Partial<@{full_type_name}>
+                ): Array<FieldError> {
                     {#if has_validators}
-                    const errors: Array<{ field: string; message: string }> = [];
+                    const errors: Array<FieldError> = [];
                     {#for field in &fields_with_validators}
                     if ("@{field.field_name}" in partial && partial.@{field.field_name} !== undefined) {
                         const __val = partial.@{field.field_name} as @{field.ts_type};
-                        {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, class_name)}
+                        {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, class_name, false)}
                         @{validation_code}
                     }
                     {/for}
@@ -688,7 +1202,20 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             result.add_import("DeserializeContext", "macroforge/serde");
             result.add_import("DeserializeError", "macroforge/serde");
             result.add_type_import("DeserializeOptions", "macroforge/serde");
+            result.add_import("FieldError", "macroforge/serde");
+            if needs_codec {
+                result.add_import("Codec", "macroforge/serde");
+            }
+            if msgpack {
+                result.add_import("MsgPack", "macroforge/serde");
+            }
+            if stream {
+                result.add_import("JsonStream", "macroforge/serde");
+            }
             result.add_import("PendingRef", "macroforge/serde");
+            if is_generic || applies_generic_types(&fields, type_params) {
+                result.add_import("TypeDeserializer", "macroforge/serde");
+            }
             Ok(result)
         }
         Data::Enum(_) => {
@@ -716,8 +1243,37 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
-            let container_opts =
-                SerdeContainerOptions::from_decorators(&interface.inner.decorators);
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &interface.inner.decorators,
+            );
+
+            // Build generic type signature if type has type params
+            let type_params = interface.type_params();
+            let is_generic = !type_params.is_empty();
+            let (generic_decl, generic_args) = if type_params.is_empty() {
+                (String::new(), String::new())
+            } else {
+                let params = type_params.join(", ");
+                (format!("<{}>", params), format!("<{}>", params))
+            };
+            let full_type_name = format!("{}{}", interface_name, generic_args);
+            let validate_field_generic_decl = if type_params.is_empty() {
+                format!("<K extends keyof {}>", interface_name)
+            } else {
+                let params = type_params.join(", ");
+                format!("<{}, K extends keyof {}>", params, full_type_name)
+            };
+            let types_type = type_deserializers_type(type_params);
+            let type_param_names = type_param_names(type_params);
+            let (ctx_params, ctx_args) = if is_generic {
+                (
+                    format!("ctx: DeserializeContext, typeArgs?: {types_type} | TypeDeserializer.Positional"),
+                    "ctx, types",
+                )
+            } else {
+                ("ctx: DeserializeContext".to_string(), "ctx")
+            };
 
             let fields: Vec<DeserializeField> = interface
                 .fields()
@@ -733,7 +1289,8 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         .clone()
                         .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                    let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                    let type_cat =
+                        TypeCategory::from_type_ir(&field.type_ir).resolve_type_params(type_params);
 
                     Some(DeserializeField {
                         json_key,
@@ -765,6 +1322,16 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             let has_required = !required_fields.is_empty();
             let has_fields = !all_fields.is_empty();
             let deny_unknown = container_opts.deny_unknown_fields;
+            let track_refs = !container_opts.plain;
+            let msgpack = container_opts.msgpack;
+            let stream = container_opts.stream;
+            check_migrations(&container_opts, input.decorator_span())?;
+            let has_version = container_opts.version.is_some();
+            let meta = container_opts.meta_keys();
+            let migration_code = generate_migrations(&container_opts, &meta, interface_name);
+            let value_ref = optional_access("value", &meta.ref_key);
+            let value_ref_id = member_access("value", &meta.ref_key);
+            let value_id = member_access("value", &meta.id_key);
 
             // Fields with validators for per-field validation
             let fields_with_validators: Vec<_> = all_fields
@@ -773,32 +1340,60 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 .cloned()
                 .collect();
             let has_validators = !fields_with_validators.is_empty();
+            let conditional_code = generate_conditional_requirements(&all_fields);
+            let has_conditional = !conditional_code.is_empty();
+            let rules_code = generate_container_validations(&container_opts.validators);
+            let has_rules = !rules_code.is_empty();
+            let needs_codec = all_fields.iter().any(|f| f.type_cat.decodes_with_runtime());
 
             let mut result = ts_template! {
                 export namespace @{interface_name} {
-                    export function fromStringifiedJSON(json: string, opts?: DeserializeOptions): Result<@{interface_name}, Array<{ field: string; message: string }>> {
+                    export function {|fromStringifiedJSON@{generic_decl}|}(json: string, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                         try {
                             const raw = JSON.parse(json);
-                            return fromObject(raw, opts);
+                            return fromObject(raw, {#if is_generic}types, {/if}opts);
                         } catch (e) {
                             if (e instanceof DeserializeError) {
-                                return Result.err(e.errors);
+                                return Result.err(FieldError.format(e.errors, opts));
                             }
                             const message = e instanceof Error ? e.message : String(e);
-                            return Result.err([{ field: "_root", message }]);
+                            return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                         }
                     }
 
-                    export function fromObject(obj: unknown, opts?: DeserializeOptions): Result<@{interface_name}, Array<{ field: string; message: string }>> {
+                    {#if stream}
+                        export async function* {|fromJSONStream@{generic_decl}|}(chunks: AsyncIterable<string | Uint8Array>, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): AsyncGenerator<Result<@{full_type_name}, Array<FieldError>>> {
+                            for await (const raw of JsonStream.items(chunks)) {
+                                yield fromObject(raw, {#if is_generic}types, {/if}opts);
+                            }
+                        }
+                    {/if}
+
+                    {#if msgpack}
+                        export function {|fromMsgPack@{generic_decl}|}(bytes: Uint8Array, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
+                            try {
+                                const raw = MsgPack.decode(bytes);
+                                return fromObject(raw, {#if is_generic}types, {/if}opts);
+                            } catch (e) {
+                                const message = e instanceof Error ? e.message : String(e);
+                                return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
+                            }
+                        }
+                    {/if}
+
+                    export function {|fromObject@{generic_decl}|}(obj: unknown, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                         try {
                             const ctx = DeserializeContext.create();
-                            const resultOrRef = __deserialize(obj, ctx);
+                            const resultOrRef = __deserialize(obj, @{ctx_args});
 
                             if (PendingRef.is(resultOrRef)) {
-                                return Result.err([{ field: "_root", message: "@{interface_name}.fromObject: root cannot be a forward reference" }]);
+                                return Result.err(FieldError.format([{ field: "_root", message: "@{interface_name}.fromObject: root cannot be a forward reference", code: "forwardReference" }], opts));
                             }
 
                             ctx.applyPatches();
+                            if (ctx.hasPendingValidations()) {
+                                return Result.err(FieldError.format([{ field: "_root", message: "@{interface_name}.fromObject: async validators require fromObjectAsync", code: "asyncValidation" }], opts));
+                            }
                             if (opts?.freeze) {
                                 ctx.freezeAll();
                             }
@@ -806,30 +1401,68 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                             return Result.ok(resultOrRef);
                         } catch (e) {
                             if (e instanceof DeserializeError) {
-                                return Result.err(e.errors);
+                                return Result.err(FieldError.format(e.errors, opts));
                             }
                             const message = e instanceof Error ? e.message : String(e);
-                            return Result.err([{ field: "_root", message }]);
+                            return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                         }
                     }
 
-                    export function __deserialize(value: any, ctx: DeserializeContext): @{interface_name} | PendingRef {
-                        if (value?.__ref !== undefined) {
-                            return ctx.getOrDefer(value.__ref);
+                    export async function {|fromObjectAsync@{generic_decl}|}(obj: unknown, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Promise<Result<@{full_type_name}, Array<FieldError>>> {
+                        try {
+                            const ctx = DeserializeContext.create();
+                            const resultOrRef = __deserialize(obj, @{ctx_args});
+
+                            if (PendingRef.is(resultOrRef)) {
+                                return Result.err(FieldError.format([{ field: "_root", message: "@{interface_name}.fromObjectAsync: root cannot be a forward reference", code: "forwardReference" }], opts));
+                            }
+
+                            ctx.applyPatches();
+                            const asyncErrors = await ctx.settleValidations();
+                            if (asyncErrors.length > 0) {
+                                return Result.err(FieldError.format(asyncErrors, opts));
+                            }
+                            if (opts?.freeze) {
+                                ctx.freezeAll();
+                            }
+
+                            return Result.ok(resultOrRef);
+                        } catch (e) {
+                            if (e instanceof DeserializeError) {
+                                return Result.err(FieldError.format(e.errors, opts));
+                            }
+                            const message = e instanceof Error ? e.message : String(e);
+                            return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                         }
+                    }
+
+                    export function {|__deserialize@{generic_decl}|}(value: any, @{ctx_params}): @{full_type_name} | PendingRef {
+                        {#if is_generic}
+                            const types = TypeDeserializer.named(typeArgs, [@{type_param_names}]);
+                        {/if}
+                        {#if track_refs}
+                            if (@{value_ref} !== undefined) {
+                                return ctx.getOrDefer(@{value_ref_id});
+                            }
+                        {/if}
 
                         if (typeof value !== "object" || value === null || Array.isArray(value)) {
-                            throw new DeserializeError([{ field: "_root", message: "@{interface_name}.__deserialize: expected an object" }]);
+                            throw new DeserializeError([{ field: "_root", message: "@{interface_name}.__deserialize: expected an object", code: "invalidType", params: { expected: "object" } }]);
                         }
 
-                        const obj = value as Record<string, unknown>;
-                        const errors: Array<{ field: string; message: string }> = [];
+                        {#if has_version}
+                            let obj = value as Record<string, unknown>;
+                            @{migration_code}
+                        {:else}
+                            const obj = value as Record<string, unknown>;
+                        {/if}
+                        const errors: Array<FieldError> = [];
 
                         {#if deny_unknown}
-                            const knownKeys = new Set(["__type", "__id", "__ref", {#for key in known_keys}"@{key}", {/for}]);
+                            const knownKeys = new Set([{#if track_refs}"@{meta.type_key}", "@{meta.id_key}", "@{meta.ref_key}", {/if}{#if has_version}"@{meta.version_key}", {/if}{#for key in known_keys}"@{key}", {/for}]);
                             for (const key of Object.keys(obj)) {
                                 if (!knownKeys.has(key)) {
-                                    errors.push({ field: key, message: "unknown field" });
+                                    errors.push({ field: key, message: "unknown field", code: "unknownField" });
                                 }
                             }
                         {/if}
@@ -837,20 +1470,26 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         {#if has_required}
                             {#for field in &required_fields}
                                 if (!("@{field.json_key}" in obj)) {
-                                    errors.push({ field: "@{field.json_key}", message: "missing required field" });
+                                    errors.push({ field: "@{field.json_key}", message: "missing required field", code: "required" });
                                 }
                             {/for}
                         {/if}
 
+                        {#if has_conditional}
+                            @{conditional_code}
+                        {/if}
+
                         if (errors.length > 0) {
                             throw new DeserializeError(errors);
                         }
 
                         const instance: any = {};
 
-                        if (obj.__id !== undefined) {
-                            ctx.register(obj.__id as number, instance);
-                        }
+                        {#if track_refs}
+                            if (@{value_id} !== undefined) {
+                                ctx.register(@{value_id} as number, instance);
+                            }
+                        {/if}
 
                         ctx.trackForFreeze(instance);
 
@@ -861,6 +1500,14 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                     if ("@{field.json_key}" in obj && obj["@{field.json_key}"] !== undefined) {
                                         const @{raw_var} = obj["@{field.json_key}"];
                                         {#match &field.type_cat}
+                                            {:case category if category.is_codec()}
+                                                {$let decoded = field.type_cat.decode_expr(&raw_var).unwrap_or_default()}
+                                                try {
+                                                    instance.@{field.field_name} = @{decoded};
+                                                } catch (__e) {
+                                                    errors.push({ field: "@{field.json_key}", message: String((__e as Error)?.message ?? __e), code: "invalidType" });
+                                                }
+
                                             {:case TypeCategory::Date}
                                                 instance.@{field.field_name} = typeof @{raw_var} === "string" ? new Date(@{raw_var}) : @{raw_var};
 
@@ -874,7 +1521,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
 
                                             {:case TypeCategory::Serializable(type_name)}
                                                 if (typeof (@{type_name} as any)?.__deserialize === "function") {
-                                                    const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx);
+                                                    const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx@{type_args_suffix(type_name, type_params)});
                                                     if (PendingRef.is(__result)) {
                                                         instance.@{field.field_name} = null;
                                                         ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
@@ -885,6 +1532,34 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                                     instance.@{field.field_name} = @{raw_var};
                                                 }
 
+                                            {:case TypeCategory::Generic(param) | TypeCategory::NullableGeneric(param) | TypeCategory::OptionalGeneric(param)}
+                                                {#if let Some(kept) = field.type_cat.generic_passthrough()}
+                                                    if (@{raw_var} === @{kept}) {
+                                                        instance.@{field.field_name} = @{kept};
+                                                    } else
+                                                {/if}
+                                                {
+                                                    const __result = TypeDeserializer.apply(types?.@{param}, @{raw_var}, ctx);
+                                                    if (PendingRef.is(__result)) {
+                                                        instance.@{field.field_name} = null;
+                                                        ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
+                                                    } else {
+                                                        instance.@{field.field_name} = __result;
+                                                    }
+                                                }
+
+                                            {:case TypeCategory::GenericArray(param)}
+                                                if (Array.isArray(@{raw_var})) {
+                                                    instance.@{field.field_name} = (@{raw_var} as any[]).map((item, idx) => {
+                                                        const __item = TypeDeserializer.apply(types?.@{param}, item, ctx);
+                                                        if (PendingRef.is(__item)) {
+                                                            ctx.deferPatch(__item.id, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                            return null as @{param};
+                                                        }
+                                                        return __item;
+                                                    });
+                                                }
+
                                             {:case _}
                                                 instance.@{field.field_name} = @{raw_var};
                                         {/match}
@@ -898,6 +1573,14 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                     {
                                         const @{raw_var} = obj["@{field.json_key}"];
                                         {#match &field.type_cat}
+                                            {:case category if category.is_codec()}
+                                                {$let decoded = field.type_cat.decode_expr(&raw_var).unwrap_or_default()}
+                                                try {
+                                                    instance.@{field.field_name} = @{decoded};
+                                                } catch (__e) {
+                                                    errors.push({ field: "@{field.json_key}", message: String((__e as Error)?.message ?? __e), code: "invalidType" });
+                                                }
+
                                             {:case TypeCategory::Date}
                                                 instance.@{field.field_name} = typeof @{raw_var} === "string" ? new Date(@{raw_var}) : @{raw_var};
 
@@ -911,7 +1594,7 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
 
                                             {:case TypeCategory::Serializable(type_name)}
                                                 if (typeof (@{type_name} as any)?.__deserialize === "function") {
-                                                    const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx);
+                                                    const __result = (@{type_name} as any).__deserialize(@{raw_var}, ctx@{type_args_suffix(type_name, type_params)});
                                                     if (PendingRef.is(__result)) {
                                                         instance.@{field.field_name} = null;
                                                         ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
@@ -922,6 +1605,34 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                                     instance.@{field.field_name} = @{raw_var};
                                                 }
 
+                                            {:case TypeCategory::Generic(param) | TypeCategory::NullableGeneric(param) | TypeCategory::OptionalGeneric(param)}
+                                                {#if let Some(kept) = field.type_cat.generic_passthrough()}
+                                                    if (@{raw_var} === @{kept}) {
+                                                        instance.@{field.field_name} = @{kept};
+                                                    } else
+                                                {/if}
+                                                {
+                                                    const __result = TypeDeserializer.apply(types?.@{param}, @{raw_var}, ctx);
+                                                    if (PendingRef.is(__result)) {
+                                                        instance.@{field.field_name} = null;
+                                                        ctx.deferPatch(__result.id, (v) => { instance.@{field.field_name} = v; });
+                                                    } else {
+                                                        instance.@{field.field_name} = __result;
+                                                    }
+                                                }
+
+                                            {:case TypeCategory::GenericArray(param)}
+                                                if (Array.isArray(@{raw_var})) {
+                                                    instance.@{field.field_name} = (@{raw_var} as any[]).map((item, idx) => {
+                                                        const __item = TypeDeserializer.apply(types?.@{param}, item, ctx);
+                                                        if (PendingRef.is(__item)) {
+                                                            ctx.deferPatch(__item.id, (v) => { instance.@{field.field_name}[idx] = v; });
+                                                            return null as @{param};
+                                                        }
+                                                        return __item;
+                                                    });
+                                                }
+
                                             {:case _}
                                                 instance.@{field.field_name} = @{raw_var};
                                         {/match}
@@ -930,24 +1641,30 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                             {/for}
                         {/if}
 
+                        {#if has_rules}
+                            if (errors.length === 0) {
+                                @{rules_code}
+                            }
+                        {/if}
+
                         if (errors.length > 0) {
                             throw new DeserializeError(errors);
                         }
 
-                        return instance as @{interface_name};
+                        return instance as @{full_type_name};
                     }
 
-                    export function validateField<K extends keyof @{interface_name}>(
+                    export function {|validateField@{validate_field_generic_decl}|}(
                         field: K,
-                        value: @{interface_name}[K]
-                    ): Array<{ field: string; message: string }> {
+                        value: @{full_type_name}[K]
+                    ): Array<FieldError> {
                         {#if has_validators}
-                        const errors: Array<{ field: string; message: string }> = [];
+                        const errors: Array<FieldError> = [];
                         switch (field) {
                             {#for field in &fields_with_validators}
                             case "@{field.field_name}": {
                                 const __val = value as @{field.ts_type};
-                                {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, interface_name)}
+                                {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, interface_name, false)}
                                 @{validation_code}
                                 break;
                             }
@@ -959,15 +1676,15 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         {/if}
                     }
 
-                    export function validateFields(
-                        partial: Partial<@{interface_name}>
-                    ): Array<{ field: string; message: string }> {
+                    export function {|validateFields@{generic_decl}|}(
+                        partial: Partial<@{full_type_name}>
+                    ): Array<FieldError> {
                         {#if has_validators}
-                        const errors: Array<{ field: string; message: string }> = [];
+                        const errors: Array<FieldError> = [];
                         {#for field in &fields_with_validators}
                         if ("@{field.field_name}" in partial && partial.@{field.field_name} !== undefined) {
                             const __val = partial.@{field.field_name} as @{field.ts_type};
-                            {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, interface_name)}
+                            {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, interface_name, false)}
                             @{validation_code}
                         }
                         {/for}
@@ -982,7 +1699,20 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             result.add_import("DeserializeContext", "macroforge/serde");
             result.add_import("DeserializeError", "macroforge/serde");
             result.add_type_import("DeserializeOptions", "macroforge/serde");
+            result.add_import("FieldError", "macroforge/serde");
+            if needs_codec {
+                result.add_import("Codec", "macroforge/serde");
+            }
+            if msgpack {
+                result.add_import("MsgPack", "macroforge/serde");
+            }
+            if stream {
+                result.add_import("JsonStream", "macroforge/serde");
+            }
             result.add_import("PendingRef", "macroforge/serde");
+            if is_generic || applies_generic_types(&fields, type_params) {
+                result.add_import("TypeDeserializer", "macroforge/serde");
+            }
             Ok(result)
         }
         Data::TypeAlias(type_alias) => {
@@ -997,6 +1727,17 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 (format!("<{}>", params), format!("<{}>", params))
             };
             let full_type_name = format!("{}{}", type_name, generic_args);
+            let is_generic = !type_params.is_empty();
+            let types_type = type_deserializers_type(type_params);
+            let type_param_names = type_param_names(type_params);
+            let (ctx_params, ctx_args) = if is_generic {
+                (
+                    format!("ctx: DeserializeContext, typeArgs?: {types_type} | TypeDeserializer.Positional"),
+                    "ctx, types",
+                )
+            } else {
+                ("ctx: DeserializeContext".to_string(), "ctx")
+            };
 
             // Create combined generic declarations for validateField that include K
             let validate_field_generic_decl = if type_params.is_empty() {
@@ -1006,9 +1747,26 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 format!("<{}, K extends keyof {}>", params, full_type_name)
             };
 
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &type_alias.inner.decorators,
+            );
+            let track_refs = !container_opts.plain;
+            let msgpack = container_opts.msgpack;
+            let stream = container_opts.stream;
+            check_migrations(&container_opts, input.decorator_span())?;
+            let has_version = container_opts.version.is_some();
+            let meta = container_opts.meta_keys();
+            let migration_code = generate_migrations(&container_opts, &meta, type_name);
+            let value_ref = optional_access("value", &meta.ref_key);
+            let value_ref_id = member_access("value", &meta.ref_key);
+            let value_id = member_access("value", &meta.id_key);
+            let value_type = optional_access("(value as any)", &meta.type_key);
+            let instance_type = member_access("instance", &meta.type_key);
+            let instance_id = member_access("instance", &meta.id_key);
+
             if type_alias.is_object() {
                 // Extract fields for object type aliases to support validation
-                let container_opts = SerdeContainerOptions::from_decorators(&type_alias.inner.decorators);
                 let object_fields = type_alias.as_object().unwrap_or(&[]);
 
                 let fields: Vec<DeserializeField> = object_fields
@@ -1024,7 +1782,8 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                             .clone()
                             .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                        let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                        let type_cat = TypeCategory::from_type_ir(&field.type_ir)
+                            .resolve_type_params(type_params);
 
                         Some(DeserializeField {
                             json_key,
@@ -1050,25 +1809,55 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                     .collect();
                 let has_validators = !fields_with_validators.is_empty();
 
+                // Fields typed by a type parameter are deserialized with the caller's `types`,
+                // and codec fields (bigint, bytes, URL, ...) are decoded from their JSON form
+                let patched_fields: Vec<_> = all_fields
+                    .iter()
+                    .filter(|f| {
+                        f.type_cat.is_codec() || f.type_cat.is_generic()
+                    })
+                    .cloned()
+                    .collect();
+
                 let mut result = ts_template! {
                     export namespace @{type_name} {
-                        export function {|fromStringifiedJSON@{generic_decl}|}(json: string, opts?: DeserializeOptions): Result<@{full_type_name}, Array<{ field: string; message: string }>> {
+                        export function {|fromStringifiedJSON@{generic_decl}|}(json: string, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                             try {
                                 const raw = JSON.parse(json);
-                                return fromObject(raw, opts);
+                                return fromObject(raw, {#if is_generic}types, {/if}opts);
                             } catch (e) {
                                 if (e instanceof DeserializeError) {
-                                    return Result.err(e.errors);
+                                    return Result.err(FieldError.format(e.errors, opts));
                                 }
                                 const message = e instanceof Error ? e.message : String(e);
-                                return Result.err([{ field: "_root", message }]);
+                                return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                             }
                         }
 
-                        export function {|fromObject@{generic_decl}|}(obj: unknown, opts?: DeserializeOptions): Result<@{full_type_name}, Array<{ field: string; message: string }>> {
+                        {#if stream}
+                            export async function* {|fromJSONStream@{generic_decl}|}(chunks: AsyncIterable<string | Uint8Array>, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): AsyncGenerator<Result<@{full_type_name}, Array<FieldError>>> {
+                                for await (const raw of JsonStream.items(chunks)) {
+                                    yield fromObject(raw, {#if is_generic}types, {/if}opts);
+                                }
+                            }
+                        {/if}
+
+                        {#if msgpack}
+                            export function {|fromMsgPack@{generic_decl}|}(bytes: Uint8Array, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
+                                try {
+                                    const raw = MsgPack.decode(bytes);
+                                    return fromObject(raw, {#if is_generic}types, {/if}opts);
+                                } catch (e) {
+                                    const message = e instanceof Error ? e.message : String(e);
+                                    return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
+                                }
+                            }
+                        {/if}
+
+                        export function {|fromObject@{generic_decl}|}(obj: unknown, {#if is_generic}types: @{types_type}, {/if}opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                             try {
                                 const ctx = DeserializeContext.create();
-                                const result = __deserialize@{generic_args}(obj, ctx);
+                                const result = __deserialize@{generic_args}(obj, @{ctx_args});
                                 ctx.applyPatches();
                                 if (opts?.freeze) {
                                     ctx.freezeAll();
@@ -1076,41 +1865,93 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                 return Result.ok<@{full_type_name}>(result);
                             } catch (e) {
                                 if (e instanceof DeserializeError) {
-                                    return Result.err(e.errors);
+                                    return Result.err(FieldError.format(e.errors, opts));
                                 }
                                 const message = e instanceof Error ? e.message : String(e);
-                                return Result.err([{ field: "_root", message }]);
+                                return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                             }
                         }
 
-                        export function {|__deserialize@{generic_decl}|}(value: any, ctx: DeserializeContext): @{full_type_name} {
-                            if (value?.__ref !== undefined) {
-                                return ctx.getOrDefer(value.__ref) as @{full_type_name};
-                            }
+                        export function {|__deserialize@{generic_decl}|}(value: any, @{ctx_params}): @{full_type_name} {
+                            {#if is_generic}
+                                const types = TypeDeserializer.named(typeArgs, [@{type_param_names}]);
+                            {/if}
+                            {#if track_refs}
+                                if (@{value_ref} !== undefined) {
+                                    return ctx.getOrDefer(@{value_ref_id}) as @{full_type_name};
+                                }
+                            {/if}
 
-                            const instance = { ...value };
-                            delete instance.__type;
-                            delete instance.__id;
+                            {#if has_version}
+                                let obj = value as Record<string, unknown>;
+                                @{migration_code}
+                                const instance = { ...obj };
+                                delete instance["@{meta.version_key}"];
+                            {:else}
+                                const instance = { ...value };
+                            {/if}
+                            {#if track_refs}
+                                delete @{instance_type};
+                                delete @{instance_id};
 
-                            if (value.__id !== undefined) {
-                                ctx.register(value.__id as number, instance);
-                            }
+                                if (@{value_id} !== undefined) {
+                                    ctx.register(@{value_id} as number, instance);
+                                }
+                            {/if}
 
                             ctx.trackForFreeze(instance);
+
+                            {#for field in &patched_fields}
+                                {#match &field.type_cat}
+                                    {:case TypeCategory::Generic(param) | TypeCategory::NullableGeneric(param) | TypeCategory::OptionalGeneric(param)}
+                                        if (instance["@{field.json_key}"] !== undefined{#if matches!(field.type_cat, TypeCategory::NullableGeneric(_))} && instance["@{field.json_key}"] !== null{/if}) {
+                                            const __result = TypeDeserializer.apply(types?.@{param}, instance["@{field.json_key}"], ctx);
+                                            if (PendingRef.is(__result)) {
+                                                instance["@{field.json_key}"] = null;
+                                                ctx.deferPatch(__result.id, (v) => { instance["@{field.json_key}"] = v; });
+                                            } else {
+                                                instance["@{field.json_key}"] = __result;
+                                            }
+                                        }
+
+                                    {:case TypeCategory::GenericArray(param)}
+                                        if (Array.isArray(instance["@{field.json_key}"])) {
+                                            instance["@{field.json_key}"] = (instance["@{field.json_key}"] as any[]).map((item, idx) => {
+                                                const __item = TypeDeserializer.apply(types?.@{param}, item, ctx);
+                                                if (PendingRef.is(__item)) {
+                                                    ctx.deferPatch(__item.id, (v) => { instance["@{field.json_key}"][idx] = v; });
+                                                    return null;
+                                                }
+                                                return __item;
+                                            });
+                                        }
+
+                                    {:case _}
+                                        {$let decoded = field.type_cat.decode_expr("__raw").unwrap_or_default()}
+                                        if (instance["@{field.json_key}"] !== undefined) {
+                                            const __raw = instance["@{field.json_key}"];
+                                            try {
+                                                instance["@{field.json_key}"] = @{decoded};
+                                            } catch (__e) {
+                                                throw new DeserializeError([{ field: "@{field.json_key}", message: String((__e as Error)?.message ?? __e), code: "invalidType" }]);
+                                            }
+                                        }
+                                {/match}
+                            {/for}
                             return instance as @{full_type_name};
                         }
 
                         export function validateField@{validate_field_generic_decl}(
                             field: K,
                             value: @{full_type_name}[K]
-                        ): Array<{ field: string; message: string }> {
+                        ): Array<FieldError> {
                             {#if has_validators}
-                            const errors: Array<{ field: string; message: string }> = [];
+                            const errors: Array<FieldError> = [];
                             switch (field) {
                                 {#for field in &fields_with_validators}
                                 case "@{field.field_name}": {
                                     const __val = value as @{field.ts_type};
-                                    {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, type_name)}
+                                    {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, type_name, false)}
                                     @{validation_code}
                                     break;
                                 }
@@ -1124,13 +1965,13 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
 
                         export function {|validateFields@{generic_decl}|}(
                             partial: Partial<@{full_type_name}>
-                        ): Array<{ field: string; message: string }> {
+                        ): Array<FieldError> {
                             {#if has_validators}
-                            const errors: Array<{ field: string; message: string }> = [];
+                            const errors: Array<FieldError> = [];
                             {#for field in &fields_with_validators}
                             if ("@{field.field_name}" in partial && partial.@{field.field_name} !== undefined) {
                                 const __val = partial.@{field.field_name} as @{field.ts_type};
-                                {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, type_name)}
+                                {$let validation_code = generate_field_validations(&field.validators, "__val", &field.json_key, type_name, false)}
                                 @{validation_code}
                             }
                             {/for}
@@ -1145,25 +1986,66 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 result.add_import("DeserializeContext", "macroforge/serde");
                 result.add_import("DeserializeError", "macroforge/serde");
                 result.add_type_import("DeserializeOptions", "macroforge/serde");
+                result.add_import("FieldError", "macroforge/serde");
+                if all_fields.iter().any(|f| f.type_cat.decodes_with_runtime()) {
+                    result.add_import("Codec", "macroforge/serde");
+                }
+                if msgpack {
+                    result.add_import("MsgPack", "macroforge/serde");
+                }
+                if stream {
+                    result.add_import("JsonStream", "macroforge/serde");
+                }
+                if is_generic {
+                    result.add_import("PendingRef", "macroforge/serde");
+                    result.add_import("TypeDeserializer", "macroforge/serde");
+                }
                 Ok(result)
             } else {
-                // Union type (including string literal unions) - dispatch based on __type or return as-is
+                if has_version {
+                    return Err(MacroforgeError::new(
+                        input.decorator_span(),
+                        "@serde version is only supported on classes, interfaces and object type aliases",
+                    ));
+                }
+
+                // Union type (including string literal unions) - dispatch based on the type tag or return as-is
                 let mut result = ts_template! {
                     export namespace @{type_name} {
-                        export function {|fromStringifiedJSON@{generic_decl}|}(json: string, opts?: DeserializeOptions): Result<@{full_type_name}, Array<{ field: string; message: string }>> {
+                        export function {|fromStringifiedJSON@{generic_decl}|}(json: string, opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                             try {
                                 const raw = JSON.parse(json);
                                 return fromObject(raw, opts);
                             } catch (e) {
                                 if (e instanceof DeserializeError) {
-                                    return Result.err(e.errors);
+                                    return Result.err(FieldError.format(e.errors, opts));
                                 }
                                 const message = e instanceof Error ? e.message : String(e);
-                                return Result.err([{ field: "_root", message }]);
+                                return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                             }
                         }
 
-                        export function {|fromObject@{generic_decl}|}(obj: unknown, opts?: DeserializeOptions): Result<@{full_type_name}, Array<{ field: string; message: string }>> {
+                        {#if stream}
+                            export async function* {|fromJSONStream@{generic_decl}|}(chunks: AsyncIterable<string | Uint8Array>, opts?: DeserializeOptions): AsyncGenerator<Result<@{full_type_name}, Array<FieldError>>> {
+                                for await (const raw of JsonStream.items(chunks)) {
+                                    yield fromObject(raw, opts);
+                                }
+                            }
+                        {/if}
+
+                        {#if msgpack}
+                            export function {|fromMsgPack@{generic_decl}|}(bytes: Uint8Array, opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
+                                try {
+                                    const raw = MsgPack.decode(bytes);
+                                    return fromObject(raw, opts);
+                                } catch (e) {
+                                    const message = e instanceof Error ? e.message : String(e);
+                                    return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
+                                }
+                            }
+                        {/if}
+
+                        export function {|fromObject@{generic_decl}|}(obj: unknown, opts?: DeserializeOptions): Result<@{full_type_name}, Array<FieldError>> {
                             try {
                                 const ctx = DeserializeContext.create();
                                 const result = __deserialize@{generic_args}(obj, ctx);
@@ -1174,24 +2056,28 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                                 return Result.ok<@{full_type_name}>(result);
                             } catch (e) {
                                 if (e instanceof DeserializeError) {
-                                    return Result.err(e.errors);
+                                    return Result.err(FieldError.format(e.errors, opts));
                                 }
                                 const message = e instanceof Error ? e.message : String(e);
-                                return Result.err([{ field: "_root", message }]);
+                                return Result.err(FieldError.format([{ field: "_root", message, code: "invalidInput" }], opts));
                             }
                         }
 
                         export function {|__deserialize@{generic_decl}|}(value: any, ctx: DeserializeContext): @{full_type_name} {
-                            if (value?.__ref !== undefined) {
-                                return ctx.getOrDefer(value.__ref) as @{full_type_name};
-                            }
+                            {#if track_refs}
+                                if (@{value_ref} !== undefined) {
+                                    return ctx.getOrDefer(@{value_ref_id}) as @{full_type_name};
+                                }
+                            {/if}
 
-                            // For union types with __type, delegate to the appropriate type
-                            if (typeof (value as any)?.__type === "string") {
-                                // Look up deserializer by type name
-                                // This requires the types in the union to be imported and have __deserialize
-                                throw new Error("@{type_name}.__deserialize: polymorphic deserialization requires type registry (TODO)");
-                            }
+                            {#if track_refs}
+                                // For union types with a type tag, delegate to the appropriate type
+                                if (typeof @{value_type} === "string") {
+                                    // Look up deserializer by type name
+                                    // This requires the types in the union to be imported and have __deserialize
+                                    throw new Error("@{type_name}.__deserialize: polymorphic deserialization requires type registry (TODO)");
+                                }
+                            {/if}
 
                             return value as @{full_type_name};
                         }
@@ -1201,13 +2087,13 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                         export function validateField@{validate_field_generic_decl}(
                             field: K,
                             value: @{full_type_name}[K]
-                        ): Array<{ field: string; message: string }> {
+                        ): Array<FieldError> {
                             return [];
                         }
 
                         export function {|validateFields@{generic_decl}|}(
                             partial: Partial<@{full_type_name}>
-                        ): Array<{ field: string; message: string }> {
+                        ): Array<FieldError> {
                             return [];
                         }
                     }
@@ -1216,6 +2102,13 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 result.add_import("DeserializeContext", "macroforge/serde");
                 result.add_import("DeserializeError", "macroforge/serde");
                 result.add_type_import("DeserializeOptions", "macroforge/serde");
+                result.add_import("FieldError", "macroforge/serde");
+                if msgpack {
+                    result.add_import("MsgPack", "macroforge/serde");
+                }
+                if stream {
+                    result.add_import("JsonStream", "macroforge/serde");
+                }
                 Ok(result)
             }
         }
@@ -3,8 +3,13 @@
 //! Generates JSON serialization methods with cycle detection:
 //! - For classes: `toStringifiedJSON()`, `toJSON()`, and `__serialize(ctx)`
 //! - For interfaces: `namespace InterfaceName { toStringifiedJSON, __serialize }`
+//! - With `@serde(msgpack)`: `toMsgPack()` encoding the same `__serialize`
+//!   output as MessagePack
 //!
-//! Uses `__id` and `__ref` markers for object identity tracking.
+//! Uses `__id` and `__ref` markers for object identity tracking, unless the
+//! container opts into plain JSON with `@serde(plain)`. The marker prefix is
+//! configurable with `@serde({ meta_prefix: "$" })`. Versioned containers
+//! (`@serde({ version: 3 })`) also write a `__version` marker.
 
 use crate::macros::{body, ts_macro_derive, ts_template};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
@@ -32,7 +37,12 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
-            let container_opts = SerdeContainerOptions::from_decorators(&class.inner.decorators);
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &class.inner.decorators,
+            );
+
+            let type_params = class.type_params();
 
             // Collect serializable fields
             let fields: Vec<SerializeField> = class
@@ -49,7 +59,8 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                         .clone()
                         .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                    let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                    let type_cat =
+                        TypeCategory::from_type_ir(&field.type_ir).resolve_type_params(type_params);
 
                     Some(SerializeField {
                         json_key,
@@ -67,36 +78,75 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
 
             let has_regular = !regular_fields.is_empty();
             let has_flatten = !flatten_fields.is_empty();
+            let plain = container_opts.plain;
+            let meta = container_opts.meta_keys();
+            let has_version = container_opts.version.is_some();
+            let version = container_opts.version.unwrap_or(1);
+            let msgpack = container_opts.msgpack;
+            let create_ctx = container_opts.serialize_context();
 
             let mut result = body! {
                 toStringifiedJSON(): string {
-                    const ctx = SerializeContext.create();
+                    const ctx = @{create_ctx};
                     return JSON.stringify(this.__serialize(ctx));
                 }
 
+                {#if msgpack}
+                    toMsgPack(): Uint8Array {
+                        const ctx = @{create_ctx};
+                        return MsgPack.encode(this.__serialize(ctx));
+                    }
+                {/if}
+
                 toObject(): Record<string, unknown> {
-                    const ctx = SerializeContext.create();
+                    const ctx = @{create_ctx};
                     return this.__serialize(ctx);
                 }
 
                 __serialize(ctx: SerializeContext): Record<string, unknown> {
-                    // Check if already serialized (cycle detection)
-                    const existingId = ctx.getId(this);
-                    if (existingId !== undefined) {
-                        return { __ref: existingId };
-                    }
+                    {#if plain}
+                        // Plain JSON: shared objects are written again, cycles throw
+                        ctx.enter(this, "@{class_name}");
+                        const result: Record<string, unknown> = {};
+                    {:else}
+                        let result: Record<string, unknown>;
+                        if (ctx.plain) {
+                            // Written inside a plain container: no metadata
+                            ctx.enter(this, "@{class_name}");
+                            result = {};
+                        } else {
+                            // Check if already serialized (cycle detection)
+                            const existingId = ctx.getId(this);
+                            if (existingId !== undefined) {
+                                return { @{meta.ref_prop}: existingId };
+                            }
 
-                    // Register this object
-                    const __id = ctx.register(this);
+                            // Register this object
+                            const __id = ctx.register(this);
 
-                    const result: Record<string, unknown> = {
-                        __type: "@{class_name}",
-                        __id,
-                    };
+                            result = {
+                                @{meta.type_prop}: "@{class_name}",
+                                @{meta.id_prop}: __id,
+                            };
+                        }
+                    {/if}
+                    {#if has_version}
+                        result["@{meta.version_key}"] = @{version};
+                    {/if}
 
                     {#if has_regular}
                         {#for field in regular_fields}
                             {#match &field.type_cat}
+                                {:case category if category.is_codec()}
+                                    {$let encoded = field.type_cat.encode_expr(&format!("this.{}", field.field_name)).unwrap_or_default()}
+                                    {#if field.optional}
+                                        if (this.@{field.field_name} !== undefined) {
+                                            result["@{field.json_key}"] = @{encoded};
+                                        }
+                                    {:else}
+                                        result["@{field.json_key}"] = @{encoded};
+                                    {/if}
+
                                 {:case TypeCategory::Primitive}
                                     {#if field.optional}
                                         if (this.@{field.field_name} !== undefined) {
@@ -199,7 +249,16 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                             : this.@{field.field_name};
                                     {/if}
 
-                                {:case TypeCategory::Unknown}
+                                {:case TypeCategory::Generic(_) | TypeCategory::GenericArray(_) | TypeCategory::NullableGeneric(_) | TypeCategory::OptionalGeneric(_)}
+                                    {#if field.optional || matches!(field.type_cat, TypeCategory::OptionalGeneric(_))}
+                                        if (this.@{field.field_name} !== undefined) {
+                                            result["@{field.json_key}"] = SerializeContext.serializeAny(this.@{field.field_name}, ctx);
+                                        }
+                                    {:else}
+                                        result["@{field.json_key}"] = SerializeContext.serializeAny(this.@{field.field_name}, ctx);
+                                    {/if}
+
+                                {:case _}
                                     {#if field.optional}
                                         if (this.@{field.field_name} !== undefined) {
                                             result["@{field.json_key}"] = this.@{field.field_name};
@@ -218,8 +277,8 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                     const __flattened = typeof (this.@{field.field_name} as any)?.__serialize === "function"
                                         ? (this.@{field.field_name} as any).__serialize(ctx)
                                         : this.@{field.field_name};
-                                    // Remove __type and __id from flattened object
-                                    const { __type: _, __id: __, ...rest } = __flattened as any;
+                                    // Remove the type and id metadata from the flattened object
+                                    const { @{meta.type_prop}: _, @{meta.id_prop}: __, ...rest } = __flattened as any;
                                     Object.assign(result, rest);
                                 }
                             {:else}
@@ -227,18 +286,32 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                     const __flattened = typeof (this.@{field.field_name} as any)?.__serialize === "function"
                                         ? (this.@{field.field_name} as any).__serialize(ctx)
                                         : this.@{field.field_name};
-                                    // Remove __type and __id from flattened object
-                                    const { __type: _, __id: __, ...rest } = __flattened as any;
+                                    // Remove the type and id metadata from the flattened object
+                                    const { @{meta.type_prop}: _, @{meta.id_prop}: __, ...rest } = __flattened as any;
                                     Object.assign(result, rest);
                                 }
                             {/if}
                         {/for}
                     {/if}
 
+                    {#if plain}
+                        ctx.leave(this);
+                    {:else}
+                        if (ctx.plain) {
+                            ctx.leave(this);
+                        }
+                    {/if}
                     return result;
                 }
             };
+            warn_nested_meta_prefix(&mut result, &container_opts, &input, &fields);
             result.add_import("SerializeContext", "macroforge/serde");
+            if fields.iter().any(|f| f.type_cat.encodes_with_runtime()) {
+                result.add_import("Codec", "macroforge/serde");
+            }
+            if msgpack {
+                result.add_import("MsgPack", "macroforge/serde");
+            }
             Ok(result)
         }
         Data::Enum(_) => {
@@ -258,8 +331,20 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
-            let container_opts =
-                SerdeContainerOptions::from_decorators(&interface.inner.decorators);
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &interface.inner.decorators,
+            );
+
+            // Build generic type signature if type has type params
+            let type_params = interface.type_params();
+            let (generic_decl, generic_args) = if type_params.is_empty() {
+                (String::new(), String::new())
+            } else {
+                let params = type_params.join(", ");
+                (format!("<{}>", params), format!("<{}>", params))
+            };
+            let full_type_name = format!("{}{}", interface_name, generic_args);
 
             // Collect serializable fields from interface
             let fields: Vec<SerializeField> = interface
@@ -276,7 +361,8 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                         .clone()
                         .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                    let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                    let type_cat =
+                        TypeCategory::from_type_ir(&field.type_ir).resolve_type_params(type_params);
 
                     Some(SerializeField {
                         json_key,
@@ -294,37 +380,76 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
 
             let has_regular = !regular_fields.is_empty();
             let has_flatten = !flatten_fields.is_empty();
+            let plain = container_opts.plain;
+            let meta = container_opts.meta_keys();
+            let has_version = container_opts.version.is_some();
+            let version = container_opts.version.unwrap_or(1);
+            let msgpack = container_opts.msgpack;
+            let create_ctx = container_opts.serialize_context();
 
             let mut result = ts_template! {
                 export namespace @{interface_name} {
-                    export function toStringifiedJSON(self: @{interface_name}): string {
-                        const ctx = SerializeContext.create();
+                    export function {|toStringifiedJSON@{generic_decl}|}(self: @{full_type_name}): string {
+                        const ctx = @{create_ctx};
                         return JSON.stringify(__serialize(self, ctx));
                     }
 
-                    export function toObject(self: @{interface_name}): Record<string, unknown> {
-                        const ctx = SerializeContext.create();
+                    {#if msgpack}
+                        export function {|toMsgPack@{generic_decl}|}(self: @{full_type_name}): Uint8Array {
+                            const ctx = @{create_ctx};
+                            return MsgPack.encode(__serialize(self, ctx));
+                        }
+                    {/if}
+
+                    export function {|toObject@{generic_decl}|}(self: @{full_type_name}): Record<string, unknown> {
+                        const ctx = @{create_ctx};
                         return __serialize(self, ctx);
                     }
 
-                    export function __serialize(self: @{interface_name}, ctx: SerializeContext): Record<string, unknown> {
-                        // Check if already serialized (cycle detection)
-                        const existingId = ctx.getId(self);
-                        if (existingId !== undefined) {
-                            return { __ref: existingId };
-                        }
+                    export function {|__serialize@{generic_decl}|}(self: @{full_type_name}, ctx: SerializeContext): Record<string, unknown> {
+                        {#if plain}
+                            // Plain JSON: shared objects are written again, cycles throw
+                            ctx.enter(self, "@{interface_name}");
+                            const result: Record<string, unknown> = {};
+                        {:else}
+                            let result: Record<string, unknown>;
+                            if (ctx.plain) {
+                                // Written inside a plain container: no metadata
+                                ctx.enter(self, "@{interface_name}");
+                                result = {};
+                            } else {
+                                // Check if already serialized (cycle detection)
+                                const existingId = ctx.getId(self);
+                                if (existingId !== undefined) {
+                                    return { @{meta.ref_prop}: existingId };
+                                }
 
-                        // Register this object
-                        const __id = ctx.register(self);
+                                // Register this object
+                                const __id = ctx.register(self);
 
-                        const result: Record<string, unknown> = {
-                            __type: "@{interface_name}",
-                            __id,
-                        };
+                                result = {
+                                    @{meta.type_prop}: "@{interface_name}",
+                                    @{meta.id_prop}: __id,
+                                };
+                            }
+                        {/if}
+                        {#if has_version}
+                            result["@{meta.version_key}"] = @{version};
+                        {/if}
 
                         {#if has_regular}
                             {#for field in regular_fields}
                                 {#match &field.type_cat}
+                                    {:case category if category.is_codec()}
+                                        {$let encoded = field.type_cat.encode_expr(&format!("self.{}", field.field_name)).unwrap_or_default()}
+                                        {#if field.optional}
+                                            if (self.@{field.field_name} !== undefined) {
+                                                result["@{field.json_key}"] = @{encoded};
+                                            }
+                                        {:else}
+                                            result["@{field.json_key}"] = @{encoded};
+                                        {/if}
+
                                     {:case TypeCategory::Primitive}
                                         {#if field.optional}
                                             if (self.@{field.field_name} !== undefined) {
@@ -427,7 +552,16 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                                 : self.@{field.field_name};
                                         {/if}
 
-                                    {:case TypeCategory::Unknown}
+                                    {:case TypeCategory::Generic(_) | TypeCategory::GenericArray(_) | TypeCategory::NullableGeneric(_) | TypeCategory::OptionalGeneric(_)}
+                                        {#if field.optional || matches!(field.type_cat, TypeCategory::OptionalGeneric(_))}
+                                            if (self.@{field.field_name} !== undefined) {
+                                                result["@{field.json_key}"] = SerializeContext.serializeAny(self.@{field.field_name}, ctx);
+                                            }
+                                        {:else}
+                                            result["@{field.json_key}"] = SerializeContext.serializeAny(self.@{field.field_name}, ctx);
+                                        {/if}
+
+                                    {:case _}
                                         {#if field.optional}
                                             if (self.@{field.field_name} !== undefined) {
                                                 result["@{field.json_key}"] = self.@{field.field_name};
@@ -446,7 +580,7 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                         const __flattened = typeof (self.@{field.field_name} as any)?.__serialize === "function"
                                             ? (self.@{field.field_name} as any).__serialize(ctx)
                                             : self.@{field.field_name};
-                                        const { __type: _, __id: __, ...rest } = __flattened as any;
+                                        const { @{meta.type_prop}: _, @{meta.id_prop}: __, ...rest } = __flattened as any;
                                         Object.assign(result, rest);
                                     }
                                 {:else}
@@ -454,18 +588,32 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                                         const __flattened = typeof (self.@{field.field_name} as any)?.__serialize === "function"
                                             ? (self.@{field.field_name} as any).__serialize(ctx)
                                             : self.@{field.field_name};
-                                        const { __type: _, __id: __, ...rest } = __flattened as any;
+                                        const { @{meta.type_prop}: _, @{meta.id_prop}: __, ...rest } = __flattened as any;
                                         Object.assign(result, rest);
                                     }
                                 {/if}
                             {/for}
                         {/if}
 
+                        {#if plain}
+                            ctx.leave(self);
+                        {:else}
+                            if (ctx.plain) {
+                                ctx.leave(self);
+                            }
+                        {/if}
                         return result;
                     }
                 }
             };
+            warn_nested_meta_prefix(&mut result, &container_opts, &input, &fields);
             result.add_import("SerializeContext", "macroforge/serde");
+            if fields.iter().any(|f| f.type_cat.encodes_with_runtime()) {
+                result.add_import("Codec", "macroforge/serde");
+            }
+            if msgpack {
+                result.add_import("MsgPack", "macroforge/serde");
+            }
             Ok(result)
         }
         Data::TypeAlias(type_alias) => {
@@ -480,11 +628,15 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                 (format!("<{}>", params), format!("<{}>", params))
             };
             let full_type_name = format!("{}{}", type_name, generic_args);
+            let container_opts = SerdeContainerOptions::with_defaults(
+                &input.context.macro_options,
+                &type_alias.inner.decorators,
+            );
+            let msgpack = container_opts.msgpack;
+            let create_ctx = container_opts.serialize_context();
 
             if type_alias.is_object() {
                 // Object type: serialize fields
-                let container_opts =
-                    SerdeContainerOptions::from_decorators(&type_alias.inner.decorators);
 
                 let fields: Vec<SerializeField> = type_alias
                     .as_object()
@@ -501,7 +653,8 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                             .clone()
                             .unwrap_or_else(|| container_opts.rename_all.apply(&field.name));
 
-                        let type_cat = TypeCategory::from_ts_type(&field.ts_type);
+                        let type_cat = TypeCategory::from_type_ir(&field.type_ir)
+                            .resolve_type_params(type_params);
 
                         Some(SerializeField {
                             json_key,
@@ -515,60 +668,117 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
 
                 let regular_fields: Vec<_> = fields.iter().filter(|f| !f.flatten).cloned().collect();
                 let has_regular = !regular_fields.is_empty();
+                let plain = container_opts.plain;
+                let meta = container_opts.meta_keys();
+                let has_version = container_opts.version.is_some();
+                let version = container_opts.version.unwrap_or(1);
 
                 let mut result = ts_template! {
                     export namespace @{type_name} {
                         export function {|toStringifiedJSON@{generic_decl}|}(value: @{full_type_name}): string {
-                            const ctx = SerializeContext.create();
+                            const ctx = @{create_ctx};
                             return JSON.stringify(__serialize(value, ctx));
                         }
 
+                        {#if msgpack}
+                            export function {|toMsgPack@{generic_decl}|}(value: @{full_type_name}): Uint8Array {
+                                const ctx = @{create_ctx};
+                                return MsgPack.encode(__serialize(value, ctx));
+                            }
+                        {/if}
+
                         export function {|toObject@{generic_decl}|}(value: @{full_type_name}): Record<string, unknown> {
-                            const ctx = SerializeContext.create();
+                            const ctx = @{create_ctx};
                             return __serialize(value, ctx);
                         }
 
                         export function {|__serialize@{generic_decl}|}(value: @{full_type_name}, ctx: SerializeContext): Record<string, unknown> {
-                            const existingId = ctx.getId(value);
-                            if (existingId !== undefined) {
-                                return { __ref: existingId };
-                            }
+                            {#if plain}
+                                ctx.enter(value, "@{type_name}");
+                                const result: Record<string, unknown> = {};
+                            {:else}
+                                let result: Record<string, unknown>;
+                                if (ctx.plain) {
+                                    ctx.enter(value, "@{type_name}");
+                                    result = {};
+                                } else {
+                                    const existingId = ctx.getId(value);
+                                    if (existingId !== undefined) {
+                                        return { @{meta.ref_prop}: existingId };
+                                    }
 
-                            const __id = ctx.register(value);
-                            const result: Record<string, unknown> = {
-                                __type: "@{type_name}",
-                                __id,
-                            };
+                                    const __id = ctx.register(value);
+                                    result = {
+                                        @{meta.type_prop}: "@{type_name}",
+                                        @{meta.id_prop}: __id,
+                                    };
+                                }
+                            {/if}
+                            {#if has_version}
+                                result["@{meta.version_key}"] = @{version};
+                            {/if}
 
                             {#if has_regular}
                                 {#for field in regular_fields}
+                                    {$let is_generic = field.type_cat.is_generic()}
+                                    {$let access = format!("value.{}", field.field_name)}
+                                    {$let encoded = if field.type_cat.is_codec() { field.type_cat.encode_expr(&access).unwrap_or_default() } else { access.clone() }}
                                     {#if field.optional}
                                         if (value.@{field.field_name} !== undefined) {
-                                            result["@{field.json_key}"] = value.@{field.field_name};
+                                            {#if is_generic}
+                                                result["@{field.json_key}"] = SerializeContext.serializeAny(value.@{field.field_name}, ctx);
+                                            {:else}
+                                                result["@{field.json_key}"] = @{encoded};
+                                            {/if}
                                         }
                                     {:else}
-                                        result["@{field.json_key}"] = value.@{field.field_name};
+                                        {#if is_generic}
+                                            result["@{field.json_key}"] = SerializeContext.serializeAny(value.@{field.field_name}, ctx);
+                                        {:else}
+                                            result["@{field.json_key}"] = @{encoded};
+                                        {/if}
                                     {/if}
                                 {/for}
                             {/if}
 
+                            {#if plain}
+                                ctx.leave(value);
+                            {:else}
+                                if (ctx.plain) {
+                                    ctx.leave(value);
+                                }
+                            {/if}
                             return result;
                         }
                     }
                 };
+                warn_nested_meta_prefix(&mut result, &container_opts, &input, &fields);
                 result.add_import("SerializeContext", "macroforge/serde");
+                if fields.iter().any(|f| f.type_cat.encodes_with_runtime()) {
+                    result.add_import("Codec", "macroforge/serde");
+                }
+                if msgpack {
+                    result.add_import("MsgPack", "macroforge/serde");
+                }
                 Ok(result)
             } else {
                 // Union, tuple, or simple alias: delegate to inner type's __serialize if available
                 let mut result = ts_template! {
                     export namespace @{type_name} {
                         export function {|toStringifiedJSON@{generic_decl}|}(value: @{full_type_name}): string {
-                            const ctx = SerializeContext.create();
+                            const ctx = @{create_ctx};
                             return JSON.stringify(__serialize(value, ctx));
                         }
 
+                        {#if msgpack}
+                            export function {|toMsgPack@{generic_decl}|}(value: @{full_type_name}): Uint8Array {
+                                const ctx = @{create_ctx};
+                                return MsgPack.encode(__serialize(value, ctx));
+                            }
+                        {/if}
+
                         export function {|toObject@{generic_decl}|}(value: @{full_type_name}): unknown {
-                            const ctx = SerializeContext.create();
+                            const ctx = @{create_ctx};
                             return __serialize(value, ctx);
                         }
 
@@ -581,12 +791,34 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
                     }
                 };
                 result.add_import("SerializeContext", "macroforge/serde");
+                if msgpack {
+                    result.add_import("MsgPack", "macroforge/serde");
+                }
                 Ok(result)
             }
         }
     }
 }
 
+/// Report a container `meta_prefix` that its nested types will not pick up
+fn warn_nested_meta_prefix(
+    result: &mut TsStream,
+    container_opts: &SerdeContainerOptions,
+    input: &DeriveInput,
+    fields: &[SerializeField],
+) {
+    let mut nested: Vec<String> = fields.iter().filter_map(|f| f.type_cat.nested_type()).collect();
+    nested.sort();
+    nested.dedup();
+    if let Some(warning) = container_opts.nested_meta_prefix_warning(
+        &input.context.macro_options,
+        &nested,
+        input.decorator_span(),
+    ) {
+        result.add_diagnostic(warning);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,7 +3,10 @@
 pub mod derive_deserialize;
 pub mod derive_serialize;
 
-use crate::ts_syn::abi::DecoratorIR;
+use std::collections::BTreeMap;
+
+use crate::builtin::derive_common::{is_identifier, property_key};
+use crate::ts_syn::abi::{DecoratorIR, Diagnostic, DiagnosticLevel, SpanIR, TypeIR};
 
 /// Naming convention for JSON field renaming
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -25,6 +28,7 @@ impl RenameAll {
             "screamingsnakecase" => Some(Self::ScreamingSnakeCase),
             "kebabcase" => Some(Self::KebabCase),
             "pascalcase" => Some(Self::PascalCase),
+            "none" => Some(Self::None),
             _ => None,
         }
     }
@@ -41,16 +45,76 @@ impl RenameAll {
     }
 }
 
+/// Prefix of the `__type`, `__id` and `__ref` metadata keys
+pub const DEFAULT_META_PREFIX: &str = "__";
+
 /// Container-level serde options (on the class/interface itself)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SerdeContainerOptions {
     pub rename_all: RenameAll,
     pub deny_unknown_fields: bool,
+    /// Plain JSON: no type tags or reference tracking, cycles are an error
+    pub plain: bool,
+    /// Prefix of the metadata keys (`type`, `id`, `ref`)
+    pub meta_prefix: String,
+    /// Also generate MessagePack `toMsgPack`/`fromMsgPack`
+    pub msgpack: bool,
+    /// Also generate `fromJSONStream` over a top-level JSON array
+    pub stream: bool,
+    /// Current schema version, written as the `version` metadata key
+    pub version: Option<u32>,
+    /// Migration function names keyed by the version they upgrade from
+    pub migrations: BTreeMap<u32, String>,
+    /// Whole-object rules from `validate: ["passwordsMatch"]`, run on the
+    /// built instance (always `Validator::Custom`)
+    pub validators: Vec<ValidatorSpec>,
+}
+
+impl Default for SerdeContainerOptions {
+    fn default() -> Self {
+        Self {
+            rename_all: RenameAll::default(),
+            deny_unknown_fields: false,
+            plain: false,
+            meta_prefix: DEFAULT_META_PREFIX.to_string(),
+            msgpack: false,
+            stream: false,
+            version: None,
+            migrations: BTreeMap::new(),
+            validators: Vec::new(),
+        }
+    }
 }
 
 impl SerdeContainerOptions {
-    pub fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+    /// Start from the project-wide `macroOptions` (`renameAll`,
+    /// `denyUnknownFields`, `plain`, `metaPrefix`, `msgpack`, `stream`), then
+    /// apply `@serde(...)` on the container
+    pub fn with_defaults(defaults: &serde_json::Value, decorators: &[DecoratorIR]) -> Self {
         let mut opts = Self::default();
+        if let Some(convention) = defaults
+            .get("renameAll")
+            .and_then(|v| v.as_str())
+            .and_then(RenameAll::from_str)
+        {
+            opts.rename_all = convention;
+        }
+        if let Some(deny) = defaults.get("denyUnknownFields").and_then(|v| v.as_bool()) {
+            opts.deny_unknown_fields = deny;
+        }
+        if let Some(plain) = defaults.get("plain").and_then(|v| v.as_bool()) {
+            opts.plain = plain;
+        }
+        if let Some(prefix) = defaults.get("metaPrefix").and_then(|v| v.as_str()) {
+            opts.meta_prefix = prefix.to_string();
+        }
+        if let Some(msgpack) = defaults.get("msgpack").and_then(|v| v.as_bool()) {
+            opts.msgpack = msgpack;
+        }
+        if let Some(stream) = defaults.get("stream").and_then(|v| v.as_bool()) {
+            opts.stream = stream;
+        }
+
         for decorator in decorators {
             if !decorator.name.eq_ignore_ascii_case("serde") {
                 continue;
@@ -63,12 +127,141 @@ impl SerdeContainerOptions {
                 opts.rename_all = convention;
             }
 
-            if has_flag(args, "deny_unknown_fields") {
+            if flag_explicit_false(args, "deny_unknown_fields") {
+                opts.deny_unknown_fields = false;
+            } else if has_flag(args, "deny_unknown_fields") {
                 opts.deny_unknown_fields = true;
             }
+
+            if flag_explicit_false(args, "plain") {
+                opts.plain = false;
+            } else if has_flag(args, "plain") {
+                opts.plain = true;
+            }
+
+            if let Some(prefix) = extract_named_string(args, "meta_prefix") {
+                opts.meta_prefix = prefix;
+            }
+
+            if flag_explicit_false(args, "msgpack") {
+                opts.msgpack = false;
+            } else if has_flag(args, "msgpack") {
+                opts.msgpack = true;
+            }
+
+            if flag_explicit_false(args, "stream") {
+                opts.stream = false;
+            } else if has_flag(args, "stream") {
+                opts.stream = true;
+            }
+
+            if let Some(version) = extract_named_u32(args, "version") {
+                opts.version = Some(version);
+            }
+
+            if let Some(migrations) = extract_migrations(args) {
+                opts.migrations = migrations;
+            }
+
+            opts.validators.extend(extract_container_validators(args));
         }
         opts
     }
+
+    pub fn meta_keys(&self) -> MetaKeys {
+        MetaKeys::new(&self.meta_prefix)
+    }
+
+    /// The `SerializeContext` an entry point starts from; plain containers
+    /// ask nested values to skip their metadata as well
+    pub fn serialize_context(&self) -> &'static str {
+        if self.plain {
+            "SerializeContext.create({ plain: true })"
+        } else {
+            "SerializeContext.create()"
+        }
+    }
+
+    /// Warn when this container picks its own `meta_prefix` but writes
+    /// nested serializable types: those keep the prefix from their own
+    /// options, so one document would mix two spellings of the markers
+    pub fn nested_meta_prefix_warning(
+        &self,
+        defaults: &serde_json::Value,
+        nested: &[String],
+        span: SpanIR,
+    ) -> Option<Diagnostic> {
+        let project_prefix = defaults
+            .get("metaPrefix")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_META_PREFIX);
+        if self.plain || self.meta_prefix == project_prefix || nested.is_empty() {
+            return None;
+        }
+        let message = format!(
+            "meta_prefix \"{}\" does not apply to nested types ({}); they write and read their own \"{}\" markers",
+            self.meta_prefix,
+            nested.join(", "),
+            project_prefix
+        );
+        Some(
+            Diagnostic::new(DiagnosticLevel::Warning, message)
+                .with_span(span)
+                .with_help(format!(
+                    "set `macroOptions.serde.metaPrefix` to \"{}\" to use it for every type",
+                    self.meta_prefix
+                ))
+                .with_code("serde-nested-meta-prefix"),
+        )
+    }
+
+    /// Versions below `version` that have no `migrate` entry
+    pub fn missing_migrations(&self) -> Vec<u32> {
+        let current = self.version.unwrap_or(1);
+        (1..current)
+            .filter(|from| !self.migrations.contains_key(from))
+            .collect()
+    }
+}
+
+/// The `type`, `id`, `ref` and `version` metadata keys, spelled for generated code
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaKeys {
+    /// Key names: `__type`, `__id`, `__ref`, `__version`
+    pub type_key: String,
+    pub id_key: String,
+    pub ref_key: String,
+    pub version_key: String,
+    /// Object literal properties: `__type` or `"@type"`
+    pub type_prop: String,
+    pub id_prop: String,
+    pub ref_prop: String,
+}
+
+impl MetaKeys {
+    pub fn new(prefix: &str) -> Self {
+        let type_key = format!("{prefix}type");
+        let id_key = format!("{prefix}id");
+        let ref_key = format!("{prefix}ref");
+        Self {
+            type_prop: property_key(&type_key),
+            id_prop: property_key(&id_key),
+            ref_prop: property_key(&ref_key),
+            type_key,
+            id_key,
+            ref_key,
+            version_key: format!("{prefix}version"),
+        }
+    }
+}
+
+/// `object?.key`, or `object?.["key"]` when `key` is not an identifier
+pub fn optional_access(object: &str, key: &str) -> String {
+    if is_identifier(key) {
+        format!("{object}?.{key}")
+    } else {
+        format!("{object}?.[{key:?}]")
+    }
 }
 
 /// Field-level serde options
@@ -145,6 +338,28 @@ pub enum TypeCategory {
     Map(String, String),
     Set(String),
     Serializable(String),
+    /// `bigint`, written as a decimal string
+    BigInt,
+    /// `Uint8Array` or `ArrayBuffer` (the type name), written as base64
+    Bytes(String),
+    /// `URL`, written as its `href`
+    Url,
+    /// `RegExp`, written as `/source/flags`
+    RegExp,
+    /// `Temporal.*` (the type after `Temporal.`), written with `toString()`
+    Temporal(String),
+    /// A fixed-length tuple, by element
+    Tuple(Vec<TypeCategory>),
+    /// `Record<K, V>`, by value type
+    Record(Box<TypeCategory>),
+    /// A type parameter of the container, e.g. `T` in `Page<T>`
+    Generic(String),
+    /// An array of a container type parameter, e.g. `T[]` in `Page<T>`
+    GenericArray(String),
+    /// A container type parameter or `null`, e.g. `T | null`
+    NullableGeneric(String),
+    /// A container type parameter or `undefined`, e.g. `T | undefined`
+    OptionalGeneric(String),
     Unknown,
 }
 
@@ -154,12 +369,33 @@ impl TypeCategory {
 
         // Handle primitives
         match trimmed {
-            "string" | "number" | "boolean" | "null" | "undefined" | "bigint" => {
+            "string" | "number" | "boolean" | "null" | "undefined" => {
                 return Self::Primitive;
             }
             "Date" => return Self::Date,
             _ => {}
         }
+        if let Some(codec) = Self::from_codec_name(trimmed) {
+            return codec;
+        }
+
+        // Handle [A, B]
+        if let Some(inner) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return Self::tuple(
+                split_top_level(inner)
+                    .into_iter()
+                    .map(Self::from_ts_type)
+                    .collect(),
+            );
+        }
+
+        // Handle Record<K, V>
+        if trimmed.starts_with("Record<") && trimmed.ends_with('>') {
+            let inner = &trimmed[7..trimmed.len() - 1];
+            if let Some(comma_pos) = find_top_level_comma(inner) {
+                return Self::record(Self::from_ts_type(&inner[comma_pos + 1..]));
+            }
+        }
 
         // Handle Array<T> or T[]
         if trimmed.starts_with("Array<") && trimmed.ends_with('>') {
@@ -218,6 +454,327 @@ impl TypeCategory {
     }
 }
 
+/// Type names that exist only at the type level or have no `__deserialize`,
+/// so they must not be treated as serializable classes
+const NON_SERIALIZABLE_TYPES: &[&str] = &[
+    "String", "Number", "Boolean", "Object", "Function", "Symbol", "Promise", "Record",
+    "Partial", "Required", "Readonly", "Pick", "Omit", "Exclude", "Extract", "NonNullable",
+    "ReturnType", "Parameters", "InstanceType", "Awaited",
+];
+
+impl TypeCategory {
+    /// Classify a structured type from [`FieldIR::type_ir`](crate::ts_syn::abi::FieldIR).
+    ///
+    /// Unlike [`TypeCategory::from_ts_type`] this sees through parentheses
+    /// and nested generics, so `(A | B)[]` is an array and
+    /// `Record<string, T | undefined>` is not mistaken for an optional.
+    pub fn from_type_ir(ty: &TypeIR) -> Self {
+        if let Some(element) = ty.array_element() {
+            return Self::Array(element.to_string());
+        }
+
+        match ty {
+            TypeIR::Keyword { name } => match name.as_str() {
+                "string" | "number" | "boolean" | "null" | "undefined" => Self::Primitive,
+                "bigint" => Self::BigInt,
+                _ => Self::Unknown,
+            },
+            TypeIR::Tuple { elements } => {
+                if elements.iter().any(|e| e.optional || e.rest) {
+                    return Self::Unknown;
+                }
+                Self::tuple(elements.iter().map(|e| Self::from_type_ir(&e.ty)).collect())
+            }
+            TypeIR::Literal { .. } => Self::Primitive,
+            TypeIR::Union { types } => {
+                if types.iter().any(|t| t.is_keyword("undefined")) {
+                    return match ty.without(|t| t.is_keyword("undefined")) {
+                        Some(rest) => Self::Optional(rest.to_string()),
+                        None => Self::Primitive,
+                    };
+                }
+                if types.iter().any(|t| t.is_keyword("null")) {
+                    return match ty.without(|t| t.is_keyword("null")) {
+                        Some(rest) => Self::Nullable(rest.to_string()),
+                        None => Self::Primitive,
+                    };
+                }
+                if types
+                    .iter()
+                    .all(|t| matches!(Self::from_type_ir(t), Self::Primitive | Self::BigInt))
+                {
+                    return Self::Primitive;
+                }
+                Self::Unknown
+            }
+            TypeIR::Reference { name, type_args } => match (name.as_str(), type_args.as_slice()) {
+                ("Date", []) => Self::Date,
+                ("Map" | "ReadonlyMap", [key, value]) => Self::Map(key.to_string(), value.to_string()),
+                ("Set" | "ReadonlySet", [inner]) => Self::Set(inner.to_string()),
+                ("Record", [_, value]) => Self::record(Self::from_type_ir(value)),
+                (name, []) if let Some(codec) = Self::from_codec_name(name) => codec,
+                (name, _) if NON_SERIALIZABLE_TYPES.contains(&name) => Self::Unknown,
+                (name, _) if name.chars().next().is_some_and(char::is_uppercase) => {
+                    Self::Serializable(ty.to_string())
+                }
+                _ => Self::Unknown,
+            },
+            // Mapped, conditional and similar types keep their source text
+            TypeIR::Other { source } => Self::from_ts_type(source),
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Types with a dedicated JSON codec, by name
+    fn from_codec_name(name: &str) -> Option<Self> {
+        match name {
+            "bigint" => Some(Self::BigInt),
+            "Uint8Array" | "ArrayBuffer" => Some(Self::Bytes(name.to_string())),
+            "URL" => Some(Self::Url),
+            "RegExp" => Some(Self::RegExp),
+            _ => name
+                .strip_prefix("Temporal.")
+                .map(|kind| Self::Temporal(kind.to_string())),
+        }
+    }
+
+    /// A tuple only needs its own codec when one of its elements does
+    fn tuple(elements: Vec<Self>) -> Self {
+        if elements.iter().any(|e| e.decode_expr("x").is_some()) {
+            Self::Tuple(elements)
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// A record only needs its own codec when its values do
+    fn record(value: Self) -> Self {
+        if value.decode_expr("x").is_some() {
+            Self::Record(Box::new(value))
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Whether values of this category go through a dedicated codec
+    /// (`bigint`, bytes, `URL`, `RegExp`, `Temporal.*`, tuples and records),
+    /// directly or as the element of an array, nullable or optional type
+    pub fn is_codec(&self) -> bool {
+        match self {
+            Self::BigInt
+            | Self::Bytes(_)
+            | Self::Url
+            | Self::RegExp
+            | Self::Temporal(_)
+            | Self::Tuple(_)
+            | Self::Record(_) => true,
+            _ => self.wrapped_codec().is_some(),
+        }
+    }
+
+    /// The codec category inside an array, nullable or optional type, e.g.
+    /// `bigint` in `bigint | null` or `URL` in `URL[]`
+    fn wrapped_codec(&self) -> Option<Self> {
+        match self {
+            Self::Array(inner) | Self::Nullable(inner) | Self::Optional(inner) => {
+                Some(Self::from_ts_type(inner)).filter(Self::is_codec)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether encoding this codec category needs the `Codec` runtime helpers
+    pub fn encodes_with_runtime(&self) -> bool {
+        self.is_codec() && self.encode_expr("x").is_some_and(|e| e.contains("Codec."))
+    }
+
+    /// Whether decoding this codec category needs the `Codec` runtime helpers
+    pub fn decodes_with_runtime(&self) -> bool {
+        self.is_codec() && self.decode_expr("x").is_some_and(|e| e.contains("Codec."))
+    }
+
+    /// JS expression turning `value` into its JSON form, for categories
+    /// whose values are converted by a codec rather than by `__serialize`
+    pub fn encode_expr(&self, value: &str) -> Option<String> {
+        Some(match self {
+            Self::Date => format!("{value}.toISOString()"),
+            Self::BigInt | Self::RegExp => format!("String({value})"),
+            Self::Bytes(_) => format!("Codec.encodeBase64({value})"),
+            Self::Url => format!("{value}.href"),
+            Self::Temporal(_) => format!("{value}.toString()"),
+            Self::Tuple(elements) => {
+                let items: Vec<String> = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        let item = format!("{value}[{i}]");
+                        element.encode_expr(&item).unwrap_or(item)
+                    })
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Self::Record(inner) => {
+                let encoded = inner.encode_expr("x")?;
+                format!("Codec.mapValues({value}, (x: any) => {encoded})")
+            }
+            Self::Array(_) => {
+                let encoded = self.wrapped_codec()?.encode_expr("x")?;
+                format!("{value}.map((x: any) => {encoded})")
+            }
+            Self::Nullable(_) => {
+                let encoded = self.wrapped_codec()?.encode_expr(value)?;
+                format!("({value} === null ? null : {encoded})")
+            }
+            Self::Optional(_) => {
+                let encoded = self.wrapped_codec()?.encode_expr(value)?;
+                format!("({value} === undefined ? undefined : {encoded})")
+            }
+            _ => return None,
+        })
+    }
+
+    /// JS expression rebuilding a value of this category from its JSON form `raw`
+    pub fn decode_expr(&self, raw: &str) -> Option<String> {
+        Some(match self {
+            Self::Date => format!("new Date({raw} as string)"),
+            Self::BigInt => format!("Codec.decodeBigInt({raw})"),
+            Self::Bytes(name) if name == "ArrayBuffer" => {
+                format!("Codec.decodeBase64({raw}).buffer")
+            }
+            Self::Bytes(_) => format!("Codec.decodeBase64({raw})"),
+            Self::Url => format!("new URL({raw} as string)"),
+            Self::RegExp => format!("Codec.decodeRegExp({raw})"),
+            Self::Temporal(kind) => format!("Temporal.{kind}.from({raw} as string)"),
+            Self::Tuple(elements) => {
+                let items: Vec<String> = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        let item = format!("({raw} as any[])[{i}]");
+                        element.decode_expr(&item).unwrap_or(item)
+                    })
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Self::Record(inner) => {
+                let decoded = inner.decode_expr("x")?;
+                format!("Codec.mapValues({raw} as Record<string, unknown>, (x: any) => {decoded})")
+            }
+            Self::Array(_) => {
+                let decoded = self.wrapped_codec()?.decode_expr("x")?;
+                format!("({raw} as any[]).map((x: any) => {decoded})")
+            }
+            Self::Nullable(_) => {
+                let decoded = self.wrapped_codec()?.decode_expr(raw)?;
+                format!("({raw} === null ? null : {decoded})")
+            }
+            Self::Optional(_) => {
+                let decoded = self.wrapped_codec()?.decode_expr(raw)?;
+                format!("({raw} === undefined ? undefined : {decoded})")
+            }
+            _ => return None,
+        })
+    }
+
+    /// The serializable type a field of this category writes through its
+    /// `__serialize`, looking inside arrays, sets, maps and optionals
+    pub fn nested_type(&self) -> Option<String> {
+        match self {
+            Self::Serializable(name) => Some(name.clone()),
+            Self::Array(inner) | Self::Optional(inner) | Self::Nullable(inner) | Self::Set(inner) => {
+                Self::from_ts_type(inner).nested_type()
+            }
+            Self::Map(_, value) => Self::from_ts_type(value).nested_type(),
+            _ => None,
+        }
+    }
+
+    /// Re-classify references to the container's own type parameters, which
+    /// [`TypeCategory::from_type_ir`] cannot tell apart from serializable types.
+    pub fn resolve_type_params(self, type_params: &[String]) -> Self {
+        match self {
+            Self::Serializable(name) if type_params.contains(&name) => Self::Generic(name),
+            Self::Array(inner) if type_params.contains(&inner) => Self::GenericArray(inner),
+            Self::Nullable(inner) if type_params.contains(&inner) => Self::NullableGeneric(inner),
+            Self::Optional(inner) if type_params.contains(&inner) => Self::OptionalGeneric(inner),
+            other => other,
+        }
+    }
+
+    /// Whether values of this category go through a type parameter's deserializer
+    pub fn is_generic(&self) -> bool {
+        matches!(
+            self,
+            Self::Generic(_) | Self::GenericArray(_) | Self::NullableGeneric(_) | Self::OptionalGeneric(_)
+        )
+    }
+
+    /// The value a `T | null` or `T | undefined` field keeps as-is instead of
+    /// handing it to the type parameter's deserializer
+    pub fn generic_passthrough(&self) -> Option<&'static str> {
+        match self {
+            Self::NullableGeneric(_) => Some("null"),
+            Self::OptionalGeneric(_) => Some("undefined"),
+            _ => None,
+        }
+    }
+}
+
+/// The container's type parameter names as string literals, e.g. `"K", "V"`,
+/// used to key type deserializers that were passed in declaration order
+pub fn type_param_names(type_params: &[String]) -> String {
+    type_params
+        .iter()
+        .map(|param| format!("{param:?}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Extra `__deserialize` arguments for a serializable `type_name`: the
+/// deserializers for its type arguments in declaration order, e.g.
+/// `, [User]` for `Page<User>`. Empty for a type without arguments.
+pub fn type_args_suffix(type_name: &str, type_params: &[String]) -> String {
+    match type_args_list(type_name, type_params) {
+        Some(list) => format!(", {list}"),
+        None => String::new(),
+    }
+}
+
+/// `[A, B]` for the type arguments of `Name<A, B>`
+fn type_args_list(type_name: &str, type_params: &[String]) -> Option<String> {
+    let open = type_name.find('<')?;
+    let args = type_name[open + 1..].trim_end().strip_suffix('>')?;
+    let items: Vec<String> = split_top_level(args)
+        .into_iter()
+        .map(|arg| type_arg_deserializer(arg, type_params))
+        .collect();
+    Some(format!("[{}]", items.join(", ")))
+}
+
+/// The `TypeDeserializer` expression for one type argument; `undefined`
+/// keeps values of that type as they are
+fn type_arg_deserializer(arg: &str, type_params: &[String]) -> String {
+    match TypeCategory::from_ts_type(arg).resolve_type_params(type_params) {
+        TypeCategory::Generic(param) => format!("types?.{param}"),
+        TypeCategory::Date => "Date".to_string(),
+        TypeCategory::Serializable(name) => match (name.find('<'), type_args_list(&name, type_params)) {
+            (Some(open), Some(list)) => format!("TypeDeserializer.of({}, {list})", &name[..open]),
+            _ => name,
+        },
+        _ => "undefined".to_string(),
+    }
+}
+
+/// Type of the per-type-parameter deserializers a generic container takes,
+/// e.g. `{ T: TypeDeserializer<T> }` for `Page<T>`
+pub fn type_deserializers_type(type_params: &[String]) -> String {
+    let entries: Vec<String> = type_params
+        .iter()
+        .map(|param| format!("{param}: TypeDeserializer<{param}>"))
+        .collect();
+    format!("{{ {} }}", entries.join("; "))
+}
+
 // ============================================================================
 // Validator types for field validation
 // ============================================================================
@@ -267,6 +824,13 @@ pub enum Validator {
     MultipleOf(f64),
     Uint8,
 
+    // Value set validator; holds JS literal sources such as `"a"` or `3`
+    OneOf(Vec<String>),
+
+    // Conditional presence: field name and optional JS literal to compare with
+    RequiredIf(String, Option<String>),
+    RequiredUnless(String, Option<String>),
+
     // Array validators
     MaxItems(usize),
     MinItems(usize),
@@ -363,6 +927,69 @@ fn parse_string_literal(input: &str) -> Option<String> {
     None
 }
 
+/// Value source following `name:` or `name =`, matching `name` as a whole word
+fn named_value<'a>(args: &'a str, name: &str) -> Option<&'a str> {
+    let lower = args.to_ascii_lowercase();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    lower.match_indices(name).find_map(|(idx, _)| {
+        let before_ok = !lower[..idx].ends_with(is_word);
+        let remainder = args[idx + name.len()..].trim_start();
+        let value = remainder
+            .strip_prefix(':')
+            .or_else(|| remainder.strip_prefix('='))?;
+        before_ok.then(|| value.trim_start())
+    })
+}
+
+fn extract_named_u32(args: &str, name: &str) -> Option<u32> {
+    let value = named_value(args, name)?;
+    let digits: String = value.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Parse `migrate: { 1: "fromV1", "2": fromV2 }`
+fn extract_migrations(args: &str) -> Option<BTreeMap<u32, String>> {
+    let value = named_value(args, "migrate")?;
+    if !value.starts_with('{') {
+        return None;
+    }
+    let content = extract_bracket_content(value, '{', '}')?;
+    let migrations = split_array_items(&content)
+        .iter()
+        .filter_map(|entry| {
+            let (key, func) = entry.split_once(':')?;
+            let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+            let func = func.trim();
+            let func = parse_string_literal(func).unwrap_or_else(|| func.to_string());
+            Some((key.parse().ok()?, func))
+        })
+        .collect();
+    Some(migrations)
+}
+
+/// Split on commas outside `<>`, `[]`, `()` and `{}`
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '[' | '(' | '{' => depth += 1,
+            '>' | ']' | ')' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = s[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
 /// Find the position of a comma at the top level (not inside <> brackets)
 fn find_top_level_comma(s: &str) -> Option<usize> {
     let mut depth = 0;
@@ -596,6 +1223,23 @@ fn parse_validator_with_args(name: &str, args: &str) -> Option<Validator> {
             }
         }
         "multipleof" => args.trim().parse().ok().map(Validator::MultipleOf),
+        "oneof" => {
+            let values: Vec<String> = split_array_items(args).iter().map(|v| literal_arg(v)).collect();
+            (!values.is_empty()).then_some(Validator::OneOf(values))
+        }
+        "requiredif" | "requiredunless" => {
+            let parts = split_array_items(args);
+            let field = parse_validator_string_arg(parts.first()?)?;
+            let value = parts.get(1).map(|v| literal_arg(v));
+            if parts.len() > 2 {
+                return None;
+            }
+            Some(if name_lower == "requiredif" {
+                Validator::RequiredIf(field, value)
+            } else {
+                Validator::RequiredUnless(field, value)
+            })
+        }
         "maxitems" => args.trim().parse().ok().map(Validator::MaxItems),
         "minitems" => args.trim().parse().ok().map(Validator::MinItems),
         "itemscount" => args.trim().parse().ok().map(Validator::ItemsCount),
@@ -646,6 +1290,47 @@ fn parse_validator_with_args(name: &str, args: &str) -> Option<Validator> {
     }
 }
 
+/// A validator argument as a JS literal: quoted strings are normalized to
+/// double quotes, anything else (numbers, booleans, null) is kept as written
+fn literal_arg(input: &str) -> String {
+    let trimmed = input.trim();
+    match parse_string_literal(trimmed) {
+        Some(s) => format!("{s:?}"),
+        None => trimmed.to_string(),
+    }
+}
+
+/// Parse container rules: `validate: ["passwordsMatch", { validate: "fn", message: "..." }]`
+fn extract_container_validators(args: &str) -> Vec<ValidatorSpec> {
+    let Some(value) = named_value(args, "validate") else {
+        return Vec::new();
+    };
+    let Some(content) = extract_bracket_content(value, '[', ']') else {
+        return Vec::new();
+    };
+    let rule = |name: &str| match parse_validator_string(name) {
+        Some(custom @ Validator::Custom(_)) => custom,
+        _ => Validator::Custom(name.trim().to_string()),
+    };
+    split_array_items(&content)
+        .iter()
+        .filter_map(|item| {
+            if item.starts_with('{') {
+                let inner = extract_bracket_content(item, '{', '}')?;
+                Some(ValidatorSpec {
+                    validator: rule(&extract_named_string(&inner, "validate")?),
+                    custom_message: extract_named_string(&inner, "message"),
+                })
+            } else {
+                Some(ValidatorSpec {
+                    validator: rule(&parse_string_literal(item)?),
+                    custom_message: None,
+                })
+            }
+        })
+        .collect()
+}
+
 /// Parse a string argument (handles both quoted and unquoted)
 fn parse_validator_string_arg(input: &str) -> Option<String> {
     let trimmed = input.trim();
@@ -731,6 +1416,7 @@ pub fn to_pascal_case(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::builtin::derive_common::member_access;
     use crate::ts_syn::abi::SpanIR;
 
     fn span() -> SpanIR {
@@ -797,17 +1483,139 @@ mod tests {
     #[test]
     fn test_container_rename_all() {
         let decorator = make_decorator(r#"{ rename_all: "camelCase" }"#);
-        let opts = SerdeContainerOptions::from_decorators(&[decorator]);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
         assert_eq!(opts.rename_all, RenameAll::CamelCase);
     }
 
     #[test]
     fn test_container_deny_unknown_fields() {
         let decorator = make_decorator("deny_unknown_fields");
-        let opts = SerdeContainerOptions::from_decorators(&[decorator]);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
         assert!(opts.deny_unknown_fields);
     }
 
+    #[test]
+    fn test_container_defaults_from_macro_options() {
+        let defaults = serde_json::json!({ "renameAll": "camelCase", "denyUnknownFields": true });
+
+        let opts = SerdeContainerOptions::with_defaults(&defaults, &[]);
+        assert_eq!(opts.rename_all, RenameAll::CamelCase);
+        assert!(opts.deny_unknown_fields);
+
+        let decorator = make_decorator(r#"{ rename_all: "none", deny_unknown_fields: false }"#);
+        let opts = SerdeContainerOptions::with_defaults(&defaults, &[decorator]);
+        assert_eq!(opts.rename_all, RenameAll::None);
+        assert!(!opts.deny_unknown_fields);
+    }
+
+    #[test]
+    fn test_container_plain_and_meta_prefix() {
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[]);
+        assert!(!opts.plain);
+        assert_eq!(opts.meta_prefix, "__");
+
+        let decorator = make_decorator(r#"{ plain: true, meta_prefix: "$" }"#);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert!(opts.plain);
+        assert_eq!(opts.meta_prefix, "$");
+
+        let defaults = serde_json::json!({ "plain": true, "metaPrefix": "@" });
+        let decorator = make_decorator("{ plain: false }");
+        let opts = SerdeContainerOptions::with_defaults(&defaults, &[decorator]);
+        assert!(!opts.plain);
+        assert_eq!(opts.meta_prefix, "@");
+    }
+
+    #[test]
+    fn test_nested_meta_prefix_warning() {
+        let nested = vec!["User".to_string()];
+        let decorator = make_decorator(r#"{ meta_prefix: "$" }"#);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        let warning = opts
+            .nested_meta_prefix_warning(&serde_json::Value::Null, &nested, span())
+            .unwrap();
+        assert_eq!(warning.level, DiagnosticLevel::Warning);
+        assert!(warning.message.contains("(User)"), "{}", warning.message);
+
+        // No nested types, or the project already uses this prefix
+        assert!(opts.nested_meta_prefix_warning(&serde_json::Value::Null, &[], span()).is_none());
+        let defaults = serde_json::json!({ "metaPrefix": "$" });
+        assert!(opts.nested_meta_prefix_warning(&defaults, &nested, span()).is_none());
+    }
+
+    #[test]
+    fn test_nested_type() {
+        let nested = |ty: &str| TypeCategory::from_ts_type(ty).nested_type();
+        assert_eq!(nested("User"), Some("User".to_string()));
+        assert_eq!(nested("User[]"), Some("User".to_string()));
+        assert_eq!(nested("User | null"), Some("User".to_string()));
+        assert_eq!(nested("Map<string, User>"), Some("User".to_string()));
+        assert_eq!(nested("string[]"), None);
+        assert_eq!(nested("Date"), None);
+    }
+
+    #[test]
+    fn test_container_msgpack() {
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[]);
+        assert!(!opts.msgpack);
+
+        let decorator = make_decorator("{ msgpack: true }");
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert!(opts.msgpack);
+
+        let defaults = serde_json::json!({ "msgpack": true });
+        let decorator = make_decorator("{ msgpack: false }");
+        let opts = SerdeContainerOptions::with_defaults(&defaults, &[decorator]);
+        assert!(!opts.msgpack);
+    }
+
+    #[test]
+    fn test_container_version_and_migrations() {
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[]);
+        assert_eq!(opts.version, None);
+        assert!(opts.missing_migrations().is_empty());
+
+        let decorator = make_decorator(r#"{ version: 3, migrate: { 1: "fromV1", "2": fromV2 } }"#);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert_eq!(opts.version, Some(3));
+        assert_eq!(opts.migrations.get(&1).map(String::as_str), Some("fromV1"));
+        assert_eq!(opts.migrations.get(&2).map(String::as_str), Some("fromV2"));
+        assert!(opts.missing_migrations().is_empty());
+
+        let decorator = make_decorator(r#"{ version: 3, migrate: { 2: "versionTwo" } }"#);
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert_eq!(opts.version, Some(3));
+        assert_eq!(opts.missing_migrations(), vec![1]);
+    }
+
+    #[test]
+    fn test_container_stream() {
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[]);
+        assert!(!opts.stream);
+
+        let decorator = make_decorator("{ stream: true }");
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert!(opts.stream);
+
+        let defaults = serde_json::json!({ "stream": true });
+        let opts = SerdeContainerOptions::with_defaults(&defaults, &[]);
+        assert!(opts.stream);
+    }
+
+    #[test]
+    fn test_meta_keys() {
+        let meta = MetaKeys::new("__");
+        assert_eq!(meta.type_key, "__type");
+        assert_eq!(meta.ref_prop, "__ref");
+        assert_eq!(member_access("obj", &meta.id_key), "obj.__id");
+        assert_eq!(optional_access("value", &meta.ref_key), "value?.__ref");
+
+        let meta = MetaKeys::new("@");
+        assert_eq!(meta.type_prop, "\"@type\"");
+        assert_eq!(member_access("obj", &meta.id_key), "obj[\"@id\"]");
+        assert_eq!(optional_access("value", &meta.ref_key), "value?.[\"@ref\"]");
+    }
+
     #[test]
     fn test_type_category_primitives() {
         assert_eq!(
@@ -881,6 +1689,185 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_type_category_from_type_ir() {
+        let user = TypeIR::reference("User", vec![]);
+        let union = |types: Vec<TypeIR>| TypeIR::Union { types };
+
+        assert_eq!(
+            TypeCategory::from_type_ir(&TypeIR::keyword("string")),
+            TypeCategory::Primitive
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&TypeIR::reference("Date", vec![])),
+            TypeCategory::Date
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&TypeIR::Array {
+                element: Box::new(union(vec![user.clone(), TypeIR::keyword("null")]))
+            }),
+            TypeCategory::Array("User | null".into())
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&TypeIR::reference(
+                "Map",
+                vec![TypeIR::keyword("string"), TypeIR::reference("Array", vec![user.clone()])]
+            )),
+            TypeCategory::Map("string".into(), "Array<User>".into())
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&union(vec![
+                user.clone(),
+                TypeIR::keyword("null"),
+                TypeIR::keyword("undefined")
+            ])),
+            TypeCategory::Optional("User | null".into())
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&user),
+            TypeCategory::Serializable("User".into())
+        );
+    }
+
+    #[test]
+    fn test_type_category_resolve_type_params() {
+        let params = vec!["T".to_string()];
+        let resolve = |ty: TypeIR| TypeCategory::from_type_ir(&ty).resolve_type_params(&params);
+
+        assert_eq!(
+            resolve(TypeIR::reference("T", vec![])),
+            TypeCategory::Generic("T".into())
+        );
+        assert_eq!(
+            resolve(TypeIR::Array {
+                element: Box::new(TypeIR::reference("T", vec![]))
+            }),
+            TypeCategory::GenericArray("T".into())
+        );
+        assert_eq!(
+            resolve(TypeIR::reference("User", vec![])),
+            TypeCategory::Serializable("User".into())
+        );
+        let or = |keyword: &str| TypeIR::Union {
+            types: vec![TypeIR::reference("T", vec![]), TypeIR::keyword(keyword)],
+        };
+        assert_eq!(resolve(or("null")), TypeCategory::NullableGeneric("T".into()));
+        assert_eq!(resolve(or("undefined")), TypeCategory::OptionalGeneric("T".into()));
+        assert_eq!(
+            type_deserializers_type(&["K".to_string(), "V".to_string()]),
+            "{ K: TypeDeserializer<K>; V: TypeDeserializer<V> }"
+        );
+    }
+
+    #[test]
+    fn test_type_args_suffix() {
+        let params = vec!["T".to_string()];
+        assert_eq!(type_args_suffix("User", &params), "");
+        assert_eq!(type_args_suffix("Page<User>", &params), ", [User]");
+        assert_eq!(type_args_suffix("Page<T>", &params), ", [types?.T]");
+        assert_eq!(
+            type_args_suffix("Pair<string, Date>", &params),
+            ", [undefined, Date]"
+        );
+        assert_eq!(
+            type_args_suffix("Page<Box<User>>", &params),
+            ", [TypeDeserializer.of(Box, [User])]"
+        );
+    }
+
+    #[test]
+    fn test_type_category_from_type_ir_nested_generics() {
+        // The string classifier sees the `|` and the uppercase name here
+        let record = TypeIR::reference(
+            "Record",
+            vec![
+                TypeIR::keyword("string"),
+                TypeIR::Union {
+                    types: vec![TypeIR::keyword("number"), TypeIR::keyword("undefined")],
+                },
+            ],
+        );
+        assert_eq!(TypeCategory::from_type_ir(&record), TypeCategory::Unknown);
+
+        let literals = TypeIR::Union {
+            types: vec![
+                TypeIR::Literal { value: crate::ts_syn::abi::LiteralTypeIR::String("on".into()) },
+                TypeIR::Literal { value: crate::ts_syn::abi::LiteralTypeIR::String("off".into()) },
+            ],
+        };
+        assert_eq!(TypeCategory::from_type_ir(&literals), TypeCategory::Primitive);
+    }
+
+    #[test]
+    fn test_type_category_codecs() {
+        assert_eq!(TypeCategory::from_ts_type("bigint"), TypeCategory::BigInt);
+        assert_eq!(
+            TypeCategory::from_ts_type("Uint8Array"),
+            TypeCategory::Bytes("Uint8Array".into())
+        );
+        assert_eq!(TypeCategory::from_ts_type("URL"), TypeCategory::Url);
+        assert_eq!(
+            TypeCategory::from_ts_type("Temporal.PlainDate"),
+            TypeCategory::Temporal("PlainDate".into())
+        );
+        assert_eq!(
+            TypeCategory::from_ts_type("[bigint, string]"),
+            TypeCategory::Tuple(vec![TypeCategory::BigInt, TypeCategory::Primitive])
+        );
+        assert_eq!(
+            TypeCategory::from_ts_type("Record<string, RegExp>"),
+            TypeCategory::Record(Box::new(TypeCategory::RegExp))
+        );
+        // Plain JSON tuples and records need no codec
+        assert_eq!(
+            TypeCategory::from_ts_type("[string, number]"),
+            TypeCategory::Unknown
+        );
+
+        let tuple = TypeIR::Tuple {
+            elements: vec![
+                crate::ts_syn::abi::TupleElementIR {
+                    label: None,
+                    ty: TypeIR::reference("Date", vec![]),
+                    optional: false,
+                    rest: false,
+                },
+                crate::ts_syn::abi::TupleElementIR {
+                    label: None,
+                    ty: TypeIR::reference("ArrayBuffer", vec![]),
+                    optional: false,
+                    rest: false,
+                },
+            ],
+        };
+        let cat = TypeCategory::from_type_ir(&tuple);
+        assert_eq!(
+            cat.encode_expr("v").unwrap(),
+            "[v[0].toISOString(), Codec.encodeBase64(v[1])]"
+        );
+        assert_eq!(
+            cat.decode_expr("raw").unwrap(),
+            "[new Date((raw as any[])[0] as string), Codec.decodeBase64((raw as any[])[1]).buffer]"
+        );
+
+        let record = TypeIR::reference(
+            "Record",
+            vec![TypeIR::keyword("string"), TypeIR::keyword("bigint")],
+        );
+        assert_eq!(
+            TypeCategory::from_type_ir(&record)
+                .decode_expr("raw")
+                .unwrap(),
+            "Codec.mapValues(raw as Record<string, unknown>, (x: any) => Codec.decodeBigInt(x))"
+        );
+
+        // A bigint union member keeps the union primitive, so validators still apply
+        let union = TypeIR::Union {
+            types: vec![TypeIR::keyword("bigint"), TypeIR::keyword("string")],
+        };
+        assert_eq!(TypeCategory::from_type_ir(&union), TypeCategory::Primitive);
+    }
+
     #[test]
     fn test_rename_all_camel_case() {
         assert_eq!(RenameAll::CamelCase.apply("user_name"), "userName");
@@ -1201,6 +2188,55 @@ mod tests {
         ));
     }
 
+    // ========================================================================
+    // Value set and conditional validator parsing tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_one_of_and_conditional_validators() {
+        assert_eq!(
+            parse_validator_string("oneOf('draft', \"live\", 3)"),
+            Some(Validator::OneOf(vec![
+                "\"draft\"".into(),
+                "\"live\"".into(),
+                "3".into()
+            ]))
+        );
+        assert_eq!(parse_validator_string("oneOf()"), None);
+        assert_eq!(
+            parse_validator_string("requiredIf(contactByEmail)"),
+            Some(Validator::RequiredIf("contactByEmail".into(), None))
+        );
+        assert_eq!(
+            parse_validator_string("requiredUnless('kind', 'person')"),
+            Some(Validator::RequiredUnless(
+                "kind".into(),
+                Some("\"person\"".into())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_container_validators() {
+        let decorator = make_decorator(
+            r#"{ validate: ["passwordsMatch", { validate: "custom(datesOrdered)", message: "end before start" }] }"#,
+        );
+        let opts = SerdeContainerOptions::with_defaults(&serde_json::Value::Null, &[decorator]);
+        assert_eq!(opts.validators.len(), 2);
+        assert_eq!(
+            opts.validators[0].validator,
+            Validator::Custom("passwordsMatch".into())
+        );
+        assert_eq!(
+            opts.validators[1].validator,
+            Validator::Custom("datesOrdered".into())
+        );
+        assert_eq!(
+            opts.validators[1].custom_message.as_deref(),
+            Some("end before start")
+        );
+    }
+
     // ========================================================================
     // Case sensitivity tests
     // ========================================================================
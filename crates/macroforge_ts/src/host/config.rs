@@ -1,11 +1,38 @@
 //! Configuration for the macro host
-
-use super::error::Result;
+//!
+//! A project's configuration comes from the nearest `macroforge.json` (or
+//! legacy `macroforge.config.json`), or from a `"macroforge"` key in the
+//! nearest `package.json` / `tsconfig.json`. Each file is validated against
+//! `macroforge.schema.json`, may `extends` other config files, and may carry
+//! per-glob `overrides`.
+
+use super::config_schema::validate_config;
+use super::error::{MacroError, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 const DEFAULT_CONFIG_FILENAME: &str = "macroforge.json";
 const LEGACY_CONFIG_FILENAME: &str = "macroforge.config.json";
+/// Key holding the configuration inside `package.json` and `tsconfig.json`
+const EMBEDDED_CONFIG_KEY: &str = "macroforge";
+/// `macroOptions` key shared by every macro in [`SERDE_MACROS`]
+const SERDE_OPTIONS_KEY: &str = "serde";
+/// Macros reading the serde container options, with the options each one
+/// takes from the shared key and the other wire direction
+const SERDE_MACROS: &[(&str, &[&str])] = &[
+    ("Serialize", &["renameAll", "plain", "metaPrefix", "msgpack"]),
+    (
+        "Deserialize",
+        &["renameAll", "plain", "metaPrefix", "msgpack", "denyUnknownFields", "stream"],
+    ),
+    ("Patch", &["renameAll"]),
+    ("Mock", &["renameAll"]),
+];
+/// The two directions of the wire format, which must agree on its options
+const SERDE_WIRE_MACROS: &[&str] = &["Serialize", "Deserialize"];
 
 /// Configuration for the macro host system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,9 +40,12 @@ const LEGACY_CONFIG_FILENAME: &str = "macroforge.config.json";
 #[derive(Default)]
 pub struct MacroConfig {
     /// List of macro packages to load
+    #[serde(default)]
     pub macro_packages: Vec<String>,
 
     /// Whether to allow native macros (default: false for security)
+    ///
+    /// Required to load `.so`/`.dylib`/`.dll` entries of `macro_packages`.
     #[serde(default)]
     pub allow_native_macros: bool,
 
@@ -31,6 +61,62 @@ pub struct MacroConfig {
     /// Defaults to false to strip macro markers from emitted code.
     #[serde(default)]
     pub keep_decorators: bool,
+
+    /// Project-wide default options per macro name, e.g.
+    /// `{"Serialize": {"renameAll": "camelCase"}}`
+    ///
+    /// Passed to macros as `MacroContextIR::macro_options`; options on the
+    /// decorator itself take precedence.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub macro_options: std::collections::HashMap<String, Value>,
+
+    /// Settings for files matching glob patterns, applied in order by
+    /// [`MacroConfig::for_file`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<ConfigOverride>,
+
+    /// Schema warnings from loading, such as unknown properties, each
+    /// prefixed with the file they were found in
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// Settings applied to the files matching `files`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigOverride {
+    /// Glob patterns relative to the config root, e.g. `src/legacy/**`
+    pub files: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_decorators: Option<bool>,
+
+    /// Merged option by option into [`MacroConfig::macro_options`]
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub macro_options: std::collections::HashMap<String, Value>,
+
+    /// `files` compiled on load (or on first use for configs built in code)
+    #[serde(skip)]
+    pub(crate) glob_set: OnceLock<GlobSet>,
+}
+
+impl ConfigOverride {
+    fn glob_set(&self) -> &GlobSet {
+        self.glob_set.get_or_init(|| {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &self.files {
+                // Invalid patterns are rejected when the config is loaded
+                if let Ok(glob) = Glob::new(pattern) {
+                    builder.add(glob);
+                }
+            }
+            builder.build().unwrap_or_else(|_| GlobSet::empty())
+        })
+    }
+
+    fn matches(&self, relative_path: &str) -> bool {
+        self.glob_set().is_match(relative_path)
+    }
 }
 
 /// Runtime mode for macro execution
@@ -92,10 +178,32 @@ fn default_max_diagnostics() -> usize {
 }
 
 impl MacroConfig {
-    /// Load configuration from a file
+    /// Load configuration from a file, resolving `extends`
+    ///
+    /// For `package.json` and `tsconfig.json` the configuration is read from
+    /// their `"macroforge"` key.
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content)?)
+        Ok(Self::load_with_sources(path.as_ref())?.0)
+    }
+
+    /// Load configuration from `path`, also returning every file it was read
+    /// from (`path` and everything it `extends`)
+    pub(crate) fn load_with_sources(path: &Path) -> Result<(Self, Vec<PathBuf>)> {
+        let mut warnings = Vec::new();
+        let mut sources = Vec::new();
+        let value = load_layered(path, &mut Vec::new(), &mut sources, &mut warnings)?;
+        let mut config = Self::from_value(value, path)?;
+        config.warnings = warnings;
+        for config_override in &config.overrides {
+            config_override.glob_set();
+        }
+        Ok((config, sources))
+    }
+
+    fn from_value(value: Value, path: &Path) -> Result<Self> {
+        serde_json::from_value(value).map_err(|err| {
+            MacroError::InvalidConfig(format!("{}: {err}", path.display()))
+        })
     }
 
     /// Try to find and load configuration file, returning both config and its directory
@@ -110,16 +218,41 @@ impl MacroConfig {
         Ok(Self::find_with_root()?.map(|(cfg, _)| cfg))
     }
 
-    fn find_config_in_ancestors(start_dir: &Path) -> Result<Option<(Self, std::path::PathBuf)>> {
+    /// Find the configuration governing `start_dir`, searching up to the
+    /// nearest package root
+    pub fn find_config_in_ancestors(start_dir: &Path) -> Result<Option<(Self, std::path::PathBuf)>> {
+        match Self::find_config_file(start_dir, &mut Vec::new())? {
+            Some((path, root)) => Ok(Some((Self::from_file(&path)?, root))),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the file holding the configuration governing `start_dir` and the
+    /// directory it was found in, without loading it
+    ///
+    /// Every path whose presence or content decided the result is appended
+    /// to `checked`.
+    pub(crate) fn find_config_file(
+        start_dir: &Path,
+        checked: &mut Vec<PathBuf>,
+    ) -> Result<Option<(PathBuf, PathBuf)>> {
         let mut current = start_dir.to_path_buf();
 
         loop {
-            if let Some(config) = Self::load_if_exists(&current.join(DEFAULT_CONFIG_FILENAME))? {
-                return Ok(Some((config, current.clone())));
+            for file in [DEFAULT_CONFIG_FILENAME, LEGACY_CONFIG_FILENAME] {
+                let path = current.join(file);
+                checked.push(path.clone());
+                if path.exists() {
+                    return Ok(Some((path, current)));
+                }
             }
 
-            if let Some(config) = Self::load_if_exists(&current.join(LEGACY_CONFIG_FILENAME))? {
-                return Ok(Some((config, current.clone())));
+            for host_file in ["package.json", "tsconfig.json"] {
+                let path = current.join(host_file);
+                checked.push(path.clone());
+                if path.exists() && embedded_config(&path)?.is_some() {
+                    return Ok(Some((path, current)));
+                }
             }
 
             // Check for package.json as a stop condition
@@ -137,12 +270,59 @@ impl MacroConfig {
         Ok(None)
     }
 
-    fn load_if_exists(path: &Path) -> Result<Option<Self>> {
-        if path.exists() {
-            Ok(Some(Self::from_file(path)?))
-        } else {
-            Ok(None)
+    /// The configuration for one file: every override whose `files` match
+    /// `file` (relative to `root`, the directory the config was found in) is
+    /// applied in order
+    pub fn for_file(&self, root: &Path, file: &str) -> MacroConfig {
+        let mut config = self.clone();
+        config.overrides.clear();
+
+        let path = Path::new(file);
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches("./");
+
+        for config_override in self.overrides.iter().filter(|o| o.matches(relative)) {
+            if let Some(keep) = config_override.keep_decorators {
+                config.keep_decorators = keep;
+            }
+            for (name, options) in &config_override.macro_options {
+                merge(
+                    config.macro_options.entry(name.clone()).or_insert(Value::Null),
+                    options.clone(),
+                );
+            }
+        }
+        config
+    }
+
+    /// Project-wide options for `macro_name` (`null` when none are set)
+    ///
+    /// Serde macros start from the shared `"serde"` options and the options
+    /// of the other direction, so `{"Serialize": {"renameAll": "camelCase"}}`
+    /// alone also makes `Deserialize` read camelCase keys. Only the options
+    /// the macro reads are shared (`denyUnknownFields` stays with
+    /// `Deserialize`). The macro's own options are applied last.
+    pub fn macro_options_for(&self, macro_name: &str) -> Value {
+        let mut options = Value::Null;
+        if let Some((_, keys)) = SERDE_MACROS.iter().find(|(name, _)| *name == macro_name) {
+            let shared = SERDE_WIRE_MACROS.iter().filter(|name| **name != macro_name);
+            for layer_key in std::iter::once(&SERDE_OPTIONS_KEY).chain(shared) {
+                let Some(Value::Object(layer)) = self.macro_options.get(*layer_key) else {
+                    continue;
+                };
+                let scoped: serde_json::Map<String, Value> = layer
+                    .iter()
+                    .filter(|(key, _)| keys.contains(&key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                merge(&mut options, Value::Object(scoped));
+            }
         }
+        if let Some(own) = self.macro_options.get(macro_name) {
+            merge(&mut options, own.clone());
+        }
+        options
     }
 
     /// Save configuration to a file
@@ -153,6 +333,240 @@ impl MacroConfig {
     }
 }
 
+/// Read the raw configuration object in `path`, validated against the schema
+///
+/// Schema warnings are appended to `warnings`.
+fn read_config_value(path: &Path, warnings: &mut Vec<String>) -> Result<Value> {
+    let value = match embedded_config(path)? {
+        Some(value) => value,
+        None if is_host_file(path) => {
+            return Err(MacroError::InvalidConfig(format!(
+                "{} has no \"{EMBEDDED_CONFIG_KEY}\" key",
+                path.display()
+            )));
+        }
+        None => {
+            let content = std::fs::read_to_string(path)?;
+            serde_json::from_str(&content).map_err(|err| {
+                MacroError::InvalidConfig(format!("{}: {err}", path.display()))
+            })?
+        }
+    };
+
+    let problems = validate_config(&value);
+    if !problems.errors.is_empty() {
+        return Err(MacroError::InvalidConfig(format!(
+            "{} does not match macroforge.schema.json:\n  {}",
+            path.display(),
+            problems.errors.join("\n  ")
+        )));
+    }
+    warnings.extend(
+        problems
+            .warnings
+            .into_iter()
+            .map(|warning| format!("{}: {warning}", path.display())),
+    );
+    if let Some(Value::Array(overrides)) = value.get("overrides") {
+        for pattern in overrides
+            .iter()
+            .filter_map(|o| o.get("files")?.as_array())
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            Glob::new(pattern).map_err(|err| {
+                MacroError::InvalidConfig(format!("{}: /overrides: {err}", path.display()))
+            })?;
+        }
+    }
+    Ok(value)
+}
+
+fn is_host_file(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|name| name.to_str()),
+        Some("package.json" | "tsconfig.json")
+    )
+}
+
+/// The `"macroforge"` object of a `package.json` or `tsconfig.json`
+fn embedded_config(path: &Path) -> Result<Option<Value>> {
+    if !is_host_file(path) {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    // tsconfig.json allows comments and trailing commas
+    let Ok(mut host) = serde_json::from_str::<Value>(&strip_jsonc(&content)) else {
+        return Ok(None);
+    };
+    Ok(host
+        .as_object_mut()
+        .and_then(|object| object.remove(EMBEDDED_CONFIG_KEY)))
+}
+
+/// Load `path` and everything it `extends`, later layers overriding earlier ones
+///
+/// Each file read is appended to `sources`.
+fn load_layered(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+    sources: &mut Vec<PathBuf>,
+    warnings: &mut Vec<String>,
+) -> Result<Value> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical) {
+        return Err(MacroError::InvalidConfig(format!(
+            "circular \"extends\" through {}",
+            path.display()
+        )));
+    }
+    chain.push(canonical);
+    sources.push(path.to_path_buf());
+
+    let mut value = read_config_value(path, warnings)?;
+    let parents = match value.as_object_mut().and_then(|object| object.remove("extends")) {
+        Some(Value::String(parent)) => vec![parent],
+        Some(Value::Array(parents)) => parents
+            .into_iter()
+            .filter_map(|p| p.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut merged = Value::Object(Default::default());
+    for parent in parents {
+        let parent_path = resolve_extends(dir, &parent).ok_or_else(|| {
+            MacroError::InvalidConfig(format!(
+                "{}: cannot resolve \"extends\": \"{parent}\"",
+                path.display()
+            ))
+        })?;
+        merge(&mut merged, load_layered(&parent_path, chain, sources, warnings)?);
+    }
+    merge(&mut merged, value);
+
+    chain.pop();
+    Ok(merged)
+}
+
+/// Resolve an `extends` entry: a path relative to the extending file, or a
+/// package in `node_modules` (its `macroforge.json`, or a file inside it)
+fn resolve_extends(dir: &Path, entry: &str) -> Option<PathBuf> {
+    if entry.starts_with('.') || Path::new(entry).is_absolute() {
+        let path = dir.join(entry);
+        return path.is_file().then_some(path);
+    }
+
+    for ancestor in dir.ancestors() {
+        let candidate = ancestor.join("node_modules").join(entry);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        for file in [DEFAULT_CONFIG_FILENAME, "package.json"] {
+            let path = candidate.join(file);
+            if path.is_file() && (file != "package.json" || embedded_config(&path).ok()??.is_object())
+            {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// Deep-merge `layer` into `base`: objects merge key by key, `overrides`
+/// lists concatenate, and everything else is replaced
+fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match (base.get_mut(&key), value) {
+                    (Some(Value::Array(existing)), Value::Array(more)) if key == "overrides" => {
+                        existing.extend(more);
+                    }
+                    (Some(existing), value) => merge(existing, value),
+                    (None, value) => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+/// Remove comments and trailing commas so JSONC parses as JSON
+fn strip_jsonc(source: &str) -> String {
+    let mut without_comments = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            without_comments.push(c);
+            match c {
+                '\\' => without_comments.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                without_comments.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        without_comments.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => without_comments.push(c),
+        }
+    }
+
+    let mut out = String::with_capacity(without_comments.len());
+    let chars: Vec<char> = without_comments.chars().collect();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if !matches!(next, Some('}' | ']')) {
+                out.push(c);
+            }
+        } else {
+            out.push(c);
+        }
+        i += 1;
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,6 +579,9 @@ mod tests {
             macro_runtime_overrides: Default::default(),
             limits: Default::default(),
             keep_decorators: false,
+            macro_options: Default::default(),
+            overrides: vec![],
+            warnings: vec![],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -173,4 +590,198 @@ mod tests {
         assert_eq!(config.macro_packages, parsed.macro_packages);
         assert_eq!(config.allow_native_macros, parsed.allow_native_macros);
     }
+
+    fn write(path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_extends_merges_base_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("node_modules/@acme/config/macroforge.json"),
+            r#"{ "macroPackages": ["@acme/macros"], "limits": { "maxDiagnostics": 10 },
+                 "overrides": [{ "files": ["src/legacy/**"], "keepDecorators": true }] }"#,
+        );
+        write(
+            &dir.path().join("base.json"),
+            r#"{ "extends": "@acme/config", "limits": { "maxOutputSize": 1024 } }"#,
+        );
+        let app = dir.path().join("apps/web");
+        write(
+            &app.join("macroforge.json"),
+            r#"{ "extends": "../../base.json", "limits": { "maxDiagnostics": 20 },
+                 "overrides": [{ "files": ["**/*.gen.ts"], "keepDecorators": false }] }"#,
+        );
+
+        let (config, root) = MacroConfig::find_config_in_ancestors(&app).unwrap().unwrap();
+        assert_eq!(root, app);
+        assert_eq!(config.macro_packages, vec!["@acme/macros".to_string()]);
+        assert_eq!(config.limits.max_diagnostics, 20);
+        assert_eq!(config.limits.max_output_size, 1024);
+        assert_eq!(config.overrides.len(), 2);
+    }
+
+    #[test]
+    fn test_overrides_apply_by_glob() {
+        let config = MacroConfig {
+            overrides: vec![
+                ConfigOverride {
+                    files: vec!["src/legacy/**".to_string()],
+                    keep_decorators: Some(true),
+                    ..Default::default()
+                },
+                ConfigOverride {
+                    files: vec!["**/*.gen.ts".to_string()],
+                    keep_decorators: Some(false),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let root = Path::new("/repo");
+
+        assert!(config.for_file(root, "/repo/src/legacy/user.ts").keep_decorators);
+        assert!(config.for_file(root, "src/legacy/user.ts").keep_decorators);
+        assert!(!config.for_file(root, "/repo/src/legacy/user.gen.ts").keep_decorators);
+        assert!(!config.for_file(root, "/repo/src/user.ts").keep_decorators);
+    }
+
+    #[test]
+    fn test_config_in_package_json_and_tsconfig() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("package.json"),
+            r#"{ "name": "app", "macroforge": { "keepDecorators": true } }"#,
+        );
+        let (config, _) = MacroConfig::find_config_in_ancestors(dir.path()).unwrap().unwrap();
+        assert!(config.keep_decorators);
+
+        let other = tempfile::tempdir().unwrap();
+        write(&other.path().join("package.json"), r#"{ "name": "lib" }"#);
+        write(
+            &other.path().join("tsconfig.json"),
+            "{\n  // compiler settings\n  \"compilerOptions\": { \"strict\": true, },\n  /* macros */ \"macroforge\": { \"macroPackages\": [\"@acme/macros\"] },\n}",
+        );
+        let (config, _) = MacroConfig::find_config_in_ancestors(other.path()).unwrap().unwrap();
+        assert_eq!(config.macro_packages, vec!["@acme/macros".to_string()]);
+    }
+
+    #[test]
+    fn test_invalid_config_reports_schema_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("macroforge.json");
+        write(&path, r#"{ "keepDecorators": "yes", "macroPackage": [] }"#);
+
+        let err = MacroConfig::from_file(&path).unwrap_err().to_string();
+        assert!(err.contains("/keepDecorators: expected boolean, found string \"yes\""), "{err}");
+        assert!(!err.contains("unknown property"), "{err}");
+    }
+
+    #[test]
+    fn test_unknown_properties_are_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("base.json"), r#"{ "editor.tabSize": 2 }"#);
+        let path = dir.path().join("macroforge.json");
+        write(
+            &path,
+            r#"{ "extends": "./base.json", "keepDecorators": true, "macroPackage": [] }"#,
+        );
+
+        let config = MacroConfig::from_file(&path).unwrap();
+        assert!(config.keep_decorators);
+        assert_eq!(config.warnings.len(), 2, "{:?}", config.warnings);
+        assert!(config.warnings[1].ends_with("base.json: /editor.tabSize: unknown property"));
+        assert!(
+            config.warnings[0]
+                .ends_with("macroforge.json: /macroPackage: unknown property (did you mean \"macroPackages\"?)"),
+            "{:?}",
+            config.warnings
+        );
+    }
+
+    #[test]
+    fn test_circular_extends_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("a.json"), r#"{ "extends": "./b.json" }"#);
+        write(&dir.path().join("b.json"), r#"{ "extends": "./a.json" }"#);
+
+        let err = MacroConfig::from_file(dir.path().join("a.json")).unwrap_err().to_string();
+        assert!(err.contains("circular"), "{err}");
+    }
+
+    #[test]
+    fn test_macro_options_merge_with_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("macroforge.json");
+        write(
+            &path,
+            r#"{
+                "macroOptions": { "Serialize": { "renameAll": "camelCase", "denyUnknownFields": true } },
+                "overrides": [
+                    { "files": ["src/api/**"], "macroOptions": { "Serialize": { "renameAll": "snake_case" } } }
+                ]
+            }"#,
+        );
+        let config = MacroConfig::from_file(&path).unwrap();
+
+        let defaults = config.macro_options_for("Serialize");
+        assert_eq!(defaults["renameAll"], "camelCase");
+        assert_eq!(config.macro_options_for("Debug"), Value::Null);
+
+        let api = config.for_file(dir.path(), "src/api/user.ts");
+        let options = api.macro_options_for("Serialize");
+        assert_eq!(options["renameAll"], "snake_case");
+        assert_eq!(options["denyUnknownFields"], true);
+    }
+
+    #[test]
+    fn test_serde_macros_share_options() {
+        let mut config = MacroConfig::default();
+        config.macro_options.insert(
+            "Serialize".to_string(),
+            serde_json::json!({ "renameAll": "camelCase", "plain": true }),
+        );
+        let deserialize = config.macro_options_for("Deserialize");
+        assert_eq!(deserialize["renameAll"], "camelCase");
+        assert_eq!(deserialize["plain"], true);
+        assert_eq!(config.macro_options_for("Patch")["renameAll"], "camelCase");
+        assert_eq!(config.macro_options_for("Debug"), Value::Null);
+
+        config.macro_options.insert(
+            "serde".to_string(),
+            serde_json::json!({ "metaPrefix": "$", "renameAll": "snake_case" }),
+        );
+        config.macro_options.insert(
+            "Deserialize".to_string(),
+            serde_json::json!({ "denyUnknownFields": true }),
+        );
+        let serialize = config.macro_options_for("Serialize");
+        assert_eq!(serialize["metaPrefix"], "$");
+        assert_eq!(serialize["renameAll"], "camelCase");
+        assert_eq!(serialize.get("denyUnknownFields"), None);
+        let deserialize = config.macro_options_for("Deserialize");
+        assert_eq!(deserialize["renameAll"], "camelCase");
+        assert_eq!(deserialize["metaPrefix"], "$");
+        assert_eq!(deserialize["denyUnknownFields"], true);
+    }
+
+    #[test]
+    fn test_shared_serde_options_are_scoped_per_macro() {
+        let mut config = MacroConfig::default();
+        config.macro_options.insert(
+            "serde".to_string(),
+            serde_json::json!({ "renameAll": "camelCase", "denyUnknownFields": true, "stream": true }),
+        );
+        let deserialize = config.macro_options_for("Deserialize");
+        assert_eq!(deserialize["denyUnknownFields"], true);
+        assert_eq!(deserialize["stream"], true);
+        for name in ["Serialize", "Patch", "Mock"] {
+            let options = config.macro_options_for(name);
+            assert_eq!(options, serde_json::json!({ "renameAll": "camelCase" }), "{name}");
+        }
+    }
 }
@@ -0,0 +1,220 @@
+//! Validation of configuration files against `macroforge.schema.json`
+//!
+//! Implements the subset of JSON Schema the shipped schema uses (`type`,
+//! `properties`, `additionalProperties`, `required`, `items`, `enum`,
+//! `minimum`, `anyOf`), so the same file drives editor completion and the
+//! errors reported when a config is loaded. Unknown properties are only
+//! warnings: they are ignored when loading, and older configs often carry
+//! editor-only or stray keys.
+
+use std::sync::LazyLock;
+
+use serde_json::Value;
+
+/// The configuration schema shipped with the npm package
+pub const CONFIG_SCHEMA: &str = include_str!("../../macroforge.schema.json");
+
+static SCHEMA: LazyLock<Value> =
+    LazyLock::new(|| serde_json::from_str(CONFIG_SCHEMA).expect("macroforge.schema.json is valid JSON"));
+
+/// Problems found by [`validate_config`], one message each, prefixed with
+/// the JSON pointer of the offending value
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConfigValidation {
+    /// Values of the wrong shape; the config cannot be loaded
+    pub errors: Vec<String>,
+    /// Unknown properties, which are ignored
+    pub warnings: Vec<String>,
+}
+
+/// Validate a config value against the schema
+pub fn validate_config(config: &Value) -> ConfigValidation {
+    let mut problems = ConfigValidation::default();
+    validate(config, &SCHEMA, "", &mut problems);
+    problems
+}
+
+fn validate(value: &Value, schema: &Value, pointer: &str, problems: &mut ConfigValidation) {
+    let at = if pointer.is_empty() { "/" } else { pointer };
+
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        let matched = options.iter().any(|option| {
+            let mut scratch = ConfigValidation::default();
+            validate(value, option, pointer, &mut scratch);
+            scratch.errors.is_empty()
+        });
+        if !matched {
+            let expected: Vec<&str> = options
+                .iter()
+                .filter_map(|option| option.get("type").and_then(Value::as_str))
+                .collect();
+            problems.errors.push(format!(
+                "{at}: expected {}, found {}",
+                expected.join(" or "),
+                describe(value)
+            ));
+        }
+        return;
+    }
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str)
+        && !has_type(value, expected)
+    {
+        problems.errors.push(format!("{at}: expected {expected}, found {}", describe(value)));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let names: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        problems.errors.push(format!(
+            "{at}: expected one of {}, found {}",
+            names.join(", "),
+            describe(value)
+        ));
+    }
+
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+        && let Some(n) = value.as_f64()
+        && n < minimum
+    {
+        problems.errors.push(format!("{at}: must be at least {minimum}, found {n}"));
+    }
+
+    if let (Some(items), Value::Array(elements)) = (schema.get("items"), value) {
+        for (i, element) in elements.iter().enumerate() {
+            validate(element, items, &format!("{pointer}/{i}"), problems);
+        }
+    }
+
+    if let Value::Object(object) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    problems
+                        .errors
+                        .push(format!("{at}: missing required property \"{name}\""));
+                }
+            }
+        }
+
+        for (key, child) in object {
+            let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+            if let Some(child_schema) = properties.and_then(|props| props.get(key)) {
+                validate(child, child_schema, &child_pointer, problems);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    let suggestion = properties
+                        .and_then(|props| closest(key, props.keys().map(String::as_str)))
+                        .map(|name| format!(" (did you mean \"{name}\"?)"))
+                        .unwrap_or_default();
+                    problems
+                        .warnings
+                        .push(format!("{child_pointer}: unknown property{suggestion}"));
+                }
+                Some(additional @ Value::Object(_)) => {
+                    validate(child, additional, &child_pointer, problems);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_u64() || value.is_i64(),
+        "number" => value.is_number(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean {b}"),
+        Value::Number(n) => format!("number {n}"),
+        Value::String(s) => format!("string {s:?}"),
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Known property within two edits of `key`, for typo suggestions
+fn closest<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (edit_distance(&key.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j + 1])
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repo_config_is_valid() {
+        let config: Value =
+            serde_json::from_str(include_str!("../../../../macroforge.json")).unwrap();
+        assert_eq!(validate_config(&config), ConfigValidation::default());
+    }
+
+    #[test]
+    fn test_reports_pointer_and_suggestion() {
+        let problems = validate_config(&json!({
+            "keepDecorator": true,
+            "limits": { "maxDiagnostics": "100" },
+            "macroRuntimeOverrides": { "@acme/macros": "jit" },
+            "overrides": [{ "keepDecorators": true }],
+            "extends": 3
+        }));
+        assert_eq!(
+            problems.errors,
+            vec![
+                "/extends: expected string or array, found number 3".to_string(),
+                "/limits/maxDiagnostics: expected integer, found string \"100\"".to_string(),
+                "/macroRuntimeOverrides/@acme~1macros: expected one of \"wasm\", \"native\", found string \"jit\"".to_string(),
+                "/overrides/0: missing required property \"files\"".to_string(),
+            ]
+        );
+        assert_eq!(
+            problems.warnings,
+            vec!["/keepDecorator: unknown property (did you mean \"keepDecorators\"?)".to_string()]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Consistency checks between runtime output and type output
+//!
+//! A macro produces runtime patches and type patches separately, so nothing
+//! stops it from adding a runtime method while forgetting the declaration.
+//! This module lowers both outputs and reports members that exist at runtime
+//! but are missing from the types, or whose parameter counts disagree.
+
+use std::collections::BTreeMap;
+
+use swc_core::ecma::ast::{Decl, ModuleDecl, ModuleItem, Stmt, TsModuleDecl, TsNamespaceBody};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel, MethodAstIR};
+use crate::ts_syn::{lower_classes, parse_ts_module};
+
+use super::expand::internal_member_names;
+use super::{MacroError, MacroExpansion, Result};
+
+/// What kind of disagreement was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsistencyIssueKind {
+    /// The member exists in the runtime output but not in the type output
+    MissingFromTypes,
+    /// The member exists in both, but no type signature has the runtime parameter count
+    ParameterCountMismatch { runtime: usize, types: Vec<usize> },
+}
+
+/// A single runtime/type disagreement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyIssue {
+    /// Container that owns the member, e.g. `class User` or `namespace User`
+    pub container: String,
+    /// Member name
+    pub member: String,
+    pub kind: ConsistencyIssueKind,
+}
+
+impl ConsistencyIssue {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            ConsistencyIssueKind::MissingFromTypes => format!(
+                "`{}` in {} exists at runtime but is missing from the type output",
+                self.member, self.container
+            ),
+            ConsistencyIssueKind::ParameterCountMismatch { runtime, types } => format!(
+                "`{}` in {} takes {} parameter(s) at runtime but {} in the type output",
+                self.member,
+                self.container,
+                runtime,
+                types
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ),
+        }
+    }
+
+    /// Convert to a warning diagnostic (without a span; the member may not exist in the source)
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(DiagnosticLevel::Warning, self.message())
+            .with_help("the macro emits a runtime member without a matching declaration")
+            .with_code("runtime-type-mismatch")
+    }
+}
+
+/// Members of one container: name -> parameter counts (`None` for properties)
+type Surface = BTreeMap<String, BTreeMap<String, Vec<Option<usize>>>>;
+
+/// Compare the members declared in `runtime_code` against `type_code`.
+pub fn check_consistency(runtime_code: &str, type_code: &str) -> Result<Vec<ConsistencyIssue>> {
+    let runtime = collect_surface(runtime_code)?;
+    let types = collect_surface(type_code)?;
+    // `/** @internal */` members are left out of the types on purpose
+    let internal = internal_member_names(runtime_code);
+
+    let mut issues = Vec::new();
+    for (container, members) in &runtime {
+        let declared = types.get(container);
+        for (member, runtime_shapes) in members {
+            let Some(type_shapes) = declared.and_then(|d| d.get(member)) else {
+                if internal.contains(member) {
+                    continue;
+                }
+                issues.push(ConsistencyIssue {
+                    container: container.clone(),
+                    member: member.clone(),
+                    kind: ConsistencyIssueKind::MissingFromTypes,
+                });
+                continue;
+            };
+
+            let type_counts: Vec<usize> = type_shapes.iter().filter_map(|c| *c).collect();
+            if type_counts.is_empty() {
+                // Declared as a property (e.g. `foo: () => void`); nothing to compare.
+                continue;
+            }
+            for runtime_count in runtime_shapes.iter().filter_map(|c| *c) {
+                if !type_counts.contains(&runtime_count) {
+                    issues.push(ConsistencyIssue {
+                        container: container.clone(),
+                        member: member.clone(),
+                        kind: ConsistencyIssueKind::ParameterCountMismatch {
+                            runtime: runtime_count,
+                            types: type_counts.clone(),
+                        },
+                    });
+                    break;
+                }
+            }
+        }
+    }
+    Ok(issues)
+}
+
+impl MacroExpansion {
+    /// Check the runtime output against the type output.
+    ///
+    /// When a macro produced no type patches the original `source` is the
+    /// type surface, so runtime-only additions are still reported.
+    pub fn check_consistency(&self, source: &str) -> Result<Vec<ConsistencyIssue>> {
+        let types = self.type_output.as_deref().unwrap_or(source);
+        check_consistency(&self.code, types)
+    }
+}
+
+fn collect_surface(code: &str) -> Result<Surface> {
+    let module = parse_ts_module(code)
+        .map_err(|e| MacroError::Source(format!("Parse error: {:?}", e)))?;
+
+    let mut surface = Surface::new();
+    let classes = lower_classes(&module, code)
+        .map_err(|e| MacroError::Source(format!("Lower error: {:?}", e)))?;
+    for class in classes {
+        let members = surface.entry(format!("class {}", class.name)).or_default();
+        for field in &class.fields {
+            members.entry(field.name.clone()).or_default().push(None);
+        }
+        for method in &class.methods {
+            let params = match &method.member_ast {
+                Some(MethodAstIR::Method(m)) => m.function.params.len(),
+                Some(MethodAstIR::Constructor(c)) => c.params.len(),
+                None => continue,
+            };
+            members.entry(method.name.clone()).or_default().push(Some(params));
+        }
+    }
+
+    let mut namespaces = NamespaceCollector { surface: &mut surface };
+    module.visit_with(&mut namespaces);
+    Ok(surface)
+}
+
+/// Collects exported functions and variables of `namespace X { ... }` blocks
+struct NamespaceCollector<'a> {
+    surface: &'a mut Surface,
+}
+
+impl Visit for NamespaceCollector<'_> {
+    fn visit_ts_module_decl(&mut self, n: &TsModuleDecl) {
+        let Some(name) = n.id.as_ident().map(|i| i.sym.to_string()) else {
+            return;
+        };
+        let Some(TsNamespaceBody::TsModuleBlock(block)) = &n.body else {
+            return;
+        };
+
+        let members = self.surface.entry(format!("namespace {name}")).or_default();
+        for item in &block.body {
+            let decl = match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+                // Members of `declare namespace` blocks are implicitly exported
+                ModuleItem::Stmt(Stmt::Decl(decl)) if n.declare => decl,
+                _ => continue,
+            };
+            match decl {
+                Decl::Fn(f) => members
+                    .entry(f.ident.sym.to_string())
+                    .or_default()
+                    .push(Some(f.function.params.len())),
+                Decl::Var(var) => {
+                    for declarator in &var.decls {
+                        if let Some(ident) = declarator.name.as_ident() {
+                            members.entry(ident.id.sym.to_string()).or_default().push(None);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent_class() {
+        let runtime = "class User { name: string; toString(): string { return this.name; } }";
+        let types = "class User { name: string; toString(): string; }";
+        assert!(check_consistency(runtime, types).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_method_missing_from_types() {
+        let runtime = "class User { clone(): User { return new User(); } }";
+        let types = "class User { }";
+        let issues = check_consistency(runtime, types).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].container, "class User");
+        assert_eq!(issues[0].member, "clone");
+        assert_eq!(issues[0].kind, ConsistencyIssueKind::MissingFromTypes);
+    }
+
+    #[test]
+    fn test_internal_members_are_not_reported() {
+        let runtime = "class User {\n    /** @internal */\n    __clone(ctx: unknown): User { return this; }\n}";
+        let types = "class User { }";
+        assert!(check_consistency(runtime, types).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unparsable_output_is_a_source_error() {
+        let err = check_consistency("class User {", "class User { }").unwrap_err();
+        assert!(matches!(err, MacroError::Source(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_parameter_count_mismatch() {
+        let runtime = "class User { equals(a: User, b: unknown): boolean { return true; } }";
+        let types = "class User { equals(other: User): boolean; }";
+        let issues = check_consistency(runtime, types).unwrap();
+        assert_eq!(
+            issues[0].kind,
+            ConsistencyIssueKind::ParameterCountMismatch {
+                runtime: 2,
+                types: vec![1]
+            }
+        );
+        assert!(issues[0].message().contains("takes 2 parameter(s) at runtime but 1"));
+    }
+
+    #[test]
+    fn test_overloads_accept_any_matching_count() {
+        let runtime = "class A { f(a: number, b?: number) { } }";
+        let types = "class A { f(a: number): void; f(a: number, b: number): void; }";
+        assert!(check_consistency(runtime, types).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_namespace_functions() {
+        let runtime = r#"
+interface Point { x: number; }
+export namespace Point {
+    export function toString(self: Point): string { return ""; }
+    export function hashCode(self: Point): number { return 0; }
+}
+"#;
+        let types = r#"
+interface Point { x: number; }
+export declare namespace Point {
+    function toString(self: Point): string;
+}
+"#;
+        let issues = check_consistency(runtime, types).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].container, "namespace Point");
+        assert_eq!(issues[0].member, "hashCode");
+    }
+
+    #[test]
+    fn test_builtin_derives_are_consistent() {
+        let source = r#"
+/** @derive(Debug, Clone, PartialEq, Hash, Default, Serialize, Deserialize) */
+class User {
+    name: string;
+    age: number;
+}
+
+/** @derive(Debug, Clone, PartialEq) */
+interface Point {
+    x: number;
+    y: number;
+}
+"#;
+        let expander = crate::host::MacroExpander::with_config(
+            crate::host::MacroConfig::default(),
+            std::env::current_dir().unwrap(),
+        )
+        .unwrap();
+        let expansion = swc_core::common::GLOBALS
+            .set(&Default::default(), || expander.expand_source(source, "test.ts"))
+            .unwrap();
+        let issues = expansion.check_consistency(source).unwrap();
+        assert!(issues.is_empty(), "unexpected issues: {issues:#?}");
+    }
+}
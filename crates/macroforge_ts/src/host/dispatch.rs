@@ -5,6 +5,7 @@ use crate::ts_syn::TsStream;
 use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel, MacroContextIR, MacroResult};
 
 /// Dispatches macro calls to registered macro implementations
+#[derive(Clone)]
 pub struct MacroDispatcher {
     registry: MacroRegistry,
 }
@@ -19,30 +20,30 @@ impl MacroDispatcher {
     pub fn dispatch(&self, ctx: MacroContextIR) -> MacroResult {
         // Look up the macro in the registry, with fallback to name-only lookup
         // This supports both exact module paths and dynamic module resolution
-        match self
-            .registry
-            .lookup_with_fallback(&ctx.module_path, &ctx.macro_name)
-        {
-            Ok(macro_impl) => {
+        match self.registry.resolve(&ctx.module_path, &ctx.macro_name) {
+            Ok(resolution) => {
+                let macro_impl = resolution.macro_impl;
+
                 // Check ABI version compatibility
                 let impl_abi = macro_impl.abi_version();
                 if impl_abi != ctx.abi_version {
                     return MacroResult {
                         runtime_patches: vec![],
                         type_patches: vec![],
-                        diagnostics: vec![Diagnostic {
-                            level: DiagnosticLevel::Error,
-                            message: format!(
-                                "ABI version mismatch: expected {}, got {}",
-                                ctx.abi_version, impl_abi
-                            ),
-                            span: Some(ctx.decorator_span),
-                            notes: vec![],
-                            help: Some(
-                                "The macro may need to be rebuilt with the current ABI version"
-                                    .to_string(),
-                            ),
-                        }],
+                        diagnostics: vec![
+                            Diagnostic::new(
+                                DiagnosticLevel::Error,
+                                format!(
+                                    "ABI version mismatch: expected {}, got {}",
+                                    ctx.abi_version, impl_abi
+                                ),
+                            )
+                            .with_span(ctx.decorator_span)
+                            .with_help(
+                                "The macro may need to be rebuilt with the current ABI version",
+                            )
+                            .with_code("abi-version-mismatch"),
+                        ],
                         tokens: None,
                         debug: None,
                     };
@@ -56,13 +57,14 @@ impl MacroDispatcher {
                             return MacroResult {
                                 runtime_patches: vec![],
                                 type_patches: vec![],
-                                diagnostics: vec![Diagnostic {
-                                    level: DiagnosticLevel::Error,
-                                    message: format!("Failed to create TsStream: {:?}", err),
-                                    span: Some(ctx.decorator_span),
-                                    notes: vec![],
-                                    help: None,
-                                }],
+                                diagnostics: vec![
+                                    Diagnostic::new(
+                                        DiagnosticLevel::Error,
+                                        format!("Failed to create TsStream: {:?}", err),
+                                    )
+                                    .with_span(ctx.decorator_span)
+                                    .with_code("stream-error"),
+                                ],
                                 tokens: None,
                                 debug: None,
                             };
@@ -73,7 +75,16 @@ impl MacroDispatcher {
                 match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                     macro_impl.run(input)
                 })) {
-                    Ok(result) => result,
+                    Ok(mut result) => {
+                        if !resolution.ambiguous_with.is_empty() {
+                            result.diagnostics.push(ambiguous_macro_warning(
+                                &ctx,
+                                &resolution.module,
+                                &resolution.ambiguous_with,
+                            ));
+                        }
+                        result
+                    }
                     Err(panic_err) => {
                         let panic_msg = if let Some(s) = panic_err.downcast_ref::<String>() {
                             s.clone()
@@ -86,13 +97,14 @@ impl MacroDispatcher {
                         MacroResult {
                             runtime_patches: vec![],
                             type_patches: vec![],
-                            diagnostics: vec![Diagnostic {
-                                level: DiagnosticLevel::Error,
-                                message: format!("Macro execution panicked: {}", panic_msg),
-                                span: Some(ctx.decorator_span),
-                                notes: vec![],
-                                help: None,
-                            }],
+                            diagnostics: vec![
+                                Diagnostic::new(
+                                    DiagnosticLevel::Error,
+                                    format!("Macro execution panicked: {}", panic_msg),
+                                )
+                                .with_span(ctx.decorator_span)
+                                .with_code("macro-panicked"),
+                            ],
                             tokens: None,
                             debug: None,
                         }
@@ -102,18 +114,18 @@ impl MacroDispatcher {
             Err(_err) => MacroResult {
                 runtime_patches: vec![],
                 type_patches: vec![],
-                diagnostics: vec![Diagnostic {
-                    level: DiagnosticLevel::Error,
-                    message: format!(
-                        "Macro '{}' not found in module '{}'",
-                        ctx.macro_name, ctx.module_path
-                    ),
-                    span: Some(ctx.decorator_span),
-                    notes: vec![],
-                    help: Some(
-                        "Make sure the macro package is installed and configured".to_string(),
-                    ),
-                }],
+                diagnostics: vec![
+                    Diagnostic::new(
+                        DiagnosticLevel::Error,
+                        format!(
+                            "Macro '{}' not found in module '{}'",
+                            ctx.macro_name, ctx.module_path
+                        ),
+                    )
+                    .with_span(ctx.decorator_span)
+                    .with_help("Make sure the macro package is installed and configured")
+                    .with_code("macro-not-found"),
+                ],
                 tokens: None,
                 debug: None,
             },
@@ -126,6 +138,28 @@ impl MacroDispatcher {
     }
 }
 
+/// Warning for a name-only fallback that had to pick between several modules
+fn ambiguous_macro_warning(ctx: &MacroContextIR, resolved: &str, others: &[String]) -> Diagnostic {
+    let message = format!(
+        "Macro '{}' is not exported by '{}'; using the one from '{}' although {} also export it",
+        ctx.macro_name,
+        ctx.module_path,
+        resolved,
+        others
+            .iter()
+            .map(|module| format!("'{module}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Diagnostic::new(DiagnosticLevel::Warning, message)
+        .with_span(ctx.error_span())
+        .with_help(format!(
+            "import '{}' from the package that should provide it",
+            ctx.macro_name
+        ))
+        .with_code("ambiguous-macro")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,24 +185,12 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_dispatch() {
-        let registry = MacroRegistry::new();
-        let test_macro = Arc::new(TestMacro {
-            name: "Debug".to_string(),
-        });
-
-        registry
-            .register("@macro/derive", "Debug", test_macro)
-            .unwrap();
-
-        let dispatcher = MacroDispatcher::new(registry);
-
-        let ctx = MacroContextIR {
+    fn test_context(module_path: &str) -> MacroContextIR {
+        MacroContextIR {
             abi_version: 1,
             macro_kind: MacroKind::Derive,
             macro_name: "Debug".to_string(),
-            module_path: "@macro/derive".to_string(),
+            module_path: module_path.to_string(),
             decorator_span: SpanIR { start: 0, end: 10 },
             macro_name_span: None,
             target_span: SpanIR {
@@ -196,9 +218,48 @@ mod tests {
                 members: vec![],
             }),
             target_source: "class Test {}".to_string(),
-        };
+            macro_options: Default::default(),
+            file_derives: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let registry = MacroRegistry::new();
+        let test_macro = Arc::new(TestMacro {
+            name: "Debug".to_string(),
+        });
+
+        registry
+            .register("@macro/derive", "Debug", test_macro)
+            .unwrap();
+
+        let dispatcher = MacroDispatcher::new(registry);
+
+        let ctx = test_context("@macro/derive");
 
         let result = dispatcher.dispatch(ctx);
         assert!(result.diagnostics.is_empty());
     }
+
+    #[test]
+    fn test_dispatch_warns_on_ambiguous_fallback() {
+        let registry = MacroRegistry::new();
+        for module in ["@a/macros", "@b/macros"] {
+            let test_macro = Arc::new(TestMacro {
+                name: "Validate".to_string(),
+            });
+            registry.register(module, "Validate", test_macro).unwrap();
+        }
+        let dispatcher = MacroDispatcher::new(registry);
+
+        let mut ctx = test_context("./validators");
+        ctx.macro_name = "Validate".to_string();
+        let result = dispatcher.dispatch(ctx);
+        assert_eq!(result.diagnostics.len(), 1);
+        let diag = &result.diagnostics[0];
+        assert_eq!(diag.level, DiagnosticLevel::Warning);
+        assert_eq!(diag.code.as_deref(), Some("ambiguous-macro"));
+        assert!(diag.message.contains("'@a/macros'") && diag.message.contains("'@b/macros'"));
+    }
 }
@@ -0,0 +1,192 @@
+//! Registry diagnostics for `macroforge doctor`
+//!
+//! Answers "why is my macro not running": which `macroPackages` entries were
+//! found and where, which macros ended up in the registry under which module,
+//! and which names are exported by more than one module (and therefore
+//! resolve by fallback order when imported from elsewhere).
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel, MacroKind};
+
+use super::MacroExpander;
+use super::derived::DYNAMIC_MODULE_MARKER;
+use super::package_registry::{PackageSource, ResolvedPackage};
+
+/// A macro as registered, with the package that compiled it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredMacro {
+    pub module: String,
+    pub name: String,
+    pub kind: MacroKind,
+    /// Crate or native package that provides the implementation, if known
+    pub package: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Snapshot of the resolved macro configuration
+#[derive(Debug, Clone)]
+pub struct DoctorReport {
+    pub root: PathBuf,
+    pub allow_native_macros: bool,
+    pub packages: Vec<ResolvedPackage>,
+    /// Sorted by module, then name
+    pub macros: Vec<RegisteredMacro>,
+    /// Names exported by several modules, with those modules
+    pub ambiguous: Vec<(String, Vec<String>)>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DoctorReport {
+    pub fn new(expander: &MacroExpander, root: &Path) -> Self {
+        let registry = expander.dispatcher.registry();
+        let mut macros: Vec<RegisteredMacro> = registry
+            .all_macros()
+            .into_iter()
+            .filter(|(key, _)| key.module != DYNAMIC_MODULE_MARKER)
+            .map(|(key, macro_impl)| RegisteredMacro {
+                module: key.module,
+                name: key.name,
+                kind: macro_impl.kind(),
+                package: macro_impl.package().map(str::to_string),
+                version: macro_impl.version().map(str::to_string),
+            })
+            .collect();
+        macros.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+
+        Self {
+            root: root.to_path_buf(),
+            allow_native_macros: expander.config().allow_native_macros,
+            packages: expander.packages().to_vec(),
+            macros,
+            ambiguous: registry.ambiguous_names(),
+            diagnostics: expander.config_diagnostics(),
+        }
+    }
+
+    /// Whether any configuration warning or error was found
+    pub fn has_problems(&self) -> bool {
+        !self.diagnostics.is_empty() || !self.ambiguous.is_empty()
+    }
+}
+
+impl fmt::Display for PackageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Embedded => f.write_str("embedded"),
+            Self::Derived => f.write_str("built-in"),
+            Self::Native { path, macros } => {
+                write!(f, "native library {} ({macros} macros)", path.display())
+            }
+            Self::Node { path } => write!(f, "node package {}", path.display()),
+            Self::Unresolved => f.write_str("unresolved"),
+        }
+    }
+}
+
+fn kind_name(kind: MacroKind) -> &'static str {
+    match kind {
+        MacroKind::Derive => "derive",
+        MacroKind::Attribute => "attribute",
+        MacroKind::Call => "call",
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "config root: {}", self.root.display())?;
+        let native = if self.allow_native_macros { "allowed" } else { "disabled" };
+        writeln!(f, "native macros: {native}")?;
+
+        writeln!(f, "\npackages:")?;
+        if self.packages.is_empty() {
+            writeln!(f, "  (none)")?;
+        }
+        for package in &self.packages {
+            writeln!(f, "  {}: {}", package.name, package.source)?;
+        }
+
+        writeln!(f, "\nmacros:")?;
+        let mut current_module = None;
+        for registered in &self.macros {
+            if current_module != Some(&registered.module) {
+                writeln!(f, "  {}", registered.module)?;
+                current_module = Some(&registered.module);
+            }
+            write!(f, "    {} ({})", registered.name, kind_name(registered.kind))?;
+            match (&registered.package, &registered.version) {
+                (Some(package), Some(version)) => writeln!(f, " {package}@{version}")?,
+                (Some(package), None) => writeln!(f, " {package}")?,
+                _ => writeln!(f)?,
+            }
+        }
+
+        if !self.ambiguous.is_empty() {
+            writeln!(f, "\nambiguous names (resolved to the first module when imported elsewhere):")?;
+            for (name, modules) in &self.ambiguous {
+                writeln!(f, "  {name}: {}", modules.join(", "))?;
+            }
+        }
+
+        for diag in &self.diagnostics {
+            let level = match diag.level {
+                DiagnosticLevel::Error => "error",
+                DiagnosticLevel::Warning => "warning",
+                DiagnosticLevel::Info => "info",
+            };
+            match &diag.code {
+                Some(code) => writeln!(f, "\n{level}[{code}]: {}", diag.message)?,
+                None => writeln!(f, "\n{level}: {}", diag.message)?,
+            }
+            for note in &diag.notes {
+                writeln!(f, "  note: {note}")?;
+            }
+            if let Some(help) = &diag.help {
+                writeln!(f, "  help: {help}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::MacroConfig;
+
+    fn report_for(config: MacroConfig) -> DoctorReport {
+        let dir = tempfile::tempdir().unwrap();
+        let expander = MacroExpander::with_config(config, dir.path().to_path_buf()).unwrap();
+        DoctorReport::new(&expander, dir.path())
+    }
+
+    #[test]
+    fn test_lists_builtin_macros_with_versions() {
+        let report = report_for(MacroConfig::default());
+        let debug = report
+            .macros
+            .iter()
+            .find(|m| m.module == "@macro/derive" && m.name == "Debug")
+            .expect("Debug should be registered");
+        assert_eq!(debug.kind, MacroKind::Derive);
+        assert_eq!(debug.package.as_deref(), Some("macroforge_ts"));
+        assert_eq!(debug.version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        assert!(!report.macros.iter().any(|m| m.module == DYNAMIC_MODULE_MARKER));
+        assert!(!report.packages.iter().any(|p| p.name == DYNAMIC_MODULE_MARKER));
+    }
+
+    #[test]
+    fn test_reports_unresolved_packages() {
+        let report = report_for(MacroConfig {
+            macro_packages: vec!["missing-macros".to_string()],
+            ..Default::default()
+        });
+        assert!(report.has_problems());
+
+        let text = report.to_string();
+        assert!(text.contains("missing-macros: unresolved"), "{text}");
+        assert!(text.contains("warning[unresolved-macro-package]"), "{text}");
+        assert!(text.contains("    Debug (derive) macroforge_ts@"), "{text}");
+    }
+}
@@ -16,6 +16,10 @@ pub enum MacroError {
     #[error("Invalid macro configuration: {0}")]
     InvalidConfig(String),
 
+    /// Source code (input or macro output) failed to parse or lower
+    #[error("Source error: {0}")]
+    Source(String),
+
     /// Macro execution failed
     #[error("Macro execution failed: {0}")]
     ExecutionFailed(String),
@@ -4,9 +4,12 @@
 //! It handles both classes and interfaces, supports external macro loading via Node.js,
 //! and provides source mapping for IDE integration.
 
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
 
 use anyhow::Context;
 use napi::Status;
@@ -22,6 +25,9 @@ use crate::ts_syn::{lower_classes, lower_enums, lower_interfaces, lower_type_ali
 
 use super::{
     MacroConfig, MacroDispatcher, MacroError, MacroRegistry, PatchCollector, Result, derived,
+    native,
+    package_registry::{PackageSource, ResolvedPackage, resolve_node_package},
+    patch_applicator::internal_member_code,
 };
 
 /// Default module path for built-in derive macros
@@ -33,7 +39,8 @@ const DYNAMIC_MODULE_MARKER: &str = "__DYNAMIC_MODULE__";
 /// Built-in macro names that don't need to be imported
 const BUILTIN_MACRO_NAMES: &[&str] = &[
     "Debug", "Clone", "Default", "Hash", "Ord",
-    "PartialEq", "PartialOrd", "Serialize", "Deserialize",
+    "PartialEq", "PartialOrd", "Serialize", "Deserialize", "EnumUtils",
+    "Patch", "Partial", "Mock",
 ];
 
 /// Result of macro expansion
@@ -56,12 +63,20 @@ pub struct MacroExpansion {
 /// This struct provides the expansion logic that can be reused by any macro package.
 /// Each macro package creates its own instance, which will use that package's
 /// local inventory of macros.
+#[derive(Clone)]
 pub struct MacroExpander {
     pub dispatcher: MacroDispatcher,
     config: MacroConfig,
-    /// Whether to keep decorators in emitted output (used only by host integrations that need mapping)
-    keep_decorators: bool,
+    /// Explicit override of the config's `keepDecorators` (used only by host integrations that need mapping)
+    keep_decorators: Option<bool>,
+    /// Directory the configuration was found in; `overrides` globs are relative to it
+    root_dir: std::path::PathBuf,
     external_loader: Option<ExternalMacroLoader>,
+    /// Where each `macroPackages` entry was found
+    packages: Vec<ResolvedPackage>,
+    /// Whether an expansion already reported [`Self::config_diagnostics`];
+    /// shared by the clones of a cached expander, so once per config load
+    config_reported: Arc<AtomicBool>,
 }
 
 type ContextFactory = Box<dyn Fn(String, String) -> MacroContextIR>;
@@ -102,6 +117,28 @@ impl MacroExpander {
         Self::with_config_and_env(config, root_dir, _env)
     }
 
+    /// Create an expander with the configuration governing `file`, searching
+    /// from its directory before falling back to the current directory
+    ///
+    /// Discovery results and loaded expanders are cached until one of the
+    /// files they were read from changes, so config loading and package
+    /// registration run once per config root rather than once per file.
+    /// `overrides` are still resolved for each file during expansion.
+    pub fn for_file(file: &Path) -> anyhow::Result<Self> {
+        let found = match file.parent() {
+            Some(dir) if dir.is_dir() => discover_config(dir)?,
+            _ => None,
+        };
+        let found = match found {
+            Some(found) => Some(found),
+            None => discover_config(&std::env::current_dir()?)?,
+        };
+        match found {
+            Some((config_path, root_dir)) => cached_expander(Some(config_path), root_dir),
+            None => cached_expander(None, std::env::current_dir()?),
+        }
+    }
+
     /// Create an expander with a specific config
     #[allow(dead_code)]
     pub fn with_config(config: MacroConfig, root_dir: std::path::PathBuf) -> anyhow::Result<Self> {
@@ -115,7 +152,7 @@ impl MacroExpander {
         _env: Option<&napi::Env>,
     ) -> anyhow::Result<Self> {
         let registry = MacroRegistry::new();
-        register_packages(&registry, &config, &root_dir)?;
+        let packages = register_packages(&registry, &config, &root_dir)?;
 
         debug_assert!(
             registry.contains("@macro/derive", "Debug"),
@@ -153,20 +190,68 @@ impl MacroExpander {
             registry.contains("@macro/derive", "Deserialize"),
             "Built-in @macro/derive::Deserialize macro should be registered"
         );
-
-        let keep_decorators = config.keep_decorators;
+        debug_assert!(
+            registry.contains("@macro/derive", "EnumUtils"),
+            "Built-in @macro/derive::EnumUtils macro should be registered"
+        );
+        debug_assert!(
+            registry.contains("@macro/derive", "Patch"),
+            "Built-in @macro/derive::Patch macro should be registered"
+        );
+        debug_assert!(
+            registry.contains("@macro/derive", "Mock"),
+            "Built-in @macro/derive::Mock macro should be registered"
+        );
 
         Ok(Self {
             dispatcher: MacroDispatcher::new(registry),
             config,
-            keep_decorators,
+            keep_decorators: None,
+            root_dir: root_dir.clone(),
             external_loader: Some(ExternalMacroLoader::new(root_dir)),
+            packages,
+            config_reported: Default::default(),
         })
     }
 
+    /// The configuration this expander was created with
+    pub fn config(&self) -> &MacroConfig {
+        &self.config
+    }
+
+    /// Resolution of each `macroPackages` entry
+    pub fn packages(&self) -> &[ResolvedPackage] {
+        &self.packages
+    }
+
+    /// Warnings about the macro configuration, such as unknown properties
+    /// and unresolved packages.
+    ///
+    /// Also reported once per loaded config, with the first expansion that
+    /// invokes a macro.
+    pub fn config_diagnostics(&self) -> Vec<Diagnostic> {
+        let unknown_properties = self.config.warnings.iter().map(|warning| {
+            Diagnostic::new(DiagnosticLevel::Warning, warning.clone())
+                .with_help("unknown properties are ignored; see macroforge.schema.json")
+                .with_code("unknown-config-property")
+        });
+        unknown_properties
+            .chain(self.packages.iter().filter_map(ResolvedPackage::to_diagnostic))
+            .collect()
+    }
+
     /// Control whether decorators are preserved in the expanded output.
     pub fn set_keep_decorators(&mut self, keep: bool) {
-        self.keep_decorators = keep;
+        self.keep_decorators = Some(keep);
+    }
+
+    /// Whether decorators are kept for `file_name`, honouring config `overrides`
+    fn keep_decorators_for(&self, file_name: &str) -> bool {
+        self.keep_decorators.unwrap_or_else(|| {
+            self.config
+                .for_file(&self.root_dir, file_name)
+                .keep_decorators
+        })
     }
 
     /// Expand all macros in the source code (simple API for CLI usage)
@@ -174,19 +259,19 @@ impl MacroExpander {
         use crate::ts_syn::parse_ts_module;
 
         let module = parse_ts_module(source)
-            .map_err(|e| MacroError::InvalidConfig(format!("Parse error: {:?}", e)))?;
+            .map_err(|e| MacroError::Source(format!("Parse error: {:?}", e)))?;
 
         let classes = lower_classes(&module, source)
-            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+            .map_err(|e| MacroError::Source(format!("Lower error: {:?}", e)))?;
 
         let interfaces = lower_interfaces(&module, source)
-            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+            .map_err(|e| MacroError::Source(format!("Lower error: {:?}", e)))?;
 
         let enums = lower_enums(&module, source)
-            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+            .map_err(|e| MacroError::Source(format!("Lower error: {:?}", e)))?;
 
         let type_aliases = lower_type_aliases(&module, source)
-            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+            .map_err(|e| MacroError::Source(format!("Lower error: {:?}", e)))?;
 
         let items = LoweredItems { classes, interfaces, enums, type_aliases };
         if items.is_empty() {
@@ -213,7 +298,7 @@ impl MacroExpander {
         let (mut collector, mut diagnostics) =
             self.collect_macro_patches(&module, items, file_name, source);
 
-        self.apply_and_finalize_expansion(source, &mut collector, &mut diagnostics, items_clone)
+        self.apply_and_finalize_expansion(source, file_name, &mut collector, &mut diagnostics, items_clone)
     }
 
     /// Expand all macros found in the parsed program and return the updated source code.
@@ -249,7 +334,7 @@ impl MacroExpander {
 
         let (mut collector, mut diagnostics) =
             self.collect_macro_patches(&module, items, file_name, source);
-        self.apply_and_finalize_expansion(source, &mut collector, &mut diagnostics, items_clone)
+        self.apply_and_finalize_expansion(source, file_name, &mut collector, &mut diagnostics, items_clone)
             .map_err(anyhow::Error::from)
     }
 
@@ -334,8 +419,18 @@ impl MacroExpander {
             return (collector, diagnostics);
         }
 
+        let file_derives: BTreeMap<String, Vec<String>> = derive_targets
+            .iter()
+            .map(|target| {
+                let macro_names = target.macro_names.iter().map(|(name, _)| name.clone()).collect();
+                (target.target_ir.name().to_string(), macro_names)
+            })
+            .collect();
+
+        let keep_decorators = self.keep_decorators_for(file_name);
+        let file_config = self.config.for_file(&self.root_dir, file_name);
         for target in derive_targets {
-            if !self.keep_decorators {
+            if !keep_decorators {
                 let decorator_removal = Patch::Delete {
                     span: target.decorator_span,
                 };
@@ -346,7 +441,7 @@ impl MacroExpander {
             // Process class-specific patches (field decorators and method body stripping)
             if let DeriveTargetIR::Class(class_ir) = &target.target_ir {
                 // Remove field decorators when not keeping decorators
-                if !self.keep_decorators {
+                if !keep_decorators {
                     for field in &class_ir.fields {
                         for decorator in &field.decorators {
                             let field_dec_removal = Patch::Delete {
@@ -412,7 +507,7 @@ impl MacroExpander {
             }
 
             // Remove interface field decorators when not keeping decorators
-            if !self.keep_decorators
+            if !keep_decorators
                 && let DeriveTargetIR::Interface(interface_ir) = &target.target_ir
             {
                 for field in &interface_ir.fields {
@@ -542,7 +637,10 @@ impl MacroExpander {
                 };
 
             for (macro_name, module_path) in target.macro_names {
-                let mut ctx = ctx_factory(macro_name.clone(), module_path.clone());
+                let macro_options = file_config.macro_options_for(&macro_name);
+                let mut ctx = ctx_factory(macro_name.clone(), module_path.clone())
+                    .with_macro_options(macro_options.clone())
+                    .with_file_derives(file_derives.clone());
 
                 // Calculate macro_name_span
                 if let Some(macro_name_span) =
@@ -558,7 +656,9 @@ impl MacroExpander {
                     && ctx.module_path.starts_with('.')
                 {
                     let fallback_ctx =
-                        ctx_factory(macro_name.clone(), DERIVE_MODULE_PATH.to_string());
+                        ctx_factory(macro_name.clone(), DERIVE_MODULE_PATH.to_string())
+                            .with_macro_options(macro_options)
+                            .with_file_derives(file_derives.clone());
                     result = self.dispatcher.dispatch(fallback_ctx);
                 }
 
@@ -575,20 +675,23 @@ impl MacroExpander {
                             result = external_result;
                         }
                         Err(err) => {
-                            result.diagnostics.push(Diagnostic {
-                                level: DiagnosticLevel::Error,
-                                message: format!(
-                                    "Failed to load external macro '{}::{}': {}",
-                                    ctx.macro_name, ctx.module_path, err
-                                ),
-                                span: Some(diagnostic_span_for_derive(ctx.decorator_span, source)),
-                                notes: vec![],
-                                help: None,
-                            });
+                            result.diagnostics.push(
+                                Diagnostic::new(
+                                    DiagnosticLevel::Error,
+                                    format!(
+                                        "Failed to load external macro '{}::{}': {}",
+                                        ctx.macro_name, ctx.module_path, err
+                                    ),
+                                )
+                                .with_span(diagnostic_span_for_derive(ctx.decorator_span, source))
+                                .with_code("external-macro-failed"),
+                            );
                         }
                     }
                 }
 
+                self.note_unresolved_packages(&mut result);
+
                 // Process potential token stream result
                 if let Ok((runtime, type_def)) =
                     self.process_macro_output(&mut result, &ctx, source)
@@ -613,6 +716,31 @@ impl MacroExpander {
         (collector, diagnostics)
     }
 
+    /// Point lookup failures at `macroPackages` entries that could not be resolved
+    fn note_unresolved_packages(&self, result: &mut MacroResult) {
+        let unresolved: Vec<&str> = self
+            .packages
+            .iter()
+            .filter(|pkg| !pkg.is_resolved())
+            .map(|pkg| pkg.name.as_str())
+            .collect();
+        if unresolved.is_empty() {
+            return;
+        }
+
+        for diag in &mut result.diagnostics {
+            if matches!(
+                diag.code.as_deref(),
+                Some("macro-not-found" | "external-macro-failed")
+            ) {
+                diag.notes.push(format!(
+                    "macroPackages entries that could not be resolved: {}",
+                    unresolved.join(", ")
+                ));
+            }
+        }
+    }
+
     pub(crate) fn process_macro_output(
         &self,
         result: &mut MacroResult,
@@ -671,17 +799,33 @@ impl MacroExpander {
                             }
                             "body" => {
                                 let insert_pos = derive_insert_pos(class_ir, source);
+                                let internal = internal_member_names(&code);
                                 match parse_members_from_tokens(&code) {
                                     Ok(members) => {
                                         for member in members {
+                                            let is_internal = member_name(&member)
+                                                .is_some_and(|name| internal.contains(&name));
+                                            // Emitting a parsed member only fails on invalid
+                                            // AST; the plain member is rendered the same way.
+                                            let runtime_code = if is_internal {
+                                                internal_member_code(source, insert_pos, &member)
+                                                    .unwrap_or_else(|_| {
+                                                        PatchCode::ClassMember(member.clone())
+                                                    })
+                                            } else {
+                                                PatchCode::ClassMember(member.clone())
+                                            };
                                             runtime_patches.push(Patch::Insert {
                                                 at: SpanIR {
                                                     start: insert_pos,
                                                     end: insert_pos,
                                                 },
-                                                code: PatchCode::ClassMember(member.clone()),
+                                                code: runtime_code,
                                                 source_macro: macro_name.clone(),
                                             });
+                                            if is_internal {
+                                                continue;
+                                            }
 
                                             let mut signature_member = member.clone();
                                             match &mut signature_member {
@@ -735,18 +879,19 @@ impl MacroExpander {
                                             source_macro: macro_name.clone(),
                                         });
 
-                                        result.diagnostics.push(Diagnostic {
-                                            level: DiagnosticLevel::Warning,
-                                            message: format!(
-                                                "Failed to parse macro output, inserted raw tokens: {err:?}"
-                                            ),
-                                            span: Some(diagnostic_span_for_derive(
+                                        result.diagnostics.push(
+                                            Diagnostic::new(
+                                                DiagnosticLevel::Warning,
+                                                format!(
+                                                    "Failed to parse macro output, inserted raw tokens: {err:?}"
+                                                ),
+                                            )
+                                            .with_span(diagnostic_span_for_derive(
                                                 ctx.decorator_span,
                                                 source,
-                                            )),
-                                            notes: vec![],
-                                            help: None,
-                                        });
+                                            ))
+                                            .with_code("unparsable-output"),
+                                        );
                                     }
                                 }
                             }
@@ -862,6 +1007,7 @@ impl MacroExpander {
     pub(crate) fn apply_and_finalize_expansion(
         &self,
         source: &str,
+        file_name: &str,
         collector: &mut PatchCollector,
         diagnostics: &mut Vec<Diagnostic>,
         items: LoweredItems,
@@ -873,11 +1019,11 @@ impl MacroExpander {
             .map_err(|e| MacroError::InvalidConfig(format!("Patch error: {:?}", e)))?;
 
         let type_output = if collector.has_type_patches() {
-            Some(
-                collector
-                    .apply_type_patches(source)
-                    .map_err(|e| MacroError::InvalidConfig(format!("Type patch error: {:?}", e)))?,
-            )
+            let declarations = collector
+                .apply_type_patches(source)
+                .map_err(|e| MacroError::InvalidConfig(format!("Type patch error: {:?}", e)))?;
+            // Declarations may name types macros imported (`__serialize(ctx: SerializeContext)`)
+            Some(format!("{}{declarations}", collector.type_import_code(&declarations)))
         } else {
             None
         };
@@ -889,13 +1035,24 @@ impl MacroExpander {
         };
 
         let mut code = runtime_result.code;
-        if !self.keep_decorators {
+        if !self.keep_decorators_for(file_name) {
             code = strip_decorators(&code);
         }
 
+        // Config problems explain missing or surprising macros, so surface
+        // them where macros first run rather than only in `doctor`
+        let mut all_diagnostics = if (has_patches || !diagnostics.is_empty())
+            && !self.config_reported.swap(true, Ordering::Relaxed)
+        {
+            self.config_diagnostics()
+        } else {
+            Vec::new()
+        };
+        all_diagnostics.append(diagnostics);
+
         let mut expansion = MacroExpansion {
             code,
-            diagnostics: std::mem::take(diagnostics),
+            diagnostics: all_diagnostics,
             changed: has_patches,
             type_output,
             classes,
@@ -919,19 +1076,17 @@ impl MacroExpander {
 
         if diagnostics.len() > max {
             diagnostics.truncate(max.saturating_sub(1));
-            diagnostics.push(Diagnostic {
-                level: DiagnosticLevel::Warning,
-                message: format!(
-                    "Diagnostic output truncated to {} entries per macro host configuration",
-                    max
-                ),
-                span: None,
-                notes: vec![],
-                help: Some(
-                    "Adjust `limits.maxDiagnostics` in macroforge.json to see all diagnostics"
-                        .to_string(),
-                ),
-            });
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticLevel::Warning,
+                    format!(
+                        "Diagnostic output truncated to {} entries per macro host configuration",
+                        max
+                    ),
+                )
+                .with_help("Adjust `limits.maxDiagnostics` in macroforge.json to see all diagnostics")
+                .with_code("diagnostics-truncated"),
+            );
         }
     }
 }
@@ -956,10 +1111,93 @@ fn strip_decorators(code: &str) -> String {
         .join("\n")
 }
 
+// ============================================================================
+// Expander Cache
+// ============================================================================
+
+/// A cached value and the modification times of the files it was built from
+struct Stamped<T> {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    value: T,
+}
+
+impl<T> Stamped<T> {
+    fn new(files: Vec<PathBuf>, value: T) -> Self {
+        let files = files
+            .into_iter()
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+        Self { files, value }
+    }
+
+    /// Whether none of the files was created, removed or modified since
+    fn is_fresh(&self) -> bool {
+        self.files.iter().all(|(path, stamp)| modified(path) == *stamp)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// A config file and the directory it was found in
+type FoundConfig = Option<(PathBuf, PathBuf)>;
+
+/// Config governing each directory [`MacroExpander::for_file`] searched from
+static DISCOVERED: LazyLock<Mutex<HashMap<PathBuf, Stamped<FoundConfig>>>> =
+    LazyLock::new(Default::default);
+
+/// An expander and the config file it was loaded from
+type LoadedExpander = (Option<PathBuf>, MacroExpander);
+
+/// Expanders built by [`MacroExpander::for_file`], keyed by config root
+static EXPANDERS: LazyLock<Mutex<HashMap<PathBuf, Stamped<LoadedExpander>>>> =
+    LazyLock::new(Default::default);
+
+/// The config file governing `dir` and the directory it was found in
+fn discover_config(dir: &Path) -> anyhow::Result<FoundConfig> {
+    let mut discovered = DISCOVERED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(entry) = discovered.get(dir).filter(|entry| entry.is_fresh()) {
+        return Ok(entry.value.clone());
+    }
+
+    let mut checked = Vec::new();
+    let found = MacroConfig::find_config_file(dir, &mut checked)
+        .context("failed to discover macro configuration")?;
+    discovered.insert(dir.to_path_buf(), Stamped::new(checked, found.clone()));
+    Ok(found)
+}
+
+/// An expander for the config in `config_path` (the defaults when `None`),
+/// loaded at most once per `root_dir` while its files are unchanged
+fn cached_expander(config_path: Option<PathBuf>, root_dir: PathBuf) -> anyhow::Result<MacroExpander> {
+    // Held while loading so concurrent files of one root register packages once
+    let mut expanders = EXPANDERS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(entry) = expanders.get(&root_dir)
+        && entry.value.0 == config_path
+        && entry.is_fresh()
+    {
+        return Ok(entry.value.1.clone());
+    }
+
+    let (config, sources) = match &config_path {
+        Some(path) => MacroConfig::load_with_sources(path)
+            .with_context(|| format!("failed to load {}", path.display()))?,
+        None => (MacroConfig::default(), Vec::new()),
+    };
+    let expander = MacroExpander::with_config(config, root_dir.clone())?;
+    expanders.insert(root_dir, Stamped::new(sources, (config_path, expander.clone())));
+    Ok(expander)
+}
+
 // ============================================================================
 // External Macro Loader
 // ============================================================================
 
+#[derive(Clone)]
 struct ExternalMacroLoader {
     root_dir: std::path::PathBuf,
 }
@@ -1222,6 +1460,17 @@ enum DeriveTargetIR {
     TypeAlias(TypeAliasIR),
 }
 
+impl DeriveTargetIR {
+    fn name(&self) -> &str {
+        match self {
+            DeriveTargetIR::Class(ir) => &ir.name,
+            DeriveTargetIR::Interface(ir) => &ir.name,
+            DeriveTargetIR::Enum(ir) => &ir.name,
+            DeriveTargetIR::TypeAlias(ir) => &ir.name,
+        }
+    }
+}
+
 #[derive(Clone)]
 struct DeriveTarget {
     macro_names: Vec<(String, String)>,
@@ -1229,6 +1478,58 @@ struct DeriveTarget {
     target_ir: DeriveTargetIR,
 }
 
+/// A declaration carrying `@derive(...)`, with the macros it requests
+#[derive(Debug, Clone)]
+pub(crate) struct DeriveSite {
+    /// Kind and name of the target, e.g. `class User`
+    pub target: String,
+    pub span: SpanIR,
+    pub decorator_span: SpanIR,
+    /// `(macro name, module path)` pairs in declaration order
+    pub macros: Vec<(String, String)>,
+}
+
+/// Collect every derive site in `module`, resolving module paths the same way expansion does
+pub(crate) fn collect_derive_sites(module: &Module, source: &str) -> anyhow::Result<Vec<DeriveSite>> {
+    let class_map: HashMap<SpanKey, ClassIR> = lower_classes(module, source)?
+        .into_iter()
+        .map(|class| (SpanKey::from(class.span), class))
+        .collect();
+    let interface_map: HashMap<SpanKey, InterfaceIR> = lower_interfaces(module, source)?
+        .into_iter()
+        .map(|iface| (SpanKey::from(iface.span), iface))
+        .collect();
+    let enum_map: HashMap<SpanKey, EnumIR> = lower_enums(module, source)?
+        .into_iter()
+        .map(|e| (SpanKey::from(e.span), e))
+        .collect();
+    let type_alias_map: HashMap<SpanKey, TypeAliasIR> = lower_type_aliases(module, source)?
+        .into_iter()
+        .map(|ta| (SpanKey::from(ta.span), ta))
+        .collect();
+
+    let mut sites: Vec<DeriveSite> =
+        collect_derive_targets(module, &class_map, &interface_map, &enum_map, &type_alias_map, source)
+            .into_iter()
+            .map(|target| {
+                let (target_name, span) = match &target.target_ir {
+                    DeriveTargetIR::Class(ir) => (format!("class {}", ir.name), ir.span),
+                    DeriveTargetIR::Interface(ir) => (format!("interface {}", ir.name), ir.span),
+                    DeriveTargetIR::Enum(ir) => (format!("enum {}", ir.name), ir.span),
+                    DeriveTargetIR::TypeAlias(ir) => (format!("type {}", ir.name), ir.span),
+                };
+                DeriveSite {
+                    target: target_name,
+                    span,
+                    decorator_span: target.decorator_span,
+                    macros: target.macro_names,
+                }
+            })
+            .collect();
+    sites.sort_by_key(|site| site.span.start);
+    Ok(sites)
+}
+
 /// Collect a map of identifier name -> module source from import statements
 pub fn collect_import_sources(module: &Module, source: &str) -> HashMap<String, String> {
     use swc_core::ecma::ast::{ImportDecl, ImportSpecifier, ModuleDecl, ModuleItem};
@@ -1308,19 +1609,21 @@ fn check_builtin_import_warnings(module: &Module, _source: &str) -> Vec<Diagnost
                         import_span.hi.0.saturating_sub(1),
                     );
 
-                    warnings.push(Diagnostic {
-                        level: DiagnosticLevel::Warning,
-                        message: format!(
-                            "'{}' is a built-in macro and doesn't need to be imported",
-                            local_name
-                        ),
-                        span: Some(span_ir),
-                        notes: vec![],
-                        help: Some(format!(
+                    warnings.push(
+                        Diagnostic::new(
+                            DiagnosticLevel::Warning,
+                            format!(
+                                "'{}' is a built-in macro and doesn't need to be imported",
+                                local_name
+                            ),
+                        )
+                        .with_span(span_ir)
+                        .with_help(format!(
                             "Remove this import - just use @derive({}) directly in a JSDoc comment",
                             local_name
-                        )),
-                    });
+                        ))
+                        .with_code("builtin-import"),
+                    );
                 }
             }
         }
@@ -1778,6 +2081,46 @@ fn split_by_markers(source: &str) -> Vec<(&str, String)> {
     chunks
 }
 
+/// Names of the members in macro output marked `/** @internal */`. They stay
+/// out of the type output, as `stripInternal` would leave them out.
+pub(crate) fn internal_member_names(tokens: &str) -> Vec<String> {
+    const MODIFIERS: &[&str] = &[
+        "public", "protected", "private", "static", "readonly", "async", "override", "get", "set",
+    ];
+    let mut names = Vec::new();
+    let mut rest = tokens;
+    while let Some(start) = rest.find("/**") {
+        let Some(len) = rest[start..].find("*/") else {
+            break;
+        };
+        let comment = &rest[start..start + len];
+        rest = &rest[start + len + 2..];
+        if !comment.contains("@internal") {
+            continue;
+        }
+        let name = rest
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .filter(|word| !word.is_empty())
+            .find(|word| !MODIFIERS.contains(word));
+        names.extend(name.map(str::to_string));
+    }
+    names
+}
+
+/// Name of a class member with a plain identifier key
+fn member_name(member: &swc_core::ecma::ast::ClassMember) -> Option<String> {
+    use swc_core::ecma::ast::{ClassMember, PropName};
+    let key = match member {
+        ClassMember::Method(method) => &method.key,
+        ClassMember::ClassProp(prop) => &prop.key,
+        _ => return None,
+    };
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        _ => None,
+    }
+}
+
 fn parse_members_from_tokens(
     tokens: &str,
 ) -> anyhow::Result<Vec<swc_core::ecma::ast::ClassMember>> {
@@ -1817,8 +2160,8 @@ fn available_package_registrars() -> Vec<(&'static str, PackageRegistrar)> {
 fn register_packages(
     registry: &MacroRegistry,
     config: &MacroConfig,
-    _config_root: &Path,
-) -> anyhow::Result<()> {
+    config_root: &Path,
+) -> anyhow::Result<Vec<ResolvedPackage>> {
     let mut embedded_map: HashMap<&'static str, PackageRegistrar> =
         available_package_registrars().into_iter().collect();
     for pkg in super::package_registry::registrars() {
@@ -1846,26 +2189,48 @@ fn register_packages(
     requested.sort();
     requested.dedup();
 
+    let mut packages = Vec::with_capacity(requested.len());
     for module in requested {
-        let mut found = false;
+        if native::is_native_library_path(module) {
+            let macros = register_native_library(registry, config, config_root, module)?;
+            packages.push(ResolvedPackage {
+                name: module.to_string(),
+                source: PackageSource::Native {
+                    path: config_root.join(module),
+                    macros,
+                },
+            });
+            continue;
+        }
+
+        let mut source = None;
 
         if let Some(registrar) = embedded_map.get(module) {
             registrar(registry)
                 .map_err(anyhow::Error::from)
                 .with_context(|| format!("failed to register macro package {module}"))?;
-            found = true;
+            source = Some(PackageSource::Embedded);
         }
 
         if derived_set.contains(module) {
             derived::register_module(module, registry)?;
-            found = true;
+            source.get_or_insert(PackageSource::Derived);
         }
 
-        if !found {
-            // Module not found - this is a warning, not an error
-        }
+        // Anything else must be a Node.js package the external loader can find
+        let source = source.unwrap_or_else(|| match resolve_node_package(config_root, module) {
+            Some(path) => PackageSource::Node { path },
+            None => PackageSource::Unresolved,
+        });
+        packages.push(ResolvedPackage {
+            name: module.to_string(),
+            source,
+        });
     }
 
+    // The dynamic marker is an implementation detail, not a package
+    packages.retain(|pkg| pkg.name != DYNAMIC_MODULE_MARKER);
+
     if derived_set.contains(DYNAMIC_MODULE_MARKER) {
         let _ = derived::register_module(DYNAMIC_MODULE_MARKER, registry);
     }
@@ -1881,7 +2246,28 @@ fn register_packages(
         }
     }
 
-    Ok(())
+    Ok(packages)
+}
+
+/// Load a `cdylib` macro package listed in `macroPackages`
+fn register_native_library(
+    registry: &MacroRegistry,
+    config: &MacroConfig,
+    config_root: &Path,
+    entry: &str,
+) -> anyhow::Result<usize> {
+    if !config.allow_native_macros {
+        anyhow::bail!(
+            "macro package {entry} is a native library; set \"allowNativeMacros\": true in macroforge.json to load it"
+        );
+    }
+
+    let path = config_root.join(entry);
+    let library = native::NativeLibrary::load(&path)
+        .with_context(|| format!("failed to load native macro package {entry}"))?;
+    library
+        .register(registry)
+        .with_context(|| format!("failed to register native macro package {entry}"))
 }
 
 // ============================================================================
@@ -2138,3 +2524,115 @@ class Comparable {
         assert!(warnings.iter().any(|w| w.message.contains("PartialEq")));
     }
 }
+
+#[cfg(test)]
+mod native_package_tests {
+    use super::*;
+
+    fn config_with(entry: &str, allow_native_macros: bool) -> MacroConfig {
+        MacroConfig {
+            macro_packages: vec![entry.to_string()],
+            allow_native_macros,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn native_library_requires_opt_in() {
+        let config = config_with("target/libacme_macros.so", false);
+        let err = register_packages(&MacroRegistry::new(), &config, Path::new("."))
+            .expect_err("native library should be rejected");
+        assert!(err.to_string().contains("allowNativeMacros"), "{err}");
+    }
+
+    #[test]
+    fn missing_native_library_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with("libmissing.so", true);
+        let err = register_packages(&MacroRegistry::new(), &config, dir.path())
+            .expect_err("missing library should fail");
+        assert!(format!("{err:#}").contains("libmissing.so"), "{err:#}");
+    }
+}
+
+#[cfg(test)]
+mod package_resolution_tests {
+    use super::*;
+
+    #[test]
+    fn unresolved_packages_become_config_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("node_modules/@acme/macros");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("package.json"), r#"{"name":"@acme/macros"}"#).unwrap();
+
+        let config = MacroConfig {
+            macro_packages: vec!["@acme/macros".to_string(), "missing-macros".to_string()],
+            ..Default::default()
+        };
+        let expander = MacroExpander::with_config(config, dir.path().to_path_buf()).unwrap();
+
+        let sources: Vec<_> = expander
+            .packages()
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.is_resolved()))
+            .collect();
+        assert_eq!(sources, vec![("@acme/macros", true), ("missing-macros", false)]);
+
+        let diagnostics = expander.config_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("unresolved-macro-package"));
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Warning);
+
+        let source = "/** @derive(Debug) */\nclass User { name: string; }\n";
+        let expansion = expander.expand_source(source, "user.ts").unwrap();
+        assert!(
+            expansion
+                .diagnostics
+                .iter()
+                .any(|d| d.code.as_deref() == Some("unresolved-macro-package")),
+            "{:?}",
+            expansion.diagnostics
+        );
+
+        let plain = expander.expand_source("class Plain { x = 1; }\n", "plain.ts").unwrap();
+        assert!(plain.diagnostics.is_empty(), "{:?}", plain.diagnostics);
+
+        // Reported once per load, not again for every file
+        let again = expander.clone().expand_source(source, "admin.ts").unwrap();
+        assert!(again.diagnostics.is_empty(), "{:?}", again.diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod expander_cache_tests {
+    use super::*;
+
+    #[test]
+    fn for_file_reuses_the_expander_until_the_config_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let config_path = root.join("macroforge.json");
+        std::fs::write(&config_path, r#"{ "limits": { "maxDiagnostics": 7 } }"#).unwrap();
+
+        let first = MacroExpander::for_file(&root.join("src/a.ts")).unwrap();
+        assert_eq!(first.config().limits.max_diagnostics, 7);
+        assert!(EXPANDERS.lock().unwrap().get(&root).is_some_and(|entry| entry.is_fresh()));
+
+        let second = MacroExpander::for_file(&root.join("b.ts")).unwrap();
+        assert_eq!(second.config().limits.max_diagnostics, 7);
+
+        std::fs::write(&config_path, r#"{ "limits": { "maxDiagnostics": 9 } }"#).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&config_path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let reloaded = MacroExpander::for_file(&root.join("src/a.ts")).unwrap();
+        assert_eq!(reloaded.config().limits.max_diagnostics, 9);
+    }
+}
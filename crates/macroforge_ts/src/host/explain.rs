@@ -0,0 +1,525 @@
+//! Reviewer-facing reports of what macros generated
+//!
+//! Backs `macroforge expand --diff` and `macroforge explain`. Both work from
+//! the expanded code plus its [`SourceMapping`], so they apply equally to
+//! expansions produced by the Rust expander and by the Node.js bindings.
+
+use std::fmt;
+use std::fmt::Write as _;
+
+use swc_core::ecma::ast::{Decl, ModuleDecl, ModuleItem, Stmt, TsModuleDecl, TsNamespaceBody};
+
+use crate::ts_syn::abi::{GeneratedRegion, SourceMapping};
+use crate::ts_syn::{lower_classes, parse_ts_module};
+
+use super::expand::collect_derive_sites;
+use super::{MacroError, Result};
+
+/// Lines of unchanged context around each diff hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Header used for edits that no macro claims (e.g. removed `@derive` comments)
+const SOURCE_EDITS: &str = "source edits";
+
+/// Render a unified diff of `original` against `expanded`, one section per macro.
+///
+/// Each section shows only the code attributed to that macro through
+/// `GeneratedRegion::source_macro`; edits outside generated regions (such as
+/// removed decorators) get their own leading section.
+pub fn diff_by_macro(
+    original: &str,
+    expanded: &str,
+    mapping: &SourceMapping,
+    file_name: &str,
+) -> String {
+    let regions = clamped_regions(expanded, &mapping.generated_regions);
+
+    let mut macros: Vec<&str> = Vec::new();
+    for region in &regions {
+        if !macros.contains(&region.source_macro.as_str()) {
+            macros.push(&region.source_macro);
+        }
+    }
+
+    let base = remove_regions(expanded, &regions, |_| true);
+    let mut out = String::new();
+
+    let edits = unified_diff(original, &base);
+    if !edits.is_empty() {
+        let _ = writeln!(out, "--- a/{file_name}\n+++ b/{file_name} [{SOURCE_EDITS}]");
+        out.push_str(&edits);
+    }
+
+    for name in macros {
+        let only_this = remove_regions(expanded, &regions, |r| r.source_macro != name);
+        let hunks = unified_diff(&base, &only_this);
+        if hunks.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "--- a/{file_name}\n+++ b/{file_name} [{name}]");
+        out.push_str(&hunks);
+    }
+    out
+}
+
+/// A macro applied to a derive target and the members it generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMacro {
+    pub name: String,
+    /// Module the macro was resolved from (`@macro/derive` for built-ins)
+    pub module_path: String,
+    /// Generated members, e.g. `toString()` or `User.fromJSON()`
+    pub members: Vec<String>,
+}
+
+/// Everything the expander did to the derive target at one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// Kind and name of the target, e.g. `class User`
+    pub target: String,
+    /// 1-based line of the target declaration
+    pub line: usize,
+    pub macros: Vec<AppliedMacro>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (line {})", self.target, self.line)?;
+        for applied in &self.macros {
+            writeln!(f, "  {} from {}", applied.name, applied.module_path)?;
+            if applied.members.is_empty() {
+                writeln!(f, "    (no generated members)")?;
+            }
+            for member in &applied.members {
+                writeln!(f, "    + {member}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Explain the derive target whose declaration (or `@derive` comment) spans `line`.
+///
+/// Returns `Ok(None)` when no derive target covers that line.
+pub fn explain_line(
+    source: &str,
+    expanded: &str,
+    mapping: &SourceMapping,
+    line: usize,
+) -> Result<Option<Explanation>> {
+    let module = parse_ts_module(source)
+        .map_err(|e| MacroError::Source(format!("Parse error: {:?}", e)))?;
+    let sites = collect_derive_sites(&module, source)?;
+
+    // IR spans are 1-based byte positions
+    let Some(site) = sites.into_iter().find(|site| {
+        let start = site.decorator_span.start.min(site.span.start).saturating_sub(1);
+        let end = site.span.end.saturating_sub(1);
+        line_of(source, start as usize) <= line && line <= line_of(source, end as usize)
+    }) else {
+        return Ok(None);
+    };
+
+    let start = site.span.start.saturating_sub(1);
+    let end = site.span.end.saturating_sub(1);
+    let regions = clamped_regions(expanded, &mapping.generated_regions);
+
+    let macros = site
+        .macros
+        .iter()
+        .map(|(name, module_path)| {
+            let mut members = Vec::new();
+            for region in regions.iter().filter(|r| &r.source_macro == name) {
+                let origin = insertion_point(mapping, region.start);
+                if origin < start || origin > end {
+                    continue;
+                }
+                let code = &expanded[region.start as usize..region.end as usize];
+                let in_body = origin > start && origin < end && site.target.starts_with("class ");
+                for member in generated_members(code, in_body) {
+                    if !members.contains(&member) {
+                        members.push(member);
+                    }
+                }
+            }
+            AppliedMacro {
+                name: name.clone(),
+                module_path: module_path.clone(),
+                members,
+            }
+        })
+        .collect();
+
+    Ok(Some(Explanation {
+        target: site.target,
+        line: line_of(source, start as usize),
+        macros,
+    }))
+}
+
+/// Regions sorted by position and clamped to valid char boundaries of `code`
+fn clamped_regions(code: &str, regions: &[GeneratedRegion]) -> Vec<GeneratedRegion> {
+    let clamp = |pos: u32| {
+        let mut pos = (pos as usize).min(code.len());
+        while !code.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        pos as u32
+    };
+    let mut out: Vec<GeneratedRegion> = regions
+        .iter()
+        .map(|r| GeneratedRegion::new(clamp(r.start), clamp(r.end), r.source_macro.clone()))
+        .filter(|r| !r.is_empty())
+        .collect();
+    out.sort_by_key(|r| r.start);
+    out
+}
+
+/// Copy of `code` without the regions selected by `remove`
+fn remove_regions(
+    code: &str,
+    regions: &[GeneratedRegion],
+    remove: impl Fn(&GeneratedRegion) -> bool,
+) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut pos = 0usize;
+    for region in regions.iter().filter(|r| remove(r)) {
+        let (start, end) = (region.start as usize, region.end as usize);
+        if start < pos {
+            continue;
+        }
+        out.push_str(&code[pos..start]);
+        pos = end;
+    }
+    out.push_str(&code[pos..]);
+    out
+}
+
+/// Original (0-based) offset at which the generated code at `expanded_pos` was inserted
+fn insertion_point(mapping: &SourceMapping, expanded_pos: u32) -> u32 {
+    mapping
+        .segments
+        .iter()
+        .filter(|seg| seg.expanded_end <= expanded_pos)
+        .max_by_key(|seg| seg.expanded_end)
+        .map(|seg| seg.original_end)
+        .unwrap_or(0)
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+        + 1
+}
+
+/// Names of the declarations in a generated chunk of code.
+///
+/// `in_body` chunks were inserted into a class body and are parsed as members.
+fn generated_members(code: &str, in_body: bool) -> Vec<String> {
+    let mut members = Vec::new();
+
+    if in_body {
+        let wrapped = format!("class __Generated {{\n{code}\n}}");
+        let Ok(module) = parse_ts_module(&wrapped) else {
+            return members;
+        };
+        for class in lower_classes(&module, &wrapped).unwrap_or_default() {
+            members.extend(class.fields.iter().map(|f| f.name.clone()));
+            members.extend(class.methods.iter().map(|m| {
+                let static_kw = if m.is_static { "static " } else { "" };
+                format!("{static_kw}{}()", m.name)
+            }));
+        }
+        return members;
+    }
+
+    let Ok(module) = parse_ts_module(code) else {
+        return members;
+    };
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::TsModule(ns) => namespace_members(ns, &mut members),
+            other => members.extend(decl_name(other)),
+        }
+    }
+    members
+}
+
+fn namespace_members(ns: &TsModuleDecl, out: &mut Vec<String>) {
+    let Some(ns_name) = ns.id.as_ident().map(|i| i.sym.to_string()) else {
+        return;
+    };
+    let Some(TsNamespaceBody::TsModuleBlock(block)) = &ns.body else {
+        return;
+    };
+    for item in &block.body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) if ns.declare => decl,
+            _ => continue,
+        };
+        out.extend(decl_name(decl).into_iter().map(|name| format!("{ns_name}.{name}")));
+    }
+}
+
+fn decl_name(decl: &Decl) -> Vec<String> {
+    match decl {
+        Decl::Fn(f) => vec![format!("{}()", f.ident.sym)],
+        Decl::Class(c) => vec![format!("class {}", c.ident.sym)],
+        Decl::Var(var) => var
+            .decls
+            .iter()
+            .filter_map(|d| d.name.as_ident().map(|i| i.id.sym.to_string()))
+            .collect(),
+        Decl::TsInterface(i) => vec![format!("interface {}", i.id.sym)],
+        Decl::TsTypeAlias(t) => vec![format!("type {}", t.id.sym)],
+        Decl::TsEnum(e) => vec![format!("enum {}", e.id.sym)],
+        _ => vec![],
+    }
+}
+
+/// Line edit produced by [`diff_lines`]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Unified diff hunks (without file headers); empty when the inputs match
+fn unified_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = diff_lines(&old, &new);
+
+    let changed: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| !matches!(e, Edit::Equal(..)))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    let mut out = String::new();
+    let mut idx = 0;
+    while idx < changed.len() {
+        // Extend the hunk while the next change is within two context windows
+        let mut last = idx;
+        while last + 1 < changed.len()
+            && changed[last + 1] - changed[last] <= 2 * CONTEXT_LINES + 1
+        {
+            last += 1;
+        }
+        let from = changed[idx].saturating_sub(CONTEXT_LINES);
+        let to = (changed[last] + CONTEXT_LINES + 1).min(edits.len());
+        let hunk = &edits[from..to];
+
+        // Hunk starts are 1-based line numbers (as in `diff -u`)
+        let (mut old_start, mut new_start) = (None, None);
+        let (mut old_len, mut new_len) = (0, 0);
+        let mut body = String::new();
+        for edit in hunk {
+            match *edit {
+                Edit::Equal(i, j) => {
+                    old_start.get_or_insert(i);
+                    new_start.get_or_insert(j);
+                    old_len += 1;
+                    new_len += 1;
+                    let _ = writeln!(body, " {}", old[i]);
+                }
+                Edit::Delete(i) => {
+                    old_start.get_or_insert(i);
+                    old_len += 1;
+                    let _ = writeln!(body, "-{}", old[i]);
+                }
+                Edit::Insert(j) => {
+                    new_start.get_or_insert(j);
+                    new_len += 1;
+                    let _ = writeln!(body, "+{}", new[j]);
+                }
+            }
+        }
+        let position = |start: Option<usize>, len: usize, fallback: usize| match start {
+            Some(s) => format!("{},{len}", s + 1),
+            None => format!("{fallback},0"),
+        };
+        let (old_before, new_before) = position_before(&edits[..from]);
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            position(old_start, old_len, old_before),
+            position(new_start, new_len, new_before)
+        );
+        out.push_str(&body);
+        idx = last + 1;
+    }
+    out
+}
+
+/// Number of old/new lines consumed by `edits`
+fn position_before(edits: &[Edit]) -> (usize, usize) {
+    edits.iter().fold((0, 0), |(o, n), e| match e {
+        Edit::Equal(..) => (o + 1, n + 1),
+        Edit::Delete(_) => (o + 1, n),
+        Edit::Insert(_) => (o, n + 1),
+    })
+}
+
+/// Longest-common-subsequence line diff with common prefix/suffix trimmed
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits: Vec<Edit> = (0..prefix).map(|i| Edit::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            edits.push(Edit::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(Edit::Delete(prefix + i));
+            i += 1;
+        } else {
+            edits.push(Edit::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    let (old_tail, new_tail) = (prefix + old_mid.len(), prefix + new_mid.len());
+    edits.extend((0..suffix).map(|k| Edit::Equal(old_tail + k, new_tail + k)));
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{MacroConfig, MacroExpander, MacroExpansion};
+
+    const SOURCE: &str = r#"/** @derive(Debug, Clone) */
+class User {
+    name: string;
+}
+
+/** @derive(PartialEq) */
+interface Point {
+    x: number;
+}
+"#;
+
+    fn expand(source: &str) -> MacroExpansion {
+        let expander =
+            MacroExpander::with_config(MacroConfig::default(), std::env::current_dir().unwrap())
+                .unwrap();
+        swc_core::common::GLOBALS
+            .set(&Default::default(), || expander.expand_source(source, "test.ts"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unified_diff_hunk_header() {
+        let diff = unified_diff("a\nb\nc\n", "a\nb\nx\nc\n");
+        assert_eq!(diff, "@@ -1,3 +1,4 @@\n a\n b\n+x\n c\n");
+        assert!(unified_diff("same\n", "same\n").is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_splits_distant_changes() {
+        let old: String = (0..20).map(|i| format!("{i}\n")).collect();
+        let new = old.replace("2\n", "two\n").replace("17\n", "seventeen\n");
+        let diff = unified_diff(&old, &new);
+        assert_eq!(diff.matches("@@ -").count(), 2, "{diff}");
+        assert!(diff.contains("-17\n+seventeen\n"), "{diff}");
+    }
+
+    #[test]
+    fn test_diff_groups_by_macro() {
+        let expansion = expand(SOURCE);
+        let mapping = expansion.source_mapping.as_ref().unwrap();
+        let diff = diff_by_macro(SOURCE, &expansion.code, mapping, "test.ts");
+
+        let debug = diff.find("+++ b/test.ts [Debug]").expect(&diff);
+        let clone = diff.find("+++ b/test.ts [Clone]").expect(&diff);
+        let eq = diff.find("+++ b/test.ts [PartialEq]").expect(&diff);
+        assert!(debug < clone && clone < eq, "{diff}");
+
+        let debug_section = &diff[debug..clone];
+        assert!(debug_section.contains("+    toString()"), "{debug_section}");
+        assert!(!debug_section.contains("clone("), "{debug_section}");
+        assert!(diff.contains(&format!("[{SOURCE_EDITS}]")), "{diff}");
+        assert!(diff.contains("-/** @derive(Debug, Clone) */"), "{diff}");
+    }
+
+    #[test]
+    fn test_explain_class() {
+        let expansion = expand(SOURCE);
+        let mapping = expansion.source_mapping.as_ref().unwrap();
+
+        let explanation = explain_line(SOURCE, &expansion.code, mapping, 3)
+            .unwrap()
+            .expect("line 3 is inside class User");
+        assert_eq!(explanation.target, "class User");
+        assert_eq!(explanation.line, 2);
+        let names: Vec<_> = explanation.macros.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Debug", "Clone"]);
+        assert_eq!(explanation.macros[0].module_path, "@macro/derive");
+        assert!(explanation.macros[0].members.contains(&"toString()".to_string()));
+        assert!(explanation.macros[1].members.contains(&"clone()".to_string()));
+
+        let rendered = explanation.to_string();
+        assert!(rendered.starts_with("class User (line 2)\n  Debug from @macro/derive\n"), "{rendered}");
+    }
+
+    #[test]
+    fn test_explain_interface_and_missing_target() {
+        let expansion = expand(SOURCE);
+        let mapping = expansion.source_mapping.as_ref().unwrap();
+
+        let explanation = explain_line(SOURCE, &expansion.code, mapping, 6).unwrap().unwrap();
+        assert_eq!(explanation.target, "interface Point");
+        assert!(
+            explanation.macros[0].members.iter().any(|m| m.starts_with("Point.")),
+            "{explanation}"
+        );
+
+        assert!(explain_line(SOURCE, &expansion.code, mapping, 5).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_explain_resolves_imported_module_path() {
+        let source = r#"import { Validate } from "@acme/macros";
+
+/** @derive(Debug, Validate) */
+class Form {
+    email: string;
+}
+"#;
+        // Module paths come from the source alone, so no expansion is needed
+        let explanation = explain_line(source, source, &SourceMapping::new(), 3)
+            .unwrap()
+            .unwrap();
+        assert_eq!(explanation.macros[1].name, "Validate");
+        assert_eq!(explanation.macros[1].module_path, "@acme/macros");
+    }
+}
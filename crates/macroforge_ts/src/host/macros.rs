@@ -9,3 +9,58 @@ macro_rules! register_macro_package {
         }
     };
 }
+
+/// Export the `#[ts_macro_derive]` macros of a `cdylib` crate through the
+/// native C ABI so the host can load it at runtime (see [`crate::host::native`]).
+///
+/// ```ignore
+/// macroforge_ts::export_native_macros!("@acme/macros");
+/// ```
+///
+/// The module path defaults to the crate name when omitted.
+#[macro_export]
+macro_rules! export_native_macros {
+    () => {
+        $crate::export_native_macros!(env!("CARGO_PKG_NAME"));
+    };
+    ($module:expr) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn macroforge_abi_version() -> u32 {
+            $crate::host::native::NATIVE_ABI_VERSION
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn macroforge_manifest() -> *mut ::std::ffi::c_char {
+            let manifest = $crate::host::native::guest::manifest(
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                $module,
+            );
+            $crate::host::native::guest::into_c_string(
+                $crate::serde_json::to_string(&manifest).unwrap_or_default(),
+            )
+        }
+
+        /// # Safety
+        /// `context_json` must be a valid NUL-terminated string.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn macroforge_run(
+            context_json: *const ::std::ffi::c_char,
+        ) -> *mut ::std::ffi::c_char {
+            // SAFETY: forwarded from the caller.
+            let context_json = unsafe { $crate::host::native::guest::from_c_str(context_json) };
+            $crate::host::native::guest::into_c_string($crate::host::native::guest::run(
+                env!("CARGO_PKG_NAME"),
+                context_json,
+            ))
+        }
+
+        /// # Safety
+        /// `ptr` must come from `macroforge_manifest` or `macroforge_run`.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn macroforge_free_string(ptr: *mut ::std::ffi::c_char) {
+            // SAFETY: forwarded from the caller.
+            unsafe { $crate::host::native::guest::free_c_string(ptr) }
+        }
+    };
+}
@@ -4,23 +4,34 @@
 //! It handles macro registration, dispatch, and execution.
 
 pub mod config;
+mod config_schema;
+pub mod consistency;
 pub mod derived;
 pub mod dispatch;
+pub mod doctor;
 pub mod error;
 pub mod expand;
+pub mod explain;
 pub mod macros;
+pub mod native;
 pub mod package_registry;
 pub mod patch_applicator;
 pub mod registry;
+pub mod report;
 pub mod traits;
 
 pub use config::MacroConfig;
+pub use consistency::{ConsistencyIssue, ConsistencyIssueKind, check_consistency};
 pub use dispatch::MacroDispatcher;
+pub use doctor::{DoctorReport, RegisteredMacro};
 pub use error::{MacroError, Result};
 pub use expand::{MacroExpander, MacroExpansion, collect_import_sources};
-pub use package_registry::MacroPackageRegistration;
+pub use explain::{AppliedMacro, Explanation, diff_by_macro, explain_line};
+pub use native::{NATIVE_ABI_VERSION, NativeLibrary, NativeManifest};
+pub use package_registry::{MacroPackageRegistration, PackageSource, ResolvedPackage};
 pub use patch_applicator::{PatchApplicator, PatchCollector};
-pub use registry::MacroRegistry;
+pub use registry::{MacroRegistry, MacroResolution};
+pub use report::{ExpandReport, ReportDiagnostic, TscReport};
 pub use traits::Macroforge;
 
 // Re-export commonly used types from abi
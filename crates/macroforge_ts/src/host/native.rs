@@ -0,0 +1,442 @@
+//! Native macro packages loaded from dynamic libraries
+//!
+//! A macro crate built as a `cdylib` exposes its `#[ts_macro_derive]` macros
+//! through a small C ABI generated by [`export_native_macros!`](crate::export_native_macros).
+//! Everything crossing the boundary is a NUL-terminated JSON string, so the
+//! host and the library only have to agree on [`NATIVE_ABI_VERSION`] and the
+//! serde shapes of [`MacroContextIR`] and [`MacroResult`]:
+//!
+//! | Symbol | Signature |
+//! |--------|-----------|
+//! | `macroforge_abi_version` | `extern "C" fn() -> u32` |
+//! | `macroforge_manifest` | `extern "C" fn() -> *mut c_char` ([`NativeManifest`] JSON) |
+//! | `macroforge_run` | `extern "C" fn(*const c_char) -> *mut c_char` (context JSON in, result JSON out) |
+//! | `macroforge_free_string` | `extern "C" fn(*mut c_char)` |
+//!
+//! Libraries are only loaded when `allowNativeMacros` is enabled; see
+//! [`is_native_library_path`] for which `macroPackages` entries qualify.
+
+use std::ffi::{CStr, CString, c_char};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ts_syn::TsStream;
+use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel, MacroContextIR, MacroKind, MacroResult};
+
+use super::{MacroError, MacroRegistry, Macroforge, Result};
+
+/// Version of the C ABI described in the module docs.
+///
+/// Bumped whenever a symbol signature or the meaning of the JSON payloads
+/// changes incompatibly.
+pub const NATIVE_ABI_VERSION: u32 = 1;
+
+const SYMBOL_ABI_VERSION: &[u8] = b"macroforge_abi_version\0";
+const SYMBOL_MANIFEST: &[u8] = b"macroforge_manifest\0";
+const SYMBOL_RUN: &[u8] = b"macroforge_run\0";
+const SYMBOL_FREE_STRING: &[u8] = b"macroforge_free_string\0";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *mut c_char;
+type RunFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeStringFn = unsafe extern "C" fn(*mut c_char);
+
+/// Macros exported by a native library
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeManifest {
+    /// Cargo package the library was built from
+    pub package: String,
+    /// Version of that package
+    #[serde(default)]
+    pub version: String,
+    /// Module path the macros are registered under (e.g. `@acme/macros`)
+    pub module: String,
+    pub macros: Vec<NativeMacroInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeMacroInfo {
+    pub name: String,
+    pub kind: MacroKind,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Whether a `macroPackages` entry names a dynamic library rather than a package
+pub fn is_native_library_path(entry: &str) -> bool {
+    Path::new(entry)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "so" | "dylib" | "dll"))
+}
+
+/// A loaded macro library
+///
+/// Kept alive by every [`Macroforge`] it registers, so the code is never
+/// unloaded while a macro can still be dispatched to it.
+pub struct NativeLibrary {
+    path: PathBuf,
+    manifest: NativeManifest,
+    run: RunFn,
+    free_string: FreeStringFn,
+    // Declared last so the function pointers above never outlive the mapping
+    _library: libloading::Library,
+}
+
+impl std::fmt::Debug for NativeLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeLibrary")
+            .field("path", &self.path)
+            .field("manifest", &self.manifest)
+            .finish()
+    }
+}
+
+impl NativeLibrary {
+    /// Load the library at `path` and verify its ABI version.
+    ///
+    /// Loading runs the library's initializers, which is arbitrary native
+    /// code; callers must only pass paths the user opted into.
+    pub fn load(path: &Path) -> Result<Arc<Self>> {
+        let load_error = |err: libloading::Error| {
+            MacroError::InvalidConfig(format!(
+                "failed to load native macro library {}: {err}",
+                path.display()
+            ))
+        };
+
+        // SAFETY: the user enabled `allowNativeMacros` and listed this library.
+        let library = unsafe { libloading::Library::new(path) }.map_err(load_error)?;
+
+        // SAFETY: the symbol types match the ABI in the module docs. The
+        // version is checked before any other symbol is called.
+        unsafe {
+            let abi_version = library.get::<AbiVersionFn>(SYMBOL_ABI_VERSION).map_err(load_error)?;
+            let actual = abi_version();
+            if actual != NATIVE_ABI_VERSION {
+                return Err(MacroError::AbiVersionMismatch {
+                    expected: NATIVE_ABI_VERSION,
+                    actual,
+                });
+            }
+
+            let manifest_fn = *library.get::<ManifestFn>(SYMBOL_MANIFEST).map_err(load_error)?;
+            let run = *library.get::<RunFn>(SYMBOL_RUN).map_err(load_error)?;
+            let free_string = *library.get::<FreeStringFn>(SYMBOL_FREE_STRING).map_err(load_error)?;
+
+            let manifest_json = take_c_string(manifest_fn(), free_string).ok_or_else(|| {
+                MacroError::InvalidConfig(format!(
+                    "native macro library {} returned no manifest",
+                    path.display()
+                ))
+            })?;
+            let manifest: NativeManifest = serde_json::from_str(&manifest_json)?;
+
+            Ok(Arc::new(Self {
+                path: path.to_path_buf(),
+                manifest,
+                run,
+                free_string,
+                _library: library,
+            }))
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn manifest(&self) -> &NativeManifest {
+        &self.manifest
+    }
+
+    /// Register every macro in the manifest under the manifest's module.
+    ///
+    /// Returns the number of macros registered.
+    pub fn register(self: &Arc<Self>, registry: &MacroRegistry) -> Result<usize> {
+        for info in &self.manifest.macros {
+            registry.register(
+                self.manifest.module.clone(),
+                info.name.clone(),
+                Arc::new(NativeMacro {
+                    library: Arc::clone(self),
+                    info: info.clone(),
+                }),
+            )?;
+        }
+        Ok(self.manifest.macros.len())
+    }
+
+    fn run(&self, ctx: &MacroContextIR) -> Result<MacroResult> {
+        let input = CString::new(serde_json::to_string(ctx)?)
+            .map_err(|err| MacroError::ExecutionFailed(err.to_string()))?;
+        // SAFETY: `run` takes a NUL-terminated string it does not retain and
+        // returns a string owned by the library, released via `free_string`.
+        let output = unsafe { take_c_string((self.run)(input.as_ptr()), self.free_string) }
+            .ok_or_else(|| {
+                MacroError::ExecutionFailed(format!(
+                    "native macro library {} returned no result",
+                    self.path.display()
+                ))
+            })?;
+        Ok(serde_json::from_str(&output)?)
+    }
+}
+
+/// Copy a library-owned string and hand it back to the library.
+///
+/// # Safety
+/// `ptr` must be null or a string allocated by the library that `free` releases.
+unsafe fn take_c_string(ptr: *mut c_char, free: FreeStringFn) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe {
+        let value = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+        free(ptr);
+        Some(value)
+    }
+}
+
+/// A macro implemented in a [`NativeLibrary`]
+struct NativeMacro {
+    library: Arc<NativeLibrary>,
+    info: NativeMacroInfo,
+}
+
+impl Macroforge for NativeMacro {
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    fn kind(&self) -> MacroKind {
+        self.info.kind
+    }
+
+    fn description(&self) -> &str {
+        &self.info.description
+    }
+
+    fn package(&self) -> Option<&str> {
+        Some(&self.library.manifest.package)
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(self.library.manifest.version.as_str()).filter(|v| !v.is_empty())
+    }
+
+    fn run(&self, input: TsStream) -> MacroResult {
+        let Some(ctx) = input.context() else {
+            return error_result(
+                None,
+                format!("Native macro '{}' was invoked without a macro context", self.info.name),
+            );
+        };
+        self.library.run(ctx).unwrap_or_else(|err| {
+            error_result(
+                Some(ctx),
+                format!(
+                    "Native macro '{}' from {} failed: {err}",
+                    self.info.name,
+                    self.library.path.display()
+                ),
+            )
+        })
+    }
+}
+
+fn error_result(ctx: Option<&MacroContextIR>, message: String) -> MacroResult {
+    MacroResult {
+        diagnostics: vec![
+            Diagnostic::new(DiagnosticLevel::Error, message)
+                .with_span(ctx.map(|ctx| ctx.decorator_span))
+                .with_code("native-macro-failed"),
+        ],
+        ..Default::default()
+    }
+}
+
+/// Library-side implementation of the C ABI, used by
+/// [`export_native_macros!`](crate::export_native_macros)
+#[doc(hidden)]
+pub mod guest {
+    use super::*;
+    use crate::host::derived::DerivedMacroRegistration;
+
+    /// Manifest of the `#[ts_macro_derive]` macros defined in `package`
+    pub fn manifest(package: &str, version: &str, module: &str) -> NativeManifest {
+        let macros = inventory::iter::<DerivedMacroRegistration>
+            .into_iter()
+            .map(|entry| entry.descriptor)
+            .filter(|descriptor| descriptor.package == package)
+            .map(|descriptor| NativeMacroInfo {
+                name: descriptor.name.to_string(),
+                kind: descriptor.kind,
+                description: descriptor.description.to_string(),
+            })
+            .collect();
+        NativeManifest {
+            package: package.to_string(),
+            version: version.to_string(),
+            module: module.to_string(),
+            macros,
+        }
+    }
+
+    /// Run the macro named by the context JSON and return the result JSON.
+    ///
+    /// Never panics: failures and macro panics are reported as diagnostics.
+    pub fn run(package: &str, context_json: &str) -> String {
+        let result = match serde_json::from_str::<MacroContextIR>(context_json) {
+            Ok(ctx) => run_context(package, &ctx),
+            Err(err) => error_result(None, format!("Invalid macro context JSON: {err}")),
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn run_context(package: &str, ctx: &MacroContextIR) -> MacroResult {
+        let Some(descriptor) = inventory::iter::<DerivedMacroRegistration>
+            .into_iter()
+            .map(|entry| entry.descriptor)
+            .find(|d| d.package == package && d.name == ctx.macro_name)
+        else {
+            return error_result(
+                Some(ctx),
+                format!("Macro '{}' is not exported by {package}", ctx.macro_name),
+            );
+        };
+
+        let input = match TsStream::with_context(&ctx.target_source, &ctx.file_name, ctx.clone()) {
+            Ok(input) => input,
+            Err(err) => {
+                return error_result(Some(ctx), format!("Failed to create TsStream: {err:?}"));
+            }
+        };
+
+        let macro_impl = (descriptor.constructor)();
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| macro_impl.run(input)))
+            .unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                error_result(Some(ctx), format!("Macro execution panicked: {message}"))
+            })
+    }
+
+    /// Transfer ownership of `value` to the caller of the C ABI
+    pub fn into_c_string(value: String) -> *mut c_char {
+        // Interior NULs cannot occur in JSON output
+        CString::new(value).map_or(std::ptr::null_mut(), CString::into_raw)
+    }
+
+    /// Borrow a C string passed in by the host
+    ///
+    /// # Safety
+    /// `ptr` must be null or a valid NUL-terminated string.
+    pub unsafe fn from_c_str<'a>(ptr: *const c_char) -> &'a str {
+        if ptr.is_null() {
+            return "";
+        }
+        // SAFETY: guaranteed by the caller.
+        unsafe { CStr::from_ptr(ptr) }.to_str().unwrap_or("")
+    }
+
+    /// Release a string produced by [`into_c_string`]
+    ///
+    /// # Safety
+    /// `ptr` must be null or a pointer returned by [`into_c_string`].
+    pub unsafe fn free_c_string(ptr: *mut c_char) {
+        if !ptr.is_null() {
+            // SAFETY: guaranteed by the caller.
+            drop(unsafe { CString::from_raw(ptr) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    #[test]
+    fn test_native_library_paths() {
+        assert!(is_native_library_path("./target/release/libacme_macros.so"));
+        assert!(is_native_library_path("macros/acme.dylib"));
+        assert!(is_native_library_path("C:\\macros\\acme.dll"));
+        assert!(!is_native_library_path("@acme/macros"));
+        assert!(!is_native_library_path("community-macros"));
+    }
+
+    #[test]
+    fn test_load_missing_library_fails() {
+        let err = NativeLibrary::load(Path::new("/nonexistent/libmissing.so")).unwrap_err();
+        assert!(err.to_string().contains("libmissing.so"), "{err}");
+    }
+
+    #[test]
+    fn test_guest_manifest_lists_package_macros() {
+        let manifest = guest::manifest("macroforge_ts", "0.1.0", "@macro/derive");
+        assert_eq!(manifest.module, "@macro/derive");
+        assert!(manifest.macros.iter().any(|m| m.name == "Debug"));
+        assert!(guest::manifest("other-crate", "0.1.0", "x").macros.is_empty());
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        let back: NativeManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, manifest);
+    }
+
+    fn debug_context() -> MacroContextIR {
+        let source = "class User { name: string; }";
+        let module = crate::ts_syn::parse_ts_module(source).unwrap();
+        let class = crate::ts_syn::lower_classes(&module, source).unwrap().remove(0);
+        MacroContextIR::new_derive_class(
+            "Debug".into(),
+            "@macro/derive".into(),
+            SpanIR::new(0, 0),
+            class.span,
+            "test.ts".into(),
+            class,
+            source.into(),
+        )
+    }
+
+    #[test]
+    fn test_guest_run_round_trips_json() {
+        let ctx = debug_context();
+        let output = guest::run("macroforge_ts", &serde_json::to_string(&ctx).unwrap());
+        let result: MacroResult = serde_json::from_str(&output).unwrap();
+        assert!(result.diagnostics.is_empty(), "{result:?}");
+        assert!(result.tokens.unwrap().contains("toString(): string"));
+    }
+
+    #[test]
+    fn test_guest_run_reports_unknown_macro() {
+        let mut ctx = debug_context();
+        ctx.macro_name = "Nope".into();
+        let output = guest::run("macroforge_ts", &serde_json::to_string(&ctx).unwrap());
+        let result: MacroResult = serde_json::from_str(&output).unwrap();
+        assert_eq!(result.diagnostics[0].code.as_deref(), Some("native-macro-failed"));
+        assert!(result.diagnostics[0].message.contains("not exported"));
+
+        let output = guest::run("macroforge_ts", "not json");
+        let result: MacroResult = serde_json::from_str(&output).unwrap();
+        assert!(result.diagnostics[0].message.contains("Invalid macro context JSON"));
+    }
+
+    #[test]
+    fn test_c_string_round_trip() {
+        let ptr = guest::into_c_string("{\"a\":1}".to_string());
+        // SAFETY: `ptr` comes from `into_c_string` and is freed exactly once.
+        unsafe {
+            assert_eq!(guest::from_c_str(ptr), "{\"a\":1}");
+            guest::free_c_string(ptr);
+        }
+    }
+}
@@ -1,6 +1,10 @@
-//! Global registry for macro package registrars.
+//! Global registry for macro package registrars, and resolution of
+//! `macroPackages` entries.
+
+use std::path::{Path, PathBuf};
 
 use super::{MacroRegistry, Result};
+use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel};
 
 pub struct MacroPackageRegistration {
     pub module: &'static str,
@@ -14,3 +18,162 @@ pub fn registrars() -> Vec<&'static MacroPackageRegistration> {
         .into_iter()
         .collect()
 }
+
+/// How a `macroPackages` entry was resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// Registered with [`register_macro_package!`](crate::register_macro_package)
+    Embedded,
+    /// `#[ts_macro_derive]` macros compiled into this binary
+    Derived,
+    /// Native library loaded from `path`
+    Native { path: PathBuf, macros: usize },
+    /// Node.js package; its macros are loaded when a file imports them
+    Node { path: PathBuf },
+    /// Nothing provides this package
+    Unresolved,
+}
+
+/// A `macroPackages` entry and where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPackage {
+    pub name: String,
+    pub source: PackageSource,
+}
+
+impl ResolvedPackage {
+    pub fn is_resolved(&self) -> bool {
+        self.source != PackageSource::Unresolved
+    }
+
+    /// Configuration warning for an unresolved entry
+    pub fn to_diagnostic(&self) -> Option<Diagnostic> {
+        if self.is_resolved() {
+            return None;
+        }
+        Some(
+            Diagnostic::new(
+                DiagnosticLevel::Warning,
+                format!("macro package '{}' could not be resolved", self.name),
+            )
+            .with_note(
+                "it is not compiled into macroforge and was not found in node_modules or the workspace",
+            )
+            .with_help(format!(
+                "install '{}' or remove it from macroPackages in macroforge.json",
+                self.name
+            ))
+            .with_code("unresolved-macro-package"),
+        )
+    }
+}
+
+/// Find a Node.js package the way the external macro loader does: a relative
+/// path, `node_modules` in `root` or any parent, or a workspace package.
+pub fn resolve_node_package(root: &Path, name: &str) -> Option<PathBuf> {
+    if name.starts_with('.') || Path::new(name).is_absolute() {
+        let path = root.join(name);
+        return path.exists().then_some(path);
+    }
+
+    for dir in root.ancestors() {
+        let candidate = dir.join("node_modules").join(name);
+        if candidate.join("package.json").is_file() {
+            return Some(candidate);
+        }
+    }
+
+    workspace_package_dirs(root)
+        .into_iter()
+        .find(|dir| package_name(dir).as_deref() == Some(name))
+}
+
+fn workspace_package_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut patterns = vec!["packages/*".to_string(), "playground/macro".to_string()];
+    if let Ok(contents) = std::fs::read_to_string(root.join("package.json"))
+        && let Ok(pkg) = serde_json::from_str::<serde_json::Value>(&contents)
+    {
+        let workspaces = pkg
+            .get("workspaces")
+            .map(|ws| ws.get("packages").unwrap_or(ws));
+        if let Some(serde_json::Value::Array(entries)) = workspaces {
+            patterns.extend(entries.iter().filter_map(|e| e.as_str()).map(str::to_string));
+        }
+    }
+
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        match pattern.split_once('*') {
+            None => dirs.push(root.join(pattern)),
+            Some((base, suffix)) => {
+                let Ok(entries) = std::fs::read_dir(root.join(base)) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    if entry.path().is_dir() {
+                        let dir = entry.path();
+                        let suffix = suffix.trim_start_matches('/');
+                        dirs.push(if suffix.is_empty() { dir } else { dir.join(suffix) });
+                    }
+                }
+            }
+        }
+    }
+    dirs
+}
+
+fn package_name(dir: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let pkg: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    pkg.get("name")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_package(dir: &Path, name: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("package.json"), format!(r#"{{"name":"{name}"}}"#)).unwrap();
+    }
+
+    #[test]
+    fn test_resolves_node_modules_in_parent() {
+        let root = tempfile::tempdir().unwrap();
+        write_package(&root.path().join("node_modules/@acme/macros"), "@acme/macros");
+        let nested = root.path().join("apps/web");
+        fs::create_dir_all(&nested).unwrap();
+
+        let found = resolve_node_package(&nested, "@acme/macros").unwrap();
+        assert!(found.ends_with("node_modules/@acme/macros"));
+    }
+
+    #[test]
+    fn test_resolves_workspace_package_by_name() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join("package.json"),
+            r#"{"workspaces":{"packages":["libs/*"]}}"#,
+        )
+        .unwrap();
+        write_package(&root.path().join("libs/validators"), "@acme/validators");
+
+        let found = resolve_node_package(root.path(), "@acme/validators").unwrap();
+        assert!(found.ends_with("libs/validators"));
+    }
+
+    #[test]
+    fn test_unresolved_package_diagnostic() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(resolve_node_package(root.path(), "missing-macros"), None);
+
+        let package = ResolvedPackage {
+            name: "missing-macros".to_string(),
+            source: PackageSource::Unresolved,
+        };
+        let diag = package.to_diagnostic().unwrap();
+        assert_eq!(diag.code.as_deref(), Some("unresolved-macro-package"));
+        assert!(diag.message.contains("missing-macros"));
+    }
+}
@@ -254,56 +254,7 @@ impl<'a> PatchApplicator<'a> {
 
     /// Detect indentation level at a given position by looking backwards
     fn detect_indentation(&self, position: usize) -> String {
-        let bytes = self.source.as_bytes();
-        let mut search_pos = position.saturating_sub(1);
-        let mut found_indent: Option<String> = None;
-        let search_limit = position.saturating_sub(500);
-
-        while search_pos > search_limit && search_pos < bytes.len() {
-            // Find the start of this line
-            let mut line_start = search_pos;
-            while line_start > 0 && bytes[line_start - 1] != b'\n' {
-                line_start -= 1;
-            }
-
-            // Find the end of this line
-            let mut line_end = search_pos;
-            while line_end < bytes.len() && bytes[line_end] != b'\n' {
-                line_end += 1;
-            }
-
-            if line_start >= line_end {
-                if line_start == 0 {
-                    break;
-                }
-                search_pos = line_start - 1;
-                continue;
-            }
-
-            let line = &self.source[line_start..line_end];
-            let trimmed = line.trim();
-
-            if !trimmed.is_empty()
-                && !trimmed.starts_with('}')
-                && !trimmed.starts_with('@')
-                && (trimmed.contains(':')
-                    || trimmed.contains('(')
-                    || trimmed.starts_with("constructor"))
-            {
-                let indent_count = line.chars().take_while(|c| c.is_whitespace()).count();
-                if indent_count > 0 {
-                    found_indent = Some(line.chars().take(indent_count).collect());
-                    break;
-                }
-            }
-
-            if line_start == 0 {
-                break;
-            }
-            search_pos = line_start - 1;
-        }
-
-        found_indent.unwrap_or_else(|| "  ".to_string())
+        detect_indentation(self.source, position)
     }
 
     fn sort_patches(&mut self) {
@@ -444,6 +395,64 @@ impl PatchCollector {
     pub fn get_type_patches(&self) -> &Vec<Patch> {
         &self.type_patches
     }
+
+    /// `import type` statements for the names macros imported that
+    /// `declarations` actually reference. Value imports such as helper
+    /// functions stay out of the type output, as do names it already imports.
+    pub fn type_import_code(&self, declarations: &str) -> String {
+        let mut imports: Vec<String> = Vec::new();
+        for patch in &self.runtime_patches {
+            let Patch::InsertRaw { code, context: Some(context), .. } = patch else {
+                continue;
+            };
+            if context != "import" {
+                continue;
+            }
+            let Some((names, module)) = parse_named_import(code) else {
+                continue;
+            };
+            for name in names {
+                let import = format!("import type {{ {name} }} from \"{module}\";\n");
+                if !imports.contains(&import) && references_type(declarations, name) {
+                    imports.push(import);
+                }
+            }
+        }
+        imports.concat()
+    }
+}
+
+/// Names and module of `import { A, B } from "m";` (with or without `type`)
+fn parse_named_import(code: &str) -> Option<(Vec<&str>, &str)> {
+    let rest = code.trim().strip_prefix("import")?.trim_start();
+    let rest = rest.strip_prefix("type ").unwrap_or(rest).trim_start();
+    let (names, rest) = rest.strip_prefix('{')?.split_once('}')?;
+    let module = rest.trim_start().strip_prefix("from")?.trim().trim_end_matches(';');
+    let module = module.trim_matches(|c| c == '"' || c == '\'');
+    let names = names.split(',').map(str::trim).filter(|n| !n.is_empty()).collect();
+    Some((names, module))
+}
+
+/// Whether `declarations` use `name` as a type and do not import it already.
+/// Value uses (`SerializeContext.create()`) do not count.
+fn references_type(declarations: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut as_type = false;
+    for line in declarations.lines() {
+        let imports = line.trim_start().starts_with("import ");
+        for (i, _) in line.match_indices(name) {
+            let after = &line[i + name.len()..];
+            if line[..i].ends_with(is_ident) || after.starts_with(is_ident) {
+                continue;
+            }
+            if imports {
+                return false;
+            }
+            let value_use = after.trim_start().starts_with(['(', '.']) || line[..i].ends_with("new ");
+            as_type |= !value_use;
+        }
+    }
+    as_type
 }
 
 impl Default for PatchCollector {
@@ -486,6 +495,78 @@ fn dedupe_patches(patches: &mut Vec<Patch>) -> Result<()> {
     Ok(())
 }
 
+/// Detect indentation level at a given position by looking backwards
+fn detect_indentation(source: &str, position: usize) -> String {
+    let bytes = source.as_bytes();
+    let mut search_pos = position.saturating_sub(1);
+    let mut found_indent: Option<String> = None;
+    let search_limit = position.saturating_sub(500);
+
+    while search_pos > search_limit && search_pos < bytes.len() {
+        // Find the start of this line
+        let mut line_start = search_pos;
+        while line_start > 0 && bytes[line_start - 1] != b'\n' {
+            line_start -= 1;
+        }
+
+        // Find the end of this line
+        let mut line_end = search_pos;
+        while line_end < bytes.len() && bytes[line_end] != b'\n' {
+            line_end += 1;
+        }
+
+        if line_start >= line_end {
+            if line_start == 0 {
+                break;
+            }
+            search_pos = line_start - 1;
+            continue;
+        }
+
+        let line = &source[line_start..line_end];
+        let trimmed = line.trim();
+
+        if !trimmed.is_empty()
+            && !trimmed.starts_with('}')
+            && !trimmed.starts_with('@')
+            && (trimmed.contains(':')
+                || trimmed.contains('(')
+                || trimmed.starts_with("constructor"))
+        {
+            let indent_count = line.chars().take_while(|c| c.is_whitespace()).count();
+            if indent_count > 0 {
+                found_indent = Some(line.chars().take(indent_count).collect());
+                break;
+            }
+        }
+
+        if line_start == 0 {
+            break;
+        }
+        search_pos = line_start - 1;
+    }
+
+    found_indent.unwrap_or_else(|| "  ".to_string())
+}
+
+/// Code for a class member marked `/** @internal */`, inserted at `position`
+/// (1-based) with the same layout as a [`PatchCode::ClassMember`] insertion.
+///
+/// Members are re-emitted from their AST, which drops comments, so the marker
+/// is written here for the consistency check to see.
+pub(crate) fn internal_member_code(
+    source: &str,
+    position: u32,
+    member: &swc_core::ecma::ast::ClassMember,
+) -> Result<PatchCode> {
+    let rendered = emit_node(member)?;
+    let indent = detect_indentation(source, position.saturating_sub(1) as usize);
+    Ok(PatchCode::Text(format!(
+        "\n{indent}/** @internal */\n{indent}{}\n",
+        rendered.trim()
+    )))
+}
+
 fn render_patch_code(code: &PatchCode) -> Result<String> {
     match code {
         PatchCode::Text(s) => Ok(s.clone()),
@@ -995,4 +1076,26 @@ mod tests {
         assert_eq!(result.mapping.generated_regions.len(), 1);
         assert_eq!(result.mapping.generated_regions[0].source_macro, "Debug");
     }
+
+    #[test]
+    fn test_type_import_code_keeps_only_used_types() {
+        let import = |code: &str| Patch::InsertRaw {
+            at: SpanIR { start: 1, end: 1 },
+            code: code.to_string(),
+            context: Some("import".to_string()),
+            source_macro: None,
+        };
+        let mut collector = PatchCollector::new();
+        collector.add_runtime_patches(vec![
+            import("import { SerializeContext } from \"macroforge/serde\";\n"),
+            import("import { cloneValue } from \"macroforge/clone\";\n"),
+            import("import { Result } from \"macroforge/utils\";\n"),
+        ]);
+
+        let declarations = "import { Result } from \"./result\";\nclass User {\n    __serialize(ctx: SerializeContext): Result;\n    toJSON() { return cloneValue(SerializeContext.create()); }\n}\n";
+        assert_eq!(
+            collector.type_import_code(declarations),
+            "import type { SerializeContext } from \"macroforge/serde\";\n"
+        );
+    }
 }
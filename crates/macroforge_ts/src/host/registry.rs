@@ -1,6 +1,6 @@
 //! Macro registry for managing and looking up macros
 
-use super::{MacroError, Macroforge, error::Result};
+use super::{MacroError, Macroforge, derived::DYNAMIC_MODULE_MARKER, error::Result};
 use dashmap::DashMap;
 use std::sync::Arc;
 
@@ -22,7 +22,18 @@ impl MacroKey {
     }
 }
 
+/// Outcome of [`MacroRegistry::resolve`]
+pub struct MacroResolution {
+    pub macro_impl: Arc<dyn Macroforge>,
+    /// Module the macro was found in
+    pub module: String,
+    /// Other modules exporting the same name when the lookup fell back to
+    /// name-only resolution (empty for exact matches)
+    pub ambiguous_with: Vec<String>,
+}
+
 /// Registry for all available macros
+#[derive(Clone)]
 pub struct MacroRegistry {
     /// Map from (module, name) to macro implementation
     macros: DashMap<MacroKey, Arc<dyn Macroforge>>,
@@ -86,29 +97,90 @@ impl MacroRegistry {
 
     /// Look up a macro by name only, ignoring module path
     /// This is used for fallback resolution when exact module match fails
-    /// Returns the first matching macro found
+    /// Returns the first matching macro in [`MacroRegistry::modules_exporting`] order
     pub fn lookup_by_name(&self, name: &str) -> Result<Arc<dyn Macroforge>> {
-        for entry in self.macros.iter() {
-            if entry.key().name == name {
-                return Ok(Arc::clone(&entry));
-            }
-        }
+        let mut keys: Vec<MacroKey> = self
+            .macros
+            .iter()
+            .filter(|entry| entry.key().name == name)
+            .map(|entry| entry.key().clone())
+            .collect();
+        keys.sort_by(|a, b| {
+            (a.module == DYNAMIC_MODULE_MARKER, &a.module)
+                .cmp(&(b.module == DYNAMIC_MODULE_MARKER, &b.module))
+        });
 
-        Err(MacroError::MacroNotFound {
-            module: "<any>".to_string(),
-            name: name.to_string(),
-        })
+        keys.first()
+            .and_then(|key| self.macros.get(key))
+            .map(|entry| Arc::clone(&entry))
+            .ok_or_else(|| MacroError::MacroNotFound {
+                module: "<any>".to_string(),
+                name: name.to_string(),
+            })
     }
 
     /// Look up a macro, falling back to name-only lookup if module doesn't match
     pub fn lookup_with_fallback(&self, module: &str, name: &str) -> Result<Arc<dyn Macroforge>> {
-        // First try exact match
-        if let Ok(m) = self.lookup(module, name) {
-            return Ok(m);
+        self.resolve(module, name).map(|resolution| resolution.macro_impl)
+    }
+
+    /// Like [`MacroRegistry::lookup_with_fallback`], but also reports where the
+    /// macro was found and whether the name-only fallback had to choose
+    /// between several modules
+    pub fn resolve(&self, module: &str, name: &str) -> Result<MacroResolution> {
+        if let Ok(macro_impl) = self.lookup(module, name) {
+            return Ok(MacroResolution {
+                macro_impl,
+                module: module.to_string(),
+                ambiguous_with: vec![],
+            });
         }
 
-        // Fall back to name-only lookup
-        self.lookup_by_name(name)
+        let macro_impl = self.lookup_by_name(name)?;
+        let mut modules = self.modules_exporting(name);
+        let resolved = if modules.is_empty() {
+            DYNAMIC_MODULE_MARKER.to_string()
+        } else {
+            modules.remove(0)
+        };
+        Ok(MacroResolution {
+            macro_impl,
+            module: resolved,
+            ambiguous_with: modules,
+        })
+    }
+
+    /// Modules that export a macro called `name`, sorted
+    ///
+    /// Dynamically resolved registrations are mirrored under `@macro/derive`,
+    /// so the dynamic marker itself is not listed.
+    pub fn modules_exporting(&self, name: &str) -> Vec<String> {
+        let mut modules: Vec<String> = self
+            .macros
+            .iter()
+            .filter(|entry| entry.key().name == name && entry.key().module != DYNAMIC_MODULE_MARKER)
+            .map(|entry| entry.key().module.clone())
+            .collect();
+        modules.sort();
+        modules
+    }
+
+    /// Macro names exported by more than one module, with those modules
+    pub fn ambiguous_names(&self) -> Vec<(String, Vec<String>)> {
+        let mut names: Vec<String> = self
+            .macros
+            .iter()
+            .map(|entry| entry.key().name.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let modules = self.modules_exporting(&name);
+                (modules.len() > 1).then_some((name, modules))
+            })
+            .collect()
     }
 
     /// Clear all registered macros
@@ -122,3 +194,70 @@ impl Default for MacroRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::TsStream;
+    use crate::ts_syn::abi::{MacroKind, MacroResult};
+
+    struct Named(&'static str);
+
+    impl Macroforge for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn kind(&self) -> MacroKind {
+            MacroKind::Derive
+        }
+
+        fn run(&self, _input: TsStream) -> MacroResult {
+            MacroResult::default()
+        }
+
+        fn description(&self) -> &str {
+            "test"
+        }
+    }
+
+    fn registry_with(entries: &[(&str, &'static str)]) -> MacroRegistry {
+        let registry = MacroRegistry::new();
+        for (module, name) in entries {
+            registry.register(*module, *name, Arc::new(Named(name))).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn test_exact_match_is_not_ambiguous() {
+        let registry = registry_with(&[("@a/macros", "Validate"), ("@b/macros", "Validate")]);
+        let resolution = registry.resolve("@b/macros", "Validate").unwrap();
+        assert_eq!(resolution.module, "@b/macros");
+        assert!(resolution.ambiguous_with.is_empty());
+    }
+
+    #[test]
+    fn test_name_fallback_reports_other_modules() {
+        let registry = registry_with(&[("@b/macros", "Validate"), ("@a/macros", "Validate")]);
+        let resolution = registry.resolve("./local", "Validate").unwrap();
+        assert_eq!(resolution.module, "@a/macros");
+        assert_eq!(resolution.ambiguous_with, vec!["@b/macros".to_string()]);
+        assert_eq!(
+            registry.ambiguous_names(),
+            vec![(
+                "Validate".to_string(),
+                vec!["@a/macros".to_string(), "@b/macros".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_twin_is_not_ambiguous() {
+        let registry = registry_with(&[(DYNAMIC_MODULE_MARKER, "Debug"), ("@macro/derive", "Debug")]);
+        let resolution = registry.resolve("macroforge", "Debug").unwrap();
+        assert_eq!(resolution.module, "@macro/derive");
+        assert!(resolution.ambiguous_with.is_empty());
+        assert!(registry.ambiguous_names().is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! Machine-readable expansion reports
+//!
+//! The CLI's `--format json` and `--format sarif` modes serialize these types.
+//! Field names are camelCase to match the Node.js bindings, and the schema is
+//! versioned through [`REPORT_VERSION`]; additive changes keep the version,
+//! anything else bumps it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::ts_syn::abi::{Diagnostic, DiagnosticLevel, SourceMapping};
+
+/// Version of the JSON report schema
+pub const REPORT_VERSION: u32 = 1;
+
+/// Code used for diagnostics whose producer did not assign one
+pub const DEFAULT_DIAGNOSTIC_CODE: &str = "macroforge";
+
+/// 1-based line and column (columns count characters, not bytes)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportSpan {
+    pub start: LineColumn,
+    pub end: LineColumn,
+}
+
+/// A diagnostic with its location resolved to line/column
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportDiagnostic {
+    pub file: String,
+    /// `error`, `warning` or `info`
+    pub level: String,
+    pub code: String,
+    pub message: String,
+    pub span: Option<ReportSpan>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl ReportDiagnostic {
+    /// Resolve `diag` against the `source` it was reported for
+    pub fn from_diagnostic(diag: &Diagnostic, source: &str, file: &str) -> Self {
+        Self {
+            file: file.to_string(),
+            level: level_name(&diag.level).to_string(),
+            code: diag
+                .code
+                .clone()
+                .unwrap_or_else(|| DEFAULT_DIAGNOSTIC_CODE.to_string()),
+            message: diag.message.clone(),
+            span: diag.span.map(|span| ReportSpan {
+                start: line_column(source, span.start as usize),
+                end: line_column(source, span.end as usize),
+            }),
+            notes: diag.notes.clone(),
+            help: diag.help.clone(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSegment {
+    pub original_start: u32,
+    pub original_end: u32,
+    pub expanded_start: u32,
+    pub expanded_end: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRegion {
+    pub start: u32,
+    pub end: u32,
+    pub source_macro: String,
+}
+
+/// Source mapping in the same shape as the Node.js `SourceMappingResult`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportMapping {
+    pub segments: Vec<ReportSegment>,
+    pub generated_regions: Vec<ReportRegion>,
+}
+
+impl From<&SourceMapping> for ReportMapping {
+    fn from(mapping: &SourceMapping) -> Self {
+        Self {
+            segments: mapping
+                .segments
+                .iter()
+                .map(|seg| ReportSegment {
+                    original_start: seg.original_start,
+                    original_end: seg.original_end,
+                    expanded_start: seg.expanded_start,
+                    expanded_end: seg.expanded_end,
+                })
+                .collect(),
+            generated_regions: mapping
+                .generated_regions
+                .iter()
+                .map(|region| ReportRegion {
+                    start: region.start,
+                    end: region.end,
+                    source_macro: region.source_macro.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Output of `macroforge expand --format json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpandReport {
+    pub version: u32,
+    pub file: String,
+    pub code: String,
+    pub types: Option<String>,
+    pub source_mapping: Option<ReportMapping>,
+    pub diagnostics: Vec<ReportDiagnostic>,
+}
+
+impl ExpandReport {
+    pub fn new(
+        file: &str,
+        source: &str,
+        code: String,
+        types: Option<String>,
+        mapping: Option<&SourceMapping>,
+        diagnostics: &[Diagnostic],
+    ) -> Self {
+        Self {
+            version: REPORT_VERSION,
+            file: file.to_string(),
+            code,
+            types,
+            source_mapping: mapping.map(ReportMapping::from),
+            diagnostics: diagnostics
+                .iter()
+                .map(|diag| ReportDiagnostic::from_diagnostic(diag, source, file))
+                .collect(),
+        }
+    }
+}
+
+/// Output of `macroforge tsc --format json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TscReport {
+    pub version: u32,
+    pub diagnostics: Vec<ReportDiagnostic>,
+}
+
+impl TscReport {
+    pub fn new(diagnostics: Vec<ReportDiagnostic>) -> Self {
+        Self {
+            version: REPORT_VERSION,
+            diagnostics,
+        }
+    }
+}
+
+/// Render diagnostics as a SARIF 2.1.0 log with a single run
+pub fn to_sarif(diagnostics: &[ReportDiagnostic]) -> Value {
+    let mut rules: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|diag| {
+            let mut text = diag.message.clone();
+            for note in &diag.notes {
+                text.push_str("\nnote: ");
+                text.push_str(note);
+            }
+            if let Some(help) = &diag.help {
+                text.push_str("\nhelp: ");
+                text.push_str(help);
+            }
+
+            let mut location = json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": diag.file.replace('\\', "/") }
+                }
+            });
+            if let Some(span) = diag.span {
+                location["physicalLocation"]["region"] = json!({
+                    "startLine": span.start.line,
+                    "startColumn": span.start.column,
+                    "endLine": span.end.line,
+                    "endColumn": span.end.column,
+                });
+            }
+
+            json!({
+                "ruleId": diag.code,
+                "level": sarif_level(&diag.level),
+                "message": { "text": text },
+                "locations": [location],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "macroforge",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/rymskip/macroforge-ts",
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn level_name(level: &DiagnosticLevel) -> &'static str {
+    match level {
+        DiagnosticLevel::Error => "error",
+        DiagnosticLevel::Warning => "warning",
+        DiagnosticLevel::Info => "info",
+    }
+}
+
+fn sarif_level(level: &str) -> &'static str {
+    match level {
+        "error" => "error",
+        "warning" => "warning",
+        _ => "note",
+    }
+}
+
+fn line_column(source: &str, offset: usize) -> LineColumn {
+    let mut line = 1;
+    let mut column = 1;
+    for (idx, ch) in source.char_indices() {
+        if idx >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    LineColumn { line, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn diagnostic() -> Diagnostic {
+        Diagnostic::new(DiagnosticLevel::Warning, "something odd")
+            .with_span(SpanIR::new(6, 9))
+            .with_note("first note")
+            .with_help("try this")
+            .with_code("odd-thing")
+    }
+
+    #[test]
+    fn test_diagnostic_line_column() {
+        let report = ReportDiagnostic::from_diagnostic(&diagnostic(), "line\nnext line", "a.ts");
+        assert_eq!(report.level, "warning");
+        assert_eq!(report.code, "odd-thing");
+        let span = report.span.unwrap();
+        assert_eq!(span.start, LineColumn { line: 2, column: 2 });
+        assert_eq!(span.end, LineColumn { line: 2, column: 5 });
+    }
+
+    #[test]
+    fn test_missing_code_uses_default() {
+        let mut diag = diagnostic();
+        diag.code = None;
+        let report = ReportDiagnostic::from_diagnostic(&diag, "", "a.ts");
+        assert_eq!(report.code, DEFAULT_DIAGNOSTIC_CODE);
+    }
+
+    #[test]
+    fn test_expand_report_schema() {
+        let mut mapping = SourceMapping::new();
+        mapping.add_generated(crate::ts_syn::abi::GeneratedRegion::new(0, 4, "Debug"));
+        let report = ExpandReport::new(
+            "a.ts",
+            "line\nnext line",
+            "code".to_string(),
+            None,
+            Some(&mapping),
+            &[diagnostic()],
+        );
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["version"], REPORT_VERSION);
+        assert_eq!(value["types"], Value::Null);
+        assert_eq!(value["sourceMapping"]["generatedRegions"][0]["sourceMacro"], "Debug");
+        assert_eq!(value["diagnostics"][0]["span"]["start"]["line"], 2);
+        assert_eq!(value["diagnostics"][0]["notes"][0], "first note");
+
+        let back: ExpandReport = serde_json::from_value(value).unwrap();
+        assert_eq!(back, report);
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let report = ReportDiagnostic::from_diagnostic(&diagnostic(), "line\nnext line", "src\\a.ts");
+        let sarif = to_sarif(&[report]);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "odd-thing");
+        let result = &run["results"][0];
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["message"]["text"], "something odd\nnote: first note\nhelp: try this");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/a.ts");
+        assert_eq!(location["region"]["startLine"], 2);
+    }
+}
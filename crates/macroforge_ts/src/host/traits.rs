@@ -23,6 +23,16 @@ pub trait Macroforge: Send + Sync {
     fn abi_version(&self) -> u32 {
         1
     }
+
+    /// Returns the package that provides this macro, if known
+    fn package(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the version of the package that provides this macro, if known
+    fn version(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Trait for macro packages that can provide multiple macros
@@ -51,6 +51,7 @@ pub use macroforge_ts_syn::swc_ecma_ast;
 // Internal modules
 // ============================================================================
 pub mod host;
+pub mod testing;
 
 // Re-export abi types from ts_syn
 pub use ts_syn::abi;
@@ -64,6 +65,7 @@ mod builtin;
 mod test;
 
 use crate::host::MacroExpander;
+use std::path::Path;
 
 // ============================================================================
 // Data Structures
@@ -85,6 +87,10 @@ pub struct MacroDiagnostic {
     pub message: String,
     pub start: Option<u32>,
     pub end: Option<u32>,
+    /// Stable machine-readable code, when the producer assigned one
+    pub code: Option<String>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
 }
 
 #[napi(object)]
@@ -644,10 +650,11 @@ fn expand_inner(
         return Ok(ExpandResult::unchanged(code));
     }
 
-    // We create a NEW macro host for this thread.
+    // Each call gets its own macro host, cloned from the one cached for the
+    // file's config root.
     // Note: If MacroExpander requires NAPI Env for calling back into JS,
     // that part will fail in a threaded context. Assuming pure-Rust expansion here.
-    let mut macro_host = MacroExpander::new().map_err(|err| {
+    let mut macro_host = MacroExpander::for_file(Path::new(filepath)).map_err(|err| {
         Error::new(
             Status::GenericFailure,
             format!("Failed to initialize macro host: {err:?}"),
@@ -680,6 +687,9 @@ fn expand_inner(
                     message: format!("Macro expansion skipped due to syntax error: {}", error_msg),
                     start: None,
                     end: None,
+                    code: Some("syntax-error".to_string()),
+                    notes: vec![],
+                    help: None,
                 }],
                 source_mapping: None,
             });
@@ -701,6 +711,9 @@ fn expand_inner(
             message: d.message,
             start: d.span.map(|s| s.start),
             end: d.span.map(|s| s.end),
+            code: d.code,
+            notes: d.notes,
+            help: d.help,
         })
         .collect();
 
@@ -752,7 +765,7 @@ fn expand_inner(
 }
 
 fn transform_inner(code: &str, filepath: &str) -> Result<TransformResult> {
-    let macro_host = MacroExpander::new().map_err(|err| {
+    let macro_host = MacroExpander::for_file(Path::new(filepath)).map_err(|err| {
         Error::new(
             Status::GenericFailure,
             format!("Failed to init host: {err:?}"),
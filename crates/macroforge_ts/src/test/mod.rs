@@ -516,6 +516,7 @@ fn test_apply_and_finalize_expansion_no_type_patches() {
     let result = host
         .apply_and_finalize_expansion(
             source,
+            "test.ts",
             &mut collector,
             &mut diagnostics,
             crate::host::expand::LoweredItems {
@@ -902,6 +903,1214 @@ class ValidationExample {
     });
 }
 
+
+#[test]
+fn test_config_override_keeps_decorators_for_matching_files() {
+    let source = r#"
+/** @derive(Debug) */
+class Legacy {
+    name: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let config = crate::host::MacroConfig {
+            overrides: vec![crate::host::config::ConfigOverride {
+                files: vec!["src/legacy/**".to_string()],
+                keep_decorators: Some(true),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let root = std::path::PathBuf::from("/project");
+        let host = MacroExpander::with_config(config, root).unwrap();
+
+        let legacy = host
+            .expand(source, &program, "/project/src/legacy/user.ts")
+            .unwrap();
+        assert!(legacy.code.contains("@derive(Debug)"), "{}", legacy.code);
+
+        let modern = host.expand(source, &program, "/project/src/user.ts").unwrap();
+        assert!(!modern.code.contains("@derive"), "{}", modern.code);
+    });
+}
+
+#[test]
+fn test_macro_options_provide_project_defaults() {
+    let source = r#"
+/** @derive(Serialize, Deserialize, Debug) */
+class Account {
+    user_name: string;
+}
+
+/** @derive(Serialize) */
+/** @serde({ rename_all: "none" }) */
+class Legacy {
+    user_name: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let mut config = crate::host::MacroConfig::default();
+        config.macro_options.insert(
+            "Serialize".to_string(),
+            serde_json::json!({ "renameAll": "camelCase" }),
+        );
+        config.macro_options.insert(
+            "Debug".to_string(),
+            serde_json::json!({ "renameAll": "PascalCase" }),
+        );
+        let host = MacroExpander::with_config(config, std::path::PathBuf::from("/project")).unwrap();
+        let result = host.expand(source, &program, "/project/src/account.ts").unwrap();
+
+        let (account, legacy) = result.code.split_once("class Legacy").unwrap();
+        assert!(account.contains("\"userName\""), "{}", account);
+        // Deserialize reads the keys Serialize writes
+        assert!(!account.contains("\"user_name\""), "{}", account);
+        assert!(account.contains("UserName: "), "{}", account);
+        assert!(legacy.contains("\"user_name\""), "{}", legacy);
+        assert!(!legacy.contains("userName"), "{}", legacy);
+    });
+}
+#[test]
+fn test_debug_structured_output() {
+    let source = r#"
+/** @derive(Debug) */
+/** @debug({ pretty: true, depth: 2, json: true, inspect: true }) */
+class Node {
+    name: string;
+    /** @debug(redact) */
+    secret: string;
+    parent: Node | null;
+}
+
+/** @derive(Debug) */
+class Login {
+    user: string;
+    /** @debug(redact) */
+    password: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = &result.code;
+
+        let (node, login) = code.split_once("class Login").unwrap();
+        assert!(node.contains("formatDebug(this, {"), "{}", node);
+        assert!(node.contains("pretty: true"), "{}", node);
+        assert!(node.contains("depth: 2"), "{}", node);
+        assert!(node.contains("toJSONDebug()"), "{}", node);
+        assert!(node.contains("nodejs.util.inspect.custom"), "{}", node);
+        assert!(!node.contains("this.secret"), "{}", node);
+        assert!(code.contains("macroforge/debug"), "{}", code);
+
+        // Types without container options keep the self-contained toString
+        assert!(login.contains("password: ***"), "{}", login);
+        assert!(!login.contains("this.password"), "{}", login);
+        assert!(!login.contains("formatDebug"), "{}", login);
+    });
+}
+
+#[test]
+fn test_hash_algorithm_generates_hash_into() {
+    let source = r#"
+/** @derive(Hash) */
+/** @hash({ algorithm: "fnv1a" }) */
+class Node {
+    id: number;
+    /** @hash(skip) */
+    cache: string;
+    children: Set<Node>;
+}
+
+/** @derive(Hash) */
+/** @hash({ algorithm: "md5" }) */
+class Unknown {
+    id: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = &result.code;
+
+        assert!(code.contains("hashInto(hasher: Hasher)"), "{}", code);
+        assert!(code.contains("createHasher(\"fnv1a\")"), "{}", code);
+        assert!(code.contains("hashValue(hasher, this.children)"), "{}", code);
+        assert!(!code.contains("this.cache"), "{}", code);
+        assert!(code.contains("macroforge/hash"), "{}", code);
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("unknown algorithm \"md5\"")),
+            "{:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_compare_field_options() {
+    let source = r#"
+/** @derive(Ord, PartialEq) */
+class Task {
+    /** @ord({ caseInsensitive: true }) @partialEq({ caseInsensitive: true }) */
+    name: string;
+    /** @ord({ priority: 1, reverse: true }) */
+    priority: number;
+    /** @ord({ nullsLast: true }) */
+    due: Date | null;
+    /** @ord({ compareWith: "compareVersions" }) @partialEq({ eqWith: "sameVersion" }) */
+    version: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        // Higher priority fields are compared first, reversed
+        let priority = code.find("constcmp0=(-(this.priority<typedOther.priority").expect(&code);
+        let name = code.find("constcmp1=(typeofthis.name==='string'").expect(&code);
+        assert!(priority < name);
+        assert!(code.contains("sensitivity:'accent'"), "{}", code);
+        assert!(
+            code.contains("constcmp2=(this.due==null||typedOther.due==null?(this.due==null?(typedOther.due==null?0:1):-1)"),
+            "{}",
+            code
+        );
+        assert!(code.contains("constcmp3=compareVersions(this.version,typedOther.version)"), "{}", code);
+        assert!(code.contains("sameVersion(this.version,typedOther.version)"), "{}", code);
+    });
+}
+
+#[test]
+fn test_clone_field_strategies() {
+    let source = r#"
+/** @derive(Clone) */
+class Store {
+    version: number;
+    items: Item[];
+    /** @clone(shallow) */
+    logger: Logger;
+    /** @clone(skip) */
+    cache: Map<string, Item>;
+    /** @clone({ with: "copyBuffer" }) */
+    buffer: Uint8Array;
+    parent: Store | null;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("returnthis.__clone(CloneContext.create());"), "{}", code);
+        assert!(code.contains("__clone(ctx:CloneContext):Store{"), "{}", code);
+        assert!(code.contains("ctx.set(this,cloned);"), "{}", code);
+        assert!(code.contains("cloned.version=this.version;"), "{}", code);
+        assert!(code.contains("cloned.items=cloneValue(this.items,ctx);"), "{}", code);
+        assert!(code.contains("cloned.logger=this.logger;"), "{}", code);
+        assert!(!code.contains("cloned.cache"), "{}", code);
+        assert!(code.contains("cloned.buffer=copyBuffer(this.buffer);"), "{}", code);
+        assert!(code.contains("cloned.parent=cloneValue(this.parent,ctx);"), "{}", code);
+        assert!(code.contains("from\"macroforge/clone\""), "{}", code);
+    });
+}
+
+#[test]
+fn test_enum_utils_on_enum_and_union() {
+    let source = r#"
+/** @derive(EnumUtils) */
+enum Status {
+    Active = "active",
+    Inactive = "inactive",
+}
+
+/** @derive(EnumUtils) */
+type Direction = "north" | "south";
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("exporttypeKey=\"Active\"|\"Inactive\";"), "{}", code);
+        assert!(code.contains("[\"Active\",Status.Active],"), "{}", code);
+        assert!(code.contains("caseStatus.Inactive:returnhandlers.Inactive();"), "{}", code);
+        assert!(code.contains("exportfunctionparse(str:string):Result<Status,string>"), "{}", code);
+        assert!(code.contains("exportfunctionmatch<R>(value:Direction,handlers:Handlers<R>):R"), "{}", code);
+        assert!(code.contains("\"north\":()=>R;"), "{}", code);
+        assert!(code.contains("from\"macroforge/utils\""), "{}", code);
+
+        let type_output = result.type_output.expect("should have type output").replace(char::is_whitespace, "");
+        assert!(type_output.contains("Active:()=>R;"), "{}", type_output);
+        assert!(type_output.contains("functionmatch<R>(value:Status,handlers:Handlers<R>):R"), "{}", type_output);
+    });
+}
+
+#[test]
+fn test_enum_utils_rejects_classes() {
+    let source = r#"
+/** @derive(EnumUtils) */
+class User {
+    name: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        assert!(
+            result.diagnostics.iter().any(|d| d.message.contains("EnumUtils")),
+            "{:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_serde_plain_mode_and_meta_prefix() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+/** @serde({ plain: true, deny_unknown_fields: true }) */
+class Dto {
+    id: number;
+    tags: string[];
+    owner: User | null;
+}
+
+/** @derive(Serialize, Deserialize) */
+/** @serde({ meta_prefix: "@" }) */
+class Tagged {
+    id: number;
+    owner: User | null;
+    members: User[];
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+        let (plain, tagged) = code.split_once("classTagged").unwrap();
+
+        assert!(plain.contains("ctx.enter(this,\"Dto\");"), "{}", plain);
+        assert!(plain.contains("ctx.leave(this);"), "{}", plain);
+        assert!(!plain.contains("__type"), "{}", plain);
+        assert!(!plain.contains("__id"), "{}", plain);
+        assert!(!plain.contains("__ref"), "{}", plain);
+        assert!(plain.contains("newSet([\"id\",\"tags\",\"owner\"])"), "{}", plain);
+        // Nested values are written inside a plain context
+        assert!(plain.contains("constctx=SerializeContext.create({plain:true});"), "{}", plain);
+
+        assert!(tagged.contains("\"@type\":\"Tagged\""), "{}", tagged);
+        assert!(tagged.contains("return{\"@ref\":existingId};"), "{}", tagged);
+        assert!(tagged.contains("value?.[\"@ref\"]!==undefined"), "{}", tagged);
+        assert!(tagged.contains("ctx.register(value[\"@id\"]asnumber,instance);"), "{}", tagged);
+        // Non-plain types drop their metadata when a plain container writes them
+        assert!(
+            tagged.contains("if(ctx.plain){ctx.enter(this,\"Tagged\");result={};}"),
+            "{}",
+            tagged
+        );
+        assert!(tagged.contains("if(ctx.plain){ctx.leave(this);}"), "{}", tagged);
+
+        let warnings: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code.as_deref() == Some("serde-nested-meta-prefix"))
+            .collect();
+        assert_eq!(warnings.len(), 1, "{:?}", result.diagnostics);
+        assert_eq!(warnings[0].level, DiagnosticLevel::Warning);
+        assert!(warnings[0].message.contains("(User)"), "{}", warnings[0].message);
+        assert!(
+            warnings[0].help.as_deref().unwrap().contains("macroOptions.serde.metaPrefix"),
+            "{:?}",
+            warnings[0].help
+        );
+    });
+}
+
+#[test]
+fn test_serde_msgpack_methods() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+/** @serde({ msgpack: true }) */
+class Packet {
+    id: bigint;
+    sentAt: Date;
+}
+
+/** @derive(Serialize, Deserialize) */
+interface Frame {
+    seq: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+        let (packet, frame) = code.split_once("interfaceFrame").unwrap();
+
+        assert!(code.contains("import{MsgPack}from\"macroforge/serde\";"), "{}", code);
+        assert!(
+            packet.contains("toMsgPack():Uint8Array{constctx=SerializeContext.create();returnMsgPack.encode(this.__serialize(ctx));}"),
+            "{}",
+            packet
+        );
+        assert!(packet.contains("staticfromMsgPack(bytes:Uint8Array,opts?:DeserializeOptions)"), "{}", packet);
+        assert!(packet.contains("constraw=MsgPack.decode(bytes);returnPacket.fromObject(raw,opts);"), "{}", packet);
+
+        assert!(!frame.contains("MsgPack"), "{}", frame);
+    });
+}
+
+#[test]
+fn test_serde_json_stream_methods() {
+    let source = r#"
+/** @derive(Deserialize) */
+/** @serde({ stream: true }) */
+class Row {
+    /** @serde({ validate: ["email"] }) */
+    email: string;
+}
+
+/** @derive(Deserialize) */
+/** @serde({ stream: true }) */
+interface Line {
+    text: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{JsonStream}from\"macroforge/serde\";"), "{}", code);
+        assert!(
+            code.contains("staticasync*fromJSONStream(chunks:AsyncIterable<string|Uint8Array>,opts?:DeserializeOptions):AsyncGenerator<Result<Row,"),
+            "{}",
+            code
+        );
+        assert!(code.contains("forawait(constrawofJsonStream.items(chunks)){yieldRow.fromObject(raw,opts);}"), "{}", code);
+        assert!(code.contains("exportasyncfunction*fromJSONStream(chunks:AsyncIterable<string|Uint8Array>"), "{}", code);
+        assert!(code.contains("yieldfromObject(raw,opts);"), "{}", code);
+    });
+}
+
+#[test]
+fn test_serde_versioned_migrations() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+/** @serde({ version: 3, migrate: { 1: "fromV1", 2: "fromV2" }, deny_unknown_fields: true }) */
+class Settings {
+    theme: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("result[\"__version\"]=3;"), "{}", code);
+        assert!(code.contains("letobj=valueasRecord<string,unknown>;"), "{}", code);
+        assert!(code.contains("const__version=obj[\"__version\"]??1;"), "{}", code);
+        assert!(code.contains("__version>3)"), "{}", code);
+        assert!(code.contains("if(__version<=1){obj=fromV1(objasany);}if(__version<=2){obj=fromV2(objasany);}"), "{}", code);
+        assert!(code.contains("\"__ref\",\"__version\",\"theme\""), "{}", code);
+        assert!(code.contains("ctx.register(value.__idasnumber,instance);"), "{}", code);
+    });
+}
+
+#[test]
+fn test_serde_version_reports_missing_migration() {
+    let source = r#"
+/** @derive(Deserialize) */
+/** @serde({ version: 3, migrate: { 1: "fromV1" } }) */
+class Settings {
+    theme: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("no migration from version 2")),
+            "{:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_serde_version_zero_is_rejected() {
+    let source = r#"
+/** @derive(Deserialize) */
+/** @serde({ version: 0, migrate: { 1: "fromV1" } }) */
+class Settings {
+    theme: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("version 0 must be at least 1")),
+            "{:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_serde_cross_field_and_async_validators() {
+    let source = r#"
+/** @derive(Deserialize) */
+/** @serde({ validate: ["passwordsMatch", { validate: "emailAvailable", message: "email taken" }] }) */
+class Signup {
+    /** @serde({ validate: ["oneOf('free', 'pro')"] }) */
+    plan: string;
+    /** @serde({ validate: ["requiredIf(plan, 'pro')"] }) */
+    company?: string;
+    /** @serde({ rename: "contact_email", validate: ["requiredUnless(phone)", "custom(isDeliverable)"] }) */
+    email?: string;
+    phone?: string;
+    /** @serde({ validate: ["multipleOf(5)"] }) */
+    seats: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("if(![\"free\",\"pro\"].includes(__raw_plan)){errors.push({field:\"plan\",message:\"mustbeoneof'free','pro'\",code:\"oneOf\",params:{values:[\"free\",\"pro\"]}});}"), "{}", code);
+        assert!(
+            code.contains("if((obj[\"plan\"]===\"pro\")&&obj[\"company\"]==null){errors.push({field:\"company\",message:\"isrequiredwhenplanis'pro'\",code:\"requiredIf\",params:{field:\"plan\",value:\"pro\"}});}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("if((obj[\"phone\"]==null)&&obj[\"contact_email\"]==null)"), "{}", code);
+        assert!(code.contains("ctx.checkValidation(isDeliverable(__raw_email),\"contact_email\",\"failedcustomvalidation\",errors);"), "{}", code);
+        assert!(code.contains("__raw_seats%5!==0"), "{}", code);
+        assert!(
+            code.contains("if(errors.length===0){ctx.checkValidation(passwordsMatch(instance),\"_root\",\"failedpasswordsMatch\",errors);ctx.checkValidation(emailAvailable(instance),\"_root\",\"emailtaken\",errors);}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("staticasyncfromObjectAsync(obj:unknown,opts?:DeserializeOptions):Promise<Result<Signup,"), "{}", code);
+        assert!(code.contains("constasyncErrors=awaitctx.settleValidations();"), "{}", code);
+        assert!(code.contains("if(ctx.hasPendingValidations()){returnResult.err(FieldError.format([{field:\"_root\",message:\"Signup.fromObject:asyncvalidatorsrequirefromObjectAsync\",code:\"asyncValidation\"}],opts));}"), "{}", code);
+    });
+}
+
+#[test]
+fn test_serde_error_codes_and_message_formatter() {
+    let source = r#"
+/** @derive(Deserialize) */
+/** @serde({ deny_unknown_fields: true }) */
+class Account {
+    /** @serde({ validate: ["minLength(3)", "pattern(\"^[a-z]+$\")"] }) */
+    handle: string;
+    /** @serde({ validate: ["between(18, 130)"] }) */
+    age: number;
+    /** @serde({ validate: ["email"] }) */
+    email: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{FieldError}from\"macroforge/serde\";"), "{}", code);
+        assert!(code.contains("code:\"minLength\",params:{min:3}"), "{}", code);
+        assert!(code.contains("code:\"pattern\",params:{pattern:\"^[a-z]+$\"}"), "{}", code);
+        assert!(code.contains("code:\"between\",params:{min:18,max:130}"), "{}", code);
+        assert!(code.contains("code:\"email\"})"), "{}", code);
+        assert!(code.contains("message:\"missingrequiredfield\",code:\"required\""), "{}", code);
+        assert!(code.contains("message:\"unknownfield\",code:\"unknownField\""), "{}", code);
+        assert!(code.contains("code:\"invalidType\",params:{expected:\"object\"}"), "{}", code);
+        assert!(code.contains("returnResult.err(FieldError.format(e.errors,opts));"), "{}", code);
+        assert!(code.contains("Result<Account,Array<FieldError>>"), "{}", code);
+    });
+}
+
+#[test]
+fn test_serde_generic_type_parameters() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+class Page<T> {
+    items: T[];
+    first?: T;
+    total: number;
+}
+
+/** @derive(Serialize, Deserialize) */
+interface Envelope<T> {
+    data: T;
+}
+
+/** @derive(Serialize, Deserialize) */
+type Pair<A, B> = {
+    left: A;
+    right: B[];
+};
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{TypeDeserializer}from\"macroforge/serde\";"), "{}", code);
+        assert!(
+            code.contains("staticfromObject<T>(obj:unknown,types:{T:TypeDeserializer<T>;},opts?:DeserializeOptions):Result<Page<T>,Array<FieldError>>"),
+            "{}",
+            code
+        );
+        assert!(code.contains("constresultOrRef=Page.__deserialize(obj,ctx,types);"), "{}", code);
+        assert!(code.contains("const__item=TypeDeserializer.apply(types?.T,item,ctx);"), "{}", code);
+        assert!(code.contains("const__result=TypeDeserializer.apply(types?.T,__raw_first,ctx);"), "{}", code);
+        assert!(code.contains("result[\"items\"]=SerializeContext.serializeAny(this.items,ctx);"), "{}", code);
+        assert!(code.contains("staticvalidateField<T,KextendskeyofPage<T>>"), "{}", code);
+
+        assert!(
+            code.contains("exportfunction__deserialize<T>(value:any,ctx:DeserializeContext,typeArgs?:{T:TypeDeserializer<T>}|TypeDeserializer.Positional):Envelope<T>|PendingRef{consttypes=TypeDeserializer.named(typeArgs,[\"T\"]);"),
+            "{}",
+            code
+        );
+        assert!(code.contains("exportfunction__serialize<T>(self:Envelope<T>,ctx:SerializeContext)"), "{}", code);
+
+        assert!(
+            code.contains("exportfunctionfromObject<A,B>(obj:unknown,types:{A:TypeDeserializer<A>;B:TypeDeserializer<B>},opts?:DeserializeOptions)"),
+            "{}",
+            code
+        );
+        assert!(code.contains("TypeDeserializer.apply(types?.B,item,ctx)"), "{}", code);
+        assert!(code.contains("result[\"right\"]=SerializeContext.serializeAny(value.right,ctx);"), "{}", code);
+    });
+}
+
+#[test]
+fn test_serde_generic_type_arguments() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+class Holder<T> {
+    page: Page<User>;
+    boxed: Page<Box<T>>;
+    maybe: T | null;
+    later: T | undefined;
+}
+
+/** @derive(Serialize, Deserialize) */
+type Cell<T> = {
+    maybe: T | null;
+};
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+        let (holder, cell) = code.split_once("exportnamespaceCell").unwrap();
+
+        // Type arguments of generic references are passed in declaration order
+        assert!(holder.contains("(Page<User>asany).__deserialize(__raw_page,ctx,[User]);"), "{}", holder);
+        assert!(
+            holder.contains("(Page<Box<T>>asany).__deserialize(__raw_boxed,ctx,[TypeDeserializer.of(Box,[types?.T])]);"),
+            "{}",
+            holder
+        );
+
+        // `T | null` and `T | undefined` keep null/undefined and deserialize the rest
+        assert!(
+            holder.contains("if(__raw_maybe===null){instance.maybe=null;}else{const__result=TypeDeserializer.apply(types?.T,__raw_maybe,ctx);"),
+            "{}",
+            holder
+        );
+        assert!(
+            holder.contains("if(__raw_later===undefined){instance.later=undefined;}else{const__result=TypeDeserializer.apply(types?.T,__raw_later,ctx);"),
+            "{}",
+            holder
+        );
+        assert!(holder.contains("result[\"maybe\"]=SerializeContext.serializeAny(this.maybe,ctx);"), "{}", holder);
+        assert!(
+            holder.contains("if(this.later!==undefined){result[\"later\"]=SerializeContext.serializeAny(this.later,ctx);}"),
+            "{}",
+            holder
+        );
+
+        assert!(
+            cell.contains("if(instance[\"maybe\"]!==undefined&&instance[\"maybe\"]!==null){const__result=TypeDeserializer.apply(types?.T,instance[\"maybe\"],ctx);"),
+            "{}",
+            cell
+        );
+    });
+}
+
+#[test]
+fn test_serde_codec_field_types_generated_code() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+class Blob {
+    id: bigint;
+    data: Uint8Array;
+    homepage?: URL;
+    pattern: RegExp;
+    span: [Date, bigint];
+    balances: Record<string, bigint>;
+    due: Temporal.PlainDate;
+}
+
+/** @derive(Serialize, Deserialize) */
+interface Chunk {
+    raw: ArrayBuffer;
+}
+
+/** @derive(Serialize, Deserialize) */
+type Ledger = {
+    total: bigint;
+    note: string;
+};
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{Codec}from\"macroforge/serde\";"), "{}", code);
+
+        // Serialization writes JSON-safe forms
+        assert!(code.contains("result[\"id\"]=String(this.id);"), "{}", code);
+        assert!(code.contains("result[\"data\"]=Codec.encodeBase64(this.data);"), "{}", code);
+        assert!(
+            code.contains("if(this.homepage!==undefined){result[\"homepage\"]=this.homepage.href;}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result[\"pattern\"]=String(this.pattern);"), "{}", code);
+        assert!(
+            code.contains("result[\"span\"]=[this.span[0].toISOString(),String(this.span[1])];"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("result[\"balances\"]=Codec.mapValues(this.balances,(x:any)=>String(x));"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result[\"due\"]=this.due.toString();"), "{}", code);
+        assert!(code.contains("result[\"raw\"]=Codec.encodeBase64(self.raw);"), "{}", code);
+        assert!(code.contains("result[\"total\"]=String(value.total);"), "{}", code);
+        assert!(code.contains("result[\"note\"]=value.note;"), "{}", code);
+
+        // Deserialization rebuilds the original values, reporting bad input as a field error
+        assert!(
+            code.contains("try{const__decoded=Codec.decodeBigInt(__raw_id);instance.id=__decoded;}catch(__e){errors.push({field:\"id\",message:String((__easError)?.message??__e),code:\"invalidType\"});}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("const__decoded=Codec.decodeBase64(__raw_data);"), "{}", code);
+        assert!(code.contains("const__decoded=newURL(__raw_homepageasstring);"), "{}", code);
+        assert!(code.contains("const__decoded=Codec.decodeRegExp(__raw_pattern);"), "{}", code);
+        assert!(
+            code.contains("const__decoded=[newDate((__raw_spanasany[])[0]asstring),Codec.decodeBigInt((__raw_spanasany[])[1])];"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=Codec.mapValues(__raw_balancesasRecord<string,unknown>,(x:any)=>Codec.decodeBigInt(x));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=Temporal.PlainDate.from(__raw_dueasstring);"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("instance.raw=Codec.decodeBase64(__raw_raw).buffer;"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(instance[\"total\"]!==undefined){const__raw=instance[\"total\"];try{instance[\"total\"]=Codec.decodeBigInt(__raw);}"),
+            "{}",
+            code
+        );
+    });
+}
+
+#[test]
+fn test_serde_codecs_apply_inside_arrays_and_nullables() {
+    let source = r#"
+/** @derive(Serialize, Deserialize) */
+class Wrapped {
+    parent: bigint | null;
+    ids: bigint[];
+    avatar: Uint8Array | null;
+    links: URL[];
+    cap: bigint | undefined;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(
+            code.contains("result[\"parent\"]=(this.parent===null?null:String(this.parent));"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result[\"ids\"]=this.ids.map((x:any)=>String(x));"), "{}", code);
+        assert!(
+            code.contains("result[\"avatar\"]=(this.avatar===null?null:Codec.encodeBase64(this.avatar));"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result[\"links\"]=this.links.map((x:any)=>x.href);"), "{}", code);
+        assert!(
+            code.contains("result[\"cap\"]=(this.cap===undefined?undefined:String(this.cap));"),
+            "{}",
+            code
+        );
+
+        assert!(
+            code.contains("const__decoded=(__raw_parent===null?null:Codec.decodeBigInt(__raw_parent));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=(__raw_idsasany[]).map((x:any)=>Codec.decodeBigInt(x));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=(__raw_avatar===null?null:Codec.decodeBase64(__raw_avatar));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=(__raw_linksasany[]).map((x:any)=>newURL(xasstring));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__decoded=(__raw_cap===undefined?undefined:Codec.decodeBigInt(__raw_cap));"),
+            "{}",
+            code
+        );
+    });
+}
+
+#[test]
+fn test_derive_patch_generates_patch_type_apply_and_diff() {
+    let source = r#"
+/**
+ * @derive(Patch)
+ * @serde({ rename_all: "camelCase" })
+ */
+class User {
+    user_name: string;
+    /** @serde({ rename: "mail" }) */
+    email?: string;
+    joined: Date;
+    address: Address;
+    /** @serde({ skip: true }) */
+    cache: Map<string, string>;
+}
+
+/** @derive(Partial) */
+interface Address {
+    city: string;
+    zip?: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{valuesEqual}from\"macroforge/patch\";"), "{}", code);
+        assert!(code.contains("import{isEmptyPatch}from\"macroforge/patch\";"), "{}", code);
+
+        // Patch keys follow the serde rename rules; skipped fields are left out
+        assert!(
+            code.contains("exporttypeUserPatch={\"userName\"?:string;\"mail\"?:string|null;\"joined\"?:string;\"address\"?:AddressPatch;};"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("exporttypeAddressPatch={\"city\"?:string;\"zip\"?:string|null;};"),
+            "{}",
+            code
+        );
+
+        // applyPatch: null removes optional fields, nested patches recurse, dates revive
+        assert!(
+            code.contains("staticapplyPatch(target:User|undefined,patch:UserPatch):User{constresult:any=target??Object.create(User.prototype);"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(__value===null){deleteresult.email;}else{result.email=__value;}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result.joined=newDate(__valueasstring);"), "{}", code);
+        assert!(
+            code.contains("result.address=Address.applyPatch(result.address,__value);"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("exportfunctionapplyPatch(target:Address|undefined,patch:AddressPatch):Address{constresult:any=target??{};"),
+            "{}",
+            code
+        );
+
+        // diff: changed values, null for removed optional fields, nested diffs only when non-empty
+        assert!(
+            code.contains("staticdiff(a:User|undefined,b:User):UserPatch{constprev:any=a??{};constpatch:any={};"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(!valuesEqual(prev.user_name,b.user_name)){patch[\"userName\"]=b.user_name;}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(b.email===undefined){if(prev.email!==undefined){patch[\"mail\"]=null;}}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("const__nested=Address.diff(prev.address,b.address);if(!isEmptyPatch(__nested)){patch[\"address\"]=__nested;}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(!valuesEqual(prev.joined,b.joined)){patch[\"joined\"]=b.joined.toISOString();}"),
+            "{}",
+            code
+        );
+        assert!(!code.contains("cache\"?"), "{}", code);
+    });
+}
+
+#[test]
+fn test_derive_patch_replaces_other_types_and_writes_json_values() {
+    let source = r#"
+enum Role {
+    Admin = "admin",
+    Member = "member",
+}
+
+type Status = "active" | "banned";
+
+/** @derive(Debug) */
+class Owner {
+    name: string;
+}
+
+/** @derive(Patch) */
+class Account {
+    role: Role;
+    status: Status;
+    owner: Owner;
+    id: bigint;
+    avatar: Uint8Array | null;
+    tags: Set<string>;
+    scores: Map<string, bigint>;
+    span?: [Date, bigint];
+}
+
+/** @derive(Patch) */
+interface Timeline {
+    dates: Date[];
+    maybe: Date | null;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        // Enums, string unions and types without Patch have no XPatch
+        assert!(!code.contains("RolePatch"), "{}", code);
+        assert!(!code.contains("StatusPatch"), "{}", code);
+        assert!(!code.contains("OwnerPatch"), "{}", code);
+        assert!(!code.contains("isEmptyPatch"), "{}", code);
+        assert!(code.contains("result.role=__value;"), "{}", code);
+        assert!(code.contains("result.status=__value;"), "{}", code);
+        assert!(
+            code.contains("if(!valuesEqual(prev.owner,b.owner)){patch[\"owner\"]=b.owner;}"),
+            "{}",
+            code
+        );
+
+        // Patch entries are typed and written in their JSON form
+        assert!(
+            code.contains("exporttypeAccountPatch={\"role\"?:Role;\"status\"?:Status;\"owner\"?:Owner;\"id\"?:string;\"avatar\"?:string|null;\"tags\"?:string[];\"scores\"?:Record<string,string>;\"span\"?:[string,string]|null;};"),
+            "{}",
+            code
+        );
+        assert!(code.contains("import{Codec}from\"macroforge/serde\";"), "{}", code);
+        assert!(code.contains("patch[\"id\"]=String(b.id);"), "{}", code);
+        assert!(
+            code.contains("patch[\"avatar\"]=(b.avatar==null?b.avatar:Codec.encodeBase64(b.avatar));"),
+            "{}",
+            code
+        );
+        assert!(code.contains("patch[\"tags\"]=Array.from(b.tags);"), "{}", code);
+        assert!(
+            code.contains("patch[\"scores\"]=Object.fromEntries(Array.from(b.scores,([k,x]:[any,any])=>[k,String(x)]));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("patch[\"span\"]=[b.span[0].toISOString(),String(b.span[1])];"),
+            "{}",
+            code
+        );
+
+        // Wrapped dates are encoded element by element
+        assert!(
+            code.contains("exporttypeTimelinePatch={\"dates\"?:string[];\"maybe\"?:string|null;};"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("patch[\"dates\"]=b.dates.map((x:any)=>x.toISOString());"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("patch[\"maybe\"]=(b.maybe==null?b.maybe:b.maybe.toISOString());"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("result.dates=(__valueasany[]).map((x:any)=>newDate(xasstring));"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("result.maybe=(__value==null?__value:newDate(__valueasstring));"),
+            "{}",
+            code
+        );
+
+        // applyPatch rebuilds them, including maps and sets
+        assert!(code.contains("result.id=Codec.decodeBigInt(__value);"), "{}", code);
+        assert!(code.contains("result.tags=newSet(__value);"), "{}", code);
+        assert!(
+            code.contains("result.scores=newMap(Object.entries(__value).map(([k,x]:[string,any])=>[k,Codec.decodeBigInt(x)]));"),
+            "{}",
+            code
+        );
+    });
+}
+
+#[test]
+fn test_derive_mock_generates_arbitrary_factories() {
+    let source = r#"
+enum Role {
+    Admin = "admin",
+    Member = "member",
+}
+
+/**
+ * @derive(Mock)
+ * @serde({ rename_all: "camelCase" })
+ */
+class User {
+    /** @serde({ validate: ["email", "maxLength(40)"] }) */
+    email: string;
+    /** @serde({ validate: ["minLength(2)"] }) */
+    display_name: string;
+    /** @serde({ validate: ["int", "positive"] }) */
+    age: number;
+    role: Role;
+    status: "active" | "banned";
+    /** @serde({ validate: ["maxItems(3)"] }) */
+    tags: string[];
+    nickname?: string | null;
+    /** @mock({ with: "fakeAvatar" }) */
+    avatar: Uint8Array;
+}
+
+/** @derive(Mock) */
+enum Flag {
+    On,
+    Off,
+}
+
+/** @derive(Mock) */
+type Shape = { kind: "circle"; radius: number } | { kind: "square"; side: number };
+
+/** @derive(Mock) */
+interface Point {
+    /** @serde({ validate: ["nonNegative"] }) */
+    x: number;
+    y: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let code = result.code.replace(char::is_whitespace, "");
+
+        assert!(code.contains("import{Mock}from\"macroforge/mock\";"), "{}", code);
+        assert!(code.contains("importtype{MockRng}from\"macroforge/mock\";"), "{}", code);
+
+        // Valid values follow the field types and validators
+        assert!(
+            code.contains("staticarbitrary(seed?:number|MockRng):User{constrng=Mock.seeded(seed);constinstance:any=Object.create(User.prototype);"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("instance.email=Mock.string(rng,{format:\"email\",maxLength:40});"),
+            "{}",
+            code
+        );
+        assert!(code.contains("instance.age=Mock.number(rng,{int:true,gt:0});"), "{}", code);
+        assert!(code.contains("instance.role=Role.arbitrary(rng);"), "{}", code);
+        assert!(
+            code.contains("instance.status=rng.pick([\"active\",\"banned\"]asconst);"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("if(rng.bool()){instance.nickname=Mock.union(rng,[()=>Mock.string(rng),()=>null]);}"),
+            "{}",
+            code
+        );
+        assert!(code.contains("instance.avatar=fakeAvatar(rng);"), "{}", code);
+
+        // One invalid instance per breakable validator, keyed like deserialization errors
+        assert!(
+            code.contains("staticarbitraryInvalid(seed?:number|MockRng):MockInvalid<User>[]{"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("{field:\"email\",validator:\"email\",value:Object.assign(User.arbitrary(rng),{email:\"not-an-email\"})}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("{field:\"displayName\",validator:\"minLength\",value:Object.assign(User.arbitrary(rng),{display_name:Mock.string(rng,{minLength:1,maxLength:1})})}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("tags:Mock.array(rng,()=>Mock.string(rng),{minItems:4,maxItems:4})"),
+            "{}",
+            code
+        );
+
+        // Enums, union aliases and interfaces get the factory in their namespace
+        assert!(
+            code.contains("exportfunctionarbitrary(seed?:number|MockRng):Flag{returnMock.seeded(seed).pick([Flag.On,Flag.Off]);}"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("returnMock.union(rng,[()=>({\"kind\":\"circle\"asconst,\"radius\":Mock.number(rng)}),()=>({\"kind\":\"square\"asconst,\"side\":Mock.number(rng)})]);"),
+            "{}",
+            code
+        );
+        assert!(code.contains("result.x=Mock.number(rng,{gte:0});"), "{}", code);
+        assert!(
+            code.contains("{field:\"x\",validator:\"nonNegative\",value:Object.assign(Point.arbitrary(rng),{x:-1})}"),
+            "{}",
+            code
+        );
+    });
+}
+
+#[test]
+fn test_derive_mock_requires_generator_for_unsupported_fields() {
+    let source = r#"
+/** @derive(Mock) */
+class Ticket {
+    /** @serde({ validate: ["pattern(\"^[A-Z]+$\")"] }) */
+    code: string;
+    /** @serde({ validate: ["pattern(\"^#\")"] }) */
+    label?: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+        let messages: Vec<_> = result.diagnostics.iter().map(|d| d.message.as_str()).collect();
+
+        assert!(
+            messages.iter().any(|m| m.contains("cannot generate valid values for: code (pattern validator)")),
+            "{:?}",
+            messages
+        );
+        assert!(!messages.iter().any(|m| m.contains("label")), "{:?}", messages);
+    });
+}
+
 #[test]
 fn test_generated_methods_on_separate_lines() {
     let source = r#"
@@ -2849,3 +4058,5 @@ export { User, Role, Status, createUser, users };
     assert_eq!(result.code, source, "Regular TypeScript should be returned unchanged");
     assert!(result.diagnostics.is_empty(), "No diagnostics for regular TypeScript");
 }
+
+
@@ -0,0 +1,347 @@
+//! Snapshot testing for macro authors
+//!
+//! Runs TypeScript input through [`MacroExpander`] with every macro registered
+//! in the current binary (builtins plus any `#[ts_macro_derive]` in the crate
+//! under test) and compares the expanded code, the `.d.ts` output and the
+//! diagnostics against a snapshot file.
+//!
+//! ```ignore
+//! use macroforge_ts::testing::assert_snapshot;
+//!
+//! #[test]
+//! fn user_debug() {
+//!     assert_snapshot("user_debug", r#"
+//!         /** @derive(Debug) */
+//!         class User { name: string; }
+//!     "#);
+//! }
+//! ```
+//!
+//! Snapshots live in `tests/snapshots/<name>.snap` relative to the crate being
+//! tested. Run with `MACROFORGE_UPDATE_SNAPSHOTS=1` to create or overwrite them.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use swc_core::common::GLOBALS;
+
+use crate::host::{MacroConfig, MacroExpander, MacroExpansion};
+
+/// Environment variable that switches snapshot assertions to update mode
+pub const UPDATE_ENV_VAR: &str = "MACROFORGE_UPDATE_SNAPSHOTS";
+
+/// A single snapshot comparison
+///
+/// Use [`assert_snapshot`] for the common case; construct this directly to
+/// customise the snapshot directory, the virtual file name or the config.
+#[derive(Debug, Clone)]
+pub struct SnapshotTest {
+    name: String,
+    snapshot_dir: PathBuf,
+    file_name: String,
+    config: Option<MacroConfig>,
+}
+
+impl SnapshotTest {
+    /// Create a snapshot named `name`, stored in `tests/snapshots` of the
+    /// crate under test.
+    pub fn new(name: impl Into<String>) -> Self {
+        let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+        Self {
+            name: name.into(),
+            snapshot_dir: manifest_dir.join("tests").join("snapshots"),
+            file_name: "input.ts".to_string(),
+            config: None,
+        }
+    }
+
+    /// Store the snapshot in `dir` instead of `tests/snapshots`
+    pub fn with_snapshot_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.snapshot_dir = dir.into();
+        self
+    }
+
+    /// File name passed to the macros (defaults to `input.ts`)
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = file_name.into();
+        self
+    }
+
+    /// Expand with an explicit config instead of discovering `macroforge.json`
+    pub fn with_config(mut self, config: MacroConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Path of the snapshot file
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.snapshot_dir.join(format!("{}.snap", self.name))
+    }
+
+    /// Expand `source` and compare it against the snapshot, panicking with a
+    /// diff on mismatch. Honors `MACROFORGE_UPDATE_SNAPSHOTS=1`.
+    pub fn assert(&self, source: &str) {
+        if let Err(message) = self.verify(source, update_mode()) {
+            panic!("{message}");
+        }
+    }
+
+    /// Expand `source` and compare it against the snapshot.
+    ///
+    /// With `update` set, the snapshot is (re)written instead of compared.
+    pub fn verify(&self, source: &str, update: bool) -> Result<(), String> {
+        let expansion = self.expand(source)?;
+        let actual = render_snapshot(&expansion, source);
+        let path = self.snapshot_path();
+
+        if update {
+            return write_snapshot(&path, &actual);
+        }
+
+        let expected = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => {
+                return Err(format!(
+                    "snapshot `{}` does not exist ({}).\nRe-run with {UPDATE_ENV_VAR}=1 to create it.\n\n{actual}",
+                    self.name,
+                    path.display()
+                ));
+            }
+        };
+
+        if normalize(&expected) == normalize(&actual) {
+            Ok(())
+        } else {
+            Err(format!(
+                "snapshot `{}` does not match ({}).\nRe-run with {UPDATE_ENV_VAR}=1 to accept the new output.\n\n{}",
+                self.name,
+                path.display(),
+                line_diff(&normalize(&expected), &normalize(&actual))
+            ))
+        }
+    }
+
+    /// Run the registered macros over `source`
+    pub fn expand(&self, source: &str) -> Result<MacroExpansion, String> {
+        let expander = match &self.config {
+            Some(config) => {
+                let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                MacroExpander::with_config(config.clone(), root)
+            }
+            None => MacroExpander::new(),
+        }
+        .map_err(|err| format!("failed to create macro expander: {err}"))?;
+
+        GLOBALS.set(&Default::default(), || {
+            expander
+                .expand_source(source, &self.file_name)
+                .map_err(|err| format!("failed to expand `{}`: {err}", self.file_name))
+        })
+    }
+}
+
+/// Expand `source` and compare it against `tests/snapshots/<name>.snap`
+pub fn assert_snapshot(name: &str, source: &str) {
+    SnapshotTest::new(name).assert(source);
+}
+
+/// Whether `MACROFORGE_UPDATE_SNAPSHOTS` asks for snapshots to be rewritten
+pub fn update_mode() -> bool {
+    std::env::var(UPDATE_ENV_VAR)
+        .map(|value| !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false"))
+        .unwrap_or(false)
+}
+
+/// Render an expansion in the snapshot file format
+///
+/// The format has three sections (`code`, `types`, `diagnostics`) so a
+/// single file captures everything a macro produces.
+pub fn render_snapshot(expansion: &MacroExpansion, source: &str) -> String {
+    let mut out = String::new();
+    out.push_str("--- code ---\n");
+    out.push_str(expansion.code.trim_end());
+    out.push_str("\n\n--- types ---\n");
+    match &expansion.type_output {
+        Some(types) => out.push_str(types.trim_end()),
+        None => out.push_str("(none)"),
+    }
+    out.push_str("\n\n--- diagnostics ---\n");
+    if expansion.diagnostics.is_empty() {
+        out.push_str("(none)\n");
+    }
+    for diag in &expansion.diagnostics {
+        let location = diag
+            .span
+            .map(|span| {
+                let (line, col) = offset_to_line_col(source, span.start as usize);
+                format!("{line}:{col}")
+            })
+            .unwrap_or_else(|| "-".to_string());
+        let _ = writeln!(
+            out,
+            "{} [{location}] {}",
+            format!("{:?}", diag.level).to_lowercase(),
+            diag.message
+        );
+        for note in &diag.notes {
+            let _ = writeln!(out, "  note: {note}");
+        }
+        if let Some(help) = &diag.help {
+            let _ = writeln!(out, "  help: {help}");
+        }
+    }
+    out
+}
+
+fn write_snapshot(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+    std::fs::write(path, contents).map_err(|err| format!("failed to write {}: {err}", path.display()))
+}
+
+/// Normalize line endings and trailing whitespace so snapshots survive
+/// editors and git autocrlf.
+fn normalize(text: &str) -> String {
+    let mut out: String = text
+        .replace("\r\n", "\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    out.truncate(out.trim_end().len());
+    out
+}
+
+fn offset_to_line_col(source: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for (idx, ch) in source.char_indices() {
+        if idx >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+/// Line-based diff of `expected` against `actual` (`-` expected, `+` actual)
+fn line_diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            let _ = writeln!(out, "  {}", old[i]);
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            let _ = writeln!(out, "+ {}", new[j]);
+            j += 1;
+        } else {
+            let _ = writeln!(out, "- {}", old[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+/** @derive(Debug) */
+class User {
+    name: string;
+}
+"#;
+
+    fn snapshot_in(dir: &Path) -> SnapshotTest {
+        SnapshotTest::new("user_debug")
+            .with_snapshot_dir(dir)
+            .with_config(MacroConfig::default())
+    }
+
+    #[test]
+    fn test_update_then_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = snapshot_in(dir.path());
+
+        assert!(snapshot.verify(SOURCE, false).is_err(), "missing snapshot should fail");
+        snapshot.verify(SOURCE, true).unwrap();
+        assert!(snapshot.snapshot_path().exists());
+        snapshot.verify(SOURCE, false).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = snapshot_in(dir.path());
+        snapshot.verify(SOURCE, true).unwrap();
+
+        let contents = std::fs::read_to_string(snapshot.snapshot_path()).unwrap();
+        assert!(contents.contains("--- code ---"));
+        assert!(contents.contains("toString()"));
+        assert!(contents.contains("--- types ---"));
+        assert!(contents.contains("toString(): string;"));
+        assert!(contents.contains("--- diagnostics ---\n(none)"));
+    }
+
+    #[test]
+    fn test_mismatch_reports_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = snapshot_in(dir.path());
+        snapshot.verify(SOURCE, true).unwrap();
+
+        let changed = SOURCE.replace("name: string;", "name: string;\n    age: number;");
+        let err = snapshot.verify(&changed, false).unwrap_err();
+        assert!(err.contains("does not match"), "{err}");
+        assert!(err.contains("+     age: number;"), "{err}");
+    }
+
+    #[test]
+    fn test_diagnostics_are_rendered() {
+        let snapshot = SnapshotTest::new("enum_default").with_config(MacroConfig::default());
+        let source = "/** @derive(Default) */\nenum Status { Active, Inactive }\n";
+        let expansion = snapshot.expand(source).unwrap();
+        let rendered = render_snapshot(&expansion, source);
+        assert!(
+            rendered.contains("error [1:") && rendered.contains("requires exactly one variant"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ignores_trailing_whitespace() {
+        assert_eq!(normalize("a  \r\nb\n\n"), normalize("a\nb"));
+    }
+
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff("a\nb\nc", "a\nx\nc");
+        assert_eq!(diff, "  a\n+ x\n- b\n  c\n");
+    }
+}
@@ -101,3 +101,209 @@ class User {
         String::from_utf8_lossy(&output.stderr)
     );
 }
+
+#[test]
+fn expand_check_passes_for_builtin_derives() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("checked.ts");
+
+    std::fs::write(
+        &input_path,
+        r#"
+/** @derive(Debug, Clone, PartialEq, Serialize, Deserialize) */
+export class User {
+    name: string;
+    age: number;
+}
+"#,
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("expand")
+        .arg(&input_path)
+        .arg("--builtin-only")
+        .arg("--check")
+        .output()
+        .expect("failed to run macroforge");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "runtime and type output should agree, got: {}",
+        stderr
+    );
+    assert!(!stderr.contains("check failed"), "unexpected issues: {}", stderr);
+}
+
+#[test]
+fn expand_diff_groups_hunks_by_macro() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("diffed.ts");
+
+    std::fs::write(
+        &input_path,
+        r#"/** @derive(Debug, Clone) */
+export class User {
+    name: string;
+}
+"#,
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("expand")
+        .arg(&input_path)
+        .arg("--builtin-only")
+        .arg("--diff")
+        .output()
+        .expect("failed to run macroforge");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[Debug]"), "missing Debug section: {}", stdout);
+    assert!(stdout.contains("[Clone]"), "missing Clone section: {}", stdout);
+    assert!(stdout.contains("+    toString(): string {"), "missing hunk: {}", stdout);
+    assert!(
+        !stdout.contains("(expanded) ---"),
+        "--diff should not print the whole file: {}",
+        stdout
+    );
+}
+
+#[test]
+fn explain_lists_macros_and_members() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("explained.ts");
+
+    std::fs::write(
+        &input_path,
+        r#"/** @derive(Debug, Clone) */
+export class User {
+    name: string;
+}
+"#,
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("explain")
+        .arg(format!("{}:3", input_path.display()))
+        .arg("--builtin-only")
+        .output()
+        .expect("failed to run macroforge");
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("class User (line 2)"), "{}", stdout);
+    assert!(stdout.contains("Debug from @macro/derive"), "{}", stdout);
+    assert!(stdout.contains("+ clone()"), "{}", stdout);
+
+    let missing = macroforge_bin()
+        .arg("explain")
+        .arg(format!("{}:5", input_path.display()))
+        .arg("--builtin-only")
+        .output()
+        .expect("failed to run macroforge");
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("no @derive target"));
+}
+
+#[test]
+fn expand_format_json_outputs_versioned_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("report.ts");
+
+    std::fs::write(
+        &input_path,
+        r#"/** @derive(Debug) */
+export class User {
+    name: string;
+}
+
+/** @derive(Default) */
+enum Status { Active, Inactive }
+"#,
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("expand")
+        .arg(&input_path)
+        .arg("--builtin-only")
+        .arg("--format")
+        .arg("json")
+        .output()
+        .expect("failed to run macroforge");
+
+    assert_eq!(output.status.code(), Some(0));
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a JSON report");
+    assert_eq!(report["version"], 1);
+    assert!(report["code"].as_str().unwrap().contains("toString()"));
+    assert!(report["types"].as_str().unwrap().contains("toString(): string;"));
+    assert_eq!(
+        report["sourceMapping"]["generatedRegions"][0]["sourceMacro"],
+        "Debug"
+    );
+
+    let diagnostic = &report["diagnostics"][0];
+    assert_eq!(diagnostic["level"], "error");
+    assert_eq!(diagnostic["code"], "macroforge");
+    assert_eq!(diagnostic["span"]["start"]["line"], 6);
+    assert!(diagnostic["notes"].is_array());
+}
+
+#[test]
+fn expand_format_sarif_outputs_results() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_path = temp_dir.path().join("sarif.ts");
+
+    std::fs::write(
+        &input_path,
+        "/** @derive(Default) */\nenum Status { Active, Inactive }\n",
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("expand")
+        .arg(&input_path)
+        .arg("--builtin-only")
+        .arg("--format")
+        .arg("sarif")
+        .output()
+        .expect("failed to run macroforge");
+
+    let sarif: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a SARIF log");
+    assert_eq!(sarif["version"], "2.1.0");
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["region"]["startLine"],
+        1
+    );
+}
+
+#[test]
+fn doctor_reports_registry_and_unresolved_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join("macroforge.json"),
+        r#"{ "macroPackages": ["@acme/missing-macros"] }"#,
+    )
+    .unwrap();
+
+    let output = macroforge_bin()
+        .arg("doctor")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("failed to run macroforge");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("@acme/missing-macros: unresolved"), "{}", stdout);
+    assert!(stdout.contains("Debug (derive) macroforge_ts@"), "{}", stdout);
+    assert!(stdout.contains("warning[unresolved-macro-package]"), "{}", stdout);
+}
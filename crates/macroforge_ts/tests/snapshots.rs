@@ -0,0 +1,37 @@
+//! Snapshot tests for builtin derives using the public `macroforge_ts::testing` harness.
+//!
+//! Regenerate with `MACROFORGE_UPDATE_SNAPSHOTS=1 cargo test --test snapshots`.
+
+use macroforge_ts::host::MacroConfig;
+use macroforge_ts::testing::SnapshotTest;
+
+fn snapshot(name: &str) -> SnapshotTest {
+    SnapshotTest::new(name).with_config(MacroConfig::default())
+}
+
+#[test]
+fn debug_class() {
+    snapshot("debug_class").assert(
+        r#"
+/** @derive(Debug) */
+class User {
+    name: string;
+    /** @debug({ rename: "userAge" }) */
+    age: number;
+}
+"#,
+    );
+}
+
+#[test]
+fn default_enum_without_default_variant() {
+    snapshot("default_enum_without_default_variant").assert(
+        r#"
+/** @derive(Default) */
+enum Status {
+    Active,
+    Inactive,
+}
+"#,
+    );
+}
@@ -115,6 +115,14 @@ pub fn ts_macro_derive(attr: TokenStream, item: TokenStream) -> TokenStream {
             fn description(&self) -> &str {
                 #description
             }
+
+            fn package(&self) -> Option<&str> {
+                Some(#package_expr)
+            }
+
+            fn version(&self) -> Option<&str> {
+                Some(env!("CARGO_PKG_VERSION"))
+            }
         }
 
         #[allow(non_upper_case_globals)]
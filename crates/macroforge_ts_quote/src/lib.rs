@@ -8,6 +8,8 @@
 mod template;
 #[cfg(test)]
 mod test;
+#[cfg(feature = "validate")]
+mod validate;
 
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
@@ -456,10 +458,19 @@ fn parse_interpolation(tokens: TokenStream2) -> (String, TokenStream2, bool) {
 ///
 /// The template is compiled to a string at runtime, then parsed with SWC
 /// to produce a typed AST node.
+///
+/// With the `validate` feature (enabled by default) the static parts of the
+/// template are also parsed at compile time, with interpolations replaced by
+/// placeholders, so syntax errors are reported on the offending Rust tokens.
 #[proc_macro]
 pub fn ts_template(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
 
+    #[cfg(feature = "validate")]
+    if let Err(e) = validate::validate_template(&input, validate::TemplateKind::Module) {
+        return e.to_compile_error().into();
+    }
+
     // Parse the template to generate string-building code
     // parse_template returns a tuple: (String, Vec<Patch>)
     let template_builder = match template::parse_template(input) {
@@ -483,28 +494,48 @@ pub fn ts_template(input: TokenStream) -> TokenStream {
 #[proc_macro]
 pub fn above(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
-    generate_scoped_template(input, "/* @macroforge:above */")
+    generate_scoped_template(input, "/* @macroforge:above */", TemplateTarget::Module)
 }
 
 #[proc_macro]
 pub fn below(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
-    generate_scoped_template(input, "/* @macroforge:below */")
+    generate_scoped_template(input, "/* @macroforge:below */", TemplateTarget::Module)
 }
 
 #[proc_macro]
 pub fn body(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
-    generate_scoped_template(input, "/* @macroforge:body */")
+    generate_scoped_template(input, "/* @macroforge:body */", TemplateTarget::ClassBody)
 }
 
 #[proc_macro]
 pub fn signature(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
-    generate_scoped_template(input, "/* @macroforge:signature */")
+    generate_scoped_template(input, "/* @macroforge:signature */", TemplateTarget::ClassBody)
+}
+
+/// Where a scoped template's output is inserted
+#[cfg_attr(not(feature = "validate"), allow(dead_code))]
+enum TemplateTarget {
+    Module,
+    ClassBody,
 }
 
-fn generate_scoped_template(input: TokenStream2, marker: &str) -> TokenStream {
+fn generate_scoped_template(input: TokenStream2, marker: &str, target: TemplateTarget) -> TokenStream {
+    #[cfg(feature = "validate")]
+    {
+        let kind = match target {
+            TemplateTarget::Module => validate::TemplateKind::Module,
+            TemplateTarget::ClassBody => validate::TemplateKind::ClassBody,
+        };
+        if let Err(e) = validate::validate_template(&input, kind) {
+            return e.to_compile_error().into();
+        }
+    }
+    #[cfg(not(feature = "validate"))]
+    let _ = target;
+
     let template_builder = match template::parse_template(input) {
         Ok(s) => s,
         Err(e) => return e.to_compile_error().into(),
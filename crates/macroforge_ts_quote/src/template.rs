@@ -56,7 +56,7 @@ enum Terminator {
 }
 
 // Analyzes a braces group { ... } to see if it's a Macro Tag or just TS code
-enum TagType {
+pub(crate) enum TagType {
     If(TokenStream2),
     IfLet(TokenStream2, TokenStream2),    // pattern, expression
     While(TokenStream2),                  // {#while cond}
@@ -80,7 +80,7 @@ enum TagType {
     Block,                     // Standard TypeScript Block { ... }
 }
 
-fn analyze_tag(g: &Group) -> TagType {
+pub(crate) fn analyze_tag(g: &Group) -> TagType {
     let tokens: Vec<TokenTree> = g.stream().into_iter().collect();
 
     // Check for {| ... |} ident block - must have at least | and |
@@ -999,14 +999,14 @@ fn unescape_string(s: &str) -> String {
 }
 
 /// Check if a literal is a string (starts with " or ')
-fn is_string_literal(lit: &proc_macro2::Literal) -> bool {
+pub(crate) fn is_string_literal(lit: &proc_macro2::Literal) -> bool {
     let s = lit.to_string();
     s.starts_with('"') || s.starts_with('\'') || s.starts_with("r\"") || s.starts_with("r#")
 }
 
 /// Check if a literal is a backtick template literal marker: "'^...^'"
 /// This syntax outputs JS template literals with backticks: `...`
-fn is_backtick_template(lit: &proc_macro2::Literal) -> bool {
+pub(crate) fn is_backtick_template(lit: &proc_macro2::Literal) -> bool {
     let s = lit.to_string();
     // Check for "'^...^'" pattern (the outer quotes are part of the Rust string)
     if s.starts_with("\"'^") && s.ends_with("^'\"") && s.len() >= 6 {
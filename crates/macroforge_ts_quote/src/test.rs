@@ -846,3 +846,226 @@ fn test_comment_in_context() {
         s
     );
 }
+
+// ============================================================================
+// Compile-time validation tests
+// ============================================================================
+
+#[cfg(feature = "validate")]
+mod validate_tests {
+    use crate::validate::{TemplateKind, validate_template};
+    use proc_macro2::TokenStream as TokenStream2;
+    use std::str::FromStr;
+
+    fn check(src: &str, kind: TemplateKind) -> Result<(), String> {
+        let input = TokenStream2::from_str(src).unwrap();
+        validate_template(&input, kind).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_valid_module_template() {
+        let result = check(
+            r#"
+            export namespace @{name} {
+                export function {|from@{suffix}|}(value: string): @{ty} {
+                    {#if cond}
+                    return @{expr};
+                    {:else}
+                    throw new Error("@{name} failed");
+                    {/if}
+                }
+            }
+            "#,
+            TemplateKind::Module,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_valid_class_body_template() {
+        let result = check(
+            r#"
+            static defaultValue(): @{class_name} {
+                const instance = new @{class_name}();
+                {#for field in fields}
+                instance.@{field.name} = @{field.value};
+                {/for}
+                return instance;
+            }
+            "#,
+            TemplateKind::ClassBody,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_placeholder_glued_to_ident_is_a_suffix() {
+        // `validateField@{generics}(...)` renders as `validateField<K>(...)`
+        let result = check(
+            r#"
+            export function validateField@{generic_decl}(field: K): void {}
+            "#,
+            TemplateKind::Module,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_placeholder_statements_and_members() {
+        // Interpolations on their own line may stand for whole statements
+        let result = check(
+            r#"
+            toString(): string {
+                const parts: string[] = [];
+                @{field_pushes}
+                return parts.join(", ");
+            }
+            @{extra_members}
+            "#,
+            TemplateKind::ClassBody,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_switch_and_object_bodies() {
+        let result = check(
+            r#"
+            export function check(field: string) {
+                const opts = { a: 1, ...rest };
+                switch (field) {
+                    case "a": {
+                        break;
+                    }
+                }
+            }
+            "#,
+            TemplateKind::Module,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_invalid_template_reports_error() {
+        let err = check(
+            r#"
+            export function broken(value: string) {
+                const x = ;
+            }
+            "#,
+            TemplateKind::Module,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("template produces invalid TypeScript"),
+            "unexpected error: {err}"
+        );
+        assert!(err.contains("const x"), "error should quote nearby code: {err}");
+    }
+
+    #[test]
+    fn test_error_inside_namespace_points_at_inner_code() {
+        // SWC backtracks out of `export namespace`, so the error must be
+        // located by descending into the namespace body.
+        let err = check(
+            r#"
+            export namespace @{name} {
+                export function ok() {}
+                export function broken(a: string b: number) {}
+            }
+            "#,
+            TemplateKind::Module,
+        )
+        .unwrap_err();
+        assert!(err.contains("broken"), "error should point into the body: {err}");
+    }
+
+    #[test]
+    fn test_branches_are_checked_separately() {
+        // Each branch of an `{#if}` is alternative output; they are never
+        // assembled into the same skeleton.
+        let result = check(
+            r#"
+            {#if is_async}
+            async function run() {}
+            {:else}
+            function run() {}
+            {/if}
+            "#,
+            TemplateKind::Module,
+        );
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_error_in_else_branch_is_reported() {
+        let err = check(
+            r#"
+            {#if is_async}
+            async function run() {}
+            {:else if is_gen}
+            function* run() {}
+            {:else}
+            function run(a: string b: number) {}
+            {/if}
+            "#,
+            TemplateKind::Module,
+        )
+        .unwrap_err();
+        assert!(err.contains("function run"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_error_in_later_match_arm_is_reported() {
+        let err = check(
+            r#"
+            {#match kind}
+            {:case Kind::A}
+            const a = 1;
+            {:case Kind::B}
+            const b = 2;
+            {:case _}
+            const c = ;
+            {/match}
+            "#,
+            TemplateKind::Module,
+        )
+        .unwrap_err();
+        assert!(err.contains("const c"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_branches_of_different_conditionals_are_combined() {
+        // Only the first branch of one `{#if}` with the second branch of the
+        // other is invalid, so checking branch `i` of both is not enough.
+        let err = check(
+            r#"
+            {#if split}
+            const a =
+            {:else}
+            const a = 1;
+            {/if}
+            {#if value}
+            2;
+            {:else}
+            const b = 2;
+            {/if}
+            "#,
+            TemplateKind::Module,
+        )
+        .unwrap_err();
+        assert!(err.contains("template produces invalid TypeScript"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_recovered_parse_errors_are_reported() {
+        let err = check("const mode = 010;", TemplateKind::Module).unwrap_err();
+        assert!(err.contains("const mode"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_placeholder_only_template_is_skipped() {
+        assert!(check("@{code}", TemplateKind::Module).is_ok());
+        assert!(check("{$typescript stream}", TemplateKind::ClassBody).is_ok());
+    }
+}
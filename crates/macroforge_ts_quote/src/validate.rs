@@ -0,0 +1,502 @@
+//! Compile-time validation of template output
+//!
+//! Templates are normally only parsed as TypeScript when the macro runs, so a
+//! typo inside `ts_template!` surfaces as a runtime parse error far away from
+//! the Rust source. This module builds a static *skeleton* of the code a
+//! template produces and parses it with SWC while the proc macro expands:
+//!
+//! - `@{expr}` and `{$typescript ...}` become placeholder identifiers (`__mf0`, ...)
+//! - string and backtick literals become empty placeholders of the same kind
+//! - `{#if}` / `{#match}` contribute one branch / arm per pass; every
+//!   combination of branches is checked, up to [`MAX_COMBINATIONS`] passes.
+//!   Beyond that, pass `i` takes branch `i` of every conditional, so each
+//!   branch is still parsed but not every mix of branches from different
+//!   conditionals is
+//! - `{#for}` / `{#while}` bodies are emitted once
+//! - `{$let}`, `{$do}` and comment tags contribute nothing
+//!
+//! Every chunk of the skeleton remembers the Rust span it came from, so a
+//! parse error can be reported with `compile_error!` on the offending tokens.
+//!
+//! Because interpolations can stand for anything from an identifier to a
+//! whole class member, the skeleton is tried in several parse contexts and an
+//! error is only reported when none of them accepts it.
+
+use crate::template::{TagType, analyze_tag, is_backtick_template, is_string_literal};
+use proc_macro2::{Delimiter, Group, LineColumn, Span, TokenStream as TokenStream2, TokenTree};
+use std::iter::Peekable;
+use swc_core::common::{BytePos, Spanned};
+use swc_core::ecma::ast::EsVersion;
+use swc_core::ecma::parser::{Parser, StringInput, Syntax, TsSyntax, lexer::Lexer};
+
+/// Where the template output ends up, which decides the preferred parse context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// `ts_template!`, `above!`, `below!` - module items
+    Module,
+    /// `body!`, `signature!` - class members
+    ClassBody,
+}
+
+/// Parse contexts a skeleton may be valid in: (prefix, suffix)
+const MODULE_CONTEXT: (&str, &str) = ("", "");
+const CLASS_CONTEXT: (&str, &str) = ("class __MfCheck {\n", "\n}");
+const OBJECT_CONTEXT: (&str, &str) = ("(\n{", "}\n);");
+const TYPE_CONTEXT: (&str, &str) = ("type __MfCheck = {\n", "\n};");
+const EXPR_CONTEXT: (&str, &str) = ("(\n", "\n);");
+const SWITCH_CONTEXT: (&str, &str) = ("switch (0) {\n", "\n}");
+const ENUM_CONTEXT: (&str, &str) = ("enum __MfCheck {\n", "\n}");
+
+/// Contexts tried for the contents of a nested `{ ... }` block
+const BLOCK_CONTEXTS: &[(&str, &str)] = &[
+    MODULE_CONTEXT,
+    CLASS_CONTEXT,
+    OBJECT_CONTEXT,
+    TYPE_CONTEXT,
+    SWITCH_CONTEXT,
+    ENUM_CONTEXT,
+];
+
+/// Branch combinations checked before falling back to one pass per branch index
+const MAX_COMBINATIONS: usize = 64;
+
+/// Validates that the template produces syntactically valid TypeScript.
+///
+/// Returns an error spanning the Rust tokens closest to the parse failure.
+pub fn validate_template(input: &TokenStream2, kind: TemplateKind) -> syn::Result<()> {
+    let pass = |choices: Vec<usize>, default_branch: usize| -> syn::Result<Skeleton> {
+        let mut skeleton = Skeleton {
+            choices,
+            default_branch,
+            ..Skeleton::default()
+        };
+        skeleton.walk(&mut input.clone().into_iter().peekable(), true);
+        if skeleton.has_static_code() {
+            skeleton.check(kind)?;
+        }
+        Ok(skeleton)
+    };
+
+    // Depth-first over the emitted conditionals: advance the last one that
+    // has another branch and reset everything after it.
+    let mut choices = Vec::new();
+    let mut max_branches = 1;
+    for _ in 0..MAX_COMBINATIONS {
+        let skeleton = pass(choices, 0)?;
+        max_branches = max_branches.max(skeleton.arities.iter().copied().max().unwrap_or(1));
+        let Some(next) = (0..skeleton.taken.len())
+            .rev()
+            .find(|&k| skeleton.taken[k] + 1 < skeleton.arities[k])
+        else {
+            return Ok(());
+        };
+        choices = skeleton.taken[..next].to_vec();
+        choices.push(skeleton.taken[next] + 1);
+    }
+
+    for branch in 1..max_branches {
+        pass(Vec::new(), branch)?;
+    }
+    Ok(())
+}
+
+impl Skeleton {
+    fn check(&self, kind: TemplateKind) -> syn::Result<()> {
+        let contexts: &[(&str, &str)] = match kind {
+            TemplateKind::Module => &[
+                MODULE_CONTEXT,
+                CLASS_CONTEXT,
+                OBJECT_CONTEXT,
+                TYPE_CONTEXT,
+                EXPR_CONTEXT,
+            ],
+            TemplateKind::ClassBody => &[CLASS_CONTEXT, MODULE_CONTEXT],
+        };
+
+        match self.locate_error(0, self.src.len(), contexts) {
+            None => Ok(()),
+            Some((offset, message)) => Err(syn::Error::new(
+                self.span_at(offset),
+                format!(
+                    "template produces invalid TypeScript: {message}\n  near: `{}`",
+                    self.excerpt(offset)
+                ),
+            )),
+        }
+    }
+}
+
+/// Parses `src` in each context, returning `None` as soon as one accepts it.
+///
+/// Otherwise keeps the failure that got furthest into `src`; it is the most
+/// likely interpretation of what the author meant.
+fn parse_in_any_context(src: &str, contexts: &[(&str, &str)]) -> Option<(usize, String)> {
+    let mut best: Option<(usize, String)> = None;
+    for (prefix, suffix) in contexts {
+        let (offset, message) = parse_in_context(src, prefix, suffix).err()?;
+        if best.as_ref().is_none_or(|(o, _)| offset > *o) {
+            best = Some((offset, message));
+        }
+    }
+    best
+}
+
+/// Parses `src` wrapped in `prefix`/`suffix` as a module.
+///
+/// On failure returns the error offset relative to `src` and the message.
+fn parse_in_context(src: &str, prefix: &str, suffix: &str) -> Result<(), (usize, String)> {
+    let wrapped = format!("{prefix}{src}{suffix}");
+    // Position 0 is SWC's dummy position, so start the input at 1.
+    let start = BytePos(1);
+    let end = BytePos(1 + wrapped.len() as u32);
+
+    let syntax = Syntax::Typescript(TsSyntax {
+        decorators: true,
+        ..Default::default()
+    });
+    let lexer = Lexer::new(
+        syntax,
+        EsVersion::latest(),
+        StringInput::new(&wrapped, start, end),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+
+    // Errors SWC recovered from still mean the output is invalid
+    let result = parser.parse_module();
+    let err = match result {
+        Ok(_) => match parser.take_errors().into_iter().next() {
+            Some(err) => err,
+            None => return Ok(()),
+        },
+        Err(err) => err,
+    };
+    let pos = err.span().lo.0.saturating_sub(1) as usize;
+    let offset = pos.saturating_sub(prefix.len()).min(src.len());
+    Err((offset, err.kind().msg().to_string()))
+}
+
+/// Static approximation of a template's output with a span for every chunk.
+#[derive(Default)]
+struct Skeleton {
+    src: String,
+    /// (start, end, span) for each chunk pushed into `src`
+    chunks: Vec<(usize, usize, Span)>,
+    /// Line on which the previous chunk ended
+    line: usize,
+    /// Position where the previous chunk ended
+    end: Option<LineColumn>,
+    /// Whether the previous chunk was an identifier
+    ident: bool,
+    /// Whether the previous chunk was a joint punctuation character
+    joint: bool,
+    /// Counter for placeholder names
+    placeholders: usize,
+    /// Number of chunks that are nothing but a placeholder
+    placeholder_chunks: usize,
+    /// Inner ranges of every `{ ... }` block, in source order
+    blocks: Vec<(usize, usize)>,
+    /// Branch / arm to take for each emitted conditional, in walk order
+    choices: Vec<usize>,
+    /// Branch taken by conditionals past the end of `choices`; those with
+    /// fewer branches take their last one
+    default_branch: usize,
+    /// Branch / arm taken by each emitted conditional, in walk order
+    taken: Vec<usize>,
+    /// Number of branches / arms of each emitted conditional, in walk order
+    arities: Vec<usize>,
+}
+
+impl Skeleton {
+    fn has_static_code(&self) -> bool {
+        self.chunks.len() > self.placeholder_chunks
+    }
+
+    /// Appends `text`, separating it from the previous chunk the way the
+    /// author laid out the Rust tokens (newline if the line changed).
+    fn push(&mut self, text: &str, span: Span, joint: bool) {
+        let line = span.start().line;
+        if !self.src.is_empty() && !self.joint {
+            if line > self.line {
+                self.src.push('\n');
+            } else {
+                self.src.push(' ');
+            }
+        }
+        let start = self.src.len();
+        self.src.push_str(text);
+        self.chunks.push((start, self.src.len(), span));
+        self.line = span.end().line;
+        self.end = Some(span.end());
+        self.joint = joint;
+        self.ident = text.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$');
+    }
+
+    /// Whether `span` directly follows an identifier with no whitespace in between.
+    fn glued_to_previous(&self, span: Span) -> bool {
+        self.ident && self.end == Some(span.start())
+    }
+
+    fn push_placeholder(&mut self, span: Span) {
+        let name = format!("__mf{}", self.placeholders);
+        self.placeholders += 1;
+        self.placeholder_chunks += 1;
+        self.push(&name, span, false);
+    }
+
+    /// Finds the innermost parse error in `src[start..end]`.
+    ///
+    /// SWC backtracks out of some constructs (`export namespace X { ... }`)
+    /// and reports the error at their start, so when a range fails we look
+    /// for a nested block that fails on its own and report that instead.
+    fn locate_error(
+        &self,
+        start: usize,
+        end: usize,
+        contexts: &[(&str, &str)],
+    ) -> Option<(usize, String)> {
+        let (offset, message) = parse_in_any_context(&self.src[start..end], contexts)?;
+        for (block_start, block_end) in self.child_blocks(start, end) {
+            if let Some(inner) = self.locate_error(block_start, block_end, BLOCK_CONTEXTS) {
+                return Some(inner);
+            }
+        }
+        Some((start + offset, message))
+    }
+
+    /// Inner ranges of the outermost `{ ... }` blocks within `start..end`.
+    fn child_blocks(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut children: Vec<(usize, usize)> = Vec::new();
+        for &(block_start, block_end) in &self.blocks {
+            let inside = block_start >= start && block_end <= end && (block_start, block_end) != (start, end);
+            let nested = children.last().is_some_and(|&(_, last_end)| block_start < last_end);
+            if inside && !nested {
+                children.push((block_start, block_end));
+            }
+        }
+        children
+    }
+
+    /// Span of the chunk containing `offset`, or the closest chunk before it.
+    fn span_at(&self, offset: usize) -> Span {
+        self.chunks
+            .iter()
+            .rev()
+            .find(|(start, _, _)| *start <= offset)
+            .or(self.chunks.first())
+            .map(|(_, _, span)| *span)
+            .unwrap_or_else(Span::call_site)
+    }
+
+    fn excerpt(&self, offset: usize) -> String {
+        let mut start = offset.saturating_sub(30);
+        while !self.src.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (offset + 30).min(self.src.len());
+        while !self.src.is_char_boundary(end) {
+            end += 1;
+        }
+        self.src[start..end].split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Walks template tokens, mirroring `parse_fragment`. Returns when a
+    /// branch tag (`{:else}`, `{:case}`) or end tag is reached.
+    fn walk(
+        &mut self,
+        iter: &mut Peekable<proc_macro2::token_stream::IntoIter>,
+        emit: bool,
+    ) -> Option<Closer> {
+        while let Some(token) = iter.next() {
+            match token {
+                TokenTree::Punct(p) if p.as_char() == '@' => {
+                    if matches!(iter.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace)
+                    {
+                        let group = iter.next().expect("peeked");
+                        // `name@{suffix}` glues the value onto the previous
+                        // identifier (type arguments, generic parameters);
+                        // leaving it out keeps the skeleton well-formed.
+                        if emit && !self.glued_to_previous(p.span()) {
+                            self.push_placeholder(group.span());
+                        }
+                    } else if emit {
+                        self.push("@", p.span(), false);
+                    }
+                }
+                TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => match analyze_tag(&g) {
+                    TagType::If(_) | TagType::IfLet(_, _) => {
+                        let count = self.count_segments(iter);
+                        let chosen = self.choose_branch(count, emit);
+                        let mut index = 0;
+                        while let Some(Closer::Branch) = self.walk(iter, emit && index == chosen) {
+                            index += 1;
+                        }
+                    }
+                    TagType::Match(_) => {
+                        // The segment before the first `{:case}` is not an arm
+                        let count = self.count_segments(iter);
+                        let chosen = 1 + self.choose_branch(count.saturating_sub(1).max(1), emit);
+                        let mut index = 0;
+                        while let Some(Closer::Branch) = self.walk(iter, emit && index == chosen) {
+                            index += 1;
+                        }
+                    }
+                    TagType::For(_, _) | TagType::While(_) | TagType::WhileLet(_, _) => {
+                        self.walk(iter, emit);
+                    }
+                    TagType::Else | TagType::ElseIf(_) | TagType::Case(_) => {
+                        return Some(Closer::Branch);
+                    }
+                    TagType::EndIf | TagType::EndFor | TagType::EndWhile | TagType::EndMatch => {
+                        return Some(Closer::End);
+                    }
+                    TagType::Let(_)
+                    | TagType::LetMut(_)
+                    | TagType::Do(_)
+                    | TagType::BlockComment(_)
+                    | TagType::DocComment(_) => {}
+                    TagType::Typescript(_) => {
+                        if emit {
+                            self.push_placeholder(g.span());
+                        }
+                    }
+                    TagType::IdentBlock => {
+                        if emit {
+                            let tokens: Vec<TokenTree> = g.stream().into_iter().collect();
+                            let inner = &tokens[1..tokens.len() - 1];
+                            let text = ident_block_text(inner, &mut self.placeholders);
+                            self.push(&text, g.span(), false);
+                        }
+                    }
+                    TagType::Block => self.walk_group(&g, emit),
+                },
+                TokenTree::Group(g) => self.walk_group(&g, emit),
+                TokenTree::Literal(lit) => {
+                    if emit {
+                        let replacement = if is_backtick_template(&lit) {
+                            "``".to_string()
+                        } else if is_string_literal(&lit) {
+                            "\"\"".to_string()
+                        } else {
+                            lit.to_string()
+                        };
+                        self.push(&replacement, lit.span(), false);
+                    }
+                }
+                TokenTree::Punct(p) => {
+                    if emit {
+                        let joint = p.spacing() == proc_macro2::Spacing::Joint;
+                        self.push(&p.as_char().to_string(), p.span(), joint);
+                    }
+                }
+                TokenTree::Ident(ident) => {
+                    if emit {
+                        self.push(&ident.to_string(), ident.span(), false);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Skeleton {
+    /// Number of segments (separated by `{:else}` / `{:case}`) up to the end
+    /// tag of the conditional whose opening tag was just consumed.
+    fn count_segments(&mut self, iter: &Peekable<proc_macro2::token_stream::IntoIter>) -> usize {
+        let mut lookahead = iter.clone();
+        let ident = self.ident;
+        let mut count = 1;
+        while let Some(Closer::Branch) = self.walk(&mut lookahead, false) {
+            count += 1;
+        }
+        self.ident = ident;
+        count
+    }
+
+    /// Branch this pass contributes out of `count` alternatives. Only
+    /// conditionals that are emitted count as a choice.
+    fn choose_branch(&mut self, count: usize, emit: bool) -> usize {
+        if !emit {
+            return 0;
+        }
+        let index = self.taken.len();
+        let chosen = self
+            .choices
+            .get(index)
+            .copied()
+            .unwrap_or(self.default_branch)
+            .min(count - 1);
+        self.taken.push(chosen);
+        self.arities.push(count);
+        chosen
+    }
+
+    fn walk_group(&mut self, g: &Group, emit: bool) {
+        let (open, close) = match g.delimiter() {
+            Delimiter::Parenthesis => ("(", ")"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::Brace => ("{", "}"),
+            Delimiter::None => ("", ""),
+        };
+        if emit {
+            self.push(open, g.span_open(), false);
+        }
+        let inner_start = self.src.len();
+        // A placeholder right after `{` belongs to the block, not the
+        // identifier before it.
+        self.ident = false;
+        self.walk(&mut g.stream().into_iter().peekable(), emit);
+        if emit {
+            let inner_end = self.src.len();
+            self.push(close, g.span_close(), false);
+            if g.delimiter() == Delimiter::Brace {
+                let block = (inner_start, inner_end);
+                let at = self.blocks.partition_point(|b| *b < block);
+                self.blocks.insert(at, block);
+            }
+        }
+    }
+}
+
+/// Why a nested walk stopped
+enum Closer {
+    /// `{:else}`, `{:else if}` or `{:case}` - another branch follows
+    Branch,
+    /// `{/if}`, `{/for}`, `{/while}` or `{/match}`
+    End,
+}
+
+/// Renders the contents of a `{| ... |}` block without spacing, substituting placeholders.
+fn ident_block_text(tokens: &[TokenTree], placeholders: &mut usize) -> String {
+    let mut out = String::new();
+    let mut iter = tokens.iter().peekable();
+    while let Some(token) = iter.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '@' => {
+                if matches!(iter.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace)
+                {
+                    iter.next();
+                    out.push_str(&format!("__mf{placeholders}"));
+                    *placeholders += 1;
+                } else {
+                    out.push('@');
+                }
+            }
+            TokenTree::Group(g) => {
+                let (open, close) = match g.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                let inner: Vec<TokenTree> = g.stream().into_iter().collect();
+                out.push_str(open);
+                out.push_str(&ident_block_text(&inner, placeholders));
+                out.push_str(close);
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+    out
+}
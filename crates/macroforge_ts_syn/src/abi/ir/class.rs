@@ -1,7 +1,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::abi::{swc_ast, DecoratorIR, SpanIR};
+use crate::abi::{swc_ast, DecoratorIR, SpanIR, TypeIR};
 
 #[derive(Serialize, Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
@@ -27,6 +27,9 @@ pub struct FieldIR {
     pub name: String,
     pub span: SpanIR,
     pub ts_type: String, // keep as string in v0
+    /// Structured form of `ts_type`
+    #[serde(default)]
+    pub type_ir: TypeIR,
     #[serde(skip)]
     pub type_ann: Option<Box<swc_ast::TsType>>,
     pub optional: bool,
@@ -2,6 +2,7 @@
 
 use crate::abi::{ClassIR, EnumIR, InterfaceIR, SpanIR, TypeAliasIR};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The kind of macro being executed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -66,6 +67,17 @@ pub struct MacroContextIR {
     /// The source code of the target (class, enum, etc.)
     /// This enables macros to parse the source themselves using TsStream
     pub target_source: String,
+
+    /// Project-wide defaults for this macro, from the `macroOptions` section of
+    /// the macroforge config (`null` when none are configured).
+    /// Options given on the decorator itself take precedence.
+    #[serde(default)]
+    pub macro_options: serde_json::Value,
+
+    /// Every derive target in the same file, by name, with the macros it derives.
+    /// Lets a macro tell whether the type of a field derives it too.
+    #[serde(default)]
+    pub file_derives: BTreeMap<String, Vec<String>>,
 }
 
 impl MacroContextIR {
@@ -90,6 +102,8 @@ impl MacroContextIR {
             file_name,
             target: TargetIR::Class(class),
             target_source,
+            macro_options: serde_json::Value::Null,
+            file_derives: BTreeMap::new(),
         }
     }
 
@@ -99,6 +113,35 @@ impl MacroContextIR {
         self
     }
 
+    /// Set the project-wide macro options (builder pattern)
+    pub fn with_macro_options(mut self, options: serde_json::Value) -> Self {
+        self.macro_options = options;
+        self
+    }
+
+    /// Set the derive targets of the file (builder pattern)
+    pub fn with_file_derives(mut self, file_derives: BTreeMap<String, Vec<String>>) -> Self {
+        self.file_derives = file_derives;
+        self
+    }
+
+    /// Whether `type_name` is declared in the same file and derives one of `macro_names`
+    pub fn derives(&self, type_name: &str, macro_names: &[&str]) -> bool {
+        self.file_derives
+            .get(type_name)
+            .is_some_and(|derived| derived.iter().any(|m| macro_names.contains(&m.as_str())))
+    }
+
+    /// String value of a project-wide option, e.g. `renameAll`
+    pub fn option_str(&self, key: &str) -> Option<&str> {
+        self.macro_options.get(key)?.as_str()
+    }
+
+    /// Boolean value of a project-wide option, e.g. `denyUnknownFields`
+    pub fn option_bool(&self, key: &str) -> Option<bool> {
+        self.macro_options.get(key)?.as_bool()
+    }
+
     /// Get the best span for error reporting - prefers macro_name_span if available
     pub fn error_span(&self) -> SpanIR {
         self.macro_name_span.unwrap_or(self.decorator_span)
@@ -157,6 +200,8 @@ impl MacroContextIR {
             file_name,
             target: TargetIR::Interface(interface),
             target_source,
+            macro_options: serde_json::Value::Null,
+            file_derives: BTreeMap::new(),
         }
     }
 
@@ -181,6 +226,8 @@ impl MacroContextIR {
             file_name,
             target: TargetIR::TypeAlias(type_alias),
             target_source,
+            macro_options: serde_json::Value::Null,
+            file_derives: BTreeMap::new(),
         }
     }
 
@@ -205,6 +252,8 @@ impl MacroContextIR {
             file_name,
             target: TargetIR::Enum(enum_ir),
             target_source,
+            macro_options: serde_json::Value::Null,
+            file_derives: BTreeMap::new(),
         }
     }
 }
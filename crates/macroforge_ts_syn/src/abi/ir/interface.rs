@@ -1,7 +1,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::abi::{DecoratorIR, SpanIR};
+use crate::abi::{DecoratorIR, SpanIR, TypeIR};
 
 /// Interface IR for derive macros targeting TypeScript interfaces.
 /// Similar to ClassIR but for interface declarations.
@@ -26,6 +26,9 @@ pub struct InterfaceFieldIR {
     pub name: String,
     pub span: SpanIR,
     pub ts_type: String,
+    /// Structured form of `ts_type`
+    #[serde(default)]
+    pub type_ir: TypeIR,
     pub optional: bool,
     pub readonly: bool,
     pub decorators: Vec<DecoratorIR>,
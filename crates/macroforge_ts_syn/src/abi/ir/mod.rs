@@ -4,6 +4,7 @@ pub mod decorators;
 pub mod enum_;
 pub mod interface;
 pub mod type_alias;
+pub mod type_ir;
 
 pub use class::*;
 pub use context::*;
@@ -11,3 +12,4 @@ pub use decorators::*;
 pub use enum_::*;
 pub use interface::*;
 pub use type_alias::*;
+pub use type_ir::*;
@@ -0,0 +1,309 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Structured TypeScript type, lowered from the SWC `TsType`.
+///
+/// Exposed next to the raw `ts_type` strings so macros can inspect types
+/// without re-parsing them. Types without a dedicated variant (mapped,
+/// conditional, `typeof x`, ...) are kept as source text in [`TypeIR::Other`].
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TypeIR {
+    /// `string`, `number`, `boolean`, `bigint`, `symbol`, `object`, `any`,
+    /// `unknown`, `never`, `void`, `null`, `undefined`, `this`
+    Keyword { name: String },
+    /// `User`, `Map<K, V>`, `ns.Type<T>`
+    Reference {
+        name: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        type_args: Vec<TypeIR>,
+    },
+    /// `T[]` (`Array<T>` stays a reference)
+    Array { element: Box<TypeIR> },
+    /// `[A, b?: B, ...C[]]`
+    Tuple { elements: Vec<TupleElementIR> },
+    Union { types: Vec<TypeIR> },
+    Intersection { types: Vec<TypeIR> },
+    /// `"active"`, `42`, `true`, `10n`, `` `id-${string}` ``
+    Literal { value: LiteralTypeIR },
+    /// `(a: A, b?: B) => R` or `new (a: A) => R`
+    Function {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        type_params: Vec<String>,
+        params: Vec<FnParamIR>,
+        return_type: Box<TypeIR>,
+        #[serde(default)]
+        is_constructor: bool,
+    },
+    /// `{ a: A; b?: B; [key: string]: C }`
+    Object {
+        members: Vec<ObjectMemberIR>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        index_signatures: Vec<IndexSignatureIR>,
+    },
+    /// `T["key"]`
+    IndexedAccess {
+        object: Box<TypeIR>,
+        index: Box<TypeIR>,
+    },
+    /// `keyof T`, `readonly T[]`, `unique symbol`
+    Operator { op: String, operand: Box<TypeIR> },
+    /// Anything else, as written in the source
+    Other { source: String },
+}
+
+impl Default for TypeIR {
+    /// Unannotated members are treated as `any`, matching the `ts_type` string
+    fn default() -> Self {
+        Self::keyword("any")
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum LiteralTypeIR {
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    /// Digits without the `n` suffix
+    BigInt(String),
+    /// Template literal type, as written in the source (including backticks)
+    Template(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleElementIR {
+    pub label: Option<String>,
+    pub ty: TypeIR,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub rest: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FnParamIR {
+    pub name: String,
+    pub ty: TypeIR,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub rest: bool,
+}
+
+/// Property or method of an object literal type (methods use [`TypeIR::Function`])
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectMemberIR {
+    pub name: String,
+    pub ty: TypeIR,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub readonly: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexSignatureIR {
+    pub key_name: String,
+    pub key: TypeIR,
+    pub value: TypeIR,
+    #[serde(default)]
+    pub readonly: bool,
+}
+
+impl TypeIR {
+    pub fn keyword(name: impl Into<String>) -> Self {
+        Self::Keyword { name: name.into() }
+    }
+
+    pub fn reference(name: impl Into<String>, type_args: Vec<TypeIR>) -> Self {
+        Self::Reference {
+            name: name.into(),
+            type_args,
+        }
+    }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Self::Keyword { name } if name == keyword)
+    }
+
+    /// `null` or `undefined`
+    pub fn is_nullish(&self) -> bool {
+        self.is_keyword("null") || self.is_keyword("undefined")
+    }
+
+    /// Name and type arguments of a type reference
+    pub fn as_reference(&self) -> Option<(&str, &[TypeIR])> {
+        match self {
+            Self::Reference { name, type_args } => Some((name, type_args)),
+            _ => None,
+        }
+    }
+
+    /// Members of a union, or the type itself for non-unions
+    pub fn union_members(&self) -> &[TypeIR] {
+        match self {
+            Self::Union { types } => types,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    /// Remove the union members matching `remove`, collapsing a single survivor.
+    ///
+    /// Returns `None` when nothing is left.
+    pub fn without(&self, remove: impl Fn(&TypeIR) -> bool) -> Option<TypeIR> {
+        let mut rest: Vec<TypeIR> = self
+            .union_members()
+            .iter()
+            .filter(|t| !remove(t))
+            .cloned()
+            .collect();
+        match rest.len() {
+            0 => None,
+            1 => rest.pop(),
+            _ => Some(Self::Union { types: rest }),
+        }
+    }
+
+    /// Element type of `T[]`, `readonly T[]` or `Array<T>` / `ReadonlyArray<T>`
+    pub fn array_element(&self) -> Option<&TypeIR> {
+        match self {
+            Self::Array { element } => Some(element),
+            Self::Operator { op, operand } if op == "readonly" => operand.array_element(),
+            Self::Reference { name, type_args }
+                if (name == "Array" || name == "ReadonlyArray") && type_args.len() == 1 =>
+            {
+                type_args.first()
+            }
+            _ => None,
+        }
+    }
+
+    fn needs_parens_in_array(&self) -> bool {
+        matches!(
+            self,
+            Self::Union { .. } | Self::Intersection { .. } | Self::Function { .. } | Self::Operator { .. }
+        )
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, items: &[TypeIR], sep: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        // Function types must be parenthesized inside unions and intersections
+        if matches!(item, TypeIR::Function { .. }) && sep != ", " {
+            write!(f, "({item})")?;
+        } else {
+            write!(f, "{item}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the type back to TypeScript source in a normalized form
+impl fmt::Display for TypeIR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keyword { name } => f.write_str(name),
+            Self::Reference { name, type_args } => {
+                f.write_str(name)?;
+                if !type_args.is_empty() {
+                    f.write_str("<")?;
+                    write_list(f, type_args, ", ")?;
+                    f.write_str(">")?;
+                }
+                Ok(())
+            }
+            Self::Array { element } if element.needs_parens_in_array() => {
+                write!(f, "({element})[]")
+            }
+            Self::Array { element } => write!(f, "{element}[]"),
+            Self::Tuple { elements } => {
+                f.write_str("[")?;
+                for (i, elem) in elements.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    if elem.rest {
+                        f.write_str("...")?;
+                    }
+                    match &elem.label {
+                        Some(label) => {
+                            let optional = if elem.optional { "?" } else { "" };
+                            write!(f, "{label}{optional}: {}", elem.ty)?;
+                        }
+                        None if elem.optional => write!(f, "{}?", elem.ty)?,
+                        None => write!(f, "{}", elem.ty)?,
+                    }
+                }
+                f.write_str("]")
+            }
+            Self::Union { types } => write_list(f, types, " | "),
+            Self::Intersection { types } => write_list(f, types, " & "),
+            Self::Literal { value } => match value {
+                LiteralTypeIR::String(s) => write!(f, "{s:?}"),
+                LiteralTypeIR::Number(n) => write!(f, "{n}"),
+                LiteralTypeIR::Boolean(b) => write!(f, "{b}"),
+                LiteralTypeIR::BigInt(digits) => write!(f, "{digits}n"),
+                LiteralTypeIR::Template(src) => f.write_str(src),
+            },
+            Self::Function {
+                type_params,
+                params,
+                return_type,
+                is_constructor,
+            } => {
+                if *is_constructor {
+                    f.write_str("new ")?;
+                }
+                if !type_params.is_empty() {
+                    write!(f, "<{}>", type_params.join(", "))?;
+                }
+                f.write_str("(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    let rest = if param.rest { "..." } else { "" };
+                    let optional = if param.optional { "?" } else { "" };
+                    write!(f, "{rest}{}{optional}: {}", param.name, param.ty)?;
+                }
+                write!(f, ") => {return_type}")
+            }
+            Self::Object {
+                members,
+                index_signatures,
+            } => {
+                if members.is_empty() && index_signatures.is_empty() {
+                    return f.write_str("{}");
+                }
+                f.write_str("{ ")?;
+                for sig in index_signatures {
+                    let readonly = if sig.readonly { "readonly " } else { "" };
+                    write!(f, "{readonly}[{}: {}]: {}; ", sig.key_name, sig.key, sig.value)?;
+                }
+                for member in members {
+                    let readonly = if member.readonly { "readonly " } else { "" };
+                    let optional = if member.optional { "?" } else { "" };
+                    write!(f, "{readonly}{}{optional}: {}; ", member.name, member.ty)?;
+                }
+                f.write_str("}")
+            }
+            Self::IndexedAccess { object, index } if object.needs_parens_in_array() => {
+                write!(f, "({object})[{index}]")
+            }
+            Self::IndexedAccess { object, index } => write!(f, "{object}[{index}]"),
+            Self::Operator { op, operand } => write!(f, "{op} {operand}"),
+            Self::Other { source } => f.write_str(source),
+        }
+    }
+}
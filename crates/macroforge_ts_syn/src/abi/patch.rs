@@ -194,8 +194,11 @@ pub struct MacroResult {
     pub debug: Option<String>,
 }
 
+/// Build with [`Diagnostic::new`] and the `with_*` methods; fields may be
+/// added in minor releases.
 #[derive(Serialize, Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub struct Diagnostic {
     pub level: DiagnosticLevel,
     pub message: String,
@@ -204,6 +207,43 @@ pub struct Diagnostic {
     pub notes: Vec<String>,
     /// Optional help text suggesting fixes
     pub help: Option<String>,
+    /// Stable machine-readable code (e.g. `macro-not-found`) for tooling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(level: DiagnosticLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            span: None,
+            notes: vec![],
+            help: None,
+            code: None,
+        }
+    }
+
+    /// Accepts a `SpanIR` or an `Option<SpanIR>`
+    pub fn with_span(mut self, span: impl Into<Option<SpanIR>>) -> Self {
+        self.span = span.into();
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -535,7 +535,7 @@ macro_rules! parse_ts_macro_input {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::abi::{MacroKind, SpanIR};
+    use crate::abi::{MacroKind, SpanIR, TypeIR};
 
     fn make_test_class_context() -> MacroContextIR {
         MacroContextIR {
@@ -560,6 +560,7 @@ mod tests {
                         name: "id".into(),
                         span: SpanIR::new(25, 35),
                         ts_type: "number".into(),
+                        type_ir: TypeIR::keyword("number"),
                         type_ann: None,
                         optional: false,
                         readonly: false,
@@ -571,6 +572,7 @@ mod tests {
                         name: "name".into(),
                         span: SpanIR::new(40, 55),
                         ts_type: "string".into(),
+                        type_ir: TypeIR::keyword("string"),
                         type_ann: None,
                         optional: false,
                         readonly: false,
@@ -584,6 +586,8 @@ mod tests {
                 members: vec![],
             }),
             target_source: "class User { id: number; name: string; }".into(),
+            macro_options: Default::default(),
+            file_derives: Default::default(),
         }
     }
 
@@ -639,6 +643,8 @@ mod tests {
                 is_const: false,
             }),
             target_source: "enum Status { Active, Inactive }".into(),
+            macro_options: Default::default(),
+            file_derives: Default::default(),
         };
 
         let input = DeriveInput::from_context(ctx).expect("should parse");
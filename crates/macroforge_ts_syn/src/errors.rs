@@ -32,13 +32,7 @@ impl MacroforgeError {
     }
 
     pub fn to_diagnostic(self) -> Diagnostic {
-        Diagnostic {
-            level: DiagnosticLevel::Error,
-            message: self.message,
-            span: self.span,
-            notes: vec![],
-            help: None,
-        }
+        Diagnostic::new(DiagnosticLevel::Error, self.message).with_span(self.span)
     }
 }
 
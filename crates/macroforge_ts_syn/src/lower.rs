@@ -111,7 +111,7 @@ impl<'a> Visit for ClassCollector<'a> {
             span,
             body_span,
             is_abstract: n.class.is_abstract,
-            type_params: vec![],
+            type_params: type_param_names(n.class.type_params.as_deref()),
             heritage: vec![], // TODO: lower extends/implements
             decorators,
             decorators_ast: n.class.decorators.clone(),
@@ -204,7 +204,7 @@ impl<'a> Visit for TargetCollector<'a> {
             span,
             body_span,
             is_abstract: n.class.is_abstract,
-            type_params: vec![],
+            type_params: type_param_names(n.class.type_params.as_deref()),
             heritage: vec![],
             decorators,
             decorators_ast: n.class.decorators.clone(),
@@ -259,8 +259,8 @@ fn lower_interface(n: &TsInterfaceDecl, source: &str) -> Option<InterfaceIR> {
         name,
         span,
         body_span,
-        type_params: vec![], // TODO: extract type params
-        heritage: vec![],    // TODO: extract extends
+        type_params: type_param_names(n.type_params.as_deref()),
+        heritage: vec![], // TODO: extract extends
         decorators,
         fields,
         methods,
@@ -511,6 +511,237 @@ fn lower_type_member(ts_type: &TsType, source: &str) -> TypeMember {
     TypeMember::with_decorators(kind, decorators)
 }
 
+/// Lower an SWC type annotation into a structured [`TypeIR`].
+///
+/// Parentheses are dropped; types without a dedicated variant keep their
+/// source text in [`TypeIR::Other`].
+#[cfg(feature = "swc")]
+pub fn lower_type_ir(ts_type: &TsType, source: &str) -> TypeIR {
+    match ts_type {
+        TsType::TsKeywordType(k) => TypeIR::keyword(keyword_name(k.kind)),
+        TsType::TsThisType(_) => TypeIR::keyword("this"),
+        TsType::TsParenthesizedType(p) => lower_type_ir(&p.type_ann, source),
+        TsType::TsTypeRef(r) => TypeIR::Reference {
+            name: entity_name(&r.type_name),
+            type_args: r
+                .type_params
+                .as_ref()
+                .map(|args| args.params.iter().map(|t| lower_type_ir(t, source)).collect())
+                .unwrap_or_default(),
+        },
+        TsType::TsArrayType(a) => TypeIR::Array {
+            element: Box::new(lower_type_ir(&a.elem_type, source)),
+        },
+        TsType::TsTupleType(t) => TypeIR::Tuple {
+            elements: t
+                .elem_types
+                .iter()
+                .map(|elem| lower_tuple_element(elem, source))
+                .collect(),
+        },
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(u)) => {
+            TypeIR::Union {
+                types: u.types.iter().map(|t| lower_type_ir(t, source)).collect(),
+            }
+        }
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(i)) => {
+            TypeIR::Intersection {
+                types: i.types.iter().map(|t| lower_type_ir(t, source)).collect(),
+            }
+        }
+        TsType::TsLitType(lit) => TypeIR::Literal {
+            value: match &lit.lit {
+                TsLit::Str(s) => {
+                    LiteralTypeIR::String(String::from_utf8_lossy(s.value.as_bytes()).to_string())
+                }
+                TsLit::Number(n) => LiteralTypeIR::Number(n.value),
+                TsLit::Bool(b) => LiteralTypeIR::Boolean(b.value),
+                TsLit::BigInt(b) => LiteralTypeIR::BigInt(b.value.to_string()),
+                TsLit::Tpl(_) => LiteralTypeIR::Template(snippet(source, lit.span)),
+            },
+        },
+        TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(f)) => TypeIR::Function {
+            type_params: type_param_names(f.type_params.as_deref()),
+            params: f.params.iter().map(|p| lower_fn_param(p, source)).collect(),
+            return_type: Box::new(lower_type_ir(&f.type_ann.type_ann, source)),
+            is_constructor: false,
+        },
+        TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsConstructorType(c)) => {
+            TypeIR::Function {
+                type_params: type_param_names(c.type_params.as_deref()),
+                params: c.params.iter().map(|p| lower_fn_param(p, source)).collect(),
+                return_type: Box::new(lower_type_ir(&c.type_ann.type_ann, source)),
+                is_constructor: true,
+            }
+        }
+        TsType::TsTypeLit(lit) => lower_object_type_ir(&lit.members, source),
+        TsType::TsIndexedAccessType(i) => TypeIR::IndexedAccess {
+            object: Box::new(lower_type_ir(&i.obj_type, source)),
+            index: Box::new(lower_type_ir(&i.index_type, source)),
+        },
+        TsType::TsTypeOperator(op) => TypeIR::Operator {
+            op: match op.op {
+                TsTypeOperatorOp::KeyOf => "keyof",
+                TsTypeOperatorOp::Unique => "unique",
+                TsTypeOperatorOp::ReadOnly => "readonly",
+            }
+            .to_string(),
+            operand: Box::new(lower_type_ir(&op.type_ann, source)),
+        },
+        other => TypeIR::Other {
+            source: snippet(source, other.span()).trim().to_string(),
+        },
+    }
+}
+
+#[cfg(feature = "swc")]
+fn keyword_name(kind: TsKeywordTypeKind) -> &'static str {
+    match kind {
+        TsKeywordTypeKind::TsAnyKeyword => "any",
+        TsKeywordTypeKind::TsUnknownKeyword => "unknown",
+        TsKeywordTypeKind::TsNumberKeyword => "number",
+        TsKeywordTypeKind::TsObjectKeyword => "object",
+        TsKeywordTypeKind::TsBooleanKeyword => "boolean",
+        TsKeywordTypeKind::TsBigIntKeyword => "bigint",
+        TsKeywordTypeKind::TsStringKeyword => "string",
+        TsKeywordTypeKind::TsSymbolKeyword => "symbol",
+        TsKeywordTypeKind::TsVoidKeyword => "void",
+        TsKeywordTypeKind::TsUndefinedKeyword => "undefined",
+        TsKeywordTypeKind::TsNullKeyword => "null",
+        TsKeywordTypeKind::TsNeverKeyword => "never",
+        TsKeywordTypeKind::TsIntrinsicKeyword => "intrinsic",
+    }
+}
+
+#[cfg(feature = "swc")]
+fn entity_name(name: &TsEntityName) -> String {
+    match name {
+        TsEntityName::Ident(i) => i.sym.to_string(),
+        TsEntityName::TsQualifiedName(q) => format!("{}.{}", entity_name(&q.left), q.right.sym),
+    }
+}
+
+#[cfg(feature = "swc")]
+fn type_param_names(decl: Option<&TsTypeParamDecl>) -> Vec<String> {
+    decl.map(|d| d.params.iter().map(|p| p.name.sym.to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "swc")]
+fn lower_tuple_element(elem: &TsTupleElement, source: &str) -> TupleElementIR {
+    let (ty, optional, rest) = match &*elem.ty {
+        TsType::TsOptionalType(o) => (&*o.type_ann, true, false),
+        TsType::TsRestType(r) => (&*r.type_ann, false, true),
+        ty => (ty, false, false),
+    };
+    let (label, rest) = match &elem.label {
+        Some(Pat::Ident(i)) => (Some(i.id.sym.to_string()), rest),
+        Some(Pat::Rest(r)) => (r.arg.as_ident().map(|i| i.id.sym.to_string()), true),
+        _ => (None, rest),
+    };
+    // `[name?: T]` marks the label, not the type, as optional
+    let optional = optional || matches!(&elem.label, Some(Pat::Ident(i)) if i.optional);
+    TupleElementIR {
+        label,
+        ty: lower_type_ir(ty, source),
+        optional,
+        rest,
+    }
+}
+
+#[cfg(feature = "swc")]
+fn lower_fn_param(param: &TsFnParam, source: &str) -> FnParamIR {
+    let (name, type_ann, optional, rest) = match param {
+        TsFnParam::Ident(i) => (i.id.sym.to_string(), i.type_ann.as_deref(), i.id.optional, false),
+        TsFnParam::Rest(r) => (
+            r.arg
+                .as_ident()
+                .map(|i| i.id.sym.to_string())
+                .unwrap_or_else(|| snippet(source, r.arg.span())),
+            r.type_ann.as_deref(),
+            false,
+            true,
+        ),
+        TsFnParam::Array(a) => (snippet(source, a.span), a.type_ann.as_deref(), a.optional, false),
+        TsFnParam::Object(o) => (snippet(source, o.span), o.type_ann.as_deref(), o.optional, false),
+    };
+    FnParamIR {
+        name,
+        ty: type_ann
+            .map(|ann| lower_type_ir(&ann.type_ann, source))
+            .unwrap_or_default(),
+        optional,
+        rest,
+    }
+}
+
+#[cfg(feature = "swc")]
+fn lower_object_type_ir(members: &[TsTypeElement], source: &str) -> TypeIR {
+    let mut props = vec![];
+    let mut index_signatures = vec![];
+    for member in members {
+        match member {
+            TsTypeElement::TsPropertySignature(prop) => props.push(ObjectMemberIR {
+                name: property_key_name(&prop.key, source),
+                ty: prop
+                    .type_ann
+                    .as_ref()
+                    .map(|ann| lower_type_ir(&ann.type_ann, source))
+                    .unwrap_or_default(),
+                optional: prop.optional,
+                readonly: prop.readonly,
+            }),
+            TsTypeElement::TsMethodSignature(meth) => props.push(ObjectMemberIR {
+                name: property_key_name(&meth.key, source),
+                ty: TypeIR::Function {
+                    type_params: type_param_names(meth.type_params.as_deref()),
+                    params: meth.params.iter().map(|p| lower_fn_param(p, source)).collect(),
+                    return_type: Box::new(
+                        meth.type_ann
+                            .as_ref()
+                            .map(|ann| lower_type_ir(&ann.type_ann, source))
+                            .unwrap_or_else(|| TypeIR::keyword("void")),
+                    ),
+                    is_constructor: false,
+                },
+                optional: meth.optional,
+                readonly: false,
+            }),
+            TsTypeElement::TsIndexSignature(sig) => {
+                let Some(param) = sig.params.first() else {
+                    continue;
+                };
+                let key = lower_fn_param(param, source);
+                index_signatures.push(IndexSignatureIR {
+                    key_name: key.name,
+                    key: key.ty,
+                    value: sig
+                        .type_ann
+                        .as_ref()
+                        .map(|ann| lower_type_ir(&ann.type_ann, source))
+                        .unwrap_or_default(),
+                    readonly: sig.readonly,
+                });
+            }
+            // Call/construct signatures and accessors are rare in data types
+            _ => {}
+        }
+    }
+    TypeIR::Object {
+        members: props,
+        index_signatures,
+    }
+}
+
+#[cfg(feature = "swc")]
+fn property_key_name(key: &Expr, source: &str) -> String {
+    match key {
+        Expr::Ident(i) => i.sym.to_string(),
+        Expr::Lit(Lit::Str(s)) => String::from_utf8_lossy(s.value.as_bytes()).to_string(),
+        other => snippet(source, other.span()),
+    }
+}
+
 #[cfg(feature = "swc")]
 fn lower_interface_members(
     body: &[TsTypeElement],
@@ -532,6 +763,11 @@ fn lower_interface_members(
                     .as_ref()
                     .map(|t| snippet(source, t.type_ann.span()))
                     .unwrap_or_else(|| "any".into());
+                let type_ir = prop
+                    .type_ann
+                    .as_ref()
+                    .map(|t| lower_type_ir(&t.type_ann, source))
+                    .unwrap_or_default();
 
                 // Collect decorators from leading JSDoc comments
                 let decorators = collect_leading_macro_directives(source, prop.span.lo.0 as usize);
@@ -540,6 +776,7 @@ fn lower_interface_members(
                     name,
                     span: swc_span_to_ir(prop.span),
                     ts_type,
+                    type_ir,
                     optional: prop.optional,
                     readonly: prop.readonly,
                     decorators,
@@ -598,6 +835,11 @@ fn lower_members(body: &[ClassMember], source: &str) -> (Vec<FieldIR>, Vec<Metho
                     .as_ref()
                     .map(|t| snippet(source, t.type_ann.span()))
                     .unwrap_or_else(|| "any".into());
+                let type_ir = p
+                    .type_ann
+                    .as_ref()
+                    .map(|t| lower_type_ir(&t.type_ann, source))
+                    .unwrap_or_default();
 
                 let mut decorators = lower_decorators(&p.decorators, source);
                 decorators.extend(collect_leading_macro_directives(
@@ -609,6 +851,7 @@ fn lower_members(body: &[ClassMember], source: &str) -> (Vec<FieldIR>, Vec<Metho
                     name,
                     span: swc_span_to_ir(p.span),
                     ts_type,
+                    type_ir,
                     type_ann: p.type_ann.as_ref().map(|ann| ann.type_ann.clone()),
                     optional: p.is_optional, // Changed from p.optional
                     readonly: p.readonly,
@@ -1457,4 +1700,81 @@ export type UnionWithDefault =
             }
         });
     }
+
+    #[cfg(feature = "swc")]
+    fn field_types(source: &str) -> Vec<TypeIR> {
+        GLOBALS.set(&Globals::new(), || {
+            let module = parse_module(source);
+            let interfaces = lower_interfaces(&module, source).expect("lowering to succeed");
+            interfaces[0].fields.iter().map(|f| f.type_ir.clone()).collect()
+        })
+    }
+
+    #[cfg(feature = "swc")]
+    #[test]
+    fn test_type_ir_references_and_unions() {
+        let types = field_types(
+            "interface A { a: Array<B | null>; b: Map<string, C.D[]>; c?: (string | number)[]; d; }",
+        );
+        assert_eq!(
+            types[0],
+            TypeIR::reference(
+                "Array",
+                vec![TypeIR::Union {
+                    types: vec![TypeIR::reference("B", vec![]), TypeIR::keyword("null")]
+                }]
+            )
+        );
+        assert_eq!(types[1].to_string(), "Map<string, C.D[]>");
+        assert_eq!(types[2].to_string(), "(string | number)[]");
+        assert_eq!(types[3], TypeIR::keyword("any"));
+    }
+
+    #[cfg(feature = "swc")]
+    #[test]
+    fn test_type_ir_functions_tuples_and_literals() {
+        let types = field_types(
+            r#"interface A {
+                f: <T>(x: T, y?: number, ...rest: string[]) => void;
+                t: [name: string, age?: number, ...flags: boolean[]];
+                l: "on" | 1 | true | 10n;
+            }"#,
+        );
+        let TypeIR::Function { type_params, params, return_type, is_constructor } = &types[0] else {
+            panic!("expected function type, got {:?}", types[0]);
+        };
+        assert_eq!(type_params, &["T".to_string()]);
+        assert_eq!(params.len(), 3);
+        assert!(params[1].optional && params[2].rest);
+        assert!(return_type.is_keyword("void") && !is_constructor);
+
+        let TypeIR::Tuple { elements } = &types[1] else {
+            panic!("expected tuple type, got {:?}", types[1]);
+        };
+        assert_eq!(elements[0].label.as_deref(), Some("name"));
+        assert!(elements[1].optional && elements[2].rest);
+        assert_eq!(types[1].to_string(), "[name: string, age?: number, ...flags: boolean[]]");
+
+        assert_eq!(
+            types[2].union_members()[0],
+            TypeIR::Literal { value: LiteralTypeIR::String("on".into()) }
+        );
+        assert_eq!(types[2].to_string(), "\"on\" | 1 | true | 10n");
+    }
+
+    #[cfg(feature = "swc")]
+    #[test]
+    fn test_type_ir_objects_and_operators() {
+        let types = field_types(
+            "interface A { o: { readonly x: number; y?: string; [key: string]: unknown }; k: keyof User; i: User[\"id\"]; m: { [K in Keys]: V }; }",
+        );
+        let TypeIR::Object { members, index_signatures } = &types[0] else {
+            panic!("expected object type, got {:?}", types[0]);
+        };
+        assert!(members[0].readonly && members[1].optional);
+        assert_eq!(index_signatures[0].key, TypeIR::keyword("string"));
+        assert_eq!(types[1].to_string(), "keyof User");
+        assert!(matches!(types[2], TypeIR::IndexedAccess { .. }));
+        assert_eq!(types[3], TypeIR::Other { source: "{ [K in Keys]: V }".into() });
+    }
 }
@@ -32,6 +32,8 @@ pub struct TsStream {
     pub ctx: Option<crate::abi::MacroContextIR>,
     /// Runtime patches to apply (e.g., imports at file level)
     pub runtime_patches: Vec<crate::abi::Patch>,
+    /// Non-fatal diagnostics reported alongside the generated code
+    pub diagnostics: Vec<crate::abi::Diagnostic>,
 }
 
 #[cfg(feature = "swc")]
@@ -125,6 +127,7 @@ impl TsStream {
             file_name: file_name.to_string(),
             ctx: None,
             runtime_patches: vec![],
+            diagnostics: vec![],
         })
     }
 
@@ -136,6 +139,7 @@ impl TsStream {
             file_name: "macro_output.ts".to_string(),
             ctx: None,
             runtime_patches: vec![],
+            diagnostics: vec![],
         }
     }
 
@@ -157,6 +161,7 @@ impl TsStream {
             file_name: file_name.to_string(),
             ctx: Some(ctx),
             runtime_patches: vec![],
+            diagnostics: vec![],
         })
     }
 
@@ -170,7 +175,7 @@ impl TsStream {
         crate::abi::MacroResult {
             runtime_patches: self.runtime_patches,
             type_patches: vec![],
-            diagnostics: vec![],
+            diagnostics: self.diagnostics,
             tokens: Some(self.source),
             debug: None,
         }
@@ -189,6 +194,11 @@ impl TsStream {
         });
     }
 
+    /// Report a diagnostic (usually a warning) alongside the generated code.
+    pub fn add_diagnostic(&mut self, diagnostic: crate::abi::Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
     /// Add a type-only import statement to be inserted at the top of the file.
     /// Use this for TypeScript types/interfaces that don't exist at runtime.
     pub fn add_type_import(&mut self, specifier: &str, module: &str) {
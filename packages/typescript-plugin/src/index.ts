@@ -2,7 +2,6 @@ import type ts from "typescript/lib/tsserverlibrary";
 import type { ExpandResult, MacroManifest, MacroManifestEntry, DecoratorManifestEntry } from "macroforge";
 import { NativePlugin, PositionMapper, __macroforgeGetManifest } from "macroforge";
 import path from "path";
-import fs from "fs";
 
 // Macro manifest cache for hover info
 let macroManifestCache: {
@@ -198,34 +197,6 @@ function hasMacroDirectives(text: string) {
   );
 }
 
-type MacroConfig = {
-  keepDecorators: boolean;
-};
-
-function loadMacroConfig(startDir: string): MacroConfig {
-  let current = startDir;
-  const fallback: MacroConfig = { keepDecorators: false };
-
-  while (true) {
-    const candidate = path.join(current, "macroforge.json");
-    if (fs.existsSync(candidate)) {
-      try {
-        const raw = fs.readFileSync(candidate, "utf8");
-        const parsed = JSON.parse(raw);
-        return { keepDecorators: Boolean(parsed.keepDecorators) };
-      } catch {
-        return fallback;
-      }
-    }
-
-    const parent = path.dirname(current);
-    if (parent === current) break;
-    current = parent;
-  }
-
-  return fallback;
-}
-
 function init(modules: { typescript: typeof ts }) {
   function create(info: ts.server.PluginCreateInfo) {
     const tsModule = modules.typescript;
@@ -247,9 +218,6 @@ function init(modules: { typescript: typeof ts }) {
       info.languageServiceHost.getCurrentDirectory?.() ??
       process.cwd();
 
-    const macroConfig = loadMacroConfig(getCurrentDirectory());
-    const keepDecorators = macroConfig.keepDecorators;
-
     // Log helper - delegates to Rust
     const log = (msg: string) => {
       const line = `[${new Date().toISOString()}] ${msg}`;
@@ -366,8 +334,9 @@ function init(modules: { typescript: typeof ts }) {
       try {
         log(`Processing ${fileName}`);
 
+        // keepDecorators comes from the project's macroforge config (including
+        // per-file overrides), which the native side resolves from fileName
         const result = nativePlugin.processFile(fileName, content, {
-          keepDecorators,
           version,
         });
 
@@ -45,34 +45,6 @@ export interface NapiMacrosPluginOptions {
   metadataOutputDir?: string; // Where to output metadata JSON (defaults to types dir)
 }
 
-interface MacroConfig {
-  keepDecorators: boolean;
-}
-
-function loadMacroConfig(projectRoot: string): MacroConfig {
-  let current = projectRoot;
-  const fallback: MacroConfig = { keepDecorators: false };
-
-  while (true) {
-    const candidate = path.join(current, "macroforge.json");
-    if (fs.existsSync(candidate)) {
-      try {
-        const raw = fs.readFileSync(candidate, "utf8");
-        const parsed = JSON.parse(raw);
-        return { keepDecorators: Boolean(parsed.keepDecorators) };
-      } catch {
-        return fallback;
-      }
-    }
-
-    const parent = path.dirname(current);
-    if (parent === current) break;
-    current = parent;
-  }
-
-  return fallback;
-}
-
 function getCompilerOptions(
   projectRoot: string,
 ): ts.CompilerOptions | undefined {
@@ -243,7 +215,6 @@ function napiMacrosPlugin(options: NapiMacrosPluginOptions = {}): Plugin {
       }
     | undefined;
   let projectRoot: string;
-  let macroConfig: MacroConfig = { keepDecorators: false };
   const generateTypes = options.generateTypes !== false; // Default to true
   const typesOutputDir = options.typesOutputDir || "src/macros/generated";
   const emitMetadata = options.emitMetadata !== false;
@@ -325,7 +296,6 @@ function napiMacrosPlugin(options: NapiMacrosPluginOptions = {}): Plugin {
 
     configResolved(config) {
       projectRoot = config.root;
-      macroConfig = loadMacroConfig(projectRoot);
 
       // Load the Rust binary
       try {
@@ -358,9 +328,9 @@ function napiMacrosPlugin(options: NapiMacrosPluginOptions = {}): Plugin {
       }
 
       try {
-        const result: ExpandResult = rustTransformer.expandSync(code, id, {
-          keepDecorators: macroConfig.keepDecorators,
-        });
+        // keepDecorators (and per-file overrides) come from the macroforge
+        // config, which the native side resolves from the file path
+        const result: ExpandResult = rustTransformer.expandSync(code, id);
 
         // Report diagnostics
         for (const diag of result.diagnostics) {
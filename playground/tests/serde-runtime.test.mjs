@@ -0,0 +1,185 @@
+/**
+ * Tests for the macroforge/serde runtime helpers used by the generated code.
+ */
+
+import { test, describe } from "node:test";
+import assert from "node:assert/strict";
+import { Codec, DeserializeContext, MsgPack, SerializeContext, TypeDeserializer } from "../../crates/macroforge_ts/js/serde/index.mjs";
+
+// ============================================================================
+// SerializeContext
+// ============================================================================
+
+describe("SerializeContext", () => {
+  test("is not plain unless asked", () => {
+    assert.equal(SerializeContext.create().plain, false);
+    assert.equal(SerializeContext.create({ plain: true }).plain, true);
+  });
+});
+
+// ============================================================================
+// DeserializeContext
+// ============================================================================
+
+describe("DeserializeContext", () => {
+  test("async validator rejections wait for settleValidations", async () => {
+    const unhandled = [];
+    const onUnhandled = (reason) => unhandled.push(reason);
+    process.on("unhandledRejection", onUnhandled);
+    try {
+      const ctx = DeserializeContext.create();
+      const errors = [];
+      ctx.checkValidation(Promise.reject(new Error("lookup failed")), "email", "invalid", errors);
+      ctx.checkValidation(Promise.resolve("taken"), "name", "invalid", errors);
+      assert.ok(ctx.hasPendingValidations());
+
+      // Let the rejection go unobserved for a few turns
+      await new Promise((resolve) => setTimeout(resolve, 10));
+      assert.deepEqual(unhandled, []);
+
+      await assert.rejects(ctx.settleValidations(), /lookup failed/);
+    } finally {
+      process.off("unhandledRejection", onUnhandled);
+    }
+  });
+
+  test("settles async validator results into field errors", async () => {
+    const ctx = DeserializeContext.create();
+    ctx.checkValidation(Promise.resolve(false), "email", "email taken", []);
+    ctx.checkValidation(Promise.resolve(true), "name", "invalid", []);
+
+    assert.deepEqual(await ctx.settleValidations(), [
+      { field: "email", message: "email taken", code: "custom" },
+    ]);
+    assert.ok(!ctx.hasPendingValidations());
+  });
+});
+
+// ============================================================================
+// TypeDeserializer
+// ============================================================================
+
+describe("TypeDeserializer", () => {
+  const User = { __deserialize: (value) => ({ user: value.name }) };
+
+  test("keys positional type arguments by parameter name", () => {
+    assert.deepEqual(TypeDeserializer.named([User, undefined], ["T", "U"]), { T: User });
+    const named = { T: User };
+    assert.equal(TypeDeserializer.named(named, ["T"]), named);
+    assert.equal(TypeDeserializer.named(undefined, ["T"]), undefined);
+  });
+
+  test("applies a generic type to its type arguments", () => {
+    const Box = {
+      __deserialize: (value, ctx, typeArgs) => {
+        const types = TypeDeserializer.named(typeArgs, ["T"]);
+        return { item: TypeDeserializer.apply(types?.T, value.item, ctx) };
+      },
+    };
+    const ctx = DeserializeContext.create();
+    const boxed = TypeDeserializer.apply(TypeDeserializer.of(Box, [User]), { item: { name: "Ada" } }, ctx);
+    assert.deepEqual(boxed, { item: { user: "Ada" } });
+  });
+});
+
+// ============================================================================
+// Codec
+// ============================================================================
+
+/** Encode, send through JSON text and decode, as a derived field does */
+function roundTrip(value, encode, decode) {
+  return decode(JSON.parse(JSON.stringify(encode(value))));
+}
+
+describe("Codec", () => {
+  test("bigint", () => {
+    const big = 2n ** 70n + 1n;
+    assert.equal(roundTrip(big, String, Codec.decodeBigInt), big);
+    assert.equal(Codec.decodeBigInt(42), 42n);
+    assert.throws(() => Codec.decodeBigInt(null), /expected a bigint string/);
+  });
+
+  test("bytes", () => {
+    const bytes = new Uint8Array([0, 1, 127, 128, 255]);
+    assert.deepEqual(roundTrip(bytes, Codec.encodeBase64, Codec.decodeBase64), bytes);
+    const buffer = bytes.buffer;
+    const decoded = roundTrip(buffer, Codec.encodeBase64, (raw) => Codec.decodeBase64(raw).buffer);
+    assert.deepEqual(new Uint8Array(decoded), bytes);
+  });
+
+  test("URL", () => {
+    const url = new URL("https://example.com/a?b=1#c");
+    const decoded = roundTrip(url, (u) => u.href, (raw) => new URL(raw));
+    assert.ok(decoded instanceof URL);
+    assert.equal(decoded.href, url.href);
+  });
+
+  test("RegExp", () => {
+    const pattern = /a\/b[0-9]+/gi;
+    const decoded = roundTrip(pattern, String, Codec.decodeRegExp);
+    assert.equal(decoded.source, pattern.source);
+    assert.equal(decoded.flags, pattern.flags);
+    assert.equal(Codec.decodeRegExp("^x$").source, "^x$");
+  });
+
+  test("Temporal", { skip: typeof Temporal === "undefined" && "Temporal is not available" }, () => {
+    const date = Temporal.PlainDate.from("2024-02-29");
+    const decoded = roundTrip(date, (d) => d.toString(), (raw) => Temporal.PlainDate.from(raw));
+    assert.ok(decoded.equals(date));
+  });
+
+  test("tuple", () => {
+    const span = [new Date("2024-01-01T00:00:00.000Z"), 5n];
+    const decoded = roundTrip(
+      span,
+      (t) => [t[0].toISOString(), String(t[1])],
+      (raw) => [new Date(raw[0]), Codec.decodeBigInt(raw[1])],
+    );
+    assert.deepEqual(decoded, span);
+  });
+
+  test("Record", () => {
+    const balances = { alice: 10n, bob: -3n };
+    const decoded = roundTrip(
+      balances,
+      (r) => Codec.mapValues(r, (x) => String(x)),
+      (raw) => Codec.mapValues(raw, (x) => Codec.decodeBigInt(x)),
+    );
+    assert.deepEqual(decoded, balances);
+    assert.throws(() => Codec.mapValues([], (x) => x), /expected an object/);
+  });
+
+  test("nullable and array elements", () => {
+    const ids = [1n, 2n];
+    assert.deepEqual(
+      roundTrip(ids, (v) => v.map((x) => String(x)), (raw) => raw.map((x) => Codec.decodeBigInt(x))),
+      ids,
+    );
+    const encodeNullable = (v) => (v === null ? null : Codec.encodeBase64(v));
+    const decodeNullable = (raw) => (raw === null ? null : Codec.decodeBase64(raw));
+    assert.equal(roundTrip(null, encodeNullable, decodeNullable), null);
+    assert.deepEqual(roundTrip(new Uint8Array([9]), encodeNullable, decodeNullable), new Uint8Array([9]));
+  });
+});
+
+// ============================================================================
+// MsgPack
+// ============================================================================
+
+describe("MsgPack", () => {
+  test("round-trips nested maps and arrays", () => {
+    const value = { id: 1, name: "Alice", tags: ["a", "b"], owner: { id: 2 } };
+    assert.deepEqual(MsgPack.decode(MsgPack.encode(value)), value);
+  });
+
+  test("keeps a __proto__ key as an own property", () => {
+    const value = JSON.parse('{"__proto__":{"polluted":true},"id":1}');
+    const decoded = MsgPack.decode(MsgPack.encode(value));
+
+    assert.equal(Object.getPrototypeOf(decoded), Object.prototype);
+    assert.ok(Object.hasOwn(decoded, "__proto__"));
+    assert.deepEqual(Object.keys(decoded), ["__proto__", "id"]);
+    assert.equal(decoded.polluted, undefined);
+    assert.equal({}.polluted, undefined);
+  });
+});
@@ -31,10 +31,13 @@ export const navigation: NavSection[] = [
 			{ title: 'Debug', href: '/docs/builtin-macros/debug' },
 			{ title: 'Clone', href: '/docs/builtin-macros/clone' },
 			{ title: 'Default', href: '/docs/builtin-macros/default' },
+			{ title: 'EnumUtils', href: '/docs/builtin-macros/enum-utils' },
 			{ title: 'Hash', href: '/docs/builtin-macros/hash' },
+			{ title: 'Mock', href: '/docs/builtin-macros/mock' },
 			{ title: 'Ord', href: '/docs/builtin-macros/ord' },
 			{ title: 'PartialEq', href: '/docs/builtin-macros/partial-eq' },
 			{ title: 'PartialOrd', href: '/docs/builtin-macros/partial-ord' },
+			{ title: 'Patch', href: '/docs/builtin-macros/patch' },
 			{ title: 'Serialize', href: '/docs/builtin-macros/serialize' },
 			{ title: 'Deserialize', href: '/docs/builtin-macros/deserialize' }
 		]
@@ -0,0 +1,16 @@
+import { expandExample } from '$lib/server/macroforge';
+
+export function load() {
+	return {
+		examples: {
+			enum: expandExample(`/** @derive(EnumUtils) */
+enum Status {
+  Active = "active",
+  Inactive = "inactive",
+  Pending = "pending",
+}`),
+			union: expandExample(`/** @derive(EnumUtils) */
+type Direction = "north" | "east" | "south" | "west";`)
+		}
+	};
+}
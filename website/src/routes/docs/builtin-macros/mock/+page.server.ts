@@ -0,0 +1,22 @@
+import { expandExample } from '$lib/server/macroforge';
+
+export function load() {
+	return {
+		examples: {
+			basic: expandExample(`/**
+ * @derive(Mock)
+ * @serde({ rename_all: "camelCase" })
+ */
+class User {
+  /** @serde({ validate: ["email", "maxLength(60)"] }) */
+  email: string;
+  /** @serde({ validate: ["minLength(2)"] }) */
+  display_name: string;
+  /** @serde({ validate: ["int", "positive"] }) */
+  age: number;
+  status: "active" | "banned";
+  nickname?: string;
+}`)
+		}
+	};
+}
@@ -0,0 +1,23 @@
+import { expandExample } from '$lib/server/macroforge';
+
+export function load() {
+	return {
+		examples: {
+			basic: expandExample(`/** @derive(Patch) */
+interface Address {
+  city: string;
+  zip?: string;
+}
+
+/**
+ * @derive(Patch)
+ * @serde({ rename_all: "camelCase" })
+ */
+class User {
+  display_name: string;
+  email?: string;
+  address: Address;
+}`)
+		}
+	};
+}